@@ -1,32 +1,45 @@
 from ..reflect import is_ref
 from ..state.generic import Tuple
 from ..state.ref import Post, Put
-from ..state import State
+from ..state import State, Stream
 from ..util import form_of, to_json, uri, URI
 
 
 class Column(object):
     """A column in the schema of a :class:`BTree` or :class:`Table`."""
 
-    def __init__(self, name, dtype, max_size=None):
+    def __init__(self, name, dtype, max_size=None, nullable=False, case_insensitive=False):
         self.name = name
         self.dtype = dtype
         self.max_size = max_size
+        self.nullable = nullable
+        self.case_insensitive = case_insensitive
 
     def __eq__(self, other):
-        return self.name == other.name and self.dtype == other.dtype and self.max_size == other.max_size
+        return (
+            self.name == other.name
+            and self.dtype == other.dtype
+            and self.max_size == other.max_size
+            and self.nullable == other.nullable
+            and self.case_insensitive == other.case_insensitive)
 
     def __json__(self):
-        if self.max_size is None:
+        if self.case_insensitive:
+            return to_json((self.name, self.dtype, self.max_size, self.nullable, True))
+        elif self.nullable:
+            return to_json((self.name, self.dtype, self.max_size, True))
+        elif self.max_size is None:
             return to_json((self.name, self.dtype))
         else:
             return to_json((self.name, self.dtype, self.max_size))
 
     def __repr__(self):
+        nullable = ", nullable" if self.nullable else ""
+        nullable += ", case-insensitive" if self.case_insensitive else ""
         if self.max_size is None:
-            return f"{self.name}: column type {self.dtype}"
+            return f"{self.name}: column type {self.dtype}{nullable}"
         else:
-            return f"{self.name}: column type {self.dtype}, max size {self.max_size}"
+            return f"{self.name}: column type {self.dtype}, max size {self.max_size}{nullable}"
 
 
 class Collection(State):
@@ -85,6 +98,21 @@ class Collection(State):
 
         return Load(Put(cls, schema, data))
 
+    def eq(self, other):
+        """Return `Bool(true)` if this `Collection` has the same schema and contents as the given `other`."""
+
+        from ..state.value import Bool
+        return self._post("eq", {"eq": other}, Bool)
+
+    def export(self, format="csv"):
+        """
+        Return a :class:`Stream` of the rows of this `Collection`, each encoded as a line of text.
+
+        `format` may be "csv", which begins with a header row of column names, or "ndjson".
+        """
+
+        return self._get("export", format, Stream)
+
     @property
     def schema(self):
         """Return the schema of this `Collection`."""
@@ -1,7 +1,7 @@
 """A `BTree` with a schema of named, :class:`Value`-typed :class:`Column` s."""
 
 from ..state.generic import Map, Tuple
-from ..state.number import UInt
+from ..state.number import Bool, Int, UInt
 from ..state.ref import Ref
 from ..state import State, Stream
 from ..util import form_of, to_json, uri, URI
@@ -36,6 +36,24 @@ class BTree(Collection):
         range = _handle_range(prefix)
         return self._post("", {"range": range}, BTree)
 
+    def compare(self, l, r):
+        """
+        Compare the keys `l` and `r` according to the collation of this `BTree`.
+
+        Returns -1 if `l` sorts before `r`, 1 if `l` sorts after `r`, or 0 if they are equal.
+        """
+
+        return self._post("compare", Map(l=l, r=r), Int)
+
+    def contains(self, key):
+        """
+        Return `True` if the given `key` is present in this `BTree`.
+
+        The key must specify a value for every column; to check a prefix, call `btree[prefix].count()` instead.
+        """
+
+        return self._get("contains", key, Bool)
+
     def count(self, range=None):
         """
         Return the number of keys in this `BTree`.
@@ -74,12 +92,57 @@ class BTree(Collection):
 
         return self._put("", value=key)
 
+    def insert_unique(self, key):
+        """
+        Insert the given key into this `BTree` if it is not already present.
+
+        Returns `True` if the key was inserted, or `False` if it was already present.
+        """
+
+        return self._post("insert", Map(key=key, unique=True), Bool)
+
+    def insert_validated(self, keys, max_keys):
+        """
+        Insert every key in the given :class:`Stream` of `keys` into this `BTree`, only if every key is valid.
+
+        Every key is validated before any is inserted, so an invalid key raises a `BadRequest` error naming its index
+        and leaves this `BTree` unchanged. Because the keys are held in memory until they're all validated, this
+        raises a `BadRequest` error rather than buffer more than `max_keys` keys.
+        """
+
+        return self._post("insert_validated", Map(keys=keys, max_keys=max_keys), State)
+
     def keys(self, range=None):
         """Return a :class:`Stream` of the keys in this `BTree` within the given range (if specified)."""
 
         range = _handle_range(range)
         return self._get("keys", range, Stream)
 
+    def merge_insert(self, keys):
+        """
+        Insert the given keys, which must already be sorted in ascending order, into this `BTree`.
+
+        `keys` may be another `BTree` or a `Tuple` of keys. This is faster than inserting each key separately,
+        e.g. to rebuild an index from another sorted source, but raises a `BadRequest` error if a key is out of order.
+        """
+
+        return self._post("merge_insert", Map(keys=keys), State)
+
+    def pop(self, range=None, limit=None):
+        """
+        Delete and return up to `limit` keys within the given `range` of this `BTree`, in order.
+
+        No other write to this `BTree` in the same transaction can interleave with a `pop`.
+        """
+
+        params = {}
+        if range is not None:
+            params["where"] = _handle_range(range)
+        if limit is not None:
+            params["limit"] = limit
+
+        return self._post("pop", Map(params), Tuple)
+
     def reverse(self):
         """
         Return a slice of this `BTree` with the same range but with its keys in reverse order.
@@ -1,14 +1,15 @@
 from ..decorators import closure, delete_op, get_op
 from ..error import BadRequest
 from ..state.generic import Map, Tuple
-from ..state.number import Bool, UInt
-from ..state.ref import If, Ref
+from ..state.number import Bool, F64, Number, UInt
+from ..state.ref import If, Post, Ref
 from ..state import State, Stream
 from ..util import form_of, to_json, uri, URI
 
 from .base import Collection
 from .bound import Range
 from .btree import BTree
+from .tensor import Dense
 
 
 class Schema(object):
@@ -35,11 +36,32 @@ class Table(Collection):
 
     __uri__ = uri(Collection) + "/table"
 
+    @classmethod
+    def load_csv(cls, schema, source, delimiter=None, max_errors=None):
+        """
+        Create a new `Table` with the given `schema` from a :class:`Stream` of CSV data.
+
+        The first record of `source` must name the columns of `schema`, in order. Up to `max_errors` malformed rows
+        (by default, none) are skipped. Returns a :class:`Map` with the new `table` and the number of `errors`.
+        """
+
+        params = {"schema": schema, "source": source, "delimiter": delimiter, "max_errors": max_errors}
+        return Map.expect({"table": cls, "errors": UInt})(Post(uri(cls) + "/load", params))
+
     def __getitem__(self, key):
         """Return the row with the given key, or a :class:`NotFound` error."""
 
         return self._get("", key, rtype=Map)
 
+    def add_column(self, column, default=None):
+        """
+        Add the given value :class:`Column` to this `Table` and return it with its updated schema.
+
+        Each existing row is backfilled with the given `default` value.
+        """
+
+        return self._post("add_column", Map(column=column, default=default), Table)
+
     def aggregate(self, columns, fn):
         """
         Apply the given callback to slices of this `Table` grouped by the given columns.
@@ -76,6 +98,20 @@ class Table(Collection):
         else:
             return self.where(where).count()
 
+    def count_by(self, columns):
+        """
+        Return a new `Table` with one row `(group..., count)` for each unique combination of the given `columns`.
+
+        This `Table` must support ordering by `columns`.
+        """
+
+        return self._get("count_by", columns, Table)
+
+    def create_index(self, name, columns):
+        """Add a new index on the given columns to this `Table` and return it with its updated schema."""
+
+        return self._post("create_index", Map(name=name, columns=columns), Table)
+
     def delete(self, where={}):
         """
         Delete all contents of this `Table` matching the specified where clause.
@@ -87,11 +123,55 @@ class Table(Collection):
         to_delete = self.where(where) if where else self
         return to_delete.select(self.key_names()).rows().for_each(delete_row)
 
+    def delete_where(self, where):
+        """
+        Delete every row of this `Table` within the given `where` bounds.
+
+        Unlike :meth:`delete`, if the bounds select a range of the primary key
+        this deletes the range directly rather than deleting one row at a time.
+        """
+
+        return self._post("delete_where", Map(bounds=_handle_bounds(where)), State)
+
     def delete_row(self, key):
         """Delete the row with the given key from this `Table`, if it exists."""
 
         return self._delete("", key)
 
+    def drop_column(self, name):
+        """
+        Drop the given value column from this `Table` and return it with its updated schema.
+
+        An index which depends on the column together with other columns must be dropped first.
+        """
+
+        return self._post("drop_column", Map(name=name), Table)
+
+    def drop_index(self, name):
+        """Drop the given index from this `Table` and return it with its updated schema."""
+
+        return self._post("drop_index", Map(name=name), Table)
+
+    def rename_column(self, old, new):
+        """
+        Rename the column `old` to `new` in this `Table`, including in any index, and return it with its updated schema.
+
+        No rows are rewritten.
+        """
+
+        return self._post("rename_column", Map(old=old, new=new), Table)
+
+    def explain(self):
+        """
+        Describe the indices which would be used to read the rows of this `Table`.
+
+        Returns a :class:`Map` with `indices`, a :class:`Tuple` of the `index` name and `columns`
+        of each index read (more than one for a slice which merges several indices),
+        and `full_scan`, which is `True` if this `Table` has no bounds.
+        """
+
+        return self._get("explain", rtype=Map)
+
     def group_by(self, columns):
         """Return a :class:`Stream` of the unique values of the given columns."""
 
@@ -114,6 +194,39 @@ class Table(Collection):
             BadRequest("cannot insert: key already exists"),
             self.upsert(key, values))
 
+    def insert_rows(self, rows, concurrency=None):
+        """
+        Insert every row in the given :class:`Stream` of `rows` into this `Table`.
+
+        Each row must include every key and value column. If any row is invalid, or its key is
+        already present in this `Table`, the entire operation fails and none of the rows are inserted.
+
+        At most `concurrency` rows are written at once (by default, the number of CPUs on the host).
+        """
+
+        params = {"rows": rows}
+        if concurrency is not None:
+            params["concurrency"] = concurrency
+
+        return self._post("insert", Map(params), State)
+
+    def exists(self, where=None):
+        """
+        Return `True` if the given slice of this `Table` (or the entire `Table` if no bounds are given) has any rows.
+
+        This is cheaper than `count`, since it stops reading after the first row.
+        """
+
+        if where is None:
+            return self._get("exists", rtype=Bool)
+        else:
+            return self.where(where).exists()
+
+    def first(self):
+        """Return the first row of this `Table` in its current order, or a :class:`NotFound` error if it's empty."""
+
+        return self._get("first", rtype=Tuple)
+
     def is_empty(self):
         """Return `True` if this table contains no rows."""
 
@@ -129,11 +242,21 @@ class Table(Collection):
 
         return self._get("key_names", rtype=Tuple)
 
+    def last(self):
+        """Return the last row of this `Table` in its current order, or a :class:`NotFound` error if it's empty."""
+
+        return self._get("last", rtype=Tuple)
+
     def limit(self, limit):
         """Limit the number of rows returned from this `Table`."""
 
         return self._get("limit", limit, Table)
 
+    def mean_column(self, name):
+        """Return the mean of the numeric column `name` over every row of this `Table`, as an :class:`F64`."""
+
+        return self._get("mean_column", name, F64)
+
     def order_by(self, columns, reverse=False):
         """
         Set the order in which this `Table`'s rows will be iterated over.
@@ -143,17 +266,74 @@ class Table(Collection):
 
         return self._get("order", (columns, reverse), Table)
 
+    def reverse(self):
+        """
+        Reverse the order of this `Table`'s rows.
+
+        Only a slice of a `Table` can be reversed, not the `Table` itself.
+        """
+
+        return self._get("reverse", rtype=Table)
+
+    def keyed_rows(self, where={}):
+        """Return a :class:`Stream` of the rows in this `Table`, each a :class:`Map` keyed by column name."""
+
+        where = _handle_bounds(where)
+        return self._post("keyed_rows", where, Stream)
+
     def rows(self, where={}):
         """Return a :class:`Stream` of the rows in this `Table`."""
 
         where = _handle_bounds(where)
         return self._post("rows", where, Stream)
 
+    def sample(self, n):
+        """
+        Return a new `Table` with the same schema as this `Table`, containing a random sample
+        of up to `n` of its rows.
+
+        The sample is chosen by reading every row in this `Table` once, so this takes time
+        proportional to the size of the `Table` even when `n` is small.
+        """
+
+        return self._get("sample", n, Table)
+
     def select(self, columns):
         """Return a `Table` containing only the specified columns."""
 
         return self._get("select", columns, Table)
 
+    def sum_column(self, name):
+        """Return the sum of the numeric column `name` over every row of this `Table`."""
+
+        return self._get("sum_column", name, Number)
+
+    def to_tensor(self, columns):
+        """
+        Load the given numeric `columns` of this `Table` into a 2-dimensional :class:`Dense` tensor,
+        with one row per row of this `Table` and one column per column given.
+
+        Nullable columns are not supported, since a `Tensor` cannot represent a missing value.
+        """
+
+        return self._get("to_tensor", columns, Dense)
+
+    def truncate(self):
+        """Delete every row in this `Table`, without deleting each row individually."""
+
+        return self._delete("truncate")
+
+    def verify_indices(self):
+        """
+        Check that every index of this `Table` agrees with its primary index.
+
+        Returns a :class:`Tuple` with one :class:`Map` per inconsistency, each with the name of the `index`, the
+        affected `key`, and its `kind`: "missing" if a row has no entry in the index, or "orphaned" if the entry in
+        the index has no matching row. The `Tuple` is empty if every index is consistent.
+        """
+
+        return self._get("verify_indices", rtype=Tuple)
+
     def update(self, values, where={}):
         """Update the specified rows of this table with the given `values`."""
 
@@ -165,6 +345,17 @@ class Table(Collection):
 
         return self._put("", key, values)
 
+    def update_if_version(self, key, version, expected, values):
+        """
+        Update the row with the given `key` with the given `values`, only if its `version` column equals `expected`.
+
+        The `version` column is incremented by one with each update. If it does not equal `expected`,
+        e.g. because a concurrent transaction has already updated the row, this raises a :class:`Conflict` error.
+        """
+
+        params = Map(key=key, version=version, expected=expected, values=values)
+        return self._post("update_if_version", params, State)
+
     def upsert(self, key, values):
         """
         Insert the given row into this `Table`.
@@ -174,6 +365,24 @@ class Table(Collection):
 
         return self._put("", key, values)
 
+    def upsert_row(self, key, values):
+        """
+        Insert the row with the given `key` and `values` into this `Table`, or update its `values` if it already exists.
+
+        Returns `True` if the row was inserted, or `False` if it was updated.
+        """
+
+        return self._post("upsert", Map(key=key, values=values), Bool)
+
+    def values_at(self, key):
+        """
+        Return a `Tuple` of the value columns of the row with the given complete `key`.
+
+        Raises a :class:`NotFound` error if there is no such row, or a :class:`BadRequest` error if `key` is partial.
+        """
+
+        return self._get("values_at", key, Tuple)
+
     def where(self, bounds):
         """
         Return a slice of this `Table` whose column values fall within the specified range.
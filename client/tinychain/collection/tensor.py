@@ -1,5 +1,6 @@
 """An n-dimensional array of numbers."""
 
+import math
 import typing
 
 from ..decorators import post_op
@@ -75,6 +76,9 @@ class Tensor(Collection):
         bounds = _handle_bounds(bounds)
 
         class Slice(self.__class__):
+            def accumulate(self, value):
+                return parent._put("accumulate", bounds, value)
+
             def write(self, value):
                 return parent._put("", bounds, value)
 
@@ -139,6 +143,15 @@ class Tensor(Collection):
 
         return self._get("abs", rtype=self.__class__)
 
+    def accumulate(self, value):
+        """
+        Add the given `Tensor` or `Number` to this `Tensor` in-place, broadcasting if needed.
+
+        To add to a slice of this `Tensor`, call `tensor[bounds].accumulate(value)`.
+        """
+
+        return self._put("accumulate", None, value)
+
     def acos(self):
         """Return the element-wise arccosine of this `Tensor`."""
 
@@ -154,15 +167,41 @@ class Tensor(Collection):
 
         return self._post("add", {"r": other}, Tensor)
 
-    def all(self):
-        """Return `True` if all elements in this `Tensor` are nonzero."""
+    def all(self, axis=None):
+        """
+        Return `True` if all elements in this `Tensor` are nonzero.
+
+        If an `axis` is given, return a boolean `Tensor` which is `True` where all elements along `axis` are nonzero.
+        This `Tensor` must have at least two dimensions in order to reduce it along an `axis`.
+        """
+
+        if axis is None:
+            return self._get("all", rtype=Bool)
+        else:
+            return self._post("all", {"axis": axis}, self.__class__)
+
+    def any(self, axis=None):
+        """
+        Return `True` if any element in this `Tensor` are nonzero.
+
+        If an `axis` is given, return a boolean `Tensor` which is `True` where any element along `axis` is nonzero.
+        This `Tensor` must have at least two dimensions in order to reduce it along an `axis`.
+        """
 
-        return self._get("all", rtype=Bool)
+        if axis is None:
+            return self._get("any", rtype=Bool)
+        else:
+            return self._post("any", {"axis": axis}, self.__class__)
 
-    def any(self):
-        """Return `True` if any element in this `Tensor` are nonzero."""
+    def apply_along_axis(self, axis, op):
+        """
+        Apply the named `op` to each 1-D slice of this `Tensor` along the given `axis`.
+
+        The result has the same shape as this `Tensor`. The only supported `op` is currently "normalize",
+        which subtracts the mean and divides by the standard deviation of each slice.
+        """
 
-        return self._get("any", rtype=Bool)
+        return self._post("apply_along_axis", {"axis": axis, "op": op}, Tensor)
 
     def argmax(self, axis=None):
         """Return the indices of the maximum values along the given `axis` of this `Tensor`.
@@ -192,31 +231,98 @@ class Tensor(Collection):
 
         return self._get("atanh", rtype=self.__class__)
 
+    def astype(self, number_type):
+        """An alias for :meth:`cast`."""
+
+        return self.cast(number_type)
+
     def cast(self, number_type):
-        """Cast the data type of `Tensor` into the given `number_type`."""
+        """
+        Cast the data type of `Tensor` into the given `number_type`.
+
+        Casting to :class:`Bool` maps every nonzero element (including `NaN`) to `True` and zero to `False`.
+        Casting from :class:`Bool` to a numeric type maps `False` to 0 and `True` to 1.
+        """
 
         return self._get("cast", number_type, self.__class__)
 
+    def chunk(self, start, stop):
+        """
+        Return a :class:`Stream` of the elements of this `Tensor` at flat offsets in the range `[start, stop)`.
+
+        The elements are streamed in order, including zeros, as if this `Tensor` were dense and flattened.
+        """
+
+        return self._get("chunk", (start, stop), Stream)
+
     def copy(self):
-        """Return a copy of this `Tensor`"""
+        """Return a copy of this `Tensor` which is not affected by later writes to this `Tensor`."""
+
+        return self._get("copy", rtype=self.__class__)
+
+    def diff(self, other, tolerance=None):
+        """
+        Return a :class:`Stream` of `(coord, left, right)` for each coordinate where this `Tensor` and `other`
+        differ by more than the given `tolerance`, after broadcasting.
+        """
 
-        return self.__class__(ref.Post(uri(Tensor) + "/copy_from", {"tensor": self}))
+        params = {"r": other}
+        if tolerance is not None:
+            params["tolerance"] = tolerance
+
+        return self._post("diff", Map(params), Stream)
 
     def cos(self):
         """Return the element-wise cosine of this `Tensor`."""
 
         return self._get("cos", rtype=self.__class__)
 
+    def count_nonzero(self, axis=None):
+        """Count the nonzero elements of this `Tensor` along the given `axis`, or in total if no axis is given."""
+
+        rtype = U64 if axis is None else self.__class__
+        return self._get("count_nonzero", axis, rtype)
+
     def cosh(self):
         """Return the element-wise hyperbolic cosine of this `Tensor`."""
 
         return self._get("cosh", rtype=self.__class__)
 
+    def cummax(self, axis=None):
+        """
+        Return the running maximum of this `Tensor` along the given `axis`, or of the flattened `Tensor` if no axis is given.
+
+        Once a NaN value is seen, the running maximum is NaN for the rest of the slice.
+        """
+
+        return self._get("cummax", axis, Dense)
+
+    def cummin(self, axis=None):
+        """
+        Return the running minimum of this `Tensor` along the given `axis`, or of the flattened `Tensor` if no axis is given.
+
+        Once a NaN value is seen, the running minimum is NaN for the rest of the slice.
+        """
+
+        return self._get("cummin", axis, Dense)
+
     def div(self, other):
-        """Divide this `Tensor` by another `Tensor` or `Number`, broadcasting if necessary."""
+        """
+        Divide this `Tensor` by another `Tensor` or `Number`, broadcasting if necessary.
+
+        Integer division by zero raises a :class:`BadRequest` error. Floating-point division by the `Number` zero
+        follows IEEE 754, resulting in an infinite or NaN value, but floating-point division by a `Tensor` which
+        contains a zero raises a :class:`BadRequest` error, like any other combination of two `Tensor` s with a
+        non-finite result.
+        """
 
         return self._post("div", {"r": other}, Tensor)
 
+    def dot(self, other):
+        """Return the dot product of this vector and the given vector `other`."""
+
+        return self._post("dot", {"r": other}, Number)
+
     @property
     def dtype(self):
         """Return the data type of this `Tensor`."""
@@ -243,6 +349,15 @@ class Tensor(Collection):
 
         return self._get("expand_dims", axis, self.__class__)
 
+    def gather(self, indices, axis=0):
+        """
+        Select the slices of this `Tensor` at the given 1-D integer `indices` along the given `axis`.
+
+        The result has the same shape as this `Tensor` except that the dimension of `axis` is `len(indices)`.
+        """
+
+        return self._post("gather", {"indices": indices, "axis": axis}, self.__class__)
+
     def gt(self, other):
         """Return a boolean `Tensor` with element-wise greater-than values."""
 
@@ -253,6 +368,25 @@ class Tensor(Collection):
 
         return self._post("gte", {"r": other}, Tensor)
 
+    def isfinite(self):
+        """
+        Return a boolean `Tensor` which is `True` where this `Tensor` is neither infinite nor `NaN`.
+
+        The result is always `Dense`, since zero is finite.
+        """
+
+        return self._get("isfinite", rtype=Dense)
+
+    def isinf(self):
+        """Return a boolean `Tensor` which is `True` where this `Tensor` is positive or negative infinity."""
+
+        return self._get("isinf", rtype=self.__class__)
+
+    def isnan(self):
+        """Return a boolean `Tensor` which is `True` where this `Tensor` is `NaN`."""
+
+        return self._get("isnan", rtype=self.__class__)
+
     def log(self, base=None):
         """
         Return the logarithm of this `Tensor`.
@@ -298,54 +432,219 @@ class Tensor(Collection):
 
         return self._get("ndim", rtype=UInt)
 
-    def mean(self, axis=None):
+    def masked_fill(self, mask, value):
+        """
+        Return a copy of this `Tensor` with `value` written at every position where `mask` is `True`.
+
+        `mask` is broadcast to the shape of this `Tensor`. `value` may be infinite or NaN if this `Tensor` is a float.
+        """
+
+        if isinstance(value, float) and not math.isfinite(value):
+            value = str(value)
+
+        return self._post("masked_fill", {"mask": mask, "value": value}, self.__class__)
+
+    def masked_mean(self, mask, axis=None):
+        """
+        Calculate the mean of the elements of this `Tensor` where `mask` is `True`,
+        along the given `axis`, or in total if no axis is given.
+
+        `mask` is broadcast to the shape of this `Tensor`. It is an error if no element is selected.
+        An `axis` always returns a `Tensor`, so a 1-D `Tensor` must be reduced without one.
+        """
+
+        rtype = Number if axis is None else self.__class__
+        return self._post("masked_mean", {"mask": mask, "axis": axis}, rtype)
+
+    def masked_sum(self, mask, axis=None):
+        """
+        Calculate the sum of the elements of this `Tensor` where `mask` is `True`,
+        along the given `axis`, or in total if no axis is given.
+
+        `mask` is broadcast to the shape of this `Tensor`.
+        An `axis` always returns a `Tensor`, so a 1-D `Tensor` must be reduced without one.
+        """
+
+        rtype = Number if axis is None else self.__class__
+        return self._post("masked_sum", {"mask": mask, "axis": axis}, rtype)
+
+    def max(self, axis=None, keepdims=False):
+        """
+        Return the maximum value of this `Tensor` along the given `axis`, or the overall maximum if no axis is given.
+
+        If `keepdims` is `True`, the reduced `axis` is kept with dimension 1.
+        """
+
+        rtype = Number if axis is None else self.__class__
+        return self._get("max", (axis, True) if keepdims else axis, rtype)
+
+    def mean(self, axis=None, keepdims=False):
         """
         Return the average of this `Tensor` along the given `axis`,
         or the average of the entire `Tensor` if no axis is given.
+
+        If `keepdims` is `True`, the reduced `axis` is kept with dimension 1.
         """
 
         if axis is None:
             return self.sum() / self.size
         else:
-            return self.sum(axis) / self.shape[axis]
+            return self.sum(axis, keepdims) / self.shape[axis]
+
+    def min(self, axis=None, keepdims=False):
+        """
+        Return the minimum value of this `Tensor` along the given `axis`, or the overall minimum if no axis is given.
+
+        If `keepdims` is `True`, the reduced `axis` is kept with dimension 1.
+        """
+
+        rtype = Number if axis is None else self.__class__
+        return self._get("min", (axis, True) if keepdims else axis, rtype)
 
     def mul(self, other):
         """Multiply this `Tensor` by another `Tensor` or `Number`, broadcasting if necessary."""
 
         return self._post("mul", {"r": other}, self.__class__)
 
+    def moving_average(self, window, axis=None, mode="valid"):
+        """
+        Return the mean of each `window` of consecutive elements along the given `axis` (by default, the last axis).
+
+        In "valid" mode the result is shorter than this `Tensor` by `window - 1` along `axis`. In "full" mode the
+        result has the same shape, and each of the first `window - 1` elements is the mean of the elements so far.
+        """
+
+        params = {"window": window, "mode": mode}
+        if axis is not None:
+            params["axis"] = axis
+
+        return self._post("moving_average", params, Dense)
+
+    def nan_to_num(self, nan=None, posinf=None, neginf=None):
+        """
+        Return a copy of this `Tensor` with `NaN`, positive infinity, and negative infinity replaced by finite values.
+
+        By default `NaN` is replaced with zero and infinite values with the largest finite value of this `Tensor`'s
+        dtype, or its negation. An integer `Tensor` is copied unchanged.
+        """
+
+        params = {}
+        if nan is not None:
+            params["nan"] = nan
+        if posinf is not None:
+            params["posinf"] = posinf
+        if neginf is not None:
+            params["neginf"] = neginf
+
+        return self._post("nan_to_num", params, self.__class__)
+
     def ne(self, other):
         """Return a boolean `Tensor` with element-wise not-equal values."""
 
         return self._post("ne", {"r": other}, self.__class__)
 
+    def outer(self, other):
+        """Return the outer product of this vector and the given vector `other`, as a 2-dimensional `Tensor`."""
+
+        return self._post("outer", {"r": other}, Tensor)
+
     def pow(self, other):
         """Raise this `Tensor` to the given power."""
 
         return self._post("pow", {"r": other}, self.__class__)
 
-    def product(self, axis=None):
-        """Calculate the product of this `Tensor` along the given `axis`, or the total product if no axis is given."""
+    def product(self, axis=None, keepdims=False):
+        """
+        Calculate the product of this `Tensor` along the given `axis`, or the total product if no axis is given.
+
+        If `keepdims` is `True`, the reduced `axis` is kept with dimension 1.
+        """
 
         rtype = Number if axis is None else self.__class__
-        return self._get("product", axis, rtype)
+        return self._get("product", (axis, True) if keepdims else axis, rtype)
+
+    def relu(self):
+        """Return the element-wise rectified linear unit `max(x, 0)` of this `Tensor`."""
+
+        return self._get("relu", rtype=self.__class__)
+
+    def quantile(self, q, axis=None):
+        """
+        Return the `q`th quantile(s) of this `Tensor` along the given `axis`, linearly interpolated between ranks.
+
+        `q` may be a single number in the range [0, 1] or a tuple of them. If no `axis` is given, the quantile is
+        computed over every element; in that case a single `q` returns a :class:`Number`.
+        The result always has data type :class:`F64`.
+        """
+
+        params = {"q": q}
+        if axis is not None:
+            params["axis"] = axis
+
+        if axis is None and not isinstance(q, (list, tuple)):
+            return self._post("quantile", params, F64)
+        else:
+            return self._post("quantile", params, Dense)
+
+    def repeat(self, repeats, axis):
+        """
+        Repeat each element of this `Tensor` consecutively along the given `axis`.
+
+        `repeats` is either a positive integer or a `Tuple` with one positive integer per element of `axis`,
+        e.g. `[1, 2, 3]` repeated 2 times is `[1, 1, 2, 2, 3, 3]`.
+        """
+
+        return self._post("repeat", {"repeats": repeats, "axis": axis}, self.__class__)
 
     def reshape(self, shape):
         """Return a view of this `Tensor` with the given `shape`."""
 
         return self._get("reshape", shape, self.__class__)
 
+    def roll(self, shift, axis=None):
+        """
+        Circularly shift the elements of this `Tensor` by `shift` positions along the given `axis`.
+
+        Elements shifted past the end of the axis wrap around to the beginning. If no `axis` is given,
+        the flattened `Tensor` is rolled and then restored to its original shape.
+        """
+
+        params = {"shift": shift}
+        if axis is not None:
+            params["axis"] = axis
+
+        return self._post("roll", params, self.__class__)
+
     def round(self):
         """Round this `Tensor` to the nearest integer, element-wise."""
 
         return self._get("round", rtype=self.__class__)
 
+    def scatter(self, indices, values, axis=0):
+        """
+        Return a copy of this `Tensor` with the slices of `values` written at the given 1-D integer `indices`
+        along the given `axis`.
+
+        If an index appears more than once, the last corresponding slice of `values` is the one written.
+        """
+
+        return self._post("scatter", {"indices": indices, "values": values, "axis": axis}, self.__class__)
+
     @property
     def shape(self):
         """Return the shape of this `Tensor`."""
 
         return self._get("shape", rtype=Tuple.expect(typing.Tuple[U64, ...]))
 
+    def sigmoid(self):
+        """
+        Return the element-wise logistic sigmoid `1 / (1 + exp(-x))` of this `Tensor`.
+
+        The result is always `Dense`, since `sigmoid(0) == 0.5`. An integer `Tensor` is promoted to `F64`.
+        """
+
+        return self._get("sigmoid", rtype=Dense)
+
     def sin(self):
         """Return the element-wise sine of this `Tensor`."""
 
@@ -362,6 +661,15 @@ class Tensor(Collection):
 
         return self._get("size", rtype=UInt)
 
+    def softmax(self, axis):
+        """
+        Return the softmax of each 1-D slice of this `Tensor` along the given `axis`.
+
+        The values of each slice of the result sum to 1. An integer `Tensor` is promoted to `F64`.
+        """
+
+        return self._post("softmax", {"axis": axis}, Dense)
+
     def split(self, num_or_size_splits, axis=0):
         """
         Split this `Tensor` into multiple slices along the given `axis`.
@@ -394,11 +702,18 @@ class Tensor(Collection):
 
         return self._post("sub", {"r": other}, Tensor)
 
-    def sum(self, axis=None):
-        """Calculate the sum of this `Tensor` along the given `axis`, or the total sum if no axis is given."""
+    def sum(self, axis=None, keepdims=False):
+        """
+        Calculate the sum of this `Tensor` along the given `axis`, or the total sum if no axis is given.
+
+        To avoid overflow, the sum is computed in a wider dtype, like numpy: `Bool` and unsigned integers
+        sum to `U64`, signed integers to `I64`, floats to `F64`, and complex numbers to `C64`.
+
+        If `keepdims` is `True`, the reduced `axis` is kept with dimension 1.
+        """
 
         rtype = Number if axis is None else self.__class__
-        return self._get("sum", axis, rtype)
+        return self._get("sum", (axis, True) if keepdims else axis, rtype)
 
     def tan(self):
         """Return the element-wise tangent of this `Tensor`."""
@@ -406,18 +721,71 @@ class Tensor(Collection):
         return self._get("tan", rtype=self.__class__)
 
     def tanh(self):
-        """Return the element-wise hyperbolic tangent of this `Tensor`."""
+        """Return the element-wise hyperbolic tangent of this `Tensor`. An integer `Tensor` is promoted to `F64`."""
 
         return self._get("tanh", rtype=self.__class__)
 
-    def transpose(self, permutation=None):
+    def to_table(self, include_zeros=False):
+        """
+        Return a :class:`Table` with one row per element of this `Tensor`.
+
+        The table's key columns are named `dim_0`, `dim_1`, etc., and its only value column is named `value`.
+        If this is a `Sparse` tensor, only its nonzero elements will be included unless `include_zeros` is `True`.
+        """
+
+        from .table import Table
+
+        return self._get("to_table", include_zeros, Table)
+
+    def topk(self, k, axis=None, largest=True):
+        """
+        Return a `Tuple` of the `k` largest values along the given `axis` (by default, the last axis) and their indices.
+
+        The values are sorted in descending order, or in ascending order if `largest` is `False`, in which case the
+        `k` smallest values are returned. Equal values are ordered by index, and NaN is ordered last.
+        """
+
+        params = {"k": k, "largest": largest}
+        if axis is not None:
+            params["axis"] = axis
+
+        return self._post("topk", params, Tuple)
+
+    def trace(self, offset=0):
+        """
+        Return the sum of the diagonal of this matrix.
+
+        A positive `offset` selects a diagonal above the main diagonal, and a negative `offset` one below it.
+        """
+
+        return self._get("trace", offset, Number)
+
+    def transpose(self, permutation=None, copy=False):
         """
         Return a view of this `Tensor` with its axes transposed according to the given permutation.
 
         If no permutation is given, the axes will be inverted (e.g. `(0, 1, 2)` inverts to `(2, 1, 0)`).
+
+        If `copy` is `True`, the transposed data will be written to a new `Tensor` instead of returning a view.
+        This uses as much storage as the original `Tensor`, but makes repeated reads of the result faster.
         """
 
-        return self._get("transpose", permutation, self.__class__)
+        if copy:
+            return self._post("transpose", {"permutation": permutation, "copy": True}, self.__class__)
+        else:
+            return self._get("transpose", permutation, self.__class__)
+
+    def unique(self, return_counts=False):
+        """
+        Return a 1-D `Dense` tensor of the distinct values of this `Tensor`, in ascending order.
+
+        If `return_counts` is `True`, return a `Tuple` of the distinct values and the number of times each one occurs.
+        """
+
+        if return_counts:
+            return self._get("unique", True, Tuple)
+        else:
+            return self._get("unique", rtype=Dense)
 
     def write(self, value):
         """Overwrite this `Tensor` with the given `Tensor` or `Number`, broadcasting if needed."""
@@ -437,14 +805,19 @@ class Dense(Tensor):
     __uri__ = uri(Tensor) + "/dense"
 
     @classmethod
-    def arange(cls, shape, start, stop):
+    def arange(cls, shape, start, stop, dtype=None):
         """
         Return a `Dense` tensor with the given shape containing a range of numbers
         evenly distributed between `start` and `stop`.
+
+        If no `dtype` is given, it's inferred from `start` and `stop`.
         """
 
-        dtype = type(start) if isinstance(start, Number) else Number
-        return cls.expect(shape, dtype)(ref.Get(uri(cls) + "/range", (shape, start, stop)))
+        if dtype is None:
+            dtype = type(start) if isinstance(start, Number) else Number
+            return cls.expect(shape, dtype)(ref.Get(uri(cls) + "/range", (shape, start, stop)))
+        else:
+            return cls.expect(shape, dtype)(ref.Get(uri(cls) + "/range", (shape, start, stop, dtype)))
 
     @classmethod
     def concatenate(cls, tensors, axis=None):
@@ -457,11 +830,28 @@ class Dense(Tensor):
         return cls(ref.Post(uri(cls) + "/concatenate", params))
 
     @classmethod
-    def constant(cls, shape, value):
-        """Return a `Dense` tensor filled with the given `value`."""
+    def constant(cls, shape, value, block_size=None):
+        """
+        Return a `Dense` tensor filled with the given `value`.
+
+        If `block_size` is given, it must be a power of two; it sets the number of elements stored per block on disk.
+        A larger block size is faster to read in its entirety, but slower to read or write one element at a time.
+        """
 
         dtype = type(value) if isinstance(value, Number) else Number
-        return cls.expect(shape, dtype)(ref.Get(uri(cls) + "/constant", (shape, value)))
+        key = (shape, value) if block_size is None else (shape, value, block_size)
+        return cls.expect(shape, dtype)(ref.Get(uri(cls) + "/constant", key))
+
+    @classmethod
+    def linspace(cls, start, stop, num):
+        """
+        Return a 1-D `Dense` tensor of `num` evenly spaced values from `start` to `stop`, inclusive.
+
+        The result has an integer dtype only if `start` and `stop` are integers and the spacing is exact,
+        otherwise it has dtype `F64`.
+        """
+
+        return cls.expect([num], Number)(ref.Get(uri(cls) + "/linspace", (start, stop, num)))
 
     @classmethod
     def load(cls, shape, dtype, data):
@@ -483,6 +873,15 @@ class Dense(Tensor):
 
         return Load(ref.Put(cls, (shape, dtype), data))
 
+    @classmethod
+    def meshgrid(cls, tensors):
+        """
+        Given `n` 1-D `tensors`, return a `Tuple` of `n` coordinate grids each with shape
+        `[len(tensors[0]), ..., len(tensors[n - 1])]`, where grid `i` is `tensors[i]` broadcast along axis `i`.
+        """
+
+        return Tuple(ref.Post(uri(cls) + "/meshgrid", {"tensors": tensors}))
+
     @classmethod
     def ones(cls, shape, dtype=F32):
         """
@@ -574,6 +973,15 @@ class Dense(Tensor):
 
         return self._get("sparse", rtype=Sparse)
 
+    def to_sparse(self, threshold=None):
+        """
+        Return a new :class:`Sparse` copy of this `Dense` tensor.
+
+        If a `threshold` is given, elements whose absolute value is not greater than `threshold` are dropped.
+        """
+
+        return self._get("to_sparse", threshold, Sparse)
+
 
 class Sparse(Tensor):
     """
@@ -589,6 +997,34 @@ class Sparse(Tensor):
 
     __uri__ = uri(Tensor) + "/sparse"
 
+    @classmethod
+    def adjacency(cls, edges, size, directed=True, self_loops=True, dtype=F32):
+        """
+        Construct the `[size, size]` adjacency matrix of the first `size` nodes of a graph as a `Sparse` tensor.
+
+        `edges` is a 2D tensor whose filled coordinates `[u, v]` are the edges of the graph. Each edge is written
+        to the adjacency matrix with its value (e.g. `1` for a `Bool` edge tensor) cast into the given `dtype`.
+        If `directed` is `False`, each edge is also written at `[v, u]`. If `self_loops` is `False`,
+        edges of the form `[u, u]` are omitted.
+        """
+
+        params = {"edges": edges, "size": size, "directed": directed, "self_loops": self_loops, "dtype": dtype}
+        return cls(ref.Post(uri(cls) + "/adjacency", params))
+
+    @classmethod
+    def copy_from(cls, schema, source, concurrency=None):
+        """
+        Copy a `Sparse` tensor with the given `schema` from a :class:`Stream` of (coordinate, value) elements.
+
+        At most `concurrency` elements are written at once (by default, the number of CPUs on the host).
+        """
+
+        params = {"schema": schema, "source": source}
+        if concurrency is not None:
+            params["concurrency"] = concurrency
+
+        return cls(ref.Post(uri(cls) + "/copy_from", params))
+
     @classmethod
     def zeros(cls, shape, dtype=F32):
         """
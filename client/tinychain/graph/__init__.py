@@ -4,7 +4,7 @@ from ..collection.tensor import Sparse
 from ..error import BadRequest
 from ..decorators import closure, get_op, put_op
 from ..state.generic import Map, Tuple
-from ..state.number import Bool, U32
+from ..state.number import Bool, F32, U32
 from ..state.ref import After, If, Put
 from ..util import uri
 
@@ -101,6 +101,16 @@ class Graph(Map):
 
         return self[label][from_node, to_node].write(True)
 
+    def adjacency(self, label, size, directed=True, self_loops=True, dtype=F32):
+        """
+        Return the adjacency matrix of the first `size` nodes of the edge with the given `label`,
+        as a `Sparse` tensor with shape `[size, size]`.
+
+        See :meth:`Sparse.adjacency` for a description of the other parameters.
+        """
+
+        return Sparse.adjacency(self[label], size, directed, self_loops, dtype)
+
     def remove_edge(self, label, from_node, to_node):
         """Mark `from_node` -> `to_node` as `False` in the edge :class:`Tensor` with the given `label`."""
 
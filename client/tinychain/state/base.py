@@ -226,6 +226,27 @@ class Stream(State):
 
         return self._post("map", {"op": op}, Stream)
 
+    def reduce(self, initial, op):
+        """
+        Reduce this `Stream` to a single value by calling `op` with the result so far and each item in turn.
+
+        `op` must be a PUT Op. It's called with the result so far as its key and the next item as its value,
+        starting with `initial`. The key of a PUT Op is a :class:`Value`, so `initial` and each result of `op`
+        must be a `Value` (e.g. a `Number` or a `Tuple` of Values), not a `Collection`.
+        """
+
+        rtype = type(initial) if isinstance(initial, State) else State
+        return self._post("reduce", {"initial": initial, "op": op}, rtype)
+
+    def scan(self, initial, op):
+        """
+        Like :meth:`reduce`, but return a `Stream` of each intermediate result instead of only the last.
+
+        As with :meth:`reduce`, `initial` and each result of `op` must be a :class:`Value`.
+        """
+
+        return self._post("scan", {"initial": initial, "op": op}, Stream)
+
 
 
 # User-defined object types
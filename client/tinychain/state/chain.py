@@ -64,6 +64,9 @@ class Sync(Chain):
     """
     A :class:`Chain` which keeps track of only the current transaction's operations,
     in order to recover from a transaction failure (e.g. if the host crashes).
+
+    Writes to a tensor are not tracked one at a time; instead, a copy of each tensor which a transaction
+    writes to is saved when the transaction commits.
     """
 
     __uri__ = uri(Chain) + "/sync"
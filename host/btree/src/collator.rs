@@ -0,0 +1,168 @@
+use std::cmp::Ordering;
+use std::ops::Bound;
+use std::sync::Arc;
+
+use collate::Collate;
+
+use tc_value::{Value, ValueCollator};
+
+use super::{Column, Range};
+
+/// Collates the keys of a `BTree`, applying the collation of each [`Column`] to its own values.
+#[derive(Clone, Default)]
+pub struct RowCollator {
+    columns: Arc<Vec<ValueCollator>>,
+    default: ValueCollator,
+}
+
+impl RowCollator {
+    /// Construct a new `RowCollator` for keys with the given schema.
+    pub fn new(schema: &[Column]) -> Self {
+        Self {
+            columns: Arc::new(schema.iter().map(Column::collator).collect()),
+            default: ValueCollator::default(),
+        }
+    }
+
+    /// Borrow the [`ValueCollator`] of the column at the given index.
+    pub fn column(&self, index: usize) -> &ValueCollator {
+        self.columns.get(index).unwrap_or(&self.default)
+    }
+
+    /// Return the ordering of the `left` key (or key prefix) relative to the `right` one.
+    pub fn compare_slice<L, R>(&self, left: L, right: R) -> Ordering
+    where
+        L: AsRef<[Value]>,
+        R: AsRef<[Value]>,
+    {
+        let (left, right) = (left.as_ref(), right.as_ref());
+
+        for (i, (l, r)) in left.iter().zip(right).enumerate() {
+            match self.column(i).compare(l, r) {
+                Ordering::Equal => {}
+                order => return order,
+            }
+        }
+
+        left.len().cmp(&right.len())
+    }
+
+    /// Return the indices of the first key in the given sorted `keys` which falls within `range`,
+    /// and of the first key after it which falls after `range`.
+    pub fn bisect<V: AsRef<[Value]>>(&self, keys: &[V], range: &Range) -> (usize, usize) {
+        let (prefix, start, end) = range.clone().into_inner();
+        let compare = |key: &V| self.compare_range(key.as_ref(), &prefix, &start, &end);
+
+        let left = keys.partition_point(|key| compare(key) == Ordering::Less);
+        let right = keys.partition_point(|key| compare(key) != Ordering::Greater);
+        (left, right)
+    }
+
+    /// Return the index at which the given `key` would be inserted into the given sorted `keys`,
+    /// before any equal key.
+    pub fn bisect_left<V: AsRef<[Value]>>(&self, keys: &[V], key: &[Value]) -> usize {
+        keys.partition_point(|k| self.compare_slice(k, key) == Ordering::Less)
+    }
+
+    /// Return `true` if every key within the `inner` [`Range`] also falls within `outer`.
+    pub fn contains(&self, outer: &Range, inner: &Range) -> bool {
+        let (outer_prefix, outer_start, outer_end) = outer.clone().into_inner();
+        let (inner_prefix, inner_start, inner_end) = inner.clone().into_inner();
+
+        let len = inner_prefix.len();
+        if len < outer_prefix.len() {
+            // `inner` may only bound the next column of `outer`'s prefix to a single value
+            if len + 1 < outer_prefix.len()
+                || !matches!((&outer_start, &outer_end), (Bound::Unbounded, Bound::Unbounded))
+                || self.compare_slice(&inner_prefix, &outer_prefix[..len]) != Ordering::Equal
+            {
+                return false;
+            }
+
+            let value = Bound::Included(outer_prefix[len].clone());
+            let collator = self.column(len);
+            return compare_start(collator, &inner_start, &value) != Ordering::Less
+                && compare_end(collator, &inner_end, &value) != Ordering::Greater;
+        }
+
+        let len = outer_prefix.len();
+        if self.compare_slice(&inner_prefix[..len], &outer_prefix) != Ordering::Equal {
+            return false;
+        }
+
+        let collator = self.column(len);
+        if inner_prefix.len() > len {
+            let value = Bound::Included(inner_prefix[len].clone());
+            compare_start(collator, &value, &outer_start) != Ordering::Less
+                && compare_end(collator, &value, &outer_end) != Ordering::Greater
+        } else {
+            compare_start(collator, &inner_start, &outer_start) != Ordering::Less
+                && compare_end(collator, &inner_end, &outer_end) != Ordering::Greater
+        }
+    }
+
+    // return the ordering of `key` relative to the range with the given `prefix`, `start` & `end`
+    fn compare_range(
+        &self,
+        key: &[Value],
+        prefix: &[Value],
+        start: &Bound<Value>,
+        end: &Bound<Value>,
+    ) -> Ordering {
+        let len = Ord::min(key.len(), prefix.len());
+        match self.compare_slice(&key[..len], &prefix[..len]) {
+            Ordering::Equal if key.len() > prefix.len() => {}
+            order => return order,
+        }
+
+        let value = Bound::Included(key[prefix.len()].clone());
+        let collator = self.column(prefix.len());
+        if compare_start(collator, &value, start) == Ordering::Less {
+            Ordering::Less
+        } else if compare_end(collator, &value, end) == Ordering::Greater {
+            Ordering::Greater
+        } else {
+            Ordering::Equal
+        }
+    }
+}
+
+// compare two lower bounds, where an unbounded start is the least
+fn compare_start(collator: &ValueCollator, left: &Bound<Value>, right: &Bound<Value>) -> Ordering {
+    use Bound::*;
+
+    match (left, right) {
+        (Unbounded, Unbounded) => Ordering::Equal,
+        (Unbounded, _) => Ordering::Less,
+        (_, Unbounded) => Ordering::Greater,
+        (Included(l), Included(r)) | (Excluded(l), Excluded(r)) => collator.compare(l, r),
+        (Included(l), Excluded(r)) => match collator.compare(l, r) {
+            Ordering::Equal => Ordering::Less,
+            order => order,
+        },
+        (Excluded(l), Included(r)) => match collator.compare(l, r) {
+            Ordering::Equal => Ordering::Greater,
+            order => order,
+        },
+    }
+}
+
+// compare two upper bounds, where an unbounded end is the greatest
+fn compare_end(collator: &ValueCollator, left: &Bound<Value>, right: &Bound<Value>) -> Ordering {
+    use Bound::*;
+
+    match (left, right) {
+        (Unbounded, Unbounded) => Ordering::Equal,
+        (Unbounded, _) => Ordering::Greater,
+        (_, Unbounded) => Ordering::Less,
+        (Included(l), Included(r)) | (Excluded(l), Excluded(r)) => collator.compare(l, r),
+        (Included(l), Excluded(r)) => match collator.compare(l, r) {
+            Ordering::Equal => Ordering::Greater,
+            order => order,
+        },
+        (Excluded(l), Included(r)) => match collator.compare(l, r) {
+            Ordering::Equal => Ordering::Less,
+            order => order,
+        },
+    }
+}
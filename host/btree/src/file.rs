@@ -5,25 +5,29 @@ use std::iter::FromIterator;
 use std::marker::PhantomData;
 use std::ops::Deref;
 use std::pin::Pin;
+#[cfg(feature = "instrument")]
+use std::sync::atomic::{self, AtomicU64};
 use std::sync::Arc;
 
 use async_trait::async_trait;
-use collate::Collate;
 use destream::{de, en};
 use futures::future::{self, Future, TryFutureExt};
 use futures::join;
-use futures::stream::{self, FuturesOrdered, FuturesUnordered, TryStreamExt};
+use futures::stream::{self, FuturesOrdered, FuturesUnordered, Stream, StreamExt, TryStreamExt};
 use log::debug;
 use uuid::Uuid;
 
 use tc_error::*;
 use tc_transact::fs::*;
-use tc_transact::lock::TxnLock;
+use tc_transact::lock::{TxnLock, TxnLockWriteGuard};
 use tc_transact::{Transact, Transaction, TxnId};
-use tc_value::{Value, ValueCollator};
+use tc_value::{Value, ValueType};
 use tcgeneric::{Instance, TCBoxTryFuture, TCBoxTryStream, Tuple};
 
-use super::{BTree, BTreeInstance, BTreeSlice, BTreeType, BTreeWrite, Key, Range, RowSchema};
+use super::{
+    validate_range, BTree, BTreeInstance, BTreeSlice, BTreeType, BTreeWrite, Key, Range,
+    RowCollator, RowSchema,
+};
 
 type Selection<'a> = FuturesOrdered<
     Pin<Box<dyn Future<Output = TCResult<TCBoxTryStream<'a, Key>>> + Send + Unpin + 'a>>,
@@ -210,8 +214,12 @@ struct Inner<F, D, T> {
     file: F,
     schema: RowSchema,
     order: usize,
-    collator: ValueCollator,
+    collator: RowCollator,
     root: TxnLock<NodeId>,
+    #[cfg(feature = "instrument")]
+    keys_read: AtomicU64,
+    #[cfg(feature = "instrument")]
+    ranges_read: AtomicU64,
     dir: PhantomData<D>,
     txn: PhantomData<T>,
 }
@@ -227,13 +235,23 @@ where
     Self: Clone,
 {
     fn new(file: F, schema: RowSchema, order: usize, root: NodeId) -> Self {
+        Self::with_root(file, schema, order, TxnLock::new("BTree root", root.into()))
+    }
+
+    fn with_root(file: F, schema: RowSchema, order: usize, root: TxnLock<NodeId>) -> Self {
+        let collator = RowCollator::new(&schema);
+
         BTreeFile {
             inner: Arc::new(Inner {
                 file,
                 schema,
                 order,
-                collator: ValueCollator::default(),
-                root: TxnLock::new("BTree root", root.into()),
+                collator,
+                root,
+                #[cfg(feature = "instrument")]
+                keys_read: AtomicU64::new(0),
+                #[cfg(feature = "instrument")]
+                ranges_read: AtomicU64::new(0),
                 dir: PhantomData,
                 txn: PhantomData,
             }),
@@ -259,6 +277,78 @@ where
         Ok(BTreeFile::new(file, schema, order, root))
     }
 
+    /// Delete every key in this `BTreeFile` and return a handle to the same file with the given
+    /// `schema`, e.g. to rewrite its keys after a change of schema.
+    pub async fn reset(&self, txn_id: TxnId, schema: RowSchema) -> TCResult<Self> {
+        let order = validate_schema(&schema)?;
+
+        {
+            let mut root = self.inner.root.write(txn_id).await?;
+            self.inner.file.truncate(txn_id).await?;
+
+            *root = Uuid::new_v4().into();
+            let node = Node::new(true, None);
+            self.inner
+                .file
+                .create_block(txn_id, (*root).clone(), node, DEFAULT_BLOCK_SIZE)
+                .await?;
+        }
+
+        Ok(Self::with_root(
+            self.inner.file.clone(),
+            schema,
+            order,
+            self.inner.root.clone(),
+        ))
+    }
+
+    /// Return a handle to this `BTreeFile` with the given `schema`, which may differ from its
+    /// current schema only in the names of its columns.
+    pub fn rename(&self, schema: RowSchema) -> TCResult<Self> {
+        let same_types = schema.len() == self.inner.schema.len()
+            && schema.iter().zip(&self.inner.schema).all(|(new, old)| {
+                new.dtype == old.dtype
+                    && new.max_len == old.max_len
+                    && new.nullable == old.nullable
+                    && new.case_insensitive == old.case_insensitive
+            });
+
+        if same_types {
+            Ok(Self::with_root(
+                self.inner.file.clone(),
+                schema,
+                self.inner.order,
+                self.inner.root.clone(),
+            ))
+        } else {
+            Err(TCError::unsupported(
+                "renaming the columns of a BTree cannot change their data types",
+            ))
+        }
+    }
+
+    fn _count(&self, txn_id: TxnId, node_id: NodeId) -> TCBoxTryFuture<u64> {
+        Box::pin(async move {
+            let node = self.inner.file.read_block(txn_id, node_id).await?;
+            let count = node.keys.iter().filter(|key| !key.deleted).count() as u64;
+
+            if node.leaf {
+                Ok(count)
+            } else {
+                let counts: FuturesUnordered<_> = node
+                    .children
+                    .iter()
+                    .cloned()
+                    .map(|child_id| self._count(txn_id, child_id))
+                    .collect();
+
+                counts
+                    .try_fold(count, |total, count| future::ready(Ok(total + count)))
+                    .await
+            }
+        })
+    }
+
     fn _delete_range<'a>(
         &'a self,
         txn_id: TxnId,
@@ -309,7 +399,58 @@ where
         })
     }
 
-    fn _insert(&self, txn_id: TxnId, mut node: F::Write, key: Key) -> TCBoxTryFuture<()> {
+    // insert `key` under the root node, splitting the root first if it's full
+    async fn insert_root(
+        &self,
+        txn_id: TxnId,
+        root_id: &mut TxnLockWriteGuard<NodeId>,
+        key: Key,
+    ) -> TCResult<bool> {
+        let file = &self.inner.file;
+        let order = self.inner.order;
+
+        let root = file.write_block(txn_id, (*root_id).clone()).await?;
+
+        #[cfg(debug_assertions)]
+        debug!(
+            "insert {} into BTree, root node {} has {} keys and {} children (order is {})",
+            <Tuple<Value> as std::iter::FromIterator<Value>>::from_iter(key.to_vec()),
+            *root_id,
+            root.keys.len(),
+            root.children.len(),
+            order
+        );
+
+        #[cfg(debug_assertions)]
+        debug!("root node {} is {}", *root_id, *root);
+
+        assert_eq!(root.children.is_empty(), root.leaf);
+
+        if root.keys.len() == (2 * order) - 1 {
+            debug!("split root node");
+
+            let old_root_id = (*root_id).clone();
+
+            let mut new_root = Node::new(false, None);
+            new_root.children.push(old_root_id.clone());
+
+            let (new_root_id, new_root) = file
+                .create_block_unique(txn_id, new_root, DEFAULT_BLOCK_SIZE)
+                .await?;
+
+            (*root_id) = new_root_id;
+
+            let new_root = self
+                .split_child(txn_id, new_root, old_root_id, root, 0)
+                .await?;
+
+            self._insert(txn_id, new_root, key).await
+        } else {
+            self._insert(txn_id, root, key).await
+        }
+    }
+
+    fn _insert(&self, txn_id: TxnId, mut node: F::Write, key: Key) -> TCBoxTryFuture<bool> {
         Box::pin(async move {
             let collator = &self.inner.collator;
             let file = &self.inner.file;
@@ -325,23 +466,26 @@ where
 
                 if i == node.keys.len() {
                     node.keys.insert(i, key);
-                    return Ok(());
+                    return Ok(true);
                 }
 
                 #[cfg(debug_assertions)]
                 debug!("insert key {} into {} at {}", key, *node, i);
 
                 match collator.compare_slice(&key, &node.keys[i]) {
-                    Ordering::Less => node.keys.insert(i, key),
+                    Ordering::Less => {
+                        node.keys.insert(i, key);
+                        Ok(true)
+                    }
                     Ordering::Equal => {
                         #[cfg(debug_assertions)]
                         debug!("un-delete key at {}: {}", i, key);
-                        node.keys[i].deleted = false
+                        let inserted = node.keys[i].deleted;
+                        node.keys[i].deleted = false;
+                        Ok(inserted)
                     }
-                    Ordering::Greater => panic!("error in Collate::bisect_left"),
+                    Ordering::Greater => panic!("error in RowCollator::bisect_left"),
                 }
-
-                Ok(())
             } else {
                 let child_id = node.children[i].clone();
                 let child = file.write_block(txn_id, child_id).await?;
@@ -353,11 +497,9 @@ where
                     match collator.compare_slice(&key, &node.keys[i]) {
                         Ordering::Less => self._insert(txn_id, node, key).await,
                         Ordering::Equal => {
-                            if node.keys[i].deleted {
-                                node.keys[i].deleted = false;
-                            }
-
-                            return Ok(());
+                            let inserted = node.keys[i].deleted;
+                            node.keys[i].deleted = false;
+                            Ok(inserted)
                         }
                         Ordering::Greater => {
                             let child_id = node.children[i + 1].clone();
@@ -508,10 +650,48 @@ where
             .read_block(txn_id, (*root_id).clone())
             .await?;
 
-        if reverse {
+        #[cfg(feature = "instrument")]
+        self.inner.ranges_read.fetch_add(1, atomic::Ordering::Relaxed);
+
+        #[cfg(feature = "instrument")]
+        let inner = self.inner.clone();
+
+        let keys = if reverse {
             self._slice_reverse(txn_id, root, range)
         } else {
             self._slice(txn_id, root, range)
+        }?;
+
+        #[cfg(feature = "instrument")]
+        let keys: TCBoxTryStream<'a, Key> = Box::pin(keys.inspect_ok(move |_| {
+            inner.keys_read.fetch_add(1, atomic::Ordering::Relaxed);
+        }));
+
+        Ok(keys)
+    }
+
+    /// Return the number of keys streamed from this `BTreeFile` so far, for testing.
+    #[cfg(feature = "instrument")]
+    pub fn keys_read(&self) -> u64 {
+        self.inner.keys_read.load(atomic::Ordering::Relaxed)
+    }
+
+    /// Return the number of ranges of keys read from this `BTreeFile` so far, for testing.
+    #[cfg(feature = "instrument")]
+    pub fn ranges_read(&self) -> u64 {
+        self.inner.ranges_read.load(atomic::Ordering::Relaxed)
+    }
+
+    async fn rightmost_leaf(&self, txn_id: TxnId, root_id: NodeId) -> TCResult<NodeId> {
+        let mut node_id = root_id;
+
+        loop {
+            let node = self.inner.file.read_block(txn_id, node_id.clone()).await?;
+            if node.leaf {
+                return Ok(node_id);
+            }
+
+            node_id = node.children.last().cloned().expect("rightmost child");
         }
     }
 
@@ -577,7 +757,7 @@ where
 {
     type Slice = BTreeSlice<F, D, T>;
 
-    fn collator(&'_ self) -> &'_ ValueCollator {
+    fn collator(&'_ self) -> &'_ RowCollator {
         &self.inner.collator
     }
 
@@ -589,6 +769,12 @@ where
         BTreeSlice::new(BTree::File(self), range, reverse)
     }
 
+    async fn count(&self, txn_id: TxnId) -> TCResult<u64> {
+        // count the keys in each node rather than streaming & collecting every key
+        let root_id = self.inner.root.read(txn_id).await?;
+        self._count(txn_id, (*root_id).clone()).await
+    }
+
     async fn is_empty(&self, txn_id: TxnId) -> TCResult<bool> {
         let root_id = self.inner.root.read(txn_id).await?;
         let root = self
@@ -612,6 +798,10 @@ where
         key.into_iter()
             .zip(&self.inner.schema)
             .map(|(val, col)| {
+                if col.nullable && val.is_none() {
+                    return Ok(Value::None);
+                }
+
                 val.into_type(col.dtype)
                     .ok_or_else(|| TCError::bad_request("invalid value for column", &col.name))
             })
@@ -646,55 +836,104 @@ where
     }
 
     async fn insert(&self, txn_id: TxnId, key: Key) -> TCResult<()> {
-        let key = self.validate_key(key)?;
+        self.insert_unique(txn_id, key).map_ok(|_| ()).await
+    }
 
-        let file = &self.inner.file;
-        let order = self.inner.order;
+    async fn pop(&self, txn_id: TxnId, range: Range, limit: Option<usize>) -> TCResult<Vec<Key>> {
+        let range = validate_range(range, self.schema())?;
+
+        // hold a write lock on the root node ID until the selected keys are deleted,
+        // so that no other write in this transaction can change them in the meantime
+        let root_id = self.inner.root.write(txn_id).await?;
+
+        let keys: Vec<Key> = {
+            let root = self
+                .inner
+                .file
+                .read_block(txn_id, (*root_id).clone())
+                .await?;
+
+            let keys = self.clone()._slice(txn_id, root, range)?;
+            match limit {
+                Some(limit) => keys.take(limit).try_collect().await?,
+                None => keys.try_collect().await?,
+            }
+        };
+
+        for key in &keys {
+            let range = Range::with_prefix(key.to_vec());
+            self._delete_range(txn_id, (*root_id).clone(), &range)
+                .await?;
+        }
+
+        Ok(keys)
+    }
+
+    async fn insert_unique(&self, txn_id: TxnId, key: Key) -> TCResult<bool> {
+        let key = self.validate_key(key)?;
 
         // get a write lock on the root_id while we check if a split_child is needed,
         // to avoid getting out of sync in the case of a concurrent insert in the same txn
         let mut root_id = self.inner.root.write(txn_id).await?;
         debug!("insert into BTree with root node ID {}", *root_id);
 
-        let root = file.write_block(txn_id, (*root_id).clone()).await?;
-
-        #[cfg(debug_assertions)]
-        debug!(
-            "insert {} into BTree, root node {} has {} keys and {} children (order is {})",
-            <Tuple<Value> as std::iter::FromIterator<Value>>::from_iter(key.to_vec()),
-            *root_id,
-            root.keys.len(),
-            root.children.len(),
-            order
-        );
-
-        #[cfg(debug_assertions)]
-        debug!("root node {} is {}", *root_id, *root);
-
-        assert_eq!(root.children.is_empty(), root.leaf);
+        self.insert_root(txn_id, &mut root_id, key).await
+    }
 
-        if root.keys.len() == (2 * order) - 1 {
-            debug!("split root node");
+    async fn merge_insert_from<S: Stream<Item = TCResult<Key>> + Send + Unpin>(
+        &self,
+        txn_id: TxnId,
+        mut keys: S,
+    ) -> TCResult<()> {
+        let collator = &self.inner.collator;
+        let file = &self.inner.file;
+        let order = self.inner.order;
 
-            let old_root_id = (*root_id).clone();
+        // hold the root lock for the whole load, so that no other write in this transaction
+        // can change the rightmost path of the tree in between two keys
+        let mut root_id = self.inner.root.write(txn_id).await?;
+        let mut rightmost = self.rightmost_leaf(txn_id, (*root_id).clone()).await?;
+        let mut last: Option<Key> = None;
+
+        while let Some(key) = keys.try_next().await? {
+            let key = self.validate_key(key)?;
+
+            if let Some(last) = &last {
+                if collator.compare_slice(last, &key) == Ordering::Greater {
+                    return Err(TCError::bad_request(
+                        "merge_insert_from requires keys in ascending order, found out-of-order key",
+                        Tuple::from(key),
+                    ));
+                }
+            }
 
-            let mut new_root = Node::new(false, None);
-            new_root.children.push(old_root_id.clone());
+            // a key greater than every key in the tree can be appended to the rightmost leaf
+            // without descending from the root, as long as that leaf doesn't need to be split
+            let appended = {
+                let mut leaf = file.write_block(txn_id, rightmost.clone()).await?;
 
-            let (new_root_id, new_root) = file
-                .create_block_unique(txn_id, new_root, DEFAULT_BLOCK_SIZE)
-                .await?;
+                let greatest = match leaf.keys.last() {
+                    Some(greatest) => collator.compare_slice(&key, greatest) == Ordering::Greater,
+                    None => rightmost == *root_id,
+                };
 
-            (*root_id) = new_root_id;
+                if greatest && leaf.keys.len() < (2 * order) - 1 {
+                    leaf.keys.push(NodeKey::new(key.clone()));
+                    true
+                } else {
+                    false
+                }
+            };
 
-            let new_root = self
-                .split_child(txn_id, new_root, old_root_id, root, 0)
-                .await?;
+            if !appended {
+                self.insert_root(txn_id, &mut root_id, key.clone()).await?;
+                rightmost = self.rightmost_leaf(txn_id, (*root_id).clone()).await?;
+            }
 
-            self._insert(txn_id, new_root, key).await
-        } else {
-            self._insert(txn_id, root, key).await
+            last = Some(key);
         }
+
+        Ok(())
     }
 }
 
@@ -788,6 +1027,16 @@ impl<F, D, T> fmt::Display for BTreeFile<F, D, T> {
 }
 
 fn validate_schema(schema: &RowSchema) -> TCResult<usize> {
+    if let Some(col) = schema
+        .iter()
+        .find(|col| col.case_insensitive && col.dtype != ValueType::String)
+    {
+        return Err(TCError::bad_request(
+            "case-insensitive collation is only applicable to a String column, not",
+            col,
+        ));
+    }
+
     let mut key_size = 0;
     for col in schema {
         if let Some(size) = col.dtype().size() {
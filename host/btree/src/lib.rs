@@ -6,7 +6,7 @@ use std::ops::Bound;
 
 use async_trait::async_trait;
 use destream::{de, en};
-use futures::{future, Stream, TryFutureExt, TryStreamExt};
+use futures::{future, stream, Stream, StreamExt, TryFutureExt, TryStreamExt};
 use log::debug;
 use safecast::*;
 
@@ -16,9 +16,11 @@ use tc_transact::{IntoView, Transaction, TxnId};
 use tc_value::{NumberType, Value, ValueCollator, ValueType};
 use tcgeneric::*;
 
+pub use collator::RowCollator;
 pub use file::{BTreeFile, Node};
 pub use slice::BTreeSlice;
 
+mod collator;
 mod file;
 mod slice;
 
@@ -40,7 +42,7 @@ pub trait BTreeInstance: Clone + Instance {
     type Slice: BTreeInstance;
 
     /// Borrow this `BTree`'s collator.
-    fn collator(&self) -> &ValueCollator;
+    fn collator(&self) -> &RowCollator;
 
     /// Borrow to this `BTree`'s schema.
     fn schema(&self) -> &RowSchema;
@@ -81,6 +83,16 @@ pub trait BTreeWrite: BTreeInstance {
     /// If the [`Key`] is already present, this is a no-op.
     async fn insert(&self, txn_id: TxnId, key: Key) -> TCResult<()>;
 
+    /// Delete and return up to `limit` of the [`Key`]s in the given `range`, in order.
+    ///
+    /// No other write to this `BTree` in the same transaction can interleave with a `pop`.
+    async fn pop(&self, txn_id: TxnId, range: Range, limit: Option<usize>) -> TCResult<Vec<Key>>;
+
+    /// Insert the given [`Key`] into this `BTree` if it is not already present.
+    ///
+    /// Returns `true` if the [`Key`] was inserted, or `false` if it was already present.
+    async fn insert_unique(&self, txn_id: TxnId, key: Key) -> TCResult<bool>;
+
     /// Insert all the keys from the given `Stream` into this `BTree`.
     ///
     /// This will stop and return an error if it encounters an invalid [`Key`].
@@ -94,6 +106,53 @@ pub trait BTreeWrite: BTreeInstance {
             .try_fold((), |(), ()| future::ready(Ok(())))
             .await
     }
+
+    /// Insert all the keys from the given `Stream`, which must be sorted in ascending order.
+    ///
+    /// This is faster than [`BTreeWrite::try_insert_from`] for a sorted `Stream`, e.g. when rebuilding
+    /// an index, because a [`Key`] greater than every existing key is appended directly to the
+    /// rightmost leaf node, without descending from the root. It will stop and return an error if it
+    /// encounters an invalid or out-of-order [`Key`].
+    async fn merge_insert_from<S: Stream<Item = TCResult<Key>> + Send + Unpin>(
+        &self,
+        txn_id: TxnId,
+        keys: S,
+    ) -> TCResult<()>;
+
+    /// Insert all the keys from the given `Stream` into this `BTree`, only if every [`Key`] is valid.
+    ///
+    /// Unlike [`BTreeWrite::try_insert_from`], this validates every [`Key`] before inserting any,
+    /// so an invalid [`Key`] leaves this `BTree` unchanged. The cost is that every [`Key`] is held in
+    /// memory until the whole `Stream` is validated, so this will return an error rather than buffer
+    /// more than `max_keys` keys. Prefer `try_insert_from` for a large or trusted `Stream`.
+    async fn try_insert_validated_from<S: Stream<Item = TCResult<Key>> + Send + Unpin>(
+        &self,
+        txn_id: TxnId,
+        mut keys: S,
+        max_keys: usize,
+    ) -> TCResult<()> {
+        let mut validated = Vec::new();
+
+        while let Some(key) = keys.next().await {
+            let i = validated.len();
+            if i == max_keys {
+                return Err(TCError::bad_request(
+                    "too many keys to validate before inserting, the maximum is",
+                    max_keys,
+                ));
+            }
+
+            // an error from the source stream itself is returned as-is
+            let key = self.validate_key(key?).map_err(|cause| {
+                TCError::bad_request(format!("invalid key at index {}", i), cause)
+            })?;
+
+            validated.push(key);
+        }
+
+        let validated = stream::iter(validated.into_iter().map(Ok));
+        self.try_insert_from(txn_id, validated).await
+    }
 }
 
 /// A `Column` used in the schema of a [`BTree`].
@@ -102,6 +161,8 @@ pub struct Column {
     pub name: Id,
     pub dtype: ValueType,
     pub max_len: Option<usize>,
+    pub nullable: bool,
+    pub case_insensitive: bool,
 }
 
 impl Column {
@@ -122,6 +183,38 @@ impl Column {
     pub fn max_len(&'_ self) -> &'_ Option<usize> {
         &self.max_len
     }
+
+    /// Return `true` if this column accepts `Value::None`.
+    #[inline]
+    pub fn nullable(&self) -> bool {
+        self.nullable
+    }
+
+    /// Return `true` if this column's string values are ordered without regard to case.
+    #[inline]
+    pub fn case_insensitive(&self) -> bool {
+        self.case_insensitive
+    }
+
+    /// Construct a [`ValueCollator`] which orders the values of this column.
+    pub fn collator(&self) -> ValueCollator {
+        if self.case_insensitive {
+            ValueCollator::case_insensitive()
+        } else {
+            ValueCollator::default()
+        }
+    }
+
+    /// Cast the given `value` into the data type of this column.
+    ///
+    /// If this column is nullable, `Value::None` is returned as-is.
+    pub fn cast(&self, value: Value) -> TCResult<Value> {
+        if self.nullable && value.is_none() {
+            Ok(Value::None)
+        } else {
+            self.dtype.try_cast(value)
+        }
+    }
 }
 
 impl<I: Into<Id>> From<(I, NumberType)> for Column {
@@ -135,6 +228,8 @@ impl<I: Into<Id>> From<(I, NumberType)> for Column {
             name,
             dtype,
             max_len,
+            nullable: false,
+            case_insensitive: false,
         }
     }
 }
@@ -148,6 +243,8 @@ impl From<(Id, ValueType)> for Column {
             name,
             dtype,
             max_len,
+            nullable: false,
+            case_insensitive: false,
         }
     }
 }
@@ -161,6 +258,8 @@ impl From<(Id, ValueType, usize)> for Column {
             name,
             dtype,
             max_len,
+            nullable: false,
+            case_insensitive: false,
         }
     }
 }
@@ -169,7 +268,11 @@ impl TryCastFrom<Value> for Column {
     fn can_cast_from(value: &Value) -> bool {
         debug!("Column::can_cast_from {}?", value);
 
-        value.matches::<(Id, ValueType)>() || value.matches::<(Id, ValueType, u64)>()
+        value.matches::<(Id, ValueType)>()
+            || value.matches::<(Id, ValueType, u64)>()
+            || value.matches::<(Id, ValueType, Value)>()
+            || value.matches::<(Id, ValueType, Value, bool)>()
+            || value.matches::<(Id, ValueType, Value, bool, bool)>()
     }
 
     fn opt_cast_from(value: Value) -> Option<Column> {
@@ -180,6 +283,8 @@ impl TryCastFrom<Value> for Column {
                 name,
                 dtype,
                 max_len: None,
+                nullable: false,
+                case_insensitive: false,
             })
         } else if value.matches::<(Id, ValueType, u64)>() {
             let (name, dtype, max_len) = value.opt_cast_into().unwrap();
@@ -188,6 +293,45 @@ impl TryCastFrom<Value> for Column {
                 name,
                 dtype,
                 max_len: Some(max_len),
+                nullable: false,
+                case_insensitive: false,
+            })
+        } else if value.matches::<(Id, ValueType, Value)>() {
+            let (name, dtype, max_len): (Id, ValueType, Value) = value.opt_cast_into().unwrap();
+
+            Some(Column {
+                name,
+                dtype,
+                max_len: cast_max_len(max_len)?,
+                nullable: false,
+                case_insensitive: false,
+            })
+        } else if value.matches::<(Id, ValueType, Value, bool)>() {
+            let (name, dtype, max_len, nullable): (Id, ValueType, Value, bool) =
+                value.opt_cast_into().unwrap();
+
+            Some(Column {
+                name,
+                dtype,
+                max_len: cast_max_len(max_len)?,
+                nullable,
+                case_insensitive: false,
+            })
+        } else if value.matches::<(Id, ValueType, Value, bool, bool)>() {
+            let (name, dtype, max_len, nullable, case_insensitive): (
+                Id,
+                ValueType,
+                Value,
+                bool,
+                bool,
+            ) = value.opt_cast_into().unwrap();
+
+            Some(Column {
+                name,
+                dtype,
+                max_len: cast_max_len(max_len)?,
+                nullable,
+                case_insensitive,
             })
         } else {
             None
@@ -197,14 +341,20 @@ impl TryCastFrom<Value> for Column {
 
 impl From<Column> for Value {
     fn from(column: Column) -> Self {
-        Value::Tuple(
-            vec![
-                column.name.into(),
-                column.dtype.path().into(),
-                column.max_len.map(Value::from).into(),
-            ]
-            .into(),
-        )
+        let mut tuple = vec![
+            column.name.into(),
+            column.dtype.path().into(),
+            column.max_len.map(Value::from).into(),
+        ];
+
+        if column.case_insensitive {
+            tuple.push(column.nullable.into());
+            tuple.push(column.case_insensitive.into());
+        } else if column.nullable {
+            tuple.push(column.nullable.into());
+        }
+
+        Value::Tuple(tuple.into())
     }
 }
 
@@ -229,12 +379,16 @@ impl de::Visitor for ColumnVisitor {
             .await?
             .ok_or_else(|| de::Error::invalid_length(1, "a Column data type"))?;
 
-        let max_len = seq.next_element(()).await?;
+        let max_len: Option<Option<usize>> = seq.next_element(()).await?;
+        let nullable = seq.next_element(()).await?;
+        let case_insensitive = seq.next_element(()).await?;
 
         Ok(Column {
             name,
             dtype,
-            max_len,
+            max_len: max_len.unwrap_or_default(),
+            nullable: nullable.unwrap_or_default(),
+            case_insensitive: case_insensitive.unwrap_or_default(),
         })
     }
 }
@@ -250,7 +404,11 @@ impl de::FromStream for Column {
 
 impl<'en> en::IntoStream<'en> for Column {
     fn into_stream<E: en::Encoder<'en>>(self, encoder: E) -> Result<E::Ok, E::Error> {
-        if let Some(max_len) = self.max_len {
+        if self.case_insensitive {
+            (self.name, self.dtype, self.max_len, self.nullable, true).into_stream(encoder)
+        } else if self.nullable {
+            (self.name, self.dtype, self.max_len, true).into_stream(encoder)
+        } else if let Some(max_len) = self.max_len {
             (self.name, self.dtype, max_len).into_stream(encoder)
         } else {
             (self.name, self.dtype).into_stream(encoder)
@@ -266,19 +424,35 @@ impl<'a> From<&'a Column> for (&'a Id, ValueType) {
 
 impl fmt::Debug for Column {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match self.max_len {
-            Some(max_len) => write!(f, "{}: {}({})", self.name, self.dtype, max_len),
-            None => write!(f, "{}: {}", self.name, self.dtype),
-        }
+        fmt::Display::fmt(self, f)
     }
 }
 
 impl fmt::Display for Column {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self.max_len {
-            Some(max_len) => write!(f, "{}: {}({})", self.name, self.dtype, max_len),
-            None => write!(f, "{}: {}", self.name, self.dtype),
+            Some(max_len) => write!(f, "{}: {}({})", self.name, self.dtype, max_len)?,
+            None => write!(f, "{}: {}", self.name, self.dtype)?,
+        }
+
+        if self.nullable {
+            f.write_str(" (nullable)")?;
+        }
+
+        if self.case_insensitive {
+            f.write_str(" (case-insensitive)")?;
         }
+
+        Ok(())
+    }
+}
+
+#[inline]
+fn cast_max_len(max_len: Value) -> Option<Option<usize>> {
+    if max_len.is_none() {
+        Some(None)
+    } else {
+        max_len.opt_cast_into().map(Some)
     }
 }
 
@@ -351,7 +525,7 @@ where
 {
     type Slice = Self;
 
-    fn collator(&self) -> &ValueCollator {
+    fn collator(&self) -> &RowCollator {
         match self {
             Self::File(file) => file.collator(),
             Self::Slice(slice) => slice.collator(),
@@ -419,6 +593,31 @@ where
             _ => Err(TCError::unsupported(ERR_VIEW_WRITE)),
         }
     }
+
+    async fn insert_unique(&self, txn_id: TxnId, key: Key) -> TCResult<bool> {
+        match self {
+            Self::File(file) => file.insert_unique(txn_id, key).await,
+            _ => Err(TCError::unsupported(ERR_VIEW_WRITE)),
+        }
+    }
+
+    async fn merge_insert_from<S: Stream<Item = TCResult<Key>> + Send + Unpin>(
+        &self,
+        txn_id: TxnId,
+        keys: S,
+    ) -> TCResult<()> {
+        match self {
+            Self::File(file) => file.merge_insert_from(txn_id, keys).await,
+            _ => Err(TCError::unsupported(ERR_VIEW_WRITE)),
+        }
+    }
+
+    async fn pop(&self, txn_id: TxnId, range: Range, limit: Option<usize>) -> TCResult<Vec<Key>> {
+        match self {
+            Self::File(file) => file.pop(txn_id, range, limit).await,
+            _ => Err(TCError::unsupported(ERR_VIEW_WRITE)),
+        }
+    }
 }
 
 impl<F, D, T> From<BTreeFile<F, D, T>> for BTree<F, D, T> {
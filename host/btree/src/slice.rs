@@ -1,14 +1,14 @@
 use async_trait::async_trait;
-use futures::{TryFutureExt, TryStreamExt};
+use futures::{future, TryFutureExt, TryStreamExt};
 
 use tc_error::{TCError, TCResult};
 use tc_transact::fs::{Dir, File};
 use tc_transact::{Transaction, TxnId};
-use tc_value::ValueCollator;
 use tcgeneric::{Instance, TCBoxTryStream};
 
 use super::{
-    validate_range, BTree, BTreeFile, BTreeInstance, BTreeType, Key, Node, Range, RowSchema,
+    validate_range, BTree, BTreeFile, BTreeInstance, BTreeType, Key, Node, Range, RowCollator,
+    RowSchema,
 };
 
 /// A slice of a [`BTree`]
@@ -44,7 +44,7 @@ impl<F: File<Node>, D: Dir, T: Transaction<D>> BTreeSlice<F, D, T> {
                         range: view.range,
                         reverse,
                     })
-                } else if view.range.contains(&range, view.source.collator()) {
+                } else if view.source.collator().contains(&view.range, &range) {
                     Ok(Self {
                         source,
                         range,
@@ -78,7 +78,7 @@ where
 {
     type Slice = Self;
 
-    fn collator(&'_ self) -> &'_ ValueCollator {
+    fn collator(&'_ self) -> &'_ RowCollator {
         self.source.collator()
     }
 
@@ -89,7 +89,7 @@ where
     fn slice(self, range: Range, reverse: bool) -> TCResult<Self::Slice> {
         let range = validate_range(range, self.schema())?;
 
-        if self.range.contains(&range, self.collator()) {
+        if self.collator().contains(&self.range, &range) {
             Self::new(BTree::Slice(self), range, reverse)
         } else {
             Err(TCError::unsupported(
@@ -98,6 +98,18 @@ where
         }
     }
 
+    async fn count(&self, txn_id: TxnId) -> TCResult<u64> {
+        // the number of keys in a slice doesn't depend on the order they're read in
+        let keys = self
+            .source
+            .clone()
+            .rows_in_range(txn_id, self.range.clone(), false)
+            .await?;
+
+        keys.try_fold(0u64, |count, _| future::ready(Ok(count + 1)))
+            .await
+    }
+
     async fn is_empty(&self, txn_id: TxnId) -> TCResult<bool> {
         let mut rows = self
             .source
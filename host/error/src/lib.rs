@@ -178,6 +178,11 @@ impl TCError {
         }
     }
 
+    /// Return `true` if this error indicates a conflict with a concurrent transaction.
+    pub fn is_conflict(&self) -> bool {
+        self.code == ErrorType::Conflict
+    }
+
     pub fn code(&self) -> ErrorType {
         self.code
     }
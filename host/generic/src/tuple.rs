@@ -206,6 +206,43 @@ impl<F, T1: TryCastFrom<F>, T2: TryCastFrom<F>, T3: TryCastFrom<F>, T4: TryCastF
     }
 }
 
+impl<
+        F,
+        T1: TryCastFrom<F>,
+        T2: TryCastFrom<F>,
+        T3: TryCastFrom<F>,
+        T4: TryCastFrom<F>,
+        T5: TryCastFrom<F>,
+    > TryCastFrom<Tuple<F>> for (T1, T2, T3, T4, T5)
+{
+    fn can_cast_from(source: &Tuple<F>) -> bool {
+        source.len() == 5
+            && T1::can_cast_from(&source[0])
+            && T2::can_cast_from(&source[1])
+            && T3::can_cast_from(&source[2])
+            && T4::can_cast_from(&source[3])
+            && T5::can_cast_from(&source[4])
+    }
+
+    fn opt_cast_from(mut source: Tuple<F>) -> Option<(T1, T2, T3, T4, T5)> {
+        if source.len() == 5 {
+            let fifth: Option<T5> = source.pop().unwrap().opt_cast_into();
+            let fourth: Option<T4> = source.pop().unwrap().opt_cast_into();
+            let third: Option<T3> = source.pop().unwrap().opt_cast_into();
+            let second: Option<T2> = source.pop().unwrap().opt_cast_into();
+            let first: Option<T1> = source.pop().unwrap().opt_cast_into();
+            match (first, second, third, fourth, fifth) {
+                (Some(first), Some(second), Some(third), Some(fourth), Some(fifth)) => {
+                    Some((first, second, third, fourth, fifth))
+                }
+                _ => None,
+            }
+        } else {
+            None
+        }
+    }
+}
+
 #[async_trait]
 impl<T: FromStream> FromStream for Tuple<T>
 where
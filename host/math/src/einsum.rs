@@ -4,7 +4,7 @@ use std::ops::Deref;
 use log::debug;
 
 use tc_error::*;
-use tc_tensor::{TensorAccess, TensorMath, TensorReduce, TensorTransform};
+use tc_tensor::{AxisBounds, Bounds, TensorAccess, TensorMath, TensorReduce, TensorTransform};
 use tc_transact::fs::Dir;
 use tc_transact::Transaction;
 use tcgeneric::Tuple;
@@ -22,13 +22,6 @@ const VALID_SUBSCRIPTS: [char; 52] = [
 fn parse_format<T: TensorAccess>(inputs: &[T], format: &str) -> TCResult<(Vec<Label>, Label)> {
     debug!("einsum format string is {}", format);
 
-    if !format.contains("->") {
-        return Err(TCError::bad_request(
-            "invalid format for einsum (missing '->')",
-            format,
-        ));
-    }
-
     let mut parts: VecDeque<&str> = format.split("->").collect();
     if parts.is_empty() || parts.len() > 2 {
         return Err(TCError::bad_request("invalid format for einsum", format));
@@ -126,7 +119,12 @@ fn parse_format<T: TensorAccess>(inputs: &[T], format: &str) -> TCResult<(Vec<La
         .map(|f_input| f_input.chars().collect())
         .collect::<Vec<Label>>();
 
-    let f_output = parts.pop_back().unwrap_or("");
+    let f_output = if let Some(f_output) = parts.pop_back() {
+        f_output.to_string()
+    } else {
+        implicit_output(&f_inputs, &present_subscripts)
+    };
+
     if f_output.chars().collect::<HashSet<_>>().len() != f_output.len() {
         return Err(TCError::bad_request(
             "einsum output cannot include repeated subscripts",
@@ -166,6 +164,24 @@ fn parse_format<T: TensorAccess>(inputs: &[T], format: &str) -> TCResult<(Vec<La
     Ok((f_inputs, f_output))
 }
 
+// in implicit mode (with no "->"), like numpy, the output comprises the subscripts which
+// appear exactly once in the input, in alphabetical order
+fn implicit_output(f_inputs: &[Label], present_subscripts: &HashSet<char>) -> String {
+    let mut counts = BTreeMap::<char, usize>::new();
+    for subscript in f_inputs.iter().flatten() {
+        // the elided subscripts (if any) are prepended to the output separately
+        if present_subscripts.contains(subscript) {
+            *counts.entry(*subscript).or_insert(0) += 1;
+        }
+    }
+
+    counts
+        .into_iter()
+        .filter(|(_, count)| *count == 1)
+        .map(|(subscript, _)| subscript)
+        .collect()
+}
+
 fn validate_args<T: TensorAccess>(
     f_inputs: &[Label],
     tensors: &[T],
@@ -221,7 +237,7 @@ where
     Txn: Transaction<D>,
     T: TensorAccess
         + TensorReduce<D, Txn = Txn, Reduce = T>
-        + TensorTransform<Broadcast = T, Expand = T, Transpose = T>
+        + TensorTransform<Broadcast = T, Expand = T, Reshape = T, Slice = T, Transpose = T>
         + Clone,
 {
     assert_eq!(tensor.ndim(), f_input.len());
@@ -250,11 +266,11 @@ where
     debug!("permutation of {:?} is {:?}", f_input, permutation);
     let mut tensor = tensor.transpose(Some(permutation.clone()))?;
 
-    // sum over repeated subscripts
+    // take the diagonal of repeated subscripts
     // the assumption that they have been transposed together is valid because subscripts
     // in f_output are required to be unique
     debug!(
-        "sum over repeated subscripts of {:?}",
+        "take the diagonal of repeated subscripts of {:?}",
         permutation
             .iter()
             .map(|x| f_input[*x])
@@ -266,7 +282,7 @@ where
     for i in 0..(f_input.len() - 1) {
         let subscript = f_input[permutation[i]];
         if subscript == f_input[permutation[i + 1]] {
-            tensor = tensor.sum(x)?;
+            tensor = diagonal(tensor, x)?;
         } else {
             subscripts.push(subscript);
             x += 1;
@@ -307,6 +323,25 @@ where
     }
 }
 
+// take the diagonal of the (square) axes `axis` and `axis + 1` of the given `tensor`,
+// by flattening them into one axis and selecting every (dim + 1)th element
+fn diagonal<T>(tensor: T, axis: usize) -> TCResult<T>
+where
+    T: TensorAccess + TensorTransform<Reshape = T, Slice = T>,
+{
+    let mut shape = tensor.shape().to_vec();
+    let dim = shape[axis];
+    debug_assert_eq!(dim, shape[axis + 1]);
+
+    shape[axis] = dim * dim;
+    shape.remove(axis + 1);
+    let tensor = tensor.reshape(shape.into())?;
+
+    let mut bounds = Bounds::all(tensor.shape());
+    bounds[axis] = AxisBounds::Of((0..dim).map(|i| i * (dim + 1)).collect());
+    tensor.slice(bounds)
+}
+
 fn outer_product<D, Txn, T>(
     f_inputs: &[Label],
     dimensions: &BTreeMap<char, u64>,
@@ -318,7 +353,7 @@ where
     T: TensorAccess
         + TensorMath<D, T, LeftCombine = T>
         + TensorReduce<D, Txn = Txn, Reduce = T>
-        + TensorTransform<Broadcast = T, Expand = T, Transpose = T>
+        + TensorTransform<Broadcast = T, Expand = T, Reshape = T, Slice = T, Transpose = T>
         + Clone,
 {
     assert_eq!(f_inputs.len(), tensors.len());
@@ -381,7 +416,7 @@ where
     D: Dir,
     T: TensorAccess
         + TensorMath<D, T, LeftCombine = T>
-        + TensorTransform<Broadcast = T, Expand = T, Transpose = T>
+        + TensorTransform<Broadcast = T, Expand = T, Reshape = T, Slice = T, Transpose = T>
         + TensorReduce<D, Reduce = T>
         + Clone,
 {
@@ -90,7 +90,9 @@ impl ChainInstance for BlockChain {
             .await
     }
 
-    async fn write_ahead(&self, txn_id: &TxnId) {
+    async fn write_ahead(&self, txn: &Txn) {
+        let txn_id = txn.id();
+
         {
             let block = self
                 .history
@@ -130,7 +132,7 @@ impl Persist<fs::Dir> for BlockChain {
             History::load(txn, (), dir).await?
         };
 
-        history.apply_last(txn, &subject).await?;
+        history.apply_last(txn, &subject, ChainType::Block).await?;
 
         Ok(BlockChain::new(schema, subject, history))
     }
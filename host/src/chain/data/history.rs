@@ -248,7 +248,11 @@ impl History {
         self.write_block(txn_id, (*latest).into()).await
     }
 
-    pub async fn apply_last(&self, txn: &Txn, subject: &Subject) -> TCResult<()> {
+    /// Replay the mutations of the last transaction in this `History` against the given `subject`.
+    ///
+    /// A PUT with no key to a tensor in a `SyncChain` is a snapshot of the whole tensor,
+    /// so the tensor is restored from it rather than written to.
+    pub async fn apply_last(&self, txn: &Txn, subject: &Subject, class: ChainType) -> TCResult<()> {
         let latest = *self.latest.read(*txn.id()).await?;
         let block = self.read_block(*txn.id(), latest.into()).await?;
 
@@ -266,6 +270,17 @@ impl History {
 
                         subject.delete(txn, path, key.clone()).await
                     }
+                    Mutation::Put(path, key, value)
+                        if class == ChainType::Sync
+                            && key.is_none()
+                            && subject.tensor_path(path) == Some(path.len()) =>
+                    {
+                        debug!("restore {}{} from snapshot {}", subject, path, value);
+
+                        self.resolve(txn, value.clone())
+                            .and_then(|snapshot| subject.restore_tensor(txn, path, snapshot))
+                            .await
+                    }
                     Mutation::Put(path, key, value) => {
                         debug!("replay PUT {}{}: {} <- {}", subject, path, key, value);
 
@@ -91,9 +91,7 @@ impl CollectionSchema {
                         #[cfg(feature = "tensor")]
                         CollectionType::Tensor(tt) => {
                             let schema = expect_value(schema)?;
-                            let schema = schema.try_cast_into(|v| {
-                                TCError::bad_request("invalid Tensor schema", v)
-                            })?;
+                            let schema = tc_tensor::Schema::try_from_value(schema)?;
 
                             match tt {
                                 TensorType::Dense => Ok(Self::Dense(schema)),
@@ -265,7 +263,7 @@ pub trait ChainInstance {
     async fn replicate(&self, txn: &Txn, source: Link) -> TCResult<()>;
 
     /// Write the mutation ops in the current transaction to the write-ahead log.
-    async fn write_ahead(&self, txn_id: &TxnId);
+    async fn write_ahead(&self, txn: &Txn);
 }
 
 /// The type of a [`Chain`].
@@ -389,10 +387,10 @@ impl ChainInstance for Chain {
         }
     }
 
-    async fn write_ahead(&self, txn_id: &TxnId) {
+    async fn write_ahead(&self, txn: &Txn) {
         match self {
-            Self::Block(chain) => chain.write_ahead(txn_id).await,
-            Self::Sync(chain) => chain.write_ahead(txn_id).await,
+            Self::Block(chain) => chain.write_ahead(txn).await,
+            Self::Sync(chain) => chain.write_ahead(txn).await,
         }
     }
 }
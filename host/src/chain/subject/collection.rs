@@ -36,8 +36,11 @@ use super::CollectionSchema;
 pub enum SubjectCollection {
     BTree(BTreeFile),
     Table(TableIndex),
+    /// A dense `Tensor`, whose writes are logged one at a time by a `BlockChain`;
+    /// a `SyncChain` instead saves a copy of it when a transaction which writes to it commits
     #[cfg(feature = "tensor")]
     Dense(DenseTensor<DenseTensorFile>),
+    /// A sparse `Tensor`, which is recorded the same way as a dense `Tensor`
     #[cfg(feature = "tensor")]
     Sparse(SparseTensor<SparseTable>),
 }
@@ -216,6 +219,15 @@ impl SubjectCollection {
         })
     }
 
+    /// Return `true` if this is a `Tensor`.
+    pub(super) fn is_tensor(&self) -> bool {
+        match self {
+            Self::BTree(_) | Self::Table(_) => false,
+            #[cfg(feature = "tensor")]
+            Self::Dense(_) | Self::Sparse(_) => true,
+        }
+    }
+
     pub fn schema(&self) -> CollectionSchema {
         match self {
             Self::BTree(btree) => CollectionSchema::BTree(BTreeInstance::schema(btree).clone()),
@@ -227,10 +239,10 @@ impl SubjectCollection {
         }
     }
 
-    pub async fn into_state(self, _txn_id: TxnId) -> TCResult<State> {
+    pub async fn into_state(self, txn_id: TxnId) -> TCResult<State> {
         let collection = match self {
             Self::BTree(btree) => btree.into(),
-            Self::Table(table) => table.into(),
+            Self::Table(table) => table.current(txn_id).await?.into(),
             #[cfg(feature = "tensor")]
             Self::Dense(dense) => dense.into(),
             #[cfg(feature = "tensor")]
@@ -249,6 +261,7 @@ impl SubjectCollection {
                     hash_try_stream::<Sha256, _, _, _>(keys).await
                 }
                 Self::Table(table) => {
+                    let table = table.current(*txn.id()).await?;
                     let rows = table.rows(*txn.id()).await?;
                     hash_try_stream::<Sha256, _, _, _>(rows).await
                 }
@@ -306,6 +319,8 @@ impl de::FromStream for SubjectCollection {
     }
 }
 
+/// A dense `Tensor` subject is encoded as its schema followed by a stream of its elements,
+/// and a sparse `Tensor` subject as its schema followed by a stream of its filled elements.
 #[async_trait]
 impl<'en> IntoView<'en, fs::Dir> for SubjectCollection {
     type Txn = Txn;
@@ -314,7 +329,10 @@ impl<'en> IntoView<'en, fs::Dir> for SubjectCollection {
     async fn into_view(self, txn: Self::Txn) -> TCResult<Self::View> {
         match self {
             Self::BTree(btree) => State::from(BTree::File(btree)).into_view(txn).await,
-            Self::Table(table) => State::from(Table::Table(table)).into_view(txn).await,
+            Self::Table(table) => {
+                let table = table.current(*txn.id()).await?;
+                State::from(Table::Table(table)).into_view(txn).await
+            }
             #[cfg(feature = "tensor")]
             Self::Dense(tensor) => State::from(Tensor::from(tensor)).into_view(txn).await,
             #[cfg(feature = "tensor")]
@@ -8,6 +8,7 @@ use async_trait::async_trait;
 use destream::de;
 use futures::future::{join_all, try_join_all, TryFutureExt};
 use log::debug;
+use safecast::TryCastFrom;
 use sha2::digest::{Digest, Output};
 use sha2::Sha256;
 
@@ -260,6 +261,45 @@ impl Subject {
         })
     }
 
+    /// Return the length of the prefix of the given `path` which refers to a tensor, if any.
+    pub(super) fn tensor_path(&self, path: &[PathSegment]) -> Option<usize> {
+        self.tensor(path).map(|(len, _)| len)
+    }
+
+    /// Return the tensor at the given `path`, if any.
+    pub(super) fn tensor_at(&self, path: &[PathSegment]) -> Option<Collection> {
+        self.tensor(path).map(|(_, tensor)| tensor.clone().into())
+    }
+
+    /// Restore the tensor at the given `path` from the given `snapshot`.
+    pub(super) async fn restore_tensor(
+        &self,
+        txn: &Txn,
+        path: &[PathSegment],
+        snapshot: State,
+    ) -> TCResult<()> {
+        let (_, tensor) = self
+            .tensor(path)
+            .ok_or_else(|| TCError::not_found(format!("chain tensor at {}", TCPath::from(path))))?;
+
+        let snapshot = snapshot.try_into()?;
+        tensor.restore(txn, snapshot).await
+    }
+
+    fn tensor(&self, path: &[PathSegment]) -> Option<(usize, &SubjectCollection)> {
+        let (next, suffix) = match self {
+            Self::Collection(collection) if collection.is_tensor() => return Some((0, collection)),
+            Self::Map(map) if !path.is_empty() => (map.get(&path[0])?, &path[1..]),
+            Self::Tuple(tuple) if !path.is_empty() => {
+                let i = usize::opt_cast_from(path[0].clone())?;
+                (tuple.get(i)?, &path[1..])
+            }
+            _ => return None,
+        };
+
+        next.tensor(suffix).map(|(len, tensor)| (len + 1, tensor))
+    }
+
     pub fn into_state<'a>(self, txn_id: TxnId) -> TCBoxTryFuture<'a, State> {
         Box::pin(async move {
             match self {
@@ -1,12 +1,16 @@
 //! A [`super::Chain`] which keeps only the data needed to recover the state of its subject in the
 //! event of a transaction failure.
 
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
 use async_trait::async_trait;
 use destream::de;
 use futures::future::TryFutureExt;
 use futures::join;
 use sha2::digest::Output;
 use sha2::Sha256;
+use tokio::sync::RwLock;
 
 use tc_error::*;
 use tc_transact::fs::{Persist, Store};
@@ -23,11 +27,26 @@ use super::{null_hash, ChainBlock, ChainInstance, ChainType, Schema, Subject};
 
 /// A [`super::Chain`] which keeps only the data needed to recover the state of its subject in the
 /// event of a transaction failure.
+///
+/// Writes to a tensor in the subject are not logged one at a time; instead, a snapshot of each
+/// tensor written to by a transaction is saved when the transaction commits.
 #[derive(Clone)]
 pub struct SyncChain {
     schema: Schema,
     subject: Subject,
     history: History,
+    snapshots: Arc<RwLock<HashMap<TxnId, HashSet<TCPathBuf>>>>,
+}
+
+impl SyncChain {
+    fn new(schema: Schema, subject: Subject, history: History) -> Self {
+        Self {
+            schema,
+            subject,
+            history,
+            snapshots: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
 }
 
 #[async_trait]
@@ -54,6 +73,13 @@ impl ChainInstance for SyncChain {
             block.clear_until(txn.id());
         }
 
+        if let Some(len) = self.subject.tensor_path(&path) {
+            let path: TCPathBuf = path[..len].to_vec().into();
+            let mut snapshots = self.snapshots.write().await;
+            snapshots.entry(*txn.id()).or_default().insert(path);
+            return Ok(());
+        }
+
         self.history.append_put(txn, path, key, value).await
     }
 
@@ -79,8 +105,19 @@ impl ChainInstance for SyncChain {
         Ok(())
     }
 
-    async fn write_ahead(&self, txn_id: &TxnId) {
-        self.history.commit(txn_id).await
+    async fn write_ahead(&self, txn: &Txn) {
+        let snapshots = self.snapshots.write().await.remove(txn.id());
+
+        for path in snapshots.into_iter().flatten() {
+            let tensor = self.subject.tensor_at(&path).expect("chain tensor subject");
+
+            self.history
+                .append_put(txn, path, Value::None, State::Collection(tensor))
+                .await
+                .expect("tensor snapshot");
+        }
+
+        self.history.commit(txn.id()).await
     }
 }
 
@@ -113,13 +150,9 @@ impl Persist<fs::Dir> for SyncChain {
             )));
         }
 
-        history.apply_last(txn, &subject).await?;
+        history.apply_last(txn, &subject, ChainType::Sync).await?;
 
-        Ok(SyncChain {
-            schema,
-            subject,
-            history,
-        })
+        Ok(SyncChain::new(schema, subject, history))
     }
 }
 
@@ -131,6 +164,7 @@ impl Transact for SyncChain {
 
     async fn finalize(&self, txn_id: &TxnId) {
         join!(self.subject.finalize(txn_id), self.history.finalize(txn_id));
+        self.snapshots.write().await.remove(txn_id);
     }
 }
 
@@ -171,11 +205,7 @@ impl de::Visitor for ChainVisitor {
             .await?
             .ok_or_else(|| de::Error::invalid_length(1, "the subject of a SyncChain"))?;
 
-        Ok(SyncChain {
-            schema,
-            subject,
-            history,
-        })
+        Ok(SyncChain::new(schema, subject, history))
     }
 }
 
@@ -70,6 +70,19 @@ impl Cluster {
         self.classes.get(name)
     }
 
+    /// List the names of the [`Chain`]s and [`InstanceClass`]es hosted by this cluster, in order.
+    pub fn list(&self) -> Vec<PathSegment> {
+        let mut names: Vec<PathSegment> = self
+            .chains
+            .keys()
+            .chain(self.classes.keys())
+            .cloned()
+            .collect();
+
+        names.sort();
+        names
+    }
+
     /// Borrow the public key of this cluster.
     pub fn public_key(&self) -> &[u8] {
         self.actor.public_key().as_bytes()
@@ -298,7 +311,7 @@ impl Cluster {
             owner.commit(txn).await?;
         }
 
-        self.write_ahead(txn.id()).await;
+        self.write_ahead(txn).await;
 
         let self_link = txn.link(self.link.path().clone());
         let mut replica_commits = FuturesUnordered::from_iter(
@@ -344,8 +357,8 @@ impl Cluster {
         self.finalize(txn.id()).await;
     }
 
-    pub async fn write_ahead(&self, txn_id: &TxnId) {
-        join_all(self.chains.values().map(|chain| chain.write_ahead(txn_id))).await;
+    pub async fn write_ahead(&self, txn: &Txn) {
+        join_all(self.chains.values().map(|chain| chain.write_ahead(txn))).await;
     }
 }
 
@@ -5,18 +5,22 @@ use std::fmt;
 use async_hash::hash_try_stream;
 use async_trait::async_trait;
 use destream::{de, en};
-use futures::TryFutureExt;
+use futures::{Stream, TryFutureExt, TryStreamExt};
 use log::debug;
 use sha2::digest::Output;
 use sha2::Sha256;
 
 use tc_btree::{BTreeInstance, BTreeView};
 use tc_error::*;
-use tc_table::{TableStream, TableView};
+use tc_table::{TableInstance, TableStream, TableView};
 #[cfg(feature = "tensor")]
-use tc_tensor::{Array, TensorView};
+use tc_tensor::{
+    Array, TensorAccess, TensorCompare, TensorCompareConst, TensorMath, TensorUnary, TensorView,
+};
 use tc_transact::fs::{CopyFrom, Dir};
 use tc_transact::{IntoView, Transaction};
+#[cfg(feature = "tensor")]
+use tc_value::{Number, NumberType};
 use tcgeneric::{
     path_label, Class, Id, Instance, NativeClass, PathLabel, PathSegment, TCPath, TCPathBuf,
 };
@@ -33,6 +37,8 @@ pub use tc_tensor::{DenseAccess, SparseAccess, TensorType};
 pub type BTree = tc_btree::BTree<fs::File<tc_btree::Node>, fs::Dir, Txn>;
 pub type BTreeFile = tc_btree::BTreeFile<fs::File<tc_btree::Node>, fs::Dir, Txn>;
 
+pub type Aggregate = tc_table::Aggregate<fs::File<tc_btree::Node>, fs::Dir, Txn, Table>;
+pub type Merged = tc_table::Merged<fs::File<tc_btree::Node>, fs::Dir, Txn>;
 pub type Table = tc_table::Table<fs::File<tc_btree::Node>, fs::Dir, Txn>;
 pub type TableIndex = tc_table::TableIndex<fs::File<tc_btree::Node>, fs::Dir, Txn>;
 
@@ -59,6 +65,10 @@ pub type SparseTable =
 
 pub const PREFIX: PathLabel = path_label(&["state", "collection"]);
 
+/// The maximum absolute difference between two floating-point elements considered equal
+#[cfg(feature = "tensor")]
+const TOLERANCE: f64 = 1e-6;
+
 /// The [`Class`] of a [`Collection`].
 #[derive(Clone, Copy, Eq, PartialEq)]
 pub enum CollectionType {
@@ -217,6 +227,71 @@ impl Collection {
             },
         }
     }
+
+    /// Return `true` if this `Collection` has the same schema and contents as `other`.
+    ///
+    /// `Tensor` elements are compared within a fixed tolerance if either dtype is not exact.
+    pub async fn eq(self, other: Self, txn: Txn) -> TCResult<bool> {
+        let txn_id = *txn.id();
+
+        match (self, other) {
+            (Self::BTree(this), Self::BTree(that)) => {
+                if this.schema() != that.schema() {
+                    return Ok(false);
+                }
+
+                let this = this.keys(txn_id).await?;
+                let that = that.keys(txn_id).await?;
+                try_stream_eq(this, that).await
+            }
+            (Self::Table(this), Self::Table(that)) => {
+                if this.primary_schema() != that.primary_schema() {
+                    return Ok(false);
+                }
+
+                let this = this.rows(txn_id).await?;
+                let that = that.rows(txn_id).await?;
+                try_stream_eq(this, that).await
+            }
+            #[cfg(feature = "tensor")]
+            (Self::Tensor(this), Self::Tensor(that)) => {
+                if this.shape() != that.shape() {
+                    return Ok(false);
+                }
+
+                let exact = |dtype| match dtype {
+                    NumberType::Complex(_) | NumberType::Float(_) | NumberType::Number => false,
+                    _ => true,
+                };
+
+                if exact(this.dtype()) && exact(that.dtype()) {
+                    this.eq(that)?.all(txn).await
+                } else {
+                    let tolerance = Number::from(TOLERANCE);
+                    this.sub(that)?.abs()?.lte_const(tolerance)?.all(txn).await
+                }
+            }
+            (this, that) => Err(TCError::not_implemented(format!(
+                "compare {} with {}",
+                this.class(),
+                that.class()
+            ))),
+        }
+    }
+}
+
+async fn try_stream_eq<T, S>(mut this: S, mut that: S) -> TCResult<bool>
+where
+    T: PartialEq,
+    S: Stream<Item = TCResult<T>> + Unpin,
+{
+    loop {
+        match (this.try_next().await?, that.try_next().await?) {
+            (Some(this), Some(that)) if this == that => {}
+            (None, None) => return Ok(true),
+            _ => return Ok(false),
+        }
+    }
 }
 
 impl From<BTree> for Collection {
@@ -428,3 +503,222 @@ impl<'en> en::IntoStream<'en> for CollectionView<'en> {
         map.end()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use tc_btree::{BTreeWrite, Column};
+    use tc_table::{
+        Bounds, IndexDiscrepancy, IndexSchema, TableSchema, TableSlice, TableStream, TableWrite,
+    };
+    use tc_value::{Bound, IntType, NumberType, Value};
+
+    use crate::gateway::Gateway;
+    use crate::test_support::gateway;
+    use crate::txn::TxnId;
+
+    use super::*;
+
+    fn id(name: &str) -> Id {
+        name.parse().expect("id")
+    }
+
+    async fn txn(name: &str) -> Txn {
+        let gateway = gateway(name).await;
+        gateway
+            .new_txn(TxnId::new(Gateway::time()), None)
+            .await
+            .expect("txn")
+    }
+
+    // create a table of 10 rows `(id, group, value)` with the given auxiliary indices
+    async fn table(name: &str, indices: Vec<(&str, Vec<&str>)>) -> (Txn, TableIndex) {
+        let txn = txn(name).await;
+        let txn_id = *txn.id();
+
+        let int = NumberType::Int(IntType::I64);
+        let key: Vec<Column> = vec![("id", int).into()];
+        let values: Vec<Column> = vec![("group", int).into(), ("value", int).into()];
+
+        let indices = indices
+            .into_iter()
+            .map(|(name, columns)| (id(name), columns.into_iter().map(id).collect()));
+
+        let schema = TableSchema::new(IndexSchema::from((key, values)), indices);
+
+        let dir = txn.context().create_dir_unique(txn_id).await.expect("dir");
+        let table = TableIndex::create(&dir, schema, txn_id)
+            .await
+            .expect("table");
+
+        for i in 0..10u64 {
+            table
+                .insert(
+                    txn_id,
+                    vec![Value::from(i)],
+                    vec![Value::from(i % 3), Value::from(i * 10)],
+                )
+                .await
+                .expect("insert");
+        }
+
+        (txn, table)
+    }
+
+    fn id_range(start: u64, end: u64) -> Bounds {
+        let range = (Bound::In(Value::from(start)), Bound::Ex(Value::from(end)));
+        std::iter::once((id("id"), range)).collect()
+    }
+
+    #[tokio::test]
+    async fn test_delete_where_range() {
+        // every index supports the bounds, so none of them is deleted from row-by-row
+        let (txn, table) = table("delete_where_range", vec![("by_id", vec!["id", "value"])]).await;
+        let txn_id = *txn.id();
+        let btree = table.primary().btree().clone();
+        let keys_read = btree.keys_read();
+
+        table
+            .delete_where(txn_id, id_range(0, 5))
+            .await
+            .expect("delete");
+
+        assert_eq!(btree.keys_read(), keys_read);
+        assert_eq!(table.clone().count(txn_id).await.expect("count"), 5);
+        assert!(table.verify_indices(txn_id).await.expect("verify").is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_delete_where_by_row() {
+        // the "by_group" index doesn't support the bounds, so each deleted row is removed from it
+        let indices = vec![("by_id", vec!["id", "value"]), ("by_group", vec!["group"])];
+        let (txn, table) = table("delete_where_by_row", indices).await;
+        let txn_id = *txn.id();
+        let btree = table.primary().btree().clone();
+        let keys_read = btree.keys_read();
+
+        table
+            .delete_where(txn_id, id_range(0, 5))
+            .await
+            .expect("delete");
+
+        // each deleted row is read from the primary index
+        assert_eq!(btree.keys_read(), keys_read + 5);
+        assert_eq!(table.clone().count(txn_id).await.expect("count"), 5);
+        assert!(table.verify_indices(txn_id).await.expect("verify").is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_count_without_streaming() {
+        let (txn, table) = table("count", vec![]).await;
+        let txn_id = *txn.id();
+        let btree = table.primary().btree().clone();
+        let keys_read = btree.keys_read();
+
+        assert_eq!(table.clone().count(txn_id).await.expect("count"), 10);
+        assert_eq!(btree.keys_read(), keys_read);
+
+        // a selection of columns is counted by its source
+        let selection = table.clone().select(vec![id("value")]).expect("select");
+        assert_eq!(selection.count(txn_id).await.expect("count"), 10);
+        assert_eq!(btree.keys_read(), keys_read);
+
+        let rows = table.rows(txn_id).await.expect("rows");
+        let rows: Vec<Vec<Value>> = rows.try_collect().await.expect("rows");
+        assert_eq!(rows.len(), 10);
+        assert_eq!(btree.keys_read(), keys_read + 10);
+    }
+
+    #[tokio::test]
+    async fn test_exists() {
+        let (txn, table) = table("exists", vec![]).await;
+        let txn_id = *txn.id();
+        let btree = table.primary().btree().clone();
+        let keys_read = btree.keys_read();
+
+        // only the first row of a non-empty slice is read
+        let slice = table.clone().slice(id_range(2, 8)).expect("slice");
+        assert!(slice.exists(txn_id).await.expect("exists"));
+        assert_eq!(btree.keys_read(), keys_read + 1);
+
+        let slice = table.slice(id_range(20, 30)).expect("slice");
+        assert!(!slice.exists(txn_id).await.expect("exists"));
+        assert_eq!(btree.keys_read(), keys_read + 1);
+    }
+
+    #[tokio::test]
+    async fn test_truncate() {
+        let (txn, table) = table("truncate", vec![("by_group", vec!["group"])]).await;
+        let txn_id = *txn.id();
+        let btree = table.primary().btree().clone();
+        let keys_read = btree.keys_read();
+
+        table.truncate(txn_id).await.expect("truncate");
+
+        // no row is read in order to delete it
+        assert_eq!(btree.keys_read(), keys_read);
+        assert_eq!(table.clone().count(txn_id).await.expect("count"), 0);
+
+        let bounds: Bounds = std::iter::once((id("group"), Value::from(1u64))).collect();
+        let index = table.supporting_index(&bounds).expect("index");
+        assert!(index.btree().is_empty(txn_id).await.expect("is empty"));
+    }
+
+    #[tokio::test]
+    async fn test_merge_join() {
+        let (txn, table) = table("merge_join", vec![]).await;
+        let txn_id = *txn.id();
+        let btree = table.primary().btree().clone();
+        let ranges_read = btree.ranges_read();
+
+        let slice = table.slice(id_range(2, 8)).expect("slice");
+        let rows = slice.rows(txn_id).await.expect("rows");
+        let rows: Vec<Vec<Value>> = rows.try_collect().await.expect("rows");
+
+        let ids: Vec<Value> = rows.into_iter().map(|mut row| row.remove(0)).collect();
+        let expected: Vec<Value> = (2..8i64).map(|i| Value::Number(i.into())).collect();
+        assert_eq!(ids, expected);
+
+        // re-slicing the table once per key would read one range per row, plus the keys
+        assert_eq!(btree.ranges_read(), ranges_read + 1);
+    }
+
+    #[tokio::test]
+    async fn test_verify_indices() {
+        let (txn, table) = table("verify_indices", vec![("by_group", vec!["group"])]).await;
+        let txn_id = *txn.id();
+
+        assert!(table.verify_indices(txn_id).await.expect("verify").is_empty());
+
+        let bounds: Bounds = std::iter::once((id("group"), Value::from(1u64))).collect();
+        let index = table.supporting_index(&bounds).expect("index");
+        let int = |i: i64| Value::Number(i.into());
+
+        // remove the entry for row 4 from the auxiliary index, and add an entry with no row
+        index
+            .btree()
+            .delete(txn_id, tc_btree::Range::with_prefix(vec![int(1), int(4)]))
+            .await
+            .expect("delete");
+
+        index
+            .btree()
+            .insert(txn_id, vec![int(7), int(99)])
+            .await
+            .expect("insert");
+
+        let discrepancies = table.verify_indices(txn_id).await.expect("verify");
+        assert_eq!(discrepancies.len(), 2);
+
+        let by_group = id("by_group");
+
+        assert!(discrepancies.iter().any(|discrepancy| matches!(
+            discrepancy,
+            IndexDiscrepancy::Missing(name, key) if *name == by_group && key == &[int(1), int(4)]
+        )));
+
+        assert!(discrepancies.iter().any(|discrepancy| matches!(
+            discrepancy,
+            IndexDiscrepancy::Orphaned(name, key) if *name == by_group && key == &[int(7), int(99)]
+        )));
+    }
+}
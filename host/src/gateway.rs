@@ -12,6 +12,7 @@ use futures::try_join;
 use log::debug;
 
 use tc_error::*;
+use tc_transact::Transaction;
 use tc_value::{Link, LinkHost, LinkProtocol, Value};
 use tcgeneric::{NetworkTime, TCBoxTryFuture, TCPathBuf};
 
@@ -20,6 +21,8 @@ use crate::kernel::Kernel;
 use crate::state::State;
 use crate::txn::*;
 
+const TXN_EXPIRY_INTERVAL: Duration = Duration::from_millis(100);
+
 type Error = Box<dyn std::error::Error + Send + Sync>;
 
 /// Configuration for [`Gateway`].
@@ -142,6 +145,33 @@ impl Gateway {
         self.txn_server.new_txn(self.clone(), txn_id, token).await
     }
 
+    /// Run the transaction produced by `op`, retrying with a new [`Txn`] in case of a conflict,
+    /// up to a maximum of `attempts` times.
+    pub async fn retry_on_conflict<T, F, Fut>(
+        self: &Arc<Self>,
+        attempts: usize,
+        op: F,
+    ) -> TCResult<T>
+    where
+        F: Fn(Txn) -> Fut,
+        Fut: Future<Output = TCResult<T>>,
+    {
+        retry(attempts, || async {
+            let txn = self.new_txn(TxnId::new(Self::time()), None).await?;
+            let txn_id = *txn.id();
+
+            match op(txn).await {
+                Err(cause) if cause.is_conflict() => {
+                    debug!("transaction {} conflicted, rolling back", txn_id);
+                    self.kernel.finalize(&txn_id).await;
+                    Err(cause)
+                }
+                result => result,
+            }
+        })
+        .await
+    }
+
     /// Read a simple value.
     pub async fn fetch<T: destream::FromStream<Context = ()>>(
         &self,
@@ -215,13 +245,32 @@ impl Gateway {
     /// Start this `Gateway`'s server
     pub fn listen(self: Arc<Self>) -> Pin<Box<impl Future<Output = Result<(), Error>> + 'static>> {
         Box::pin(async move {
-            match try_join!(self.clone().http_listen(), self.clone().replicate()) {
+            match try_join!(
+                self.clone().http_listen(),
+                self.clone().replicate(),
+                self.clone().expire_txns()
+            ) {
                 Ok(_) => Ok(()),
                 Err(cause) => Err(cause),
             }
         })
     }
 
+    // roll back every transaction which outlives its auth token, in case its request was dropped
+    // (e.g. on timeout) before the transaction could be committed or rolled back
+    async fn expire_txns(self: Arc<Self>) -> Result<(), Error> {
+        let mut interval = tokio::time::interval(TXN_EXPIRY_INTERVAL);
+
+        loop {
+            interval.tick().await;
+
+            for txn_id in self.txn_server.expire().await {
+                debug!("roll back expired transaction {}", txn_id);
+                self.kernel.finalize(&txn_id).await;
+            }
+        }
+    }
+
     async fn replicate(self: Arc<Self>) -> Result<(), Error> {
         let result = async move {
             for cluster in self.kernel.hosted() {
@@ -270,3 +319,101 @@ impl Gateway {
         Box::pin(listener)
     }
 }
+
+async fn retry<T, F, Fut>(attempts: usize, attempt: F) -> TCResult<T>
+where
+    F: Fn() -> Fut,
+    Fut: Future<Output = TCResult<T>>,
+{
+    let mut attempted = 0;
+
+    loop {
+        attempted += 1;
+
+        match attempt().await {
+            Err(cause) if cause.is_conflict() && attempted < attempts => {
+                debug!(
+                    "retrying after conflict (attempt {} of {})",
+                    attempted, attempts
+                );
+            }
+            result => return result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use tc_transact::lock::TxnLock;
+    use tc_transact::Transact;
+
+    use crate::test_support::gateway;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_retry_on_conflict_txn() {
+        let gateway = gateway("retry_on_conflict").await;
+        let lock = TxnLock::new("counter", 0u64);
+        let attempted = Arc::new(AtomicUsize::new(0));
+
+        let txn_id = gateway
+            .retry_on_conflict(3, |txn| {
+                let lock = lock.clone();
+                let attempted = attempted.clone();
+
+                async move {
+                    let txn_id = *txn.id();
+
+                    if attempted.fetch_add(1, Ordering::SeqCst) == 0 {
+                        // a newer transaction reads the counter before this one can write to it
+                        let _guard = lock.read(TxnId::new(Gateway::time())).await?;
+                    }
+
+                    let mut counter = lock.write(txn_id).await?;
+                    *counter += 1;
+                    Ok(txn_id)
+                }
+            })
+            .await
+            .expect("retry on conflict");
+
+        assert_eq!(attempted.load(Ordering::SeqCst), 2);
+
+        lock.commit(&txn_id).await;
+        let counter = lock.read(TxnId::new(Gateway::time())).await.expect("read");
+        assert_eq!(*counter, 1);
+    }
+
+    #[tokio::test]
+    async fn test_retry_on_conflict() {
+        let attempted = AtomicUsize::new(0);
+
+        let result = retry(3, || async {
+            if attempted.fetch_add(1, Ordering::SeqCst) == 0 {
+                Err(TCError::conflict())
+            } else {
+                Ok(attempted.load(Ordering::SeqCst))
+            }
+        })
+        .await;
+
+        assert_eq!(result.expect("retry"), 2);
+    }
+
+    #[tokio::test]
+    async fn test_retry_gives_up() {
+        let attempted = AtomicUsize::new(0);
+
+        let result: TCResult<()> = retry(3, || async {
+            attempted.fetch_add(1, Ordering::SeqCst);
+            Err(TCError::conflict())
+        })
+        .await;
+
+        assert!(result.unwrap_err().is_conflict());
+        assert_eq!(attempted.load(Ordering::SeqCst), 3);
+    }
+}
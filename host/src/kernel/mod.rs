@@ -4,10 +4,11 @@ use std::convert::{TryFrom, TryInto};
 use std::fmt;
 use std::pin::Pin;
 
-use futures::future::Future;
+use futures::future::{join_all, Future};
 use log::debug;
 use safecast::*;
 use tc_error::*;
+use tc_transact::{Transact, TxnId};
 use tc_value::{Link, Value};
 use tcgeneric::*;
 
@@ -23,6 +24,9 @@ use hosted::Hosted;
 
 mod hosted;
 
+const HOST: PathLabel = path_label(&["host"]);
+const STATE: PathLabel = path_label(&["state"]);
+
 /// The host kernel, responsible for dispatching requests to the local host
 pub struct Kernel {
     hosted: Hosted,
@@ -43,6 +47,27 @@ impl Kernel {
         self.hosted.clusters()
     }
 
+    /// List the top-level namespaces served by this host, including every hosted [`Cluster`].
+    pub fn list(&self) -> Tuple<Link> {
+        let builtin = vec![
+            Link::from(HOST),
+            Link::from(STATE),
+            Link::from(hypothetical::PATH),
+        ];
+
+        let hosted = self
+            .hosted()
+            .map(|cluster| TCPathBuf::from(cluster.path().to_vec()))
+            .map(Link::from);
+
+        builtin.into_iter().chain(hosted).collect()
+    }
+
+    /// Discard any uncommitted state of the given transaction in every hosted [`Cluster`].
+    pub async fn finalize(&self, txn_id: &TxnId) {
+        join_all(self.hosted().map(|cluster| cluster.finalize(txn_id))).await;
+    }
+
     /// Route a GET request.
     pub async fn get(&self, txn: &Txn, path: &[PathSegment], key: Value) -> TCResult<State> {
         if path.is_empty() {
@@ -51,6 +76,11 @@ impl Kernel {
                 key,
                 TCPath::from(path)
             )))
+        } else if path == &HOST[..] {
+            key.expect_none()?;
+
+            let namespaces = self.list().into_iter().map(Value::from).collect();
+            Ok(State::from(Value::Tuple(namespaces)))
         } else if let Some(class) = ScalarType::from_path(path) {
             let err = format!("cannot cast into an instance of {} from {}", class, key);
             Scalar::from(key)
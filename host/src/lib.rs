@@ -34,3 +34,6 @@ pub mod scalar;
 pub mod state;
 pub mod stream;
 pub mod txn;
+
+#[cfg(test)]
+mod test_support;
@@ -418,6 +418,9 @@ impl Route for Chain {
             Some(Box::new(ChainHandler::from(self)))
         } else if path == &COPY[..] {
             Some(Box::new(CopyHandler::from(self)))
+        } else if !path.is_empty() && path[0].as_str() == "subject" {
+            // address the subject explicitly, e.g. if one of its own paths would be shadowed
+            Some(Box::new(AppendHandler::new(self, &path[1..])))
         } else {
             Some(Box::new(AppendHandler::new(self, path)))
         }
@@ -82,7 +82,7 @@ impl<'a> Handler<'a> for ClusterHandler<'a> {
                 if txn.is_leader(self.cluster.path()) {
                     self.cluster.distribute_commit(txn).await?;
                 } else {
-                    self.cluster.write_ahead(txn.id()).await;
+                    self.cluster.write_ahead(txn).await;
                     self.cluster.commit(txn.id()).await;
                 }
 
@@ -117,6 +117,32 @@ impl<'a> From<&'a Cluster> for ClusterHandler<'a> {
     }
 }
 
+struct ListHandler<'a> {
+    cluster: &'a Cluster,
+}
+
+impl<'a> Handler<'a> for ListHandler<'a> {
+    fn get<'b>(self: Box<Self>) -> Option<GetHandler<'a, 'b>>
+    where
+        'b: 'a,
+    {
+        Some(Box::new(|_txn, key| {
+            Box::pin(async move {
+                key.expect_none()?;
+
+                let names = self.cluster.list().into_iter().map(Value::from);
+                Ok(Value::from_iter(names).into())
+            })
+        }))
+    }
+}
+
+impl<'a> From<&'a Cluster> for ListHandler<'a> {
+    fn from(cluster: &'a Cluster) -> Self {
+        Self { cluster }
+    }
+}
+
 struct ReplicaHandler<'a> {
     cluster: &'a Cluster,
 }
@@ -188,6 +214,7 @@ impl Route for Cluster {
             class.route(&path[1..])
         } else if path.len() == 1 {
             match path[0].as_str() {
+                "list" => Some(Box::new(ListHandler::from(self))),
                 "replicas" => Some(Box::new(ReplicaHandler::from(self))),
                 _ => None,
             }
@@ -1,13 +1,13 @@
 use std::iter::FromIterator;
 
-use futures::{future, StreamExt, TryFutureExt, TryStreamExt};
+use futures::{future, stream, StreamExt, TryFutureExt, TryStreamExt};
 use safecast::{Match, TryCastFrom, TryCastInto};
 
 use tc_btree::{BTreeInstance, BTreeType, BTreeWrite, Range};
 use tc_error::*;
 use tc_transact::fs::Dir;
 use tc_transact::Transaction;
-use tc_value::Value;
+use tc_value::{Number, Value};
 use tcgeneric::{label, Map, PathSegment};
 
 use crate::collection::{BTree, BTreeFile, Collection};
@@ -187,6 +187,71 @@ impl<'a, T> From<&'a T> for BTreeHandler<'a, T> {
     }
 }
 
+struct CompareHandler<'a, T> {
+    btree: &'a T,
+}
+
+impl<'a, T: BTreeInstance> Handler<'a> for CompareHandler<'a, T> {
+    fn post<'b>(self: Box<Self>) -> Option<PostHandler<'a, 'b>>
+    where
+        'b: 'a,
+    {
+        Some(Box::new(|_txn, mut params| {
+            Box::pin(async move {
+                let left: Value = params.require(&label("l").into())?;
+                let right: Value = params.require(&label("r").into())?;
+                params.expect_empty()?;
+
+                let left = left.try_cast_into(|v| TCError::bad_request("invalid BTree key", v))?;
+                let left = self.btree.validate_key(left)?;
+
+                let right =
+                    right.try_cast_into(|v| TCError::bad_request("invalid BTree key", v))?;
+                let right = self.btree.validate_key(right)?;
+
+                let ordering = self.btree.collator().compare_slice(&left, &right);
+                Ok(State::from(Number::from(ordering as i64)))
+            })
+        }))
+    }
+}
+
+impl<'a, T> From<&'a T> for CompareHandler<'a, T> {
+    fn from(btree: &'a T) -> Self {
+        Self { btree }
+    }
+}
+
+struct ContainsHandler<'a, T> {
+    btree: &'a T,
+}
+
+impl<'a, T: BTreeInstance> Handler<'a> for ContainsHandler<'a, T> {
+    fn get<'b>(self: Box<Self>) -> Option<GetHandler<'a, 'b>>
+    where
+        'b: 'a,
+    {
+        Some(Box::new(|txn, key| {
+            Box::pin(async move {
+                let key = key.try_cast_into(|v| TCError::bad_request("invalid BTree key", v))?;
+
+                // a partial key is a prefix, not a key, so reject it
+                let key = self.btree.validate_key(key)?;
+
+                let slice = self.btree.clone().slice(Range::with_prefix(key), false)?;
+                let is_empty = slice.is_empty(*txn.id()).await?;
+                Ok(State::from(!is_empty))
+            })
+        }))
+    }
+}
+
+impl<'a, T> From<&'a T> for ContainsHandler<'a, T> {
+    fn from(btree: &'a T) -> Self {
+        Self { btree }
+    }
+}
+
 struct CountHandler<'a, T> {
     btree: &'a T,
 }
@@ -256,6 +321,184 @@ impl<'a, T> From<&'a T> for FirstHandler<'a, T> {
     }
 }
 
+struct InsertHandler<'a, T> {
+    btree: &'a T,
+}
+
+impl<'a, T: BTreeWrite> Handler<'a> for InsertHandler<'a, T> {
+    fn post<'b>(self: Box<Self>) -> Option<PostHandler<'a, 'b>>
+    where
+        'b: 'a,
+    {
+        Some(Box::new(|txn, mut params| {
+            Box::pin(async move {
+                let key: Value = params.require(&label("key").into())?;
+                let key = key.try_cast_into(|v| TCError::bad_request("invalid BTree key", v))?;
+                let unique: bool = params.or_default(&label("unique").into())?;
+                params.expect_empty()?;
+
+                if unique {
+                    let inserted = self.btree.insert_unique(*txn.id(), key).await?;
+                    Ok(State::from(inserted))
+                } else {
+                    self.btree.insert(*txn.id(), key).await?;
+                    Ok(State::default())
+                }
+            })
+        }))
+    }
+}
+
+impl<'a, T> From<&'a T> for InsertHandler<'a, T> {
+    fn from(btree: &'a T) -> Self {
+        Self { btree }
+    }
+}
+
+struct InsertValidatedHandler<'a, T> {
+    btree: &'a T,
+}
+
+impl<'a, T: BTreeWrite> Handler<'a> for InsertValidatedHandler<'a, T> {
+    fn post<'b>(self: Box<Self>) -> Option<PostHandler<'a, 'b>>
+    where
+        'b: 'a,
+    {
+        Some(Box::new(|txn, mut params| {
+            Box::pin(async move {
+                let keys: TCStream = params.require(&label("keys").into())?;
+                let max_keys: u64 = params.require(&label("max_keys").into())?;
+                params.expect_empty()?;
+
+                let keys = keys.into_stream(txn.clone()).await?;
+                let keys = keys
+                    .map(|r| {
+                        r.and_then(|state| {
+                            Value::try_cast_from(state, |s| {
+                                TCError::bad_request("invalid BTree key", s)
+                            })
+                        })
+                    })
+                    .map(|r| {
+                        r.and_then(|value| {
+                            value.try_cast_into(|v| TCError::bad_request("invalid BTree key", v))
+                        })
+                    });
+
+                self.btree
+                    .try_insert_validated_from(*txn.id(), keys, max_keys as usize)
+                    .await?;
+
+                Ok(State::default())
+            })
+        }))
+    }
+}
+
+impl<'a, T> From<&'a T> for InsertValidatedHandler<'a, T> {
+    fn from(btree: &'a T) -> Self {
+        Self { btree }
+    }
+}
+
+struct MergeInsertHandler<'a, T> {
+    btree: &'a T,
+}
+
+impl<'a, T: BTreeWrite> Handler<'a> for MergeInsertHandler<'a, T> {
+    fn post<'b>(self: Box<Self>) -> Option<PostHandler<'a, 'b>>
+    where
+        'b: 'a,
+    {
+        Some(Box::new(|txn, mut params| {
+            Box::pin(async move {
+                let keys: State = params.require(&label("keys").into())?;
+                params.expect_empty()?;
+
+                match keys {
+                    State::Collection(Collection::BTree(source)) => {
+                        let keys = source.keys(*txn.id()).await?;
+                        self.btree.merge_insert_from(*txn.id(), keys).await?;
+                    }
+                    State::Tuple(keys) => {
+                        let keys = keys.into_iter().map(|key| {
+                            Value::try_cast_from(key, |s| {
+                                TCError::bad_request("invalid BTree key", s)
+                            })
+                            .and_then(|key| {
+                                key.try_cast_into(|v| TCError::bad_request("invalid BTree key", v))
+                            })
+                        });
+
+                        self.btree
+                            .merge_insert_from(*txn.id(), stream::iter(keys))
+                            .await?;
+                    }
+                    other => {
+                        return Err(TCError::bad_request(
+                            "merge_insert requires a BTree or a Tuple of sorted keys, not",
+                            other,
+                        ))
+                    }
+                }
+
+                Ok(State::default())
+            })
+        }))
+    }
+}
+
+impl<'a, T> From<&'a T> for MergeInsertHandler<'a, T> {
+    fn from(btree: &'a T) -> Self {
+        Self { btree }
+    }
+}
+
+struct PopHandler<'a, T> {
+    btree: &'a T,
+}
+
+impl<'a, T: BTreeWrite> Handler<'a> for PopHandler<'a, T> {
+    fn post<'b>(self: Box<Self>) -> Option<PostHandler<'a, 'b>>
+    where
+        'b: 'a,
+    {
+        Some(Box::new(|txn, mut params| {
+            Box::pin(async move {
+                let range = params.or_default(&label("where").into())?;
+                let range = cast_into_range(range)?;
+                let limit: Value = params.or_default(&label("limit").into())?;
+                params.expect_empty()?;
+
+                let limit = if limit.is_none() {
+                    None
+                } else {
+                    let limit = u64::try_cast_from(limit, |v| {
+                        TCError::bad_request("invalid limit for BTree::pop", v)
+                    })?;
+
+                    Some(limit as usize)
+                };
+
+                let keys = self.btree.pop(*txn.id(), range, limit).await?;
+                let keys = keys
+                    .into_iter()
+                    .map(|key| Value::Tuple(key.into()))
+                    .map(State::from)
+                    .collect();
+
+                Ok(State::Tuple(keys))
+            })
+        }))
+    }
+}
+
+impl<'a, T> From<&'a T> for PopHandler<'a, T> {
+    fn from(btree: &'a T) -> Self {
+        Self { btree }
+    }
+}
+
 struct ReverseHandler<T> {
     btree: T,
 }
@@ -348,9 +591,15 @@ where
         Some(Box::new(BTreeHandler::from(btree)))
     } else if path.len() == 1 {
         match path[0].as_str() {
+            "compare" => Some(Box::new(CompareHandler::from(btree))),
+            "contains" => Some(Box::new(ContainsHandler::from(btree))),
             "count" => Some(Box::new(CountHandler::from(btree))),
             "first" => Some(Box::new(FirstHandler::from(btree))),
+            "insert" => Some(Box::new(InsertHandler::from(btree))),
+            "insert_validated" => Some(Box::new(InsertValidatedHandler::from(btree))),
             "keys" => Some(Box::new(StreamHandler::from(btree.clone()))),
+            "merge_insert" => Some(Box::new(MergeInsertHandler::from(btree))),
+            "pop" => Some(Box::new(PopHandler::from(btree))),
             "reverse" => Some(Box::new(ReverseHandler::from(btree.clone()))),
             _ => None,
         }
@@ -1,12 +1,15 @@
-use safecast::CastInto;
+use safecast::{CastInto, TryCastInto};
 
 use tc_btree::BTreeInstance;
+use tc_error::*;
 use tc_table::TableInstance;
-use tc_value::Value;
-use tcgeneric::{PathSegment, Tuple};
+use tc_value::{TCString, Value};
+use tcgeneric::{label, Label, Map, PathSegment, Tuple};
 
 use crate::collection::{Collection, CollectionType};
 use crate::route::GetHandler;
+use crate::state::State;
+use crate::stream::{ExportFormat, TCStream};
 
 use super::{Handler, Route};
 
@@ -16,6 +19,8 @@ mod table;
 #[cfg(feature = "tensor")]
 mod tensor;
 
+const CONCURRENCY: Label = label("concurrency");
+
 impl Route for CollectionType {
     fn route<'a>(&'a self, path: &'a [PathSegment]) -> Option<Box<dyn Handler<'a> + 'a>> {
         match self {
@@ -27,6 +32,33 @@ impl Route for CollectionType {
     }
 }
 
+struct ExportHandler<'a> {
+    collection: &'a Collection,
+}
+
+impl<'a> Handler<'a> for ExportHandler<'a> {
+    fn get<'b>(self: Box<Self>) -> Option<GetHandler<'a, 'b>>
+    where
+        'b: 'a,
+    {
+        Some(Box::new(|_txn, key| {
+            Box::pin(async move {
+                let format: TCString =
+                    key.try_cast_into(|v| TCError::bad_request("invalid export format", v))?;
+
+                let format: ExportFormat = format.parse()?;
+                Ok(TCStream::export(self.collection.clone(), format).into())
+            })
+        }))
+    }
+}
+
+impl<'a> From<&'a Collection> for ExportHandler<'a> {
+    fn from(collection: &'a Collection) -> Self {
+        Self { collection }
+    }
+}
+
 struct SchemaHandler<'a> {
     collection: &'a Collection,
 }
@@ -81,6 +113,7 @@ impl Route for Collection {
 
         if path.len() == 1 {
             match path[0].as_str() {
+                "export" => Some(Box::new(ExportHandler::from(self))),
                 "schema" => Some(Box::new(SchemaHandler::from(self))),
                 _ => None,
             }
@@ -107,3 +140,17 @@ impl Route for Static {
         }
     }
 }
+
+// the number of concurrent writes to make when copying a stream into a collection
+fn concurrency(params: &mut Map<State>) -> TCResult<usize> {
+    let concurrency: u64 = params.option(&CONCURRENCY.into(), || num_cpus::get() as u64)?;
+
+    if concurrency == 0 {
+        Err(TCError::bad_request(
+            "concurrency must be a positive integer, not",
+            concurrency,
+        ))
+    } else {
+        Ok(concurrency as usize)
+    }
+}
@@ -1,22 +1,34 @@
-use futures::{future, StreamExt, TryFutureExt, TryStreamExt};
+use std::convert::TryInto;
+use std::iter::FromIterator;
+
+use futures::{future, Stream, StreamExt, TryFutureExt, TryStreamExt};
 use log::debug;
 use safecast::*;
 
 use tc_error::*;
 use tc_table::{
-    Bounds, ColumnBound, Key, TableInstance, TableOrder, TableRead, TableSlice, TableStream,
-    TableType, TableWrite,
+    Bounds, Column, IndexDiscrepancy, IndexSchema, Key, TableInstance, TableOrder, TableRead,
+    TableSlice, TableStream, TableType, TableWrite, Values,
 };
+#[cfg(feature = "tensor")]
+use tc_tensor::{promote, TensorType, PER_BLOCK};
 use tc_transact::fs::Dir;
 use tc_transact::Transaction;
-use tc_value::{Bound, Value};
-use tcgeneric::{label, Id, Map, PathSegment};
+use tc_value::{
+    FloatType, Number, NumberClass, NumberInstance, NumberType, TCString, UIntType, Value,
+    ValueType,
+};
+use tcgeneric::{label, Id, Map, PathSegment, TCBoxTryStream, TCPath, Tuple};
 
-use crate::collection::{Collection, Table, TableIndex};
+use crate::collection::{Aggregate, Collection, Merged, Table, TableIndex};
+#[cfg(feature = "tensor")]
+use crate::collection::{DenseTensor, DenseTensorFile, Tensor};
 use crate::route::{DeleteHandler, GetHandler, Handler, PostHandler, PutHandler, Route};
-use crate::scalar::Scalar;
+use crate::scalar::{OpRefType as ORT, Scalar};
 use crate::state::State;
-use crate::stream::{Source, TCStream};
+use crate::stream::{csv, Source, TCStream};
+
+use super::concurrency;
 
 impl Route for TableType {
     fn route<'a>(&'a self, path: &'a [PathSegment]) -> Option<Box<dyn Handler<'a> + 'a>> {
@@ -28,6 +40,41 @@ impl Route for TableType {
     }
 }
 
+struct AddColumnHandler<'a> {
+    table: &'a TableIndex,
+}
+
+impl<'a> Handler<'a> for AddColumnHandler<'a> {
+    fn post<'b>(self: Box<Self>) -> Option<PostHandler<'a, 'b>>
+    where
+        'b: 'a,
+    {
+        Some(Box::new(|txn, mut params| {
+            Box::pin(async move {
+                let column: Value = params.require(&label("column").into())?;
+                let column = Column::try_cast_from(column, |v| {
+                    TCError::bad_request("invalid column definition", v)
+                })?;
+
+                let default: Value = params.or_default(&label("default").into())?;
+                params.expect_empty()?;
+
+                self.table
+                    .add_column(txn, column, default)
+                    .map_ok(Collection::from)
+                    .map_ok(State::from)
+                    .await
+            })
+        }))
+    }
+}
+
+impl<'a> From<&'a TableIndex> for AddColumnHandler<'a> {
+    fn from(table: &'a TableIndex) -> Self {
+        Self { table }
+    }
+}
+
 struct CopyHandler;
 
 impl<'a> Handler<'a> for CopyHandler {
@@ -51,23 +98,11 @@ impl<'a> Handler<'a> for CopyHandler {
                 let table = TableIndex::create(&dir, schema, *txn.id()).await?;
 
                 let rows = source.into_stream(txn.clone()).await?;
-                rows.map(|r| {
-                    r.and_then(|state| {
-                        Value::try_cast_from(state, |s| {
-                            TCError::bad_request("invalid Table row", s)
-                        })
-                    })
-                })
-                .map(|r| {
-                    r.and_then(|value| {
-                        value.try_cast_into(|v| TCError::bad_request("invalid Table row", v))
-                    })
-                })
-                .map(|r| r.and_then(|row| table.schema().primary().key_values_from_tuple(row)))
-                .map_ok(|(key, values)| table.upsert(txn_id, key, values))
-                .try_buffer_unordered(num_cpus::get())
-                .try_fold((), |(), ()| future::ready(Ok(())))
-                .await?;
+                table_rows(rows, table.primary_schema())
+                    .map_ok(|(key, values)| table.upsert(txn_id, key, values))
+                    .try_buffer_unordered(num_cpus::get())
+                    .try_fold((), |(), ()| future::ready(Ok(())))
+                    .await?;
 
                 Ok(State::Collection(table.into()))
             })
@@ -98,6 +133,40 @@ impl<'a> Handler<'a> for CreateHandler {
     }
 }
 
+struct CreateIndexHandler<'a> {
+    table: &'a TableIndex,
+}
+
+impl<'a> Handler<'a> for CreateIndexHandler<'a> {
+    fn post<'b>(self: Box<Self>) -> Option<PostHandler<'a, 'b>>
+    where
+        'b: 'a,
+    {
+        Some(Box::new(|txn, mut params| {
+            Box::pin(async move {
+                let name: Id = params.require(&label("name").into())?;
+                let columns: Value = params.require(&label("columns").into())?;
+                let columns = columns
+                    .try_cast_into(|v| TCError::bad_request("invalid column list to index", v))?;
+
+                params.expect_empty()?;
+
+                self.table
+                    .create_index(txn, name, columns)
+                    .map_ok(Collection::from)
+                    .map_ok(State::from)
+                    .await
+            })
+        }))
+    }
+}
+
+impl<'a> From<&'a TableIndex> for CreateIndexHandler<'a> {
+    fn from(table: &'a TableIndex) -> Self {
+        Self { table }
+    }
+}
+
 struct ContainsHandler<'a, T> {
     table: &'a T,
 }
@@ -140,7 +209,7 @@ where
                 if key.is_none() {
                     self.table.count(*txn.id()).map_ok(State::from).await
                 } else {
-                    let bounds = cast_into_bounds(Scalar::Value(key))?;
+                    let bounds = cast_into_bounds(Scalar::Value(key), &self.table)?;
                     let slice = self.table.slice(bounds)?;
                     slice.count(*txn.id()).map_ok(State::from).await
                 }
@@ -155,215 +224,288 @@ impl<T> From<T> for CountHandler<T> {
     }
 }
 
-struct LimitHandler<T> {
+struct CountByHandler<T> {
     table: T,
 }
 
-impl<'a, T: TableStream + 'a> Handler<'a> for LimitHandler<T>
+impl<'a, T: 'a> Handler<'a> for CountByHandler<T>
 where
-    Table: From<T::Limit>,
+    Table: From<T>,
 {
     fn get<'b>(self: Box<Self>) -> Option<GetHandler<'a, 'b>>
     where
         'b: 'a,
     {
-        Some(Box::new(|_txn, key| {
+        Some(Box::new(|txn, key| {
             Box::pin(async move {
-                let limit = key.try_cast_into(|v| {
-                    TCError::bad_request("limit must be a positive integer, not", v)
-                })?;
+                let columns: Vec<Id> = match key {
+                    Value::Tuple(columns) => columns.try_cast_into(|v| {
+                        TCError::bad_request("invalid column list to count by", v)
+                    })?,
+                    column => vec![column.try_cast_into(|v| {
+                        TCError::bad_request("invalid column to count by", v)
+                    })?],
+                };
 
-                Ok(Collection::Table(self.table.limit(limit).into()).into())
+                if columns.is_empty() {
+                    return Err(TCError::bad_request(
+                        "count_by requires at least one column, found",
+                        Value::from_iter(columns),
+                    ));
+                }
+
+                let count = label("count");
+                if columns.iter().any(|name| name == &count) {
+                    return Err(TCError::bad_request(
+                        "count_by cannot group by a column with the reserved name",
+                        count,
+                    ));
+                }
+
+                let table = Table::from(self.table);
+                let schema = table.schema();
+                let mut key = Vec::with_capacity(columns.len());
+                for name in &columns {
+                    let column = schema
+                        .primary()
+                        .columns()
+                        .into_iter()
+                        .find(|col| col.name() == name)
+                        .ok_or_else(|| TCError::not_found(format!("Column {}", name)))?;
+
+                    key.push(column);
+                }
+
+                let values = vec![Column::from((
+                    count.into(),
+                    NumberType::UInt(UIntType::U64),
+                ))];
+                let schema = tc_table::TableSchema::from(IndexSchema::from((key, values)));
+
+                let txn_id = *txn.id();
+                let dir = txn.context().create_dir_unique(txn_id).await?;
+                let counts = TableIndex::create(&dir, schema, txn_id).await?;
+
+                let groups = Aggregate::new(table, columns)?.counts(txn_id).await?;
+                groups
+                    .try_for_each(|(group, count)| {
+                        counts.upsert(txn_id, group, vec![Value::from(count)])
+                    })
+                    .await?;
+
+                Ok(State::Collection(counts.into()))
             })
         }))
     }
 }
 
-impl<T> From<T> for LimitHandler<T> {
+impl<T> From<T> for CountByHandler<T> {
     fn from(table: T) -> Self {
         Self { table }
     }
 }
 
-struct LoadHandler;
-
-impl<'a> Handler<'a> for LoadHandler {
-    // TODO
-}
-
-struct OrderHandler<T> {
-    table: T,
+struct DropColumnHandler<'a> {
+    table: &'a TableIndex,
 }
 
-impl<'a, T: TableOrder + 'a> Handler<'a> for OrderHandler<T>
-where
-    Table: From<T::OrderBy>,
-{
-    fn get<'b>(self: Box<Self>) -> Option<GetHandler<'a, 'b>>
+impl<'a> Handler<'a> for DropColumnHandler<'a> {
+    fn post<'b>(self: Box<Self>) -> Option<PostHandler<'a, 'b>>
     where
         'b: 'a,
     {
-        Some(Box::new(|_txn, key| {
+        Some(Box::new(|txn, mut params| {
             Box::pin(async move {
-                let ordered = if key.matches::<(Vec<Id>, bool)>() {
-                    let (order, reverse) = key.opt_cast_into().unwrap();
-                    self.table.order_by(order, reverse)?
-                } else if key.matches::<Vec<Id>>() {
-                    let order = key.opt_cast_into().unwrap();
-                    self.table.order_by(order, false)?
-                } else {
-                    return Err(TCError::bad_request("invalid column list to order by", key));
-                };
+                let name: Id = params.require(&label("name").into())?;
+                params.expect_empty()?;
 
-                Ok(Collection::Table(ordered.into()).into())
+                self.table
+                    .drop_column(txn, name)
+                    .map_ok(Collection::from)
+                    .map_ok(State::from)
+                    .await
             })
         }))
     }
 }
 
-impl<T> From<T> for OrderHandler<T> {
-    fn from(table: T) -> Self {
+impl<'a> From<&'a TableIndex> for DropColumnHandler<'a> {
+    fn from(table: &'a TableIndex) -> Self {
         Self { table }
     }
 }
 
-struct TableHandler<'a, T> {
-    table: &'a T,
+struct DeleteWhereHandler<'a> {
+    table: &'a TableIndex,
 }
 
-impl<'a, T> Handler<'a> for TableHandler<'a, T>
-where
-    T: TableRead + TableSlice + TableWrite + Clone + 'a,
-    Table: From<T>,
-    Table: From<T::Slice>,
-{
-    fn get<'b>(self: Box<Self>) -> Option<GetHandler<'a, 'b>>
+impl<'a> Handler<'a> for DeleteWhereHandler<'a> {
+    fn post<'b>(self: Box<Self>) -> Option<PostHandler<'a, 'b>>
     where
         'b: 'a,
     {
-        Some(Box::new(|txn, key| {
+        Some(Box::new(|txn, mut params| {
             Box::pin(async move {
-                if key.is_some() {
-                    let key = primary_key(key.clone(), self.table)?;
-                    self.table
-                        .read(txn.id(), &key)
-                        .map_ok(Value::from)
-                        .map_ok(State::from)
-                        .await
-                } else {
-                    Ok(Collection::Table(self.table.clone().into()).into())
-                }
+                let bounds: Scalar = params.require(&label("bounds").into())?;
+                params.expect_empty()?;
+
+                let bounds = cast_into_bounds(bounds, self.table)?;
+                self.table.delete_where(*txn.id(), bounds).await?;
+                Ok(State::default())
             })
         }))
     }
+}
 
-    fn put<'b>(self: Box<Self>) -> Option<PutHandler<'a, 'b>>
+impl<'a> From<&'a TableIndex> for DeleteWhereHandler<'a> {
+    fn from(table: &'a TableIndex) -> Self {
+        Self { table }
+    }
+}
+
+enum ExplainHandler<'a> {
+    Slice(&'a TableIndex, Bounds),
+    Merge(&'a Merged),
+}
+
+impl<'a> Handler<'a> for ExplainHandler<'a> {
+    fn get<'b>(self: Box<Self>) -> Option<GetHandler<'a, 'b>>
     where
         'b: 'a,
     {
-        Some(Box::new(|txn, key, values| {
+        Some(Box::new(|_txn, key| {
             Box::pin(async move {
-                debug!("Table PUT {:?} <- {:?}", key, values);
-
-                let key = primary_key(key, self.table)?;
+                key.expect_none()?;
 
-                if values.is_map() {
-                    let values =
-                        values.try_into_map(|s| TCError::bad_request("invalid row values", s))?;
+                // without any bounds, every row of the index must be read
+                let (indices, full_scan) = match *self {
+                    Self::Slice(table, bounds) => {
+                        (vec![table.explain(&bounds)?], bounds.is_empty())
+                    }
+                    Self::Merge(merged) => (merged.explain()?, merged.bounds().is_empty()),
+                };
 
-                    let values = values
-                        .into_iter()
-                        .map(|(id, state)| {
-                            Value::try_cast_from(state, |s| {
-                                TCError::bad_request("invalid column value", s)
-                            })
-                            .map(|value| (id, value))
-                        })
-                        .collect::<TCResult<Map<Value>>>()?;
+                let indices = indices
+                    .into_iter()
+                    .map(|(index, columns)| {
+                        let index = vec![
+                            (label("index").into(), State::from(Value::from(index))),
+                            (label("columns").into(), State::from(Value::from_iter(columns))),
+                        ];
 
-                    self.table.update(*txn.id(), key, values).await
-                } else if values.is_tuple() {
-                    let values =
-                        values.try_into_tuple(|s| TCError::bad_request("invalid row values", s))?;
+                        State::Map(index.into_iter().collect())
+                    })
+                    .collect::<Vec<State>>();
 
-                    let values = values
-                        .into_iter()
-                        .map(|state| {
-                            state.try_cast_into(|s| TCError::bad_request("invalid column value", s))
-                        })
-                        .collect::<TCResult<Vec<Value>>>()?;
+                let explain = vec![
+                    (label("indices").into(), State::Tuple(indices.into())),
+                    (label("full_scan").into(), State::from(Value::from(full_scan))),
+                ];
 
-                    self.table.upsert(*txn.id(), key, values).await
-                } else {
-                    Err(TCError::bad_request("invalid row values", values))
-                }
+                Ok(State::Map(explain.into_iter().collect()))
             })
         }))
     }
+}
+
+struct DropIndexHandler<'a> {
+    table: &'a TableIndex,
+}
 
+impl<'a> Handler<'a> for DropIndexHandler<'a> {
     fn post<'b>(self: Box<Self>) -> Option<PostHandler<'a, 'b>>
     where
         'b: 'a,
     {
-        Some(Box::new(|_txn, mut params| {
+        Some(Box::new(|txn, mut params| {
             Box::pin(async move {
-                let bounds: Scalar = params.require(&label("bounds").into())?;
-                let bounds = cast_into_bounds(bounds)?;
+                let name: Id = params.require(&label("name").into())?;
+                params.expect_empty()?;
+
                 self.table
-                    .clone()
-                    .slice(bounds)
-                    .map(|slice| Table::from(slice))
-                    .map(Collection::Table)
-                    .map(State::Collection)
+                    .drop_index(*txn.id(), name)
+                    .map_ok(Collection::from)
+                    .map_ok(State::from)
+                    .await
             })
         }))
     }
+}
 
-    fn delete<'b>(self: Box<Self>) -> Option<DeleteHandler<'a, 'b>>
+impl<'a> From<&'a TableIndex> for DropIndexHandler<'a> {
+    fn from(table: &'a TableIndex) -> Self {
+        Self { table }
+    }
+}
+
+struct ExistsHandler<T> {
+    table: T,
+}
+
+impl<'a, T: TableSlice + TableStream + 'a> Handler<'a> for ExistsHandler<T>
+where
+    T::Slice: TableStream,
+{
+    fn get<'b>(self: Box<Self>) -> Option<GetHandler<'a, 'b>>
     where
         'b: 'a,
     {
         Some(Box::new(|txn, key| {
             Box::pin(async move {
-                let row = primary_key(key, self.table)?;
-                self.table.delete(*txn.id(), row).await
+                if key.is_none() {
+                    self.table.exists(*txn.id()).map_ok(State::from).await
+                } else {
+                    let bounds = cast_into_bounds(Scalar::Value(key), &self.table)?;
+                    let slice = self.table.slice(bounds)?;
+                    slice.exists(*txn.id()).map_ok(State::from).await
+                }
             })
         }))
     }
 }
 
-struct SchemaHandler<'a, T> {
-    table: &'a T,
-    schema: fn(&'a T) -> Value,
+impl<T> From<T> for ExistsHandler<T> {
+    fn from(table: T) -> Self {
+        Self { table }
+    }
 }
 
-impl<'a, T> SchemaHandler<'a, T> {
-    fn new(table: &'a T, schema: fn(&'a T) -> Value) -> Self {
-        Self { table, schema }
-    }
+struct FirstHandler<T> {
+    table: T,
 }
 
-impl<'a, T: TableInstance> Handler<'a> for SchemaHandler<'a, T> {
+impl<'a, T: 'a> Handler<'a> for FirstHandler<T>
+where
+    Table: From<T>,
+{
     fn get<'b>(self: Box<Self>) -> Option<GetHandler<'a, 'b>>
     where
         'b: 'a,
     {
-        Some(Box::new(|_txn, key| {
+        Some(Box::new(|txn, key| {
             Box::pin(async move {
                 key.expect_none()?;
-                Ok(State::from((self.schema)(self.table)))
+
+                let row = Table::from(self.table).first(*txn.id()).await?;
+                Ok(Value::Tuple(row.into()).into())
             })
         }))
     }
 }
 
-struct SelectHandler<T> {
+impl<T> From<T> for FirstHandler<T> {
+    fn from(table: T) -> Self {
+        Self { table }
+    }
+}
+
+struct GroupByHandler<T> {
     table: T,
 }
 
-impl<'a, T: TableStream + 'a> Handler<'a> for SelectHandler<T>
+impl<'a, T: 'a> Handler<'a> for GroupByHandler<T>
 where
-    Table: From<T::Selection>,
+    Table: From<T>,
 {
     fn get<'b>(self: Box<Self>) -> Option<GetHandler<'a, 'b>>
     where
@@ -371,73 +513,1088 @@ where
     {
         Some(Box::new(|_txn, key| {
             Box::pin(async move {
-                let columns =
-                    key.try_cast_into(|v| TCError::bad_request("invalid column list", v))?;
+                let columns = key.try_cast_into(|v| {
+                    TCError::bad_request("invalid column list to group by", v)
+                })?;
 
-                Ok(Collection::Table(self.table.select(columns)?.into()).into())
+                Table::from(self.table)
+                    .group_by(columns)
+                    .map(Collection::Table)
+                    .map(State::Collection)
             })
         }))
     }
 }
 
-impl<T> From<T> for SelectHandler<T> {
+impl<T> From<T> for GroupByHandler<T> {
     fn from(table: T) -> Self {
         Self { table }
     }
 }
 
-struct StreamHandler<T> {
-    table: T,
+struct InsertHandler<'a, T> {
+    table: &'a T,
 }
 
-impl<'a, T: TableSlice + 'a> Handler<'a> for StreamHandler<T>
+impl<'a, T: TableWrite + 'a> Handler<'a> for InsertHandler<'a, T> {
+    fn post<'b>(self: Box<Self>) -> Option<PostHandler<'a, 'b>>
+    where
+        'b: 'a,
+    {
+        Some(Box::new(|txn, mut params| {
+            Box::pin(async move {
+                let source: TCStream = params.require(&label("rows").into())?;
+                let concurrency = concurrency(&mut params)?;
+                params.expect_empty()?;
+
+                let txn_id = *txn.id();
+
+                // try_buffer_unordered only polls the source when there is capacity,
+                // so a fast producer can't get more than `concurrency` rows ahead of the inserts
+                let rows = source.into_stream(txn.clone()).await?;
+                table_rows(rows, self.table.primary_schema())
+                    .map_ok(|(key, values)| self.table.insert(txn_id, key, values))
+                    .try_buffer_unordered(concurrency)
+                    .try_fold((), |(), ()| future::ready(Ok(())))
+                    .map_ok(State::from)
+                    .await
+            })
+        }))
+    }
+}
+
+impl<'a, T> From<&'a T> for InsertHandler<'a, T> {
+    fn from(table: &'a T) -> Self {
+        Self { table }
+    }
+}
+
+struct LastHandler<T> {
+    table: T,
+}
+
+impl<'a, T: 'a> Handler<'a> for LastHandler<T>
+where
+    Table: From<T>,
+{
+    fn get<'b>(self: Box<Self>) -> Option<GetHandler<'a, 'b>>
+    where
+        'b: 'a,
+    {
+        Some(Box::new(|txn, key| {
+            Box::pin(async move {
+                key.expect_none()?;
+
+                let row = Table::from(self.table).last(*txn.id()).await?;
+                Ok(Value::Tuple(row.into()).into())
+            })
+        }))
+    }
+}
+
+impl<T> From<T> for LastHandler<T> {
+    fn from(table: T) -> Self {
+        Self { table }
+    }
+}
+
+struct LimitHandler<T> {
+    table: T,
+}
+
+impl<'a, T: TableStream + 'a> Handler<'a> for LimitHandler<T>
+where
+    Table: From<T::Limit>,
+{
+    fn get<'b>(self: Box<Self>) -> Option<GetHandler<'a, 'b>>
+    where
+        'b: 'a,
+    {
+        Some(Box::new(|_txn, key| {
+            Box::pin(async move {
+                let limit = key.try_cast_into(|v| {
+                    TCError::bad_request("limit must be a positive integer, not", v)
+                })?;
+
+                Ok(Collection::Table(self.table.limit(limit).into()).into())
+            })
+        }))
+    }
+}
+
+impl<T> From<T> for LimitHandler<T> {
+    fn from(table: T) -> Self {
+        Self { table }
+    }
+}
+
+// create a new Table from a stream of CSV data whose first record names its columns,
+// returning the new `table` and the number of malformed rows skipped as `errors`
+struct LoadHandler;
+
+impl LoadHandler {
+    fn parse_row(columns: &[Column], record: &str, delimiter: char) -> TCResult<Tuple<Value>> {
+        let cells = csv::parse_record(record, delimiter)?;
+        if cells.len() != columns.len() {
+            return Err(TCError::bad_request(
+                format!("expected {} columns but found", columns.len()),
+                cells.len(),
+            ));
+        }
+
+        columns
+            .iter()
+            .zip(cells)
+            .map(|(column, cell)| column.cast(cell))
+            .collect()
+    }
+
+    fn validate_header(columns: &[Column], record: &str, delimiter: char) -> TCResult<()> {
+        let names = csv::parse_record(record, delimiter)?;
+        let valid = names.len() == columns.len()
+            && columns
+                .iter()
+                .zip(&names)
+                .all(|(column, name)| match name {
+                    Value::String(name) => column.name.as_str() == name.as_str(),
+                    _ => false,
+                });
+
+        if valid {
+            Ok(())
+        } else {
+            Err(TCError::bad_request(
+                "expected the column names",
+                Value::from_iter(columns.iter().map(|col| Value::from(col.name.clone()))),
+            ))
+        }
+    }
+}
+
+impl<'a> Handler<'a> for LoadHandler {
+    fn post<'b>(self: Box<Self>) -> Option<PostHandler<'a, 'b>>
+    where
+        'b: 'a,
+    {
+        Some(Box::new(|txn, mut params| {
+            Box::pin(async move {
+                let schema: Value = params.require(&label("schema").into())?;
+                let schema = tc_table::TableSchema::try_cast_from(schema, |v| {
+                    TCError::bad_request("invalid Table schema", v)
+                })?;
+
+                let source: TCStream = params.require(&label("source").into())?;
+
+                let delimiter: Value = params.or_default(&label("delimiter").into())?;
+                let delimiter = if delimiter.is_none() {
+                    ','
+                } else {
+                    let delimiter: TCString = delimiter
+                        .try_cast_into(|v| TCError::bad_request("invalid CSV delimiter", v))?;
+
+                    let mut chars = delimiter.chars();
+                    match (chars.next(), chars.next()) {
+                        (Some(delimiter), None) => delimiter,
+                        _ => return Err(TCError::bad_request("invalid CSV delimiter", delimiter)),
+                    }
+                };
+
+                let max_errors: Value = params.or_default(&label("max_errors").into())?;
+                let max_errors: u64 = if max_errors.is_none() {
+                    0
+                } else {
+                    max_errors.try_cast_into(|v| {
+                        TCError::bad_request("max_errors must be a positive integer, not", v)
+                    })?
+                };
+
+                params.expect_empty()?;
+
+                let txn_id = *txn.id();
+                let dir = txn.context().create_dir_unique(txn_id).await?;
+                let table = TableIndex::create(&dir, schema, txn_id).await?;
+                let columns = table.primary_schema().columns();
+
+                let mut chunks = source.into_stream(txn.clone()).await?;
+                let mut buffer = String::new();
+                let mut line_num = 0u64;
+                let mut errors = 0u64;
+                let mut expect_header = true;
+
+                loop {
+                    let chunk = chunks.try_next().await?;
+                    let eof = chunk.is_none();
+
+                    if let Some(chunk) = chunk {
+                        let chunk = Value::try_cast_from(chunk, |s| {
+                            TCError::bad_request("expected a chunk of CSV data, not", s)
+                        })?;
+
+                        match chunk {
+                            Value::Bytes(bytes) => {
+                                let chunk = String::from_utf8(bytes.to_vec()).map_err(|cause| {
+                                    TCError::bad_request("invalid UTF8 in CSV data", cause)
+                                })?;
+
+                                buffer.push_str(&chunk);
+                            }
+                            Value::String(chunk) => buffer.push_str(&chunk),
+                            other => {
+                                return Err(TCError::bad_request(
+                                    "expected a chunk of CSV data, not",
+                                    other,
+                                ))
+                            }
+                        }
+                    }
+
+                    let records = csv::take_records(&mut buffer, eof);
+                    let mut rows = Vec::new();
+                    for record in records {
+                        // a quoted cell may span lines, so report the first line of each record
+                        line_num += 1;
+                        let start = line_num;
+                        line_num += record.matches('\n').count() as u64;
+
+                        if record.trim().is_empty() {
+                            continue;
+                        }
+
+                        if expect_header {
+                            Self::validate_header(&columns, &record, delimiter).map_err(|cause| {
+                                TCError::bad_request(
+                                    format!("invalid CSV header at line {}", start),
+                                    cause,
+                                )
+                            })?;
+
+                            expect_header = false;
+                            continue;
+                        }
+
+                        match Self::parse_row(&columns, &record, delimiter)
+                            .and_then(|row| table.primary_schema().key_values_from_tuple(row))
+                        {
+                            Ok((key, values)) => rows.push((start, key, values)),
+                            Err(cause) => {
+                                errors += 1;
+                                debug!("error parsing CSV line {}: {}", start, cause);
+
+                                if errors > max_errors {
+                                    return Err(TCError::bad_request(
+                                        format!(
+                                            "invalid CSV row at line {} ({} malformed rows)",
+                                            start, errors
+                                        ),
+                                        cause,
+                                    ));
+                                }
+                            }
+                        }
+                    }
+
+                    // insert rows one at a time so that a duplicate key is always detected
+                    for (line_num, key, values) in rows {
+                        table
+                            .insert(txn_id, key, values)
+                            .map_err(|cause| {
+                                TCError::bad_request(
+                                    format!("invalid CSV row at line {}", line_num),
+                                    cause,
+                                )
+                            })
+                            .await?;
+                    }
+
+                    if eof {
+                        break;
+                    }
+                }
+
+                let loaded = vec![
+                    (label("table").into(), State::Collection(table.into())),
+                    (label("errors").into(), State::from(Value::from(errors))),
+                ];
+
+                Ok(State::Map(loaded.into_iter().collect()))
+            })
+        }))
+    }
+}
+
+struct OrderHandler<T> {
+    table: T,
+}
+
+impl<'a, T: TableOrder + 'a> Handler<'a> for OrderHandler<T>
+where
+    Table: From<T::OrderBy>,
+{
+    fn get<'b>(self: Box<Self>) -> Option<GetHandler<'a, 'b>>
+    where
+        'b: 'a,
+    {
+        Some(Box::new(|_txn, key| {
+            Box::pin(async move {
+                let ordered = if key.matches::<(Vec<Id>, bool)>() {
+                    let (order, reverse) = key.opt_cast_into().unwrap();
+                    self.table.order_by(order, reverse)?
+                } else if key.matches::<Vec<Id>>() {
+                    let order = key.opt_cast_into().unwrap();
+                    self.table.order_by(order, false)?
+                } else {
+                    return Err(TCError::bad_request("invalid column list to order by", key));
+                };
+
+                Ok(Collection::Table(ordered.into()).into())
+            })
+        }))
+    }
+}
+
+impl<T> From<T> for OrderHandler<T> {
+    fn from(table: T) -> Self {
+        Self { table }
+    }
+}
+
+struct RenameColumnHandler<'a> {
+    table: &'a TableIndex,
+}
+
+impl<'a> Handler<'a> for RenameColumnHandler<'a> {
+    fn post<'b>(self: Box<Self>) -> Option<PostHandler<'a, 'b>>
+    where
+        'b: 'a,
+    {
+        Some(Box::new(|txn, mut params| {
+            Box::pin(async move {
+                let old: Id = params.require(&label("old").into())?;
+                let new: Id = params.require(&label("new").into())?;
+                params.expect_empty()?;
+
+                self.table
+                    .rename_column(*txn.id(), old, new)
+                    .map_ok(Collection::from)
+                    .map_ok(State::from)
+                    .await
+            })
+        }))
+    }
+}
+
+impl<'a> From<&'a TableIndex> for RenameColumnHandler<'a> {
+    fn from(table: &'a TableIndex) -> Self {
+        Self { table }
+    }
+}
+
+struct ReverseHandler<T> {
+    table: T,
+}
+
+impl<'a, T: TableOrder + 'a> Handler<'a> for ReverseHandler<T>
+where
+    Table: From<T::Reverse>,
+{
+    fn get<'b>(self: Box<Self>) -> Option<GetHandler<'a, 'b>>
+    where
+        'b: 'a,
+    {
+        Some(Box::new(|_txn, key| {
+            Box::pin(async move {
+                key.expect_none()?;
+
+                let reversed = self.table.reverse()?;
+                Ok(Collection::Table(reversed.into()).into())
+            })
+        }))
+    }
+}
+
+impl<T> From<T> for ReverseHandler<T> {
+    fn from(table: T) -> Self {
+        Self { table }
+    }
+}
+
+struct TableHandler<'a, T> {
+    table: &'a T,
+}
+
+impl<'a, T> Handler<'a> for TableHandler<'a, T>
+where
+    T: TableRead + TableSlice + TableWrite + Clone + 'a,
+    Table: From<T>,
+    Table: From<T::Slice>,
+{
+    fn get<'b>(self: Box<Self>) -> Option<GetHandler<'a, 'b>>
+    where
+        'b: 'a,
+    {
+        Some(Box::new(|txn, key| {
+            Box::pin(async move {
+                if key.is_some() {
+                    let key = primary_key(key.clone(), self.table)?;
+                    self.table
+                        .read(txn.id(), &key)
+                        .map_ok(Value::from)
+                        .map_ok(State::from)
+                        .await
+                } else {
+                    Ok(Collection::Table(self.table.clone().into()).into())
+                }
+            })
+        }))
+    }
+
+    fn put<'b>(self: Box<Self>) -> Option<PutHandler<'a, 'b>>
+    where
+        'b: 'a,
+    {
+        Some(Box::new(|txn, key, values| {
+            Box::pin(async move {
+                debug!("Table PUT {:?} <- {:?}", key, values);
+
+                let key = primary_key(key, self.table)?;
+
+                if values.is_map() {
+                    let values =
+                        values.try_into_map(|s| TCError::bad_request("invalid row values", s))?;
+
+                    let values = values
+                        .into_iter()
+                        .map(|(id, state)| {
+                            Value::try_cast_from(state, |s| {
+                                TCError::bad_request("invalid column value", s)
+                            })
+                            .map(|value| (id, value))
+                        })
+                        .collect::<TCResult<Map<Value>>>()?;
+
+                    self.table.update(*txn.id(), key, values).await
+                } else if values.is_tuple() {
+                    let values =
+                        values.try_into_tuple(|s| TCError::bad_request("invalid row values", s))?;
+
+                    let values = values
+                        .into_iter()
+                        .map(|state| {
+                            state.try_cast_into(|s| TCError::bad_request("invalid column value", s))
+                        })
+                        .collect::<TCResult<Vec<Value>>>()?;
+
+                    self.table.upsert(*txn.id(), key, values).await
+                } else {
+                    Err(TCError::bad_request("invalid row values", values))
+                }
+            })
+        }))
+    }
+
+    fn post<'b>(self: Box<Self>) -> Option<PostHandler<'a, 'b>>
+    where
+        'b: 'a,
+    {
+        Some(Box::new(|_txn, mut params| {
+            Box::pin(async move {
+                let bounds: Scalar = params.require(&label("bounds").into())?;
+                let bounds = cast_into_bounds(bounds, &self.table)?;
+                self.table
+                    .clone()
+                    .slice(bounds)
+                    .map(|slice| Table::from(slice))
+                    .map(Collection::Table)
+                    .map(State::Collection)
+            })
+        }))
+    }
+
+    fn delete<'b>(self: Box<Self>) -> Option<DeleteHandler<'a, 'b>>
+    where
+        'b: 'a,
+    {
+        Some(Box::new(|txn, key| {
+            Box::pin(async move {
+                let row = primary_key(key, self.table)?;
+                self.table.delete(*txn.id(), row).await
+            })
+        }))
+    }
+}
+
+struct SampleHandler<T> {
+    table: T,
+}
+
+impl<'a, T: TableStream + 'a> Handler<'a> for SampleHandler<T>
+where
+    Table: From<T>,
+{
+    fn get<'b>(self: Box<Self>) -> Option<GetHandler<'a, 'b>>
+    where
+        'b: 'a,
+    {
+        Some(Box::new(|txn, key| {
+            Box::pin(async move {
+                let size: u64 = key.try_cast_into(|v| {
+                    TCError::bad_request("sample size must be a positive integer, not", v)
+                })?;
+
+                let size = size
+                    .try_into()
+                    .map_err(|_| TCError::bad_request("sample size is too large", size))?;
+
+                let txn_id = *txn.id();
+                let dir = txn.context().create_dir_unique(txn_id).await?;
+                let sample = TableIndex::create(&dir, self.table.schema(), txn_id).await?;
+
+                let rows = TCStream::from(Table::from(self.table))
+                    .sample(size)
+                    .into_stream(txn.clone())
+                    .await?;
+
+                table_rows(rows, sample.primary_schema())
+                    .map_ok(|(key, values)| sample.upsert(txn_id, key, values))
+                    .try_buffer_unordered(num_cpus::get())
+                    .try_fold((), |(), ()| future::ready(Ok(())))
+                    .await?;
+
+                Ok(State::Collection(sample.into()))
+            })
+        }))
+    }
+}
+
+impl<T> From<T> for SampleHandler<T> {
+    fn from(table: T) -> Self {
+        Self { table }
+    }
+}
+
+struct SchemaHandler<'a, T> {
+    table: &'a T,
+    schema: fn(&'a T) -> Value,
+}
+
+impl<'a, T> SchemaHandler<'a, T> {
+    fn new(table: &'a T, schema: fn(&'a T) -> Value) -> Self {
+        Self { table, schema }
+    }
+}
+
+impl<'a, T: TableInstance> Handler<'a> for SchemaHandler<'a, T> {
+    fn get<'b>(self: Box<Self>) -> Option<GetHandler<'a, 'b>>
+    where
+        'b: 'a,
+    {
+        Some(Box::new(|_txn, key| {
+            Box::pin(async move {
+                key.expect_none()?;
+                Ok(State::from((self.schema)(self.table)))
+            })
+        }))
+    }
+}
+
+struct SelectHandler<T> {
+    table: T,
+}
+
+impl<'a, T: TableStream + 'a> Handler<'a> for SelectHandler<T>
+where
+    Table: From<T::Selection>,
+{
+    fn get<'b>(self: Box<Self>) -> Option<GetHandler<'a, 'b>>
+    where
+        'b: 'a,
+    {
+        Some(Box::new(|_txn, key| {
+            Box::pin(async move {
+                let columns =
+                    key.try_cast_into(|v| TCError::bad_request("invalid column list", v))?;
+
+                Ok(Collection::Table(self.table.select(columns)?.into()).into())
+            })
+        }))
+    }
+}
+
+impl<T> From<T> for SelectHandler<T> {
+    fn from(table: T) -> Self {
+        Self { table }
+    }
+}
+
+struct StreamHandler<T> {
+    table: T,
+    keyed: bool,
+}
+
+impl<T> StreamHandler<T> {
+    fn new(table: T, keyed: bool) -> Self {
+        Self { table, keyed }
+    }
+
+    fn stream(keyed: bool, table: Table) -> TCStream {
+        if keyed {
+            TCStream::keyed_rows(table)
+        } else {
+            TCStream::from(table)
+        }
+    }
+}
+
+impl<'a, T: TableSlice + 'a> Handler<'a> for StreamHandler<T>
+where
+    Table: From<T>,
+    Table: From<T::Slice>,
+{
+    fn get<'b>(self: Box<Self>) -> Option<GetHandler<'a, 'b>>
+    where
+        'b: 'a,
+    {
+        Some(Box::new(|_txn, key| {
+            Box::pin(async move {
+                if key.is_none() {
+                    Ok(Self::stream(self.keyed, Table::from(self.table)).into())
+                } else {
+                    let bounds = cast_into_bounds(Scalar::Value(key), &self.table)?;
+                    let slice = self.table.slice(bounds)?;
+                    Ok(Self::stream(self.keyed, Table::from(slice)).into())
+                }
+            })
+        }))
+    }
+
+    fn post<'b>(self: Box<Self>) -> Option<PostHandler<'a, 'b>>
+    where
+        'b: 'a,
+    {
+        Some(Box::new(|_txn, params| {
+            Box::pin(async move {
+                if params.is_empty() {
+                    // a view which can't be sliced, like a selection, can still be streamed whole
+                    return Ok(Self::stream(self.keyed, Table::from(self.table)).into());
+                }
+
+                let bounds = Scalar::try_cast_from(State::Map(params), |s| {
+                    TCError::bad_request("invalid Table bounds", s)
+                })?;
+
+                let bounds = cast_into_bounds(bounds, &self.table)?;
+                let slice = self.table.slice(bounds)?;
+                Ok(Self::stream(self.keyed, Table::from(slice)).into())
+            })
+        }))
+    }
+}
+
+impl<'a, T> From<&'a T> for TableHandler<'a, T> {
+    fn from(table: &'a T) -> Self {
+        Self { table }
+    }
+}
+
+struct SumColumnHandler<T> {
+    table: T,
+    mean: bool,
+}
+
+impl<T> SumColumnHandler<T> {
+    fn new(table: T, mean: bool) -> Self {
+        Self { table, mean }
+    }
+}
+
+impl<'a, T: TableStream + 'a> Handler<'a> for SumColumnHandler<T> {
+    fn get<'b>(self: Box<Self>) -> Option<GetHandler<'a, 'b>>
+    where
+        'b: 'a,
+    {
+        Some(Box::new(|txn, key| {
+            Box::pin(async move {
+                let name: Id =
+                    key.try_cast_into(|v| TCError::bad_request("invalid column name", v))?;
+
+                let column = self
+                    .table
+                    .schema()
+                    .primary()
+                    .columns()
+                    .into_iter()
+                    .find(|col| col.name() == &name)
+                    .ok_or_else(|| TCError::not_found(format!("Column {}", name)))?;
+
+                let dtype = match column.dtype() {
+                    ValueType::Number(dtype) => dtype,
+                    other => {
+                        return Err(TCError::bad_request(
+                            format!("cannot sum column {} of type", name),
+                            other,
+                        ))
+                    }
+                };
+
+                // a single pass tracks both the sum and the count, for the mean
+                let rows = self.table.select(vec![name])?.rows(*txn.id()).await?;
+                let (sum, count) = rows
+                    .try_fold((dtype.zero(), 0u64), |(sum, count), mut row| {
+                        let result = match row.pop() {
+                            Some(Value::Number(n)) => Ok((sum + n, count + 1)),
+                            Some(Value::None) | None => Ok((sum, count)),
+                            Some(other) => {
+                                Err(TCError::bad_request("expected a Number, not", other))
+                            }
+                        };
+
+                        future::ready(result)
+                    })
+                    .await?;
+
+                if !self.mean {
+                    Ok(Value::Number(sum).into())
+                } else if count == 0 {
+                    Err(TCError::bad_request(
+                        "cannot compute the mean of an empty column",
+                        column.name(),
+                    ))
+                } else {
+                    let sum = sum.into_type(FloatType::F64.into());
+                    Ok(Value::Number(sum / Number::from(count)).into())
+                }
+            })
+        }))
+    }
+}
+
+#[cfg(feature = "tensor")]
+struct ToTensorHandler<T> {
+    table: T,
+}
+
+#[cfg(feature = "tensor")]
+impl<'a, T> Handler<'a> for ToTensorHandler<T>
 where
-    Table: From<T>,
-    Table: From<T::Slice>,
+    T: TableStream + Clone + 'a,
+    Table: From<T::Selection>,
 {
     fn get<'b>(self: Box<Self>) -> Option<GetHandler<'a, 'b>>
     where
         'b: 'a,
     {
-        Some(Box::new(|_txn, key| {
+        Some(Box::new(|txn, key| {
             Box::pin(async move {
-                if key.is_none() {
-                    Ok(TCStream::from(Table::from(self.table)).into())
-                } else {
-                    let bounds = cast_into_bounds(Scalar::Value(key))?;
-                    let slice = self.table.slice(bounds)?;
-                    Ok(TCStream::from(Table::from(slice)).into())
+                let columns: Vec<Id> =
+                    key.try_cast_into(|v| TCError::bad_request("invalid column list", v))?;
+
+                if columns.is_empty() {
+                    return Err(TCError::bad_request(
+                        "loading a Tensor from a Table requires at least one column",
+                        Tuple::from(columns),
+                    ));
+                }
+
+                let mut dtypes = Vec::with_capacity(columns.len());
+                for name in &columns {
+                    let column = self
+                        .table
+                        .key()
+                        .iter()
+                        .chain(self.table.values())
+                        .find(|col| &col.name == name)
+                        .ok_or_else(|| TCError::not_found(format!("column {}", name)))?;
+
+                    // a Tensor has no representation of a missing value
+                    if column.nullable {
+                        return Err(TCError::bad_request(
+                            "cannot load a nullable column into a Tensor",
+                            name,
+                        ));
+                    }
+
+                    match column.dtype {
+                        ValueType::Number(dtype) => dtypes.push(dtype),
+                        other => {
+                            return Err(TCError::bad_request(
+                                format!("cannot load column {} into a Tensor", name),
+                                other,
+                            ))
+                        }
+                    }
                 }
+
+                let dtype = dtypes.into_iter().fold(NumberType::Bool, promote);
+
+                // the number of rows is not known up front, so count them first
+                let txn_id = *txn.id();
+                let num_rows = self.table.clone().count(txn_id).await?;
+                let shape = vec![num_rows, columns.len() as u64];
+
+                let rows = Table::from(self.table.select(columns)?)
+                    .rows(txn_id)
+                    .await?;
+                let values = rows
+                    .map_ok(|row| futures::stream::iter(row.into_iter().map(TCResult::Ok)))
+                    .try_flatten()
+                    .map(|r| {
+                        r.and_then(|value| {
+                            Number::try_cast_from(value, |v| {
+                                TCError::bad_request(
+                                    "cannot load a non-numeric value into a Tensor",
+                                    v,
+                                )
+                            })
+                        })
+                    });
+
+                let file = txn
+                    .context()
+                    .create_file_unique(txn_id, TensorType::Dense)
+                    .await?;
+
+                let blocks = DenseTensorFile::from_values(
+                    file,
+                    txn_id,
+                    shape.into(),
+                    dtype,
+                    values,
+                    PER_BLOCK,
+                )
+                .await?;
+
+                let tensor = Tensor::from(DenseTensor::from(blocks));
+                Ok(Collection::Tensor(tensor).into())
+            })
+        }))
+    }
+}
+
+#[cfg(feature = "tensor")]
+impl<T> From<T> for ToTensorHandler<T> {
+    fn from(table: T) -> Self {
+        Self { table }
+    }
+}
+
+struct TruncateHandler<'a> {
+    table: &'a TableIndex,
+}
+
+impl<'a> Handler<'a> for TruncateHandler<'a> {
+    fn delete<'b>(self: Box<Self>) -> Option<DeleteHandler<'a, 'b>>
+    where
+        'b: 'a,
+    {
+        Some(Box::new(|txn, key| {
+            Box::pin(async move {
+                key.expect_none()?;
+                self.table.truncate(*txn.id()).await
+            })
+        }))
+    }
+}
+
+impl<'a> From<&'a TableIndex> for TruncateHandler<'a> {
+    fn from(table: &'a TableIndex) -> Self {
+        Self { table }
+    }
+}
+
+struct VerifyIndicesHandler<'a> {
+    table: &'a TableIndex,
+}
+
+impl<'a> Handler<'a> for VerifyIndicesHandler<'a> {
+    fn get<'b>(self: Box<Self>) -> Option<GetHandler<'a, 'b>>
+    where
+        'b: 'a,
+    {
+        Some(Box::new(|txn, key| {
+            Box::pin(async move {
+                key.expect_none()?;
+
+                let discrepancies = self.table.verify_indices(*txn.id()).await?;
+                let report = discrepancies
+                    .into_iter()
+                    .map(|discrepancy| {
+                        let (kind, index, key) = match discrepancy {
+                            IndexDiscrepancy::Missing(index, key) => (label("missing"), index, key),
+                            IndexDiscrepancy::Orphaned(index, key) => {
+                                (label("orphaned"), index, key)
+                            }
+                        };
+
+                        let report = vec![
+                            (label("index").into(), State::from(Value::from(index))),
+                            (label("key").into(), State::from(Value::from_iter(key))),
+                            (
+                                label("kind").into(),
+                                State::from(Value::from(Id::from(kind))),
+                            ),
+                        ];
+
+                        State::Map(report.into_iter().collect())
+                    })
+                    .collect();
+
+                Ok(State::Tuple(report))
             })
         }))
     }
+}
+
+impl<'a> From<&'a TableIndex> for VerifyIndicesHandler<'a> {
+    fn from(table: &'a TableIndex) -> Self {
+        Self { table }
+    }
+}
 
+struct UpdateIfVersionHandler<'a, T> {
+    table: &'a T,
+}
+
+impl<'a, T: TableWrite + 'a> Handler<'a> for UpdateIfVersionHandler<'a, T> {
     fn post<'b>(self: Box<Self>) -> Option<PostHandler<'a, 'b>>
     where
         'b: 'a,
     {
-        Some(Box::new(|_txn, params| {
+        Some(Box::new(|txn, mut params| {
             Box::pin(async move {
-                let bounds = Scalar::try_cast_from(State::Map(params), |s| {
-                    TCError::bad_request("invalid Table bounds", s)
-                })?;
+                let key: Value = params.require(&label("key").into())?;
+                let version: Id = params.require(&label("version").into())?;
+                let expected: Value = params.require(&label("expected").into())?;
+                let values: Map<State> = params.require(&label("values").into())?;
+                params.expect_empty()?;
 
-                let bounds = cast_into_bounds(bounds)?;
+                let key = primary_key(key, self.table)?;
 
-                let slice = self.table.slice(bounds)?;
-                Ok(TCStream::from(Table::from(slice)).into())
+                let values = values
+                    .into_iter()
+                    .map(|(id, state)| {
+                        Value::try_cast_from(state, |s| {
+                            TCError::bad_request("invalid column value", s)
+                        })
+                        .map(|value| (id, value))
+                    })
+                    .collect::<TCResult<Map<Value>>>()?;
+
+                self.table
+                    .update_if_version(*txn.id(), key, version, expected, values)
+                    .await
+            })
+        }))
+    }
+}
+
+impl<'a, T> From<&'a T> for UpdateIfVersionHandler<'a, T> {
+    fn from(table: &'a T) -> Self {
+        Self { table }
+    }
+}
+
+struct UpsertHandler<T> {
+    table: T,
+}
+
+impl<'a, T> Handler<'a> for UpsertHandler<T>
+where
+    T: TableSlice + TableWrite + Clone + 'a,
+    T::Slice: TableStream,
+{
+    fn post<'b>(self: Box<Self>) -> Option<PostHandler<'a, 'b>>
+    where
+        'b: 'a,
+    {
+        Some(Box::new(|txn, mut params| {
+            Box::pin(async move {
+                let key: Value = params.require(&label("key").into())?;
+                let values: Value = params.require(&label("values").into())?;
+                params.expect_empty()?;
+
+                let txn_id = *txn.id();
+                let schema = self.table.schema();
+                let key = primary_key(key, &self.table)?;
+                let values =
+                    values.try_cast_into(|v| TCError::bad_request("invalid row values", v))?;
+                let values = schema.primary().validate_values(values)?;
+
+                let exists = {
+                    let bounds = Bounds::from_key(key.clone(), self.table.key());
+                    let slice = self.table.clone().slice(bounds)?;
+                    let mut rows = slice.rows(txn_id).await?;
+                    rows.try_next().await?.is_some()
+                };
+
+                if exists {
+                    let row = schema
+                        .primary()
+                        .values()
+                        .iter()
+                        .map(|col| col.name.clone())
+                        .zip(values)
+                        .collect();
+
+                    self.table.update(txn_id, key, row).await?;
+                } else {
+                    self.table.upsert(txn_id, key, values).await?;
+                }
+
+                Ok(Value::from(!exists).into())
             })
         }))
     }
 }
 
-impl<T> From<T> for StreamHandler<T> {
+impl<T> From<T> for UpsertHandler<T> {
     fn from(table: T) -> Self {
         Self { table }
     }
 }
 
-impl<'a, T> From<&'a T> for TableHandler<'a, T> {
+struct ValuesAtHandler<'a, T> {
+    table: &'a T,
+}
+
+impl<'a, T: TableRead + 'a> Handler<'a> for ValuesAtHandler<'a, T> {
+    fn get<'b>(self: Box<Self>) -> Option<GetHandler<'a, 'b>>
+    where
+        'b: 'a,
+    {
+        Some(Box::new(|txn, key| {
+            Box::pin(async move {
+                let key: Vec<Value> =
+                    key.try_cast_into(|v| TCError::bad_request("invalid Table key", v))?;
+
+                let key_len = self.table.key().len();
+                if key.len() != key_len {
+                    return Err(TCError::bad_request(
+                        format!(
+                            "values_at requires a complete key of {} columns, found",
+                            key_len
+                        ),
+                        Value::from_iter(key),
+                    ));
+                }
+
+                let key = self.table.primary_schema().validate_key(key)?;
+
+                // this is a lookup by primary key, so it only reads one path of the primary index
+                if let Some(mut row) = self.table.read(txn.id(), &key).await? {
+                    let values = row.split_off(key_len);
+                    Ok(Value::Tuple(values.into()).into())
+                } else {
+                    Err(TCError::not_found(format!(
+                        "row with key {}",
+                        Value::from_iter(key)
+                    )))
+                }
+            })
+        }))
+    }
+}
+
+impl<'a, T> From<&'a T> for ValuesAtHandler<'a, T> {
     fn from(table: &'a T) -> Self {
         Self { table }
     }
@@ -445,13 +1602,151 @@ impl<'a, T> From<&'a T> for TableHandler<'a, T> {
 
 impl Route for Table {
     fn route<'a>(&'a self, path: &'a [PathSegment]) -> Option<Box<dyn Handler<'a> + 'a>> {
+        match self {
+            Self::Table(table) => return table.route(path),
+            Self::Merge(merged) if path.len() == 1 && path[0].as_str() == "explain" => {
+                return Some(Box::new(ExplainHandler::Merge(merged)));
+            }
+            Self::TableSlice(slice) if path.len() == 1 && path[0].as_str() == "explain" => {
+                let bounds = slice.bounds().clone();
+                return Some(Box::new(ExplainHandler::Slice(slice.source(), bounds)));
+            }
+            _ => {}
+        }
+
         route(self, path)
     }
 }
 
+// routes a request to the current version of a `TableIndex` as of the request's transaction,
+// so that a schema change made through another handle is visible
+struct CurrentHandler<'a> {
+    table: &'a TableIndex,
+    path: &'a [PathSegment],
+}
+
+impl<'a> Handler<'a> for CurrentHandler<'a> {
+    fn get<'b>(self: Box<Self>) -> Option<GetHandler<'a, 'b>>
+    where
+        'b: 'a,
+    {
+        Some(Box::new(|txn, key| {
+            Box::pin(async move {
+                let table = self.table.current(*txn.id()).await?;
+                let handler = route_index(&table, self.path).ok_or_else(|| self.not_found())?;
+
+                if let Some(get_handler) = handler.get() {
+                    get_handler(txn, key).await
+                } else {
+                    Err(self.method_not_allowed(ORT::Get))
+                }
+            })
+        }))
+    }
+
+    fn put<'b>(self: Box<Self>) -> Option<PutHandler<'a, 'b>>
+    where
+        'b: 'a,
+    {
+        Some(Box::new(|txn, key, value| {
+            Box::pin(async move {
+                let table = self.table.current(*txn.id()).await?;
+                let handler = route_index(&table, self.path).ok_or_else(|| self.not_found())?;
+
+                if let Some(put_handler) = handler.put() {
+                    put_handler(txn, key, value).await
+                } else {
+                    Err(self.method_not_allowed(ORT::Put))
+                }
+            })
+        }))
+    }
+
+    fn post<'b>(self: Box<Self>) -> Option<PostHandler<'a, 'b>>
+    where
+        'b: 'a,
+    {
+        Some(Box::new(|txn, params| {
+            Box::pin(async move {
+                let table = self.table.current(*txn.id()).await?;
+                let handler = route_index(&table, self.path).ok_or_else(|| self.not_found())?;
+
+                if let Some(post_handler) = handler.post() {
+                    post_handler(txn, params).await
+                } else {
+                    Err(self.method_not_allowed(ORT::Post))
+                }
+            })
+        }))
+    }
+
+    fn delete<'b>(self: Box<Self>) -> Option<DeleteHandler<'a, 'b>>
+    where
+        'b: 'a,
+    {
+        Some(Box::new(|txn, key| {
+            Box::pin(async move {
+                let table = self.table.current(*txn.id()).await?;
+                let handler = route_index(&table, self.path).ok_or_else(|| self.not_found())?;
+
+                if let Some(delete_handler) = handler.delete() {
+                    delete_handler(txn, key).await
+                } else {
+                    Err(self.method_not_allowed(ORT::Delete))
+                }
+            })
+        }))
+    }
+}
+
+impl<'a> CurrentHandler<'a> {
+    fn not_found(&self) -> TCError {
+        TCError::not_found(TCPath::from(self.path))
+    }
+
+    fn method_not_allowed(&self, method: ORT) -> TCError {
+        TCError::method_not_allowed(method, self.table, TCPath::from(self.path))
+    }
+}
+
 impl Route for TableIndex {
     fn route<'a>(&'a self, path: &'a [PathSegment]) -> Option<Box<dyn Handler<'a> + 'a>> {
-        route(self, path)
+        if route_index(self, path).is_some() {
+            Some(Box::new(CurrentHandler { table: self, path }))
+        } else {
+            None
+        }
+    }
+}
+
+#[inline]
+fn route_index<'a>(
+    table: &'a TableIndex,
+    path: &'a [PathSegment],
+) -> Option<Box<dyn Handler<'a> + 'a>> {
+    route_schema(table, path).or_else(|| route(table, path))
+}
+
+#[inline]
+fn route_schema<'a>(
+    table: &'a TableIndex,
+    path: &[PathSegment],
+) -> Option<Box<dyn Handler<'a> + 'a>> {
+    if path.len() == 1 {
+        match path[0].as_str() {
+            "add_column" => Some(Box::new(AddColumnHandler::from(table))),
+            "create_index" => Some(Box::new(CreateIndexHandler::from(table))),
+            "delete_where" => Some(Box::new(DeleteWhereHandler::from(table))),
+            "drop_column" => Some(Box::new(DropColumnHandler::from(table))),
+            "drop_index" => Some(Box::new(DropIndexHandler::from(table))),
+            "explain" => Some(Box::new(ExplainHandler::Slice(table, Bounds::default()))),
+            "rename_column" => Some(Box::new(RenameColumnHandler::from(table))),
+            "truncate" => Some(Box::new(TruncateHandler::from(table))),
+            "verify_indices" => Some(Box::new(VerifyIndicesHandler::from(table))),
+            _ => None,
+        }
+    } else {
+        None
     }
 }
 
@@ -463,6 +1758,7 @@ where
     Table: From<T>,
     Table: From<T::Limit>,
     Table: From<T::OrderBy>,
+    Table: From<T::Reverse>,
     Table: From<T::Selection>,
     Table: From<T::Slice>,
 {
@@ -473,12 +1769,28 @@ where
             "columns" => Some(Box::new(SchemaHandler::new(table, column_schema))),
             "contains" => Some(Box::new(ContainsHandler::from(table))),
             "count" => Some(Box::new(CountHandler::from(table.clone()))),
+            "count_by" => Some(Box::new(CountByHandler::from(table.clone()))),
+            "exists" => Some(Box::new(ExistsHandler::from(table.clone()))),
+            "first" => Some(Box::new(FirstHandler::from(table.clone()))),
+            "group_by" => Some(Box::new(GroupByHandler::from(table.clone()))),
+            "insert" => Some(Box::new(InsertHandler::from(table))),
             "key_columns" => Some(Box::new(SchemaHandler::new(table, key_columns))),
             "key_names" => Some(Box::new(SchemaHandler::new(table, key_names))),
+            "keyed_rows" => Some(Box::new(StreamHandler::new(table.clone(), true))),
+            "last" => Some(Box::new(LastHandler::from(table.clone()))),
             "limit" => Some(Box::new(LimitHandler::from(table.clone()))),
+            "mean_column" => Some(Box::new(SumColumnHandler::new(table.clone(), true))),
             "order" => Some(Box::new(OrderHandler::from(table.clone()))),
+            "reverse" => Some(Box::new(ReverseHandler::from(table.clone()))),
+            "sample" => Some(Box::new(SampleHandler::from(table.clone()))),
             "select" => Some(Box::new(SelectHandler::from(table.clone()))),
-            "rows" => Some(Box::new(StreamHandler::from(table.clone()))),
+            "sum_column" => Some(Box::new(SumColumnHandler::new(table.clone(), false))),
+            "rows" => Some(Box::new(StreamHandler::new(table.clone(), false))),
+            #[cfg(feature = "tensor")]
+            "to_tensor" => Some(Box::new(ToTensorHandler::from(table.clone()))),
+            "update_if_version" => Some(Box::new(UpdateIfVersionHandler::from(table))),
+            "upsert" => Some(Box::new(UpsertHandler::from(table.clone()))),
+            "values_at" => Some(Box::new(ValuesAtHandler::from(table))),
             _ => None,
         }
     } else {
@@ -494,6 +1806,8 @@ impl Route for Static {
             Some(Box::new(CreateHandler))
         } else if path == &["copy_from"] {
             Some(Box::new(CopyHandler))
+        } else if path == &["load"] {
+            Some(Box::new(LoadHandler))
         } else {
             None
         }
@@ -501,7 +1815,7 @@ impl Route for Static {
 }
 
 #[inline]
-fn cast_into_bounds(scalar: Scalar) -> TCResult<Bounds> {
+fn cast_into_bounds<T: TableInstance>(scalar: Scalar, table: &T) -> TCResult<Bounds> {
     if scalar.is_none() {
         return Ok(Bounds::default());
     }
@@ -510,28 +1824,27 @@ fn cast_into_bounds(scalar: Scalar) -> TCResult<Bounds> {
         TCError::bad_request("invalid selection bounds for Table", s)
     })?;
 
-    scalar
-        .into_iter()
-        .map(|(col_name, bound)| {
-            if bound.matches::<(Bound, Bound)>()
-                || bound.matches::<(Bound, Value)>()
-                || bound.matches::<(Value, Bound)>()
-            {
-                Ok(ColumnBound::In(bound.opt_cast_into().unwrap()))
-            } else if bound.matches::<Value>() {
-                Ok(ColumnBound::Is(bound.opt_cast_into().unwrap()))
-            } else {
-                Err(TCError::bad_request("invalid column bound", bound))
-            }
-            .map(|bound| (col_name, bound))
-        })
-        .collect()
+    Bounds::from_object_checked(scalar, &table.primary_schema().columns())
 }
 
 #[inline]
 fn primary_key<T: TableInstance>(key: Value, table: &T) -> TCResult<Key> {
     let key: Vec<Value> = key.try_cast_into(|v| TCError::bad_request("invalid Table key", v))?;
-    table.schema().primary().validate_key(key)
+    table.primary_schema().validate_key(key)
+}
+
+// cast each `State` in the given stream of `rows` into a key and values of the given `schema`
+fn table_rows<'a>(
+    rows: TCBoxTryStream<'a, State>,
+    schema: IndexSchema,
+) -> impl Stream<Item = TCResult<(Key, Values)>> + 'a {
+    rows.map(move |r| {
+        r.and_then(|state| {
+            Value::try_cast_from(state, |s| TCError::bad_request("invalid Table row", s))
+        })
+        .and_then(|value| value.try_cast_into(|v| TCError::bad_request("invalid Table row", v)))
+        .and_then(|row| schema.key_values_from_tuple(row))
+    })
 }
 
 fn column_schema<T: TableInstance>(table: &T) -> Value {
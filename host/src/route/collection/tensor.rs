@@ -1,24 +1,27 @@
+use std::cmp::Ordering;
 use std::convert::TryInto;
 
 use futures::future::{self, Future, TryFutureExt};
-use futures::stream::{FuturesUnordered, StreamExt, TryStreamExt};
+use futures::stream::{self, FuturesUnordered, StreamExt, TryStreamExt};
 use log::debug;
 use safecast::*;
 
-use tc_btree::Node;
+use tc_btree::{Column, Node};
 use tc_error::*;
 use tc_math::*;
+use tc_table::{IndexSchema, TableSchema, TableWrite};
 use tc_tensor::*;
 use tc_transact::fs::{CopyFrom, Dir};
 use tc_transact::Transaction;
 use tc_value::{
-    Bound, FloatType, Number, NumberClass, NumberInstance, NumberType, Range, TCString, Value,
-    ValueType,
+    Bound, FloatType, Number, NumberClass, NumberIdentity, NumberInstance, NumberType, Range,
+    TCString, UIntType, Value, ValueType,
 };
-use tcgeneric::{label, Label, PathSegment, TCBoxTryFuture, Tuple};
+use tcgeneric::{label, Id, Label, PathSegment, TCBoxTryFuture, TCBoxTryStream, Tuple};
 
 use crate::collection::{
-    Collection, DenseTensor, DenseTensorFile, SparseTable, SparseTensor, Tensor,
+    Collection, DenseAccessor, DenseTensor, DenseTensorFile, SparseTable, SparseTensor, TableIndex,
+    Tensor,
 };
 use crate::fs;
 use crate::object::Object;
@@ -28,7 +31,7 @@ use crate::state::{State, StateType};
 use crate::stream::{Source, TCStream};
 use crate::txn::Txn;
 
-use super::{Handler, Route};
+use super::{concurrency, Handler, Route};
 
 const AXIS: Label = label("axis");
 const TENSOR: Label = label("tensor");
@@ -37,6 +40,251 @@ const TENSORS: Label = label("tensors");
 const MEAN: f64 = 0.0;
 const STD: f64 = 0.0;
 
+struct AccumulateHandler {
+    tensor: Tensor,
+}
+
+impl<'a> Handler<'a> for AccumulateHandler {
+    fn put<'b>(self: Box<Self>) -> Option<PutHandler<'a, 'b>>
+    where
+        'b: 'a,
+    {
+        Some(Box::new(|txn, key, value| {
+            Box::pin(async move {
+                let dtype = self.tensor.dtype();
+                if let NumberType::Bool = dtype {
+                    return Err(TCError::unsupported(
+                        "cannot accumulate values in a boolean Tensor",
+                    ));
+                }
+
+                let shape = self.tensor.shape();
+                let bounds = cast_bounds(shape, key)?;
+
+                if bounds.is_coord(shape) {
+                    let coord = bounds.as_coord(shape).expect("tensor coordinate");
+                    let value = Number::try_cast_from(value, |v| {
+                        TCError::bad_request("cannot accumulate a non-numeric value", v)
+                    })?;
+
+                    return self
+                        .tensor
+                        .accumulate_value_at(txn.clone(), coord, value)
+                        .await;
+                }
+
+                // hold the lock until the sum is written, so no other accumulation is lost
+                let _lock = self.tensor.accumulate_lock(*txn.id()).await?;
+
+                let slice = self.tensor.clone().slice(bounds.clone())?;
+                let sum = match value {
+                    State::Collection(Collection::Tensor(value)) => {
+                        let value = if value.shape() == slice.shape() {
+                            value
+                        } else {
+                            value.broadcast(slice.shape().clone())?
+                        };
+
+                        TensorMath::add(slice, value)?
+                    }
+                    State::Scalar(Scalar::Value(value)) if value.matches::<Number>() => {
+                        let value = value.opt_cast_into().expect("numeric constant");
+                        slice.add_const(value)?
+                    }
+                    other => {
+                        return Err(TCError::bad_request(
+                            "cannot accumulate a non-numeric value",
+                            other,
+                        ))
+                    }
+                };
+
+                // copy the sum before writing, since it's computed lazily from the region it overwrites
+                let sum = TensorTransform::cast_into(sum, dtype)?;
+                let sum = copy(txn, sum).await?;
+                self.tensor.write(txn.clone(), bounds, sum).await
+            })
+        }))
+    }
+}
+
+impl<T> From<T> for AccumulateHandler
+where
+    Tensor: From<T>,
+{
+    fn from(tensor: T) -> Self {
+        Self {
+            tensor: tensor.into(),
+        }
+    }
+}
+
+// construct the adjacency matrix of the first `size` nodes of a graph from its 2-D edge tensor
+struct AdjacencyHandler;
+
+impl<'a> Handler<'a> for AdjacencyHandler {
+    fn post<'b>(self: Box<Self>) -> Option<PostHandler<'a, 'b>>
+    where
+        'b: 'a,
+    {
+        Some(Box::new(|txn, mut params| {
+            Box::pin(async move {
+                let edges: Tensor = params.require(&label("edges").into())?;
+                let size: u64 = params.require(&label("size").into())?;
+                let directed: bool = params.option(&label("directed").into(), || true)?;
+                let self_loops: bool = params.option(&label("self_loops").into(), || true)?;
+                let dtype: Value = params.or_default(&label("dtype").into())?;
+                let concurrency = concurrency(&mut params)?;
+                params.expect_empty()?;
+
+                let dtype = if dtype.is_none() {
+                    FloatType::F32.into()
+                } else {
+                    let dtype = ValueType::try_cast_from(dtype, |v| {
+                        TCError::bad_request("not a NumberType", v)
+                    })?;
+
+                    dtype.try_into()?
+                };
+
+                if edges.ndim() != 2 {
+                    return Err(TCError::bad_request(
+                        "adjacency requires a 2-D edge Tensor, not one with shape",
+                        edges.shape(),
+                    ));
+                } else if size > edges.shape()[0] || size > edges.shape()[1] {
+                    return Err(TCError::bad_request(
+                        format!(
+                            "cannot construct an adjacency matrix of {} nodes from edges with shape",
+                            size
+                        ),
+                        edges.shape(),
+                    ));
+                }
+
+                let bounds = Bounds::from(vec![AxisBounds::In(0..size), AxisBounds::In(0..size)]);
+                let edges = match edges.slice(bounds)? {
+                    Tensor::Dense(dense) => {
+                        let sparse = dense.into_sparse();
+                        sparse.into_inner().filled(txn.clone()).await?
+                    }
+                    Tensor::Sparse(sparse) => sparse.into_inner().filled(txn.clone()).await?,
+                };
+
+                let schema = Schema {
+                    shape: vec![size, size].into(),
+                    dtype,
+                };
+
+                let adjacency = create_sparse(txn, schema).await?;
+                let txn_id = *txn.id();
+
+                // each filled element is an edge, whose weight is its value
+                edges
+                    .try_filter(|(coord, _weight)| {
+                        future::ready(self_loops || coord[0] != coord[1])
+                    })
+                    .map_ok(|(coord, weight)| {
+                        let adjacency = &adjacency;
+                        let weight = weight.into_type(dtype);
+
+                        async move {
+                            if !directed {
+                                let reverse = vec![coord[1], coord[0]];
+                                adjacency.write_value_at(txn_id, reverse, weight).await?;
+                            }
+
+                            adjacency.write_value_at(txn_id, coord, weight).await
+                        }
+                    })
+                    .try_buffer_unordered(concurrency)
+                    .try_fold((), |(), ()| future::ready(Ok(())))
+                    .await?;
+
+                Ok(Collection::Tensor(adjacency.into()).into())
+            })
+        }))
+    }
+}
+
+struct ApplyAlongAxisHandler {
+    tensor: Tensor,
+}
+
+impl ApplyAlongAxisHandler {
+    // subtract the mean and divide by the standard deviation of each 1-D slice along `axis`
+    fn normalize(tensor: Tensor, axis: usize) -> TCResult<Tensor> {
+        let shape = tensor.shape().clone();
+        let dim = Number::from(shape[axis]);
+
+        let tensor = match tensor.dtype() {
+            NumberType::Float(_) | NumberType::Complex(_) => tensor,
+            _ => TensorTransform::cast_into(tensor, NumberType::Float(FloatType::F64))?,
+        };
+
+        let mean = tensor
+            .clone()
+            .sum(axis)?
+            .div_const(dim)?
+            .expand_dims(axis)?
+            .broadcast(shape.clone())?;
+
+        let centered = tensor.sub(mean)?;
+
+        let std = centered
+            .clone()
+            .pow_const(2.into())?
+            .sum(axis)?
+            .div_const(dim)?
+            .pow_const(0.5.into())?
+            .expand_dims(axis)?
+            .broadcast(shape)?;
+
+        centered.div(std)
+    }
+}
+
+impl<'a> Handler<'a> for ApplyAlongAxisHandler {
+    fn post<'b>(self: Box<Self>) -> Option<PostHandler<'a, 'b>>
+    where
+        'b: 'a,
+    {
+        Some(Box::new(|_txn, mut params| {
+            Box::pin(async move {
+                let axis = params.require(&AXIS.into())?;
+                let axis = cast_axis(axis, self.tensor.ndim())?;
+                let op: Id = params.require(&label("op").into())?;
+                params.expect_empty()?;
+
+                self.tensor.shape().validate("apply_along_axis")?;
+
+                let result = match op.as_str() {
+                    "normalize" => Self::normalize(self.tensor, axis)?,
+                    other => {
+                        return Err(TCError::bad_request(
+                            "unsupported op for Tensor::apply_along_axis",
+                            other,
+                        ))
+                    }
+                };
+
+                Ok(State::Collection(result.into()))
+            })
+        }))
+    }
+}
+
+impl<T> From<T> for ApplyAlongAxisHandler
+where
+    Tensor: From<T>,
+{
+    fn from(tensor: T) -> Self {
+        Self {
+            tensor: tensor.into(),
+        }
+    }
+}
+
 struct ArgmaxHandler<T> {
     tensor: T,
 }
@@ -122,15 +370,11 @@ impl<B> From<DenseTensor<B>> for ArgsortHandler<B> {
     }
 }
 
-struct CastHandler<T> {
-    tensor: T,
+struct CastHandler {
+    tensor: Tensor,
 }
 
-impl<'a, T> Handler<'a> for CastHandler<T>
-where
-    T: TensorTransform + Send + Sync + 'a,
-    Tensor: From<T::Cast>,
-{
+impl<'a> Handler<'a> for CastHandler {
     fn get<'b>(self: Box<Self>) -> Option<GetHandler<'a, 'b>>
     where
         'b: 'a,
@@ -141,18 +385,67 @@ where
                     ValueType::try_cast_from(key, |v| TCError::bad_request("not a NumberType", v))?;
 
                 let dtype = dtype.try_into()?;
-                self.tensor
-                    .cast_into(dtype)
-                    .map(Tensor::from)
-                    .map(State::from)
+                TensorTransform::cast_into(self.tensor, dtype).map(State::from)
+            })
+        }))
+    }
+}
+
+impl<T> From<T> for CastHandler
+where
+    Tensor: From<T>,
+{
+    fn from(tensor: T) -> Self {
+        Self {
+            tensor: tensor.into(),
+        }
+    }
+}
+
+struct ChunkHandler {
+    tensor: Tensor,
+}
+
+impl<'a> Handler<'a> for ChunkHandler {
+    fn get<'b>(self: Box<Self>) -> Option<GetHandler<'a, 'b>>
+    where
+        'b: 'a,
+    {
+        Some(Box::new(|_txn, key| {
+            Box::pin(async move {
+                let (start, stop): (u64, u64) = key.try_cast_into(|v| {
+                    TCError::bad_request("invalid (start, stop) range for a Tensor chunk", v)
+                })?;
+
+                let size = self.tensor.size();
+                if start > stop || stop > size {
+                    return Err(TCError::bad_request(
+                        format!("invalid chunk of a Tensor with size {}", size),
+                        Tuple::from(vec![start, stop]),
+                    ));
+                }
+
+                // a chunk is a range of the flattened tensor, so stream its zeros too
+                let flat = self
+                    .tensor
+                    .into_dense()
+                    .reshape(vec![size].into())?
+                    .slice(Bounds::from(vec![AxisBounds::In(start..stop)]))?;
+
+                Ok(TCStream::from(Collection::Tensor(flat)).into())
             })
         }))
     }
 }
 
-impl<T> From<T> for CastHandler<T> {
+impl<T> From<T> for ChunkHandler
+where
+    Tensor: From<T>,
+{
     fn from(tensor: T) -> Self {
-        Self { tensor }
+        Self {
+            tensor: tensor.into(),
+        }
     }
 }
 
@@ -170,7 +463,7 @@ impl ConcatenateHandler {
             .create_file_unique(txn_id, TensorType::Dense)
             .await?;
 
-        DenseTensor::constant(file, txn_id, shape, dtype.zero()).await
+        DenseTensor::constant(file, txn_id, shape, dtype.additive_identity(), PER_BLOCK).await
     }
 
     async fn concatenate_axis(
@@ -261,7 +554,7 @@ impl<'a> Handler<'a> for ConcatenateHandler {
                 let dtype = tensors
                     .iter()
                     .map(TensorAccess::dtype)
-                    .fold(tensors[0].dtype(), Ord::max);
+                    .fold(tensors[0].dtype(), promote);
 
                 let axis = if axis.is_some() {
                     cast_axis(axis, tensors[0].ndim())?
@@ -292,11 +585,12 @@ impl<'a> Handler<'a> for ConstantHandler {
     {
         Some(Box::new(|txn, key| {
             Box::pin(async move {
+                let (key, block_size) = split_block_size(key)?;
                 let (shape, value): (Vec<u64>, Number) =
                     key.try_cast_into(|v| TCError::bad_request("invalid Tensor schema", v))?;
 
                 let shape = Shape::from(shape);
-                constant(&txn, shape, value)
+                constant(&txn, shape, value, block_size.unwrap_or(PER_BLOCK))
                     .map_ok(Tensor::from)
                     .map_ok(Collection::from)
                     .map_ok(State::from)
@@ -320,25 +614,7 @@ impl<'a> Handler<'a> for CopyFromHandler {
 
                 source.shape().validate("copy Tensor")?;
 
-                let copy = match source {
-                    Tensor::Dense(source) => {
-                        let file = txn
-                            .context()
-                            .create_file_unique(*txn.id(), TensorType::Dense)
-                            .await?;
-
-                        let blocks =
-                            BlockListFile::copy_from(source.into_inner(), file, txn).await?;
-
-                        DenseTensor::from(blocks.accessor()).into()
-                    }
-                    Tensor::Sparse(source) => {
-                        let dir = txn.context().create_dir_unique(*txn.id()).await?;
-                        let table = SparseTable::copy_from(source, dir, txn).await?;
-                        SparseTensor::from(table.accessor()).into()
-                    }
-                };
-
+                let copy = copy(txn, source).await?;
                 Ok(State::Collection(Collection::Tensor(copy)))
             })
         }))
@@ -355,8 +631,7 @@ impl<'a> Handler<'a> for CopyDenseHandler {
         Some(Box::new(|txn, mut params| {
             Box::pin(async move {
                 let schema: Value = params.require(&label("schema").into())?;
-                let Schema { dtype, shape } =
-                    schema.try_cast_into(|v| TCError::bad_request("invalid Tensor schema", v))?;
+                let Schema { dtype, shape } = Schema::try_from_value(schema)?;
 
                 shape.validate("copy Dense")?;
 
@@ -374,7 +649,7 @@ impl<'a> Handler<'a> for CopyDenseHandler {
 
                 let txn_id = *txn.id();
                 let file = create_file(txn).await?;
-                DenseTensorFile::from_values(file, txn_id, shape, dtype, elements)
+                DenseTensorFile::from_values(file, txn_id, shape, dtype, elements, PER_BLOCK)
                     .map_ok(DenseTensor::from)
                     .map_ok(Tensor::from)
                     .map_ok(Collection::Tensor)
@@ -395,12 +670,12 @@ impl<'a> Handler<'a> for CopySparseHandler {
         Some(Box::new(|txn, mut params| {
             Box::pin(async move {
                 let schema: Value = params.require(&label("schema").into())?;
-                let schema: Schema =
-                    schema.try_cast_into(|v| TCError::bad_request("invalid Tensor schema", v))?;
+                let schema = Schema::try_from_value(schema)?;
 
                 schema.validate("copy Sparse")?;
 
                 let source: TCStream = params.require(&label("source").into())?;
+                let concurrency = concurrency(&mut params)?;
                 params.expect_empty()?;
 
                 let elements = source.into_stream(txn.clone()).await?;
@@ -428,7 +703,7 @@ impl<'a> Handler<'a> for CopySparseHandler {
 
                 elements
                     .map_ok(|(coord, value)| tensor.write_value_at(*txn.id(), coord, value))
-                    .try_buffer_unordered(num_cpus::get())
+                    .try_buffer_unordered(concurrency)
                     .try_fold((), |(), ()| future::ready(Ok(())))
                     .await?;
 
@@ -438,6 +713,97 @@ impl<'a> Handler<'a> for CopySparseHandler {
     }
 }
 
+struct CopyHandler {
+    tensor: Tensor,
+}
+
+impl<'a> Handler<'a> for CopyHandler {
+    fn get<'b>(self: Box<Self>) -> Option<GetHandler<'a, 'b>>
+    where
+        'b: 'a,
+    {
+        Some(Box::new(|txn, key| {
+            Box::pin(async move {
+                key.expect_none()?;
+
+                copy(txn, self.tensor)
+                    .map_ok(Collection::from)
+                    .map_ok(State::from)
+                    .await
+            })
+        }))
+    }
+}
+
+impl<T> From<T> for CopyHandler
+where
+    Tensor: From<T>,
+{
+    fn from(tensor: T) -> Self {
+        Self {
+            tensor: tensor.into(),
+        }
+    }
+}
+
+struct CountNonzeroHandler {
+    tensor: Tensor,
+}
+
+impl<'a> Handler<'a> for CountNonzeroHandler {
+    fn get<'b>(self: Box<Self>) -> Option<GetHandler<'a, 'b>>
+    where
+        'b: 'a,
+    {
+        Some(Box::new(|txn, key| {
+            Box::pin(async move {
+                let axis = if key.is_none() {
+                    None
+                } else {
+                    let axis = cast_axis(key, self.tensor.ndim())?;
+                    if axis == 0 && self.tensor.ndim() == 1 {
+                        None
+                    } else {
+                        Some(axis)
+                    }
+                };
+
+                if let Some(axis) = axis {
+                    let zero = self.tensor.dtype().additive_identity();
+                    let nonzero = self.tensor.ne_const(zero)?;
+                    let nonzero = TensorTransform::cast_into(nonzero, UIntType::U64.into())?;
+                    let count = nonzero.sum(axis)?;
+                    Ok(State::Collection(count.into()))
+                } else {
+                    // a sparse tensor only stores its nonzero elements, so count them directly
+                    let count = match self.tensor {
+                        Tensor::Dense(dense) => {
+                            let sparse = dense.into_sparse();
+                            sparse.into_inner().filled_count(txn.clone()).await?
+                        }
+                        Tensor::Sparse(sparse) => {
+                            sparse.into_inner().filled_count(txn.clone()).await?
+                        }
+                    };
+
+                    Ok(Value::from(count).into())
+                }
+            })
+        }))
+    }
+}
+
+impl<T> From<T> for CountNonzeroHandler
+where
+    Tensor: From<T>,
+{
+    fn from(tensor: T) -> Self {
+        Self {
+            tensor: tensor.into(),
+        }
+    }
+}
+
 struct CreateHandler {
     class: TensorType,
 }
@@ -449,17 +815,22 @@ impl<'a> Handler<'a> for CreateHandler {
     {
         Some(Box::new(|txn, key| {
             Box::pin(async move {
-                let schema: Schema =
-                    key.try_cast_into(|v| TCError::bad_request("invalid Tensor schema", v))?;
+                let (key, block_size) = split_block_size(key)?;
+                let schema = Schema::try_from_value(key)?;
 
                 match self.class {
                     TensorType::Dense => {
-                        constant(&txn, schema.shape, schema.dtype.zero())
+                        let zero = schema.dtype.additive_identity();
+                        constant(&txn, schema.shape, zero, block_size.unwrap_or(PER_BLOCK))
                             .map_ok(Tensor::from)
                             .map_ok(Collection::Tensor)
                             .map_ok(State::Collection)
                             .await
                     }
+                    TensorType::Sparse if block_size.is_some() => Err(TCError::bad_request(
+                        "a sparse Tensor does not support a custom block size",
+                        schema,
+                    )),
                     TensorType::Sparse => {
                         create_sparse(txn, schema)
                             .map_ok(Tensor::from)
@@ -485,9 +856,21 @@ impl<'a> Handler<'a> for EinsumHandler {
                 let format: TCString = params.require(&label("format").into())?;
                 let tensors: Vec<Tensor> = params.require(&TENSORS.into())?;
 
-                einsum(&format, tensors)
-                    .map(Collection::from)
-                    .map(State::from)
+                let dtype = tensors
+                    .iter()
+                    .map(TensorAccess::dtype)
+                    .fold(NumberType::Bool, promote);
+
+                // like numpy, einsum returns the dtype of its inputs rather than the wider dtype
+                // in which a contraction is summed
+                let output = einsum(&format, tensors)?;
+                let output = if output.dtype() == dtype {
+                    output
+                } else {
+                    TensorTransform::cast_into(output, dtype)?
+                };
+
+                Ok(State::Collection(output.into()))
             })
         }))
     }
@@ -562,8 +945,132 @@ impl<T> From<T> for DiagonalHandler<T> {
     }
 }
 
-struct ExpandHandler<T> {
-    tensor: T,
+struct DiffHandler {
+    tensor: Tensor,
+}
+
+impl<'a> Handler<'a> for DiffHandler {
+    fn post<'b>(self: Box<Self>) -> Option<PostHandler<'a, 'b>>
+    where
+        'b: 'a,
+    {
+        Some(Box::new(|_txn, mut params| {
+            Box::pin(async move {
+                let r: Tensor = params.require(&label("r").into())?;
+                let tolerance = params.option(&label("tolerance").into(), || Number::from(0))?;
+                params.expect_empty()?;
+
+                if tolerance.is_nan() || tolerance < Number::from(0) {
+                    return Err(TCError::bad_request(
+                        "diff requires a non-negative tolerance, not",
+                        tolerance,
+                    ));
+                }
+
+                let dtype = promote(self.tensor.dtype(), r.dtype());
+                let l = TensorTransform::cast_into(self.tensor, dtype)?;
+                let r = TensorTransform::cast_into(r, dtype)?;
+                let (l, r) = if l.shape() == r.shape() {
+                    (l, r)
+                } else {
+                    broadcast(l, r)?
+                };
+
+                let mask = if tolerance == Number::from(0) {
+                    TensorCompare::ne(l.clone(), r.clone())?
+                } else {
+                    // subtract in floating point so that unsigned integers can't overflow
+                    let (l, r) = match dtype {
+                        NumberType::Complex(_) | NumberType::Float(_) => (l.clone(), r.clone()),
+                        _ => (
+                            TensorTransform::cast_into(l.clone(), FloatType::F64.into())?,
+                            TensorTransform::cast_into(r.clone(), FloatType::F64.into())?,
+                        ),
+                    };
+
+                    l.sub(r)?.abs()?.gt_const(tolerance)?
+                };
+
+                Ok(TCStream::diff(l, r, mask).into())
+            })
+        }))
+    }
+}
+
+impl<T> From<T> for DiffHandler
+where
+    Tensor: From<T>,
+{
+    fn from(tensor: T) -> Self {
+        Self {
+            tensor: tensor.into(),
+        }
+    }
+}
+
+struct DotHandler {
+    tensor: Tensor,
+}
+
+impl<'a> Handler<'a> for DotHandler {
+    fn post<'b>(self: Box<Self>) -> Option<PostHandler<'a, 'b>>
+    where
+        'b: 'a,
+    {
+        Some(Box::new(|txn, mut params| {
+            Box::pin(async move {
+                let r: Tensor = params.require(&label("r").into())?;
+                params.expect_empty()?;
+
+                let l = self.tensor;
+                if l.ndim() != 1 || r.ndim() != 1 {
+                    return Err(TCError::unsupported(format!(
+                        "dot product requires two vectors, not Tensors with shapes {} and {}",
+                        l.shape(),
+                        r.shape()
+                    )));
+                } else if l.shape() != r.shape() {
+                    return Err(TCError::bad_request(
+                        format!(
+                            "cannot compute the dot product of vectors with shapes {} and",
+                            l.shape()
+                        ),
+                        r.shape(),
+                    ));
+                }
+
+                let (l, r) = if l.dtype() == r.dtype() {
+                    (l, r)
+                } else {
+                    let dtype = promote(l.dtype(), r.dtype());
+                    let l = TensorTransform::cast_into(l, dtype)?;
+                    let r = TensorTransform::cast_into(r, dtype)?;
+                    (l, r)
+                };
+
+                // if either operand is sparse, the product is sparse,
+                // so only the coordinates which are filled in both will be summed
+                let product = TensorMath::mul(l, r)?;
+                let dot = product.sum_all(txn.clone()).await?;
+                Ok(Value::from(dot).into())
+            })
+        }))
+    }
+}
+
+impl<T> From<T> for DotHandler
+where
+    Tensor: From<T>,
+{
+    fn from(tensor: T) -> Self {
+        Self {
+            tensor: tensor.into(),
+        }
+    }
+}
+
+struct ExpandHandler<T> {
+    tensor: T,
 }
 
 impl<'a, T> Handler<'a> for ExpandHandler<T>
@@ -635,151 +1142,1196 @@ impl<T> From<T> for FlipHandler<T> {
     }
 }
 
-struct RandomNormalHandler;
+struct GatherHandler {
+    tensor: Tensor,
+}
 
-impl<'a> Handler<'a> for RandomNormalHandler {
+impl<'a> Handler<'a> for GatherHandler {
+    fn post<'b>(self: Box<Self>) -> Option<PostHandler<'a, 'b>>
+    where
+        'b: 'a,
+    {
+        Some(Box::new(|txn, mut params| {
+            Box::pin(async move {
+                let axis = params.require(&AXIS.into())?;
+                let axis = cast_axis(axis, self.tensor.ndim())?;
+                let indices: Tensor = params.require(&label("indices").into())?;
+                params.expect_empty()?;
+
+                let dim = self.tensor.shape()[axis];
+                let indices = read_indices(txn, indices, dim).await?;
+
+                let mut shape = self.tensor.shape().clone();
+                shape[axis] = indices.len() as u64;
+
+                let output = create_like(txn, &self.tensor, shape.clone()).await?;
+
+                let mut writes = FuturesUnordered::new();
+                for (i, index) in indices.into_iter().enumerate() {
+                    let i = i as u64;
+
+                    let mut source = Bounds::all(self.tensor.shape());
+                    source[axis] = AxisBounds::In(index..(index + 1));
+
+                    let mut dest = Bounds::all(&shape);
+                    dest[axis] = AxisBounds::In(i..(i + 1));
+
+                    let slice = self.tensor.clone().slice(source)?;
+                    writes.push(output.clone().write(txn.clone(), dest, slice));
+                }
+
+                while let Some(()) = writes.try_next().await? {
+                    // no-op
+                }
+
+                Ok(State::Collection(output.into()))
+            })
+        }))
+    }
+}
+
+impl<T> From<T> for GatherHandler
+where
+    Tensor: From<T>,
+{
+    fn from(tensor: T) -> Self {
+        Self {
+            tensor: tensor.into(),
+        }
+    }
+}
+
+struct LinspaceHandler;
+
+impl<'a> Handler<'a> for LinspaceHandler {
     fn get<'b>(self: Box<Self>) -> Option<GetHandler<'a, 'b>>
     where
         'b: 'a,
     {
         Some(Box::new(|txn, key| {
             Box::pin(async move {
-                let shape =
-                    key.try_cast_into(|v| TCError::bad_request("invalid shape for Tensor", v))?;
+                let (start, stop, num): (Number, Number, u64) = key.try_cast_into(|v| {
+                    TCError::bad_request("invalid (start, stop, num) for linspace", v)
+                })?;
 
-                let file = create_file(&txn).await?;
+                if num == 0 {
+                    return Err(TCError::bad_request(
+                        "linspace requires at least one value, not",
+                        num,
+                    ));
+                }
 
-                let tensor = BlockListFile::random_normal(
+                let is_int =
+                    |n: &Number| matches!(n.class(), NumberType::Int(_) | NumberType::UInt(_));
+
+                // use an integer dtype only if every value is an integer
+                let (start_i, stop_i) = (i64::cast_from(start), i64::cast_from(stop));
+                let exact = is_int(&start)
+                    && is_int(&stop)
+                    && (num == 1 || (stop_i - start_i) % (num as i64 - 1) == 0);
+
+                let dtype = if exact {
+                    promote(start.class(), stop.class())
+                } else {
+                    FloatType::F64.into()
+                };
+
+                let values: TCBoxTryStream<'static, Number> = if exact {
+                    let step = if num == 1 {
+                        0
+                    } else {
+                        (stop_i - start_i) / (num as i64 - 1)
+                    };
+
+                    let values = (0..num as i64)
+                        .map(move |i| Number::from(start_i + (i * step)).into_type(dtype))
+                        .map(Ok);
+
+                    Box::pin(stream::iter(values))
+                } else {
+                    let (start_f, stop_f) = (f64::cast_from(start), f64::cast_from(stop));
+                    let step = if num == 1 {
+                        0.
+                    } else {
+                        (stop_f - start_f) / (num - 1) as f64
+                    };
+
+                    let values = (0..num)
+                        .map(move |i| {
+                            if i == num - 1 && num > 1 {
+                                stop_f
+                            } else {
+                                start_f + (i as f64 * step)
+                            }
+                        })
+                        .map(Number::from)
+                        .map(Ok);
+
+                    Box::pin(stream::iter(values))
+                };
+
+                let file = create_file(txn).await?;
+                DenseTensorFile::from_values(
                     file,
                     *txn.id(),
-                    shape,
-                    FloatType::F64,
-                    MEAN.into(),
-                    STD.into(),
+                    vec![num].into(),
+                    dtype,
+                    values,
+                    PER_BLOCK,
                 )
                 .map_ok(DenseTensor::from)
                 .map_ok(Tensor::from)
-                .await?;
-
-                Ok(State::Collection(tensor.into()))
+                .map_ok(Collection::Tensor)
+                .map_ok(State::Collection)
+                .await
             })
         }))
     }
+}
+
+struct MaskedFillHandler {
+    tensor: Tensor,
+}
 
+impl<'a> Handler<'a> for MaskedFillHandler {
     fn post<'b>(self: Box<Self>) -> Option<PostHandler<'a, 'b>>
     where
         'b: 'a,
     {
         Some(Box::new(|txn, mut params| {
             Box::pin(async move {
-                let shape: Vec<u64> = params.require(&label("shape").into())?;
-                let mean = params.option(&label("mean").into(), || MEAN.into())?;
-                let std = params.option(&label("std").into(), || STD.into())?;
+                let mask: Tensor = params.require(&label("mask").into())?;
+                let value: Value = params.require(&label("value").into())?;
                 params.expect_empty()?;
 
-                let file = create_file(&txn).await?;
+                let value = match value {
+                    Value::Number(n) => Ok(n),
+                    Value::String(s) => match s.as_str() {
+                        "inf" => Ok(f64::INFINITY.into()),
+                        "-inf" => Ok(f64::NEG_INFINITY.into()),
+                        "nan" => Ok(f64::NAN.into()),
+                        _ => Err(TCError::bad_request("invalid fill value", s)),
+                    },
+                    other => Err(TCError::bad_request("invalid fill value", other)),
+                }?;
 
-                let tensor = BlockListFile::random_normal(
-                    file,
-                    *txn.id(),
-                    shape.into(),
-                    FloatType::F64,
-                    mean,
-                    std,
-                )
-                .map_ok(DenseTensor::from)
-                .map_ok(Tensor::from)
-                .await?;
+                let dtype = self.tensor.dtype();
+                match dtype {
+                    NumberType::Float(_) | NumberType::Complex(_) => {}
+                    _ if value.is_infinite() || value.is_nan() => {
+                        return Err(TCError::bad_request(
+                            format!("cannot fill a Tensor of type {} with", dtype),
+                            value,
+                        ));
+                    }
+                    _ => {}
+                }
 
-                Ok(State::Collection(tensor.into()))
+                let shape = self.tensor.shape().clone();
+                let mask = TensorTransform::cast_into(mask, NumberType::Bool)?;
+                let mask = if mask.shape() == &shape {
+                    mask
+                } else {
+                    mask.broadcast(shape)?
+                };
+
+                let output = copy(txn, self.tensor).await?;
+                fill_where(txn, &output, mask, value.into_type(dtype)).await?;
+
+                Ok(State::Collection(output.into()))
             })
         }))
     }
 }
 
-struct RandomUniformHandler;
+impl<T> From<T> for MaskedFillHandler
+where
+    Tensor: From<T>,
+{
+    fn from(tensor: T) -> Self {
+        Self {
+            tensor: tensor.into(),
+        }
+    }
+}
 
-impl<'a> Handler<'a> for RandomUniformHandler {
-    fn get<'b>(self: Box<Self>) -> Option<GetHandler<'a, 'b>>
+struct MaskedReduceHandler {
+    tensor: Tensor,
+    mean: bool,
+}
+
+impl MaskedReduceHandler {
+    fn new<T>(tensor: T, mean: bool) -> Self
+    where
+        Tensor: From<T>,
+    {
+        Self {
+            tensor: tensor.into(),
+            mean,
+        }
+    }
+}
+
+impl<'a> Handler<'a> for MaskedReduceHandler {
+    fn post<'b>(self: Box<Self>) -> Option<PostHandler<'a, 'b>>
     where
         'b: 'a,
     {
-        Some(Box::new(|txn, key| {
+        Some(Box::new(|txn, mut params| {
             Box::pin(async move {
-                let shape =
-                    key.try_cast_into(|v| TCError::bad_request("invalid shape for Tensor", v))?;
+                let mask: Tensor = params.require(&label("mask").into())?;
+                let axis: Value = params.or_default(&AXIS.into())?;
+                params.expect_empty()?;
 
-                let file = create_file(&txn).await?;
+                let op = if self.mean {
+                    "masked_mean"
+                } else {
+                    "masked_sum"
+                };
 
-                let tensor = BlockListFile::random_uniform(file, *txn.id(), shape, FloatType::F64)
-                    .map_ok(DenseTensor::from)
-                    .map_ok(Tensor::from)
-                    .await?;
+                let ndim = self.tensor.ndim();
+                let axis = if axis.is_none() {
+                    None
+                } else if ndim == 1 {
+                    // reducing a 1-D Tensor along an axis would return a scalar, not a Tensor
+                    return Err(TCError::bad_request(
+                        format!("to reduce a 1-D Tensor, call {} without an axis", op),
+                        axis,
+                    ));
+                } else {
+                    Some(cast_axis(axis, ndim)?)
+                };
 
-                Ok(State::Collection(tensor.into()))
+                let shape = self.tensor.shape().clone();
+                let mask = TensorTransform::cast_into(mask, NumberType::Bool)?;
+                let mask = if mask.shape() == &shape {
+                    mask
+                } else {
+                    mask.broadcast(shape)?
+                };
+
+                let tensor = match self.tensor.dtype() {
+                    NumberType::Float(_) | NumberType::Complex(_) => self.tensor,
+                    _ if self.mean => {
+                        TensorTransform::cast_into(self.tensor, FloatType::F64.into())?
+                    }
+                    _ => self.tensor,
+                };
+
+                // zero out the masked-out elements
+                let masked = tensor
+                    .clone()
+                    .mul(TensorTransform::cast_into(mask.clone(), tensor.dtype())?)?;
+
+                let count = TensorTransform::cast_into(mask, UIntType::U64.into())?;
+
+                match axis {
+                    Some(axis) if self.mean => {
+                        let count = count.sum(axis)?;
+                        if !count.clone().all(txn.clone()).await? {
+                            return Err(TCError::bad_request(
+                                format!("{} requires at least one unmasked element along axis", op),
+                                axis,
+                            ));
+                        }
+
+                        let count = TensorTransform::cast_into(count, masked.dtype())?;
+                        let mean = masked.sum(axis)?.div(count)?;
+                        Ok(State::Collection(mean.into()))
+                    }
+                    Some(axis) => {
+                        let sum = masked.sum(axis)?;
+                        Ok(State::Collection(sum.into()))
+                    }
+                    None if self.mean => {
+                        let count = count.sum_all(txn.clone()).await?;
+                        if count == Number::from(0u64) {
+                            return Err(TCError::unsupported(format!(
+                                "{} requires at least one unmasked element",
+                                op
+                            )));
+                        }
+
+                        let masked = masked.div_const(count)?;
+                        let mean = masked.sum_all(txn.clone()).await?;
+                        Ok(Value::from(mean).into())
+                    }
+                    None => {
+                        let sum = masked.sum_all(txn.clone()).await?;
+                        Ok(Value::from(sum).into())
+                    }
+                }
             })
         }))
     }
 }
 
-struct RangeHandler;
+struct MeshgridHandler;
 
-impl<'a> Handler<'a> for RangeHandler {
-    fn get<'b>(self: Box<Self>) -> Option<GetHandler<'a, 'b>>
+impl<'a> Handler<'a> for MeshgridHandler {
+    fn post<'b>(self: Box<Self>) -> Option<PostHandler<'a, 'b>>
     where
         'b: 'a,
     {
-        Some(Box::new(|txn, key| {
+        Some(Box::new(|_txn, mut params| {
             Box::pin(async move {
-                if key.matches::<(Vec<u64>, Number, Number)>() {
-                    let (shape, start, stop): (Vec<u64>, Number, Number) =
-                        key.opt_cast_into().unwrap();
+                let tensors: Vec<Tensor> = params.require(&TENSORS.into())?;
+                params.expect_empty()?;
+
+                if tensors.is_empty() {
+                    return Err(TCError::bad_request("no Tensors to meshgrid", "[]"));
+                }
 
-                    let file = create_file(&txn).await?;
+                let mut shape = Vec::with_capacity(tensors.len());
+                for tensor in &tensors {
+                    if tensor.ndim() != 1 {
+                        return Err(TCError::bad_request(
+                            "meshgrid requires 1-dimensional Tensors, not",
+                            tensor,
+                        ));
+                    }
 
-                    DenseTensor::range(file, *txn.id(), shape, start, stop)
-                        .map_ok(Tensor::from)
-                        .map_ok(Collection::from)
-                        .map_ok(State::from)
-                        .await
-                } else {
-                    Err(TCError::bad_request("invalid schema for range tensor", key))
+                    shape.push(tensor.shape()[0]);
                 }
+
+                let ndim = shape.len();
+                let mut grids = Vec::with_capacity(ndim);
+                for (axis, tensor) in tensors.into_iter().enumerate() {
+                    let mut axis_shape = vec![1; ndim];
+                    axis_shape[axis] = shape[axis];
+
+                    let grid = tensor
+                        .reshape(axis_shape.into())?
+                        .broadcast(shape.to_vec().into())?;
+
+                    grids.push(State::Collection(grid.into()));
+                }
+
+                Ok(State::Tuple(grids.into()))
             })
         }))
     }
 }
 
-struct ReshapeHandler<T> {
-    tensor: T,
+struct MovingAverageHandler {
+    tensor: Tensor,
+}
+
+impl<'a> Handler<'a> for MovingAverageHandler {
+    fn post<'b>(self: Box<Self>) -> Option<PostHandler<'a, 'b>>
+    where
+        'b: 'a,
+    {
+        Some(Box::new(|txn, mut params| {
+            Box::pin(async move {
+                let window: u64 = params.require(&label("window").into())?;
+                let axis: Value = params.or_default(&AXIS.into())?;
+                let mode: Value = params.or_default(&label("mode").into())?;
+                params.expect_empty()?;
+
+                self.tensor.shape().validate("moving_average")?;
+
+                let ndim = self.tensor.ndim();
+                if ndim == 0 {
+                    return Err(TCError::bad_request(
+                        "moving_average requires at least one axis, found shape",
+                        self.tensor.shape(),
+                    ));
+                }
+
+                let axis = if axis.is_none() {
+                    ndim - 1
+                } else {
+                    cast_axis(axis, ndim)?
+                };
+
+                let full = if mode.is_none() {
+                    false
+                } else {
+                    let mode: TCString = mode.try_cast_into(|v| {
+                        TCError::bad_request("invalid moving_average mode", v)
+                    })?;
+
+                    match mode.as_str() {
+                        "valid" => false,
+                        "full" => true,
+                        _ => {
+                            return Err(TCError::bad_request(
+                                "moving_average mode must be \"valid\" or \"full\", not",
+                                mode,
+                            ))
+                        }
+                    }
+                };
+
+                let dim = self.tensor.shape()[axis];
+                if window == 0 || window > dim {
+                    return Err(TCError::bad_request(
+                        format!(
+                            "moving_average window must be between 1 and {} (the dimension of axis {}), not",
+                            dim, axis
+                        ),
+                        window,
+                    ));
+                }
+
+                let tensor = match self.tensor.dtype() {
+                    NumberType::Float(_) | NumberType::Complex(_) => self.tensor,
+                    _ => TensorTransform::cast_into(self.tensor, FloatType::F64.into())?,
+                };
+
+                moving_average(txn, tensor, axis, window as usize, full)
+                    .map_ok(Collection::from)
+                    .map_ok(State::from)
+                    .await
+            })
+        }))
+    }
+}
+
+impl<T> From<T> for MovingAverageHandler
+where
+    Tensor: From<T>,
+{
+    fn from(tensor: T) -> Self {
+        Self {
+            tensor: tensor.into(),
+        }
+    }
+}
+
+struct NanToNumHandler {
+    tensor: Tensor,
+}
+
+impl<'a> Handler<'a> for NanToNumHandler {
+    fn post<'b>(self: Box<Self>) -> Option<PostHandler<'a, 'b>>
+    where
+        'b: 'a,
+    {
+        Some(Box::new(|txn, mut params| {
+            Box::pin(async move {
+                let dtype = self.tensor.dtype();
+                let (max, min): (Number, Number) = match dtype {
+                    NumberType::Float(FloatType::F32) => (f32::MAX.into(), f32::MIN.into()),
+                    NumberType::Float(_) => (f64::MAX.into(), f64::MIN.into()),
+                    NumberType::Complex(_) => {
+                        return Err(TCError::not_implemented("nan_to_num for a complex Tensor"))
+                    }
+                    _ => {
+                        // an integer Tensor can't contain NaN or infinite values
+                        params.expect_empty()?;
+                        return copy(txn, self.tensor)
+                            .map_ok(Collection::from)
+                            .map_ok(State::from)
+                            .await;
+                    }
+                };
+
+                let nan = params.option(&label("nan").into(), || dtype.zero())?;
+                let posinf = params.option(&label("posinf").into(), || max)?;
+                let neginf = params.option(&label("neginf").into(), || min)?;
+                params.expect_empty()?;
+
+                for value in [nan, posinf, neginf].iter() {
+                    if value.is_nan() || value.is_infinite() {
+                        return Err(TCError::bad_request(
+                            "nan_to_num requires a finite replacement value, not",
+                            value,
+                        ));
+                    }
+                }
+
+                let masks = vec![
+                    (is_nan(&self.tensor)?, nan),
+                    (self.tensor.clone().eq_const(f64::INFINITY.into())?, posinf),
+                    (
+                        self.tensor.clone().eq_const(f64::NEG_INFINITY.into())?,
+                        neginf,
+                    ),
+                ];
+
+                let output = copy(txn, self.tensor).await?;
+                for (mask, value) in masks {
+                    fill_where(txn, &output, mask, value.into_type(dtype)).await?;
+                }
+
+                Ok(State::Collection(output.into()))
+            })
+        }))
+    }
+}
+
+impl<T> From<T> for NanToNumHandler
+where
+    Tensor: From<T>,
+{
+    fn from(tensor: T) -> Self {
+        Self {
+            tensor: tensor.into(),
+        }
+    }
+}
+
+struct OuterHandler {
+    tensor: Tensor,
+}
+
+impl<'a> Handler<'a> for OuterHandler {
+    fn post<'b>(self: Box<Self>) -> Option<PostHandler<'a, 'b>>
+    where
+        'b: 'a,
+    {
+        Some(Box::new(|_txn, mut params| {
+            Box::pin(async move {
+                let r: Tensor = params.require(&label("r").into())?;
+                params.expect_empty()?;
+
+                let l = self.tensor;
+                if l.ndim() != 1 || r.ndim() != 1 {
+                    return Err(TCError::unsupported(format!(
+                        "outer product requires two vectors, not Tensors with shapes {} and {}",
+                        l.shape(),
+                        r.shape()
+                    )));
+                }
+
+                let (l, r) = if l.dtype() == r.dtype() {
+                    (l, r)
+                } else {
+                    let dtype = promote(l.dtype(), r.dtype());
+                    let l = TensorTransform::cast_into(l, dtype)?;
+                    let r = TensorTransform::cast_into(r, dtype)?;
+                    (l, r)
+                };
+
+                einsum("i,j->ij", vec![l, r])
+                    .map(Collection::from)
+                    .map(State::from)
+            })
+        }))
+    }
+}
+
+impl<T> From<T> for OuterHandler
+where
+    Tensor: From<T>,
+{
+    fn from(tensor: T) -> Self {
+        Self {
+            tensor: tensor.into(),
+        }
+    }
+}
+
+// a floating-point predicate like `isnan`, which is constant for a non-float `Tensor`
+struct PredicateHandler {
+    tensor: Tensor,
+    op: fn(&Tensor) -> TCResult<Tensor>,
+    non_float: bool,
+    op_name: &'static str,
+}
+
+impl PredicateHandler {
+    fn new(
+        tensor: Tensor,
+        op: fn(&Tensor) -> TCResult<Tensor>,
+        non_float: bool,
+        op_name: &'static str,
+    ) -> Self {
+        Self {
+            tensor,
+            op,
+            non_float,
+            op_name,
+        }
+    }
+}
+
+impl<'a> Handler<'a> for PredicateHandler {
+    fn get<'b>(self: Box<Self>) -> Option<GetHandler<'a, 'b>>
+    where
+        'b: 'a,
+    {
+        Some(Box::new(|txn, key| {
+            Box::pin(async move {
+                self.tensor.shape().validate(self.op_name)?;
+
+                let tensor = if key.is_none() {
+                    self.tensor
+                } else {
+                    let bounds = cast_bounds(self.tensor.shape(), key.into())?;
+                    self.tensor.slice(bounds)?
+                };
+
+                let tensor = match tensor.dtype() {
+                    NumberType::Float(_) | NumberType::Complex(_) => (self.op)(&tensor)?,
+                    _ => {
+                        // no need to read the elements of an integer Tensor
+                        let shape = tensor.shape().clone();
+                        constant(txn, shape, self.non_float.into(), PER_BLOCK)
+                            .map_ok(Tensor::from)
+                            .await?
+                    }
+                };
+
+                Ok(State::Collection(tensor.into()))
+            })
+        }))
+    }
+}
+
+struct QuantileHandler {
+    tensor: Tensor,
+}
+
+impl<'a> Handler<'a> for QuantileHandler {
+    fn post<'b>(self: Box<Self>) -> Option<PostHandler<'a, 'b>>
+    where
+        'b: 'a,
+    {
+        Some(Box::new(|txn, mut params| {
+            Box::pin(async move {
+                let q: Value = params.require(&label("q").into())?;
+                let axis: Value = params.or_default(&AXIS.into())?;
+                params.expect_empty()?;
+
+                let (q, multiple) = match q {
+                    Value::Tuple(q) => {
+                        let q = q
+                            .into_iter()
+                            .map(cast_quantile)
+                            .collect::<TCResult<Vec<f64>>>()?;
+
+                        (q, true)
+                    }
+                    q => (vec![cast_quantile(q)?], false),
+                };
+
+                if q.is_empty() {
+                    return Err(TCError::bad_request(
+                        "quantile requires at least one value of q, found",
+                        Value::Tuple(Tuple::default()),
+                    ));
+                }
+
+                if let NumberType::Complex(_) = self.tensor.dtype() {
+                    return Err(TCError::not_implemented("quantile for a complex Tensor"));
+                }
+
+                self.tensor.shape().validate("quantile")?;
+
+                if self.tensor.size() == 0 {
+                    return Err(TCError::bad_request(
+                        "cannot compute a quantile of an empty Tensor with shape",
+                        self.tensor.shape(),
+                    ));
+                }
+
+                let all = axis.is_none();
+                let (tensor, axis) = if all {
+                    let size = self.tensor.size();
+                    (self.tensor.reshape(vec![size].into())?, 0)
+                } else {
+                    let axis = cast_axis(axis, self.tensor.ndim())?;
+                    (self.tensor, axis)
+                };
+
+                let dim = tensor.shape()[axis];
+                let mut shape = tensor.shape().clone();
+                shape.remove(axis);
+                let ndim = shape.len();
+                let num_slices = shape.size();
+                shape.push(q.len() as u64);
+
+                // move the axis to reduce last, so that each row is one 1-D slice to sort
+                let permutation = axis_last(tensor.ndim(), axis);
+                let slices = tensor
+                    .transpose(Some(permutation))?
+                    .reshape(vec![num_slices, dim].into())?;
+
+                let mut values = Vec::with_capacity(num_slices as usize * q.len());
+                for i in 0..num_slices {
+                    let slice = slices.clone().slice(Bounds::from(vec![AxisBounds::At(i)]))?;
+                    values.extend(interpolate_quantiles(txn, slice, &q).await?);
+                }
+
+                let values = stream::iter(values.into_iter().map(Ok));
+                let file = create_file(txn).await?;
+                let dtype = FloatType::F64.into();
+                let quantiles =
+                    DenseTensorFile::from_values(file, *txn.id(), shape, dtype, values, PER_BLOCK)
+                        .map_ok(DenseTensor::from)
+                        .map_ok(Tensor::from)
+                        .await?;
+
+                if all && !multiple {
+                    let quantile = quantiles.read_value(txn.clone(), vec![0]).await?;
+                    Ok(Value::from(quantile).into())
+                } else if multiple {
+                    // move the axis of quantiles to the front, as in NumPy
+                    let permutation = std::iter::once(ndim).chain(0..ndim).collect();
+                    let quantiles = quantiles.transpose(Some(permutation))?;
+                    Ok(State::Collection(quantiles.into()))
+                } else {
+                    let mut shape = quantiles.shape().clone();
+                    shape.pop();
+                    let quantiles = quantiles.reshape(shape)?;
+                    Ok(State::Collection(quantiles.into()))
+                }
+            })
+        }))
+    }
+}
+
+impl<T> From<T> for QuantileHandler
+where
+    Tensor: From<T>,
+{
+    fn from(tensor: T) -> Self {
+        Self {
+            tensor: tensor.into(),
+        }
+    }
+}
+
+struct RandomNormalHandler;
+
+impl<'a> Handler<'a> for RandomNormalHandler {
+    fn get<'b>(self: Box<Self>) -> Option<GetHandler<'a, 'b>>
+    where
+        'b: 'a,
+    {
+        Some(Box::new(|txn, key| {
+            Box::pin(async move {
+                let shape =
+                    key.try_cast_into(|v| TCError::bad_request("invalid shape for Tensor", v))?;
+
+                let file = create_file(&txn).await?;
+
+                let tensor = BlockListFile::random_normal(
+                    file,
+                    *txn.id(),
+                    shape,
+                    FloatType::F64,
+                    MEAN.into(),
+                    STD.into(),
+                )
+                .map_ok(DenseTensor::from)
+                .map_ok(Tensor::from)
+                .await?;
+
+                Ok(State::Collection(tensor.into()))
+            })
+        }))
+    }
+
+    fn post<'b>(self: Box<Self>) -> Option<PostHandler<'a, 'b>>
+    where
+        'b: 'a,
+    {
+        Some(Box::new(|txn, mut params| {
+            Box::pin(async move {
+                let shape: Vec<u64> = params.require(&label("shape").into())?;
+                let mean = params.option(&label("mean").into(), || MEAN.into())?;
+                let std = params.option(&label("std").into(), || STD.into())?;
+                params.expect_empty()?;
+
+                let file = create_file(&txn).await?;
+
+                let tensor = BlockListFile::random_normal(
+                    file,
+                    *txn.id(),
+                    shape.into(),
+                    FloatType::F64,
+                    mean,
+                    std,
+                )
+                .map_ok(DenseTensor::from)
+                .map_ok(Tensor::from)
+                .await?;
+
+                Ok(State::Collection(tensor.into()))
+            })
+        }))
+    }
+}
+
+struct RandomUniformHandler;
+
+impl<'a> Handler<'a> for RandomUniformHandler {
+    fn get<'b>(self: Box<Self>) -> Option<GetHandler<'a, 'b>>
+    where
+        'b: 'a,
+    {
+        Some(Box::new(|txn, key| {
+            Box::pin(async move {
+                let shape =
+                    key.try_cast_into(|v| TCError::bad_request("invalid shape for Tensor", v))?;
+
+                let file = create_file(&txn).await?;
+
+                let tensor = BlockListFile::random_uniform(file, *txn.id(), shape, FloatType::F64)
+                    .map_ok(DenseTensor::from)
+                    .map_ok(Tensor::from)
+                    .await?;
+
+                Ok(State::Collection(tensor.into()))
+            })
+        }))
+    }
+}
+
+struct RangeHandler;
+
+impl<'a> Handler<'a> for RangeHandler {
+    fn get<'b>(self: Box<Self>) -> Option<GetHandler<'a, 'b>>
+    where
+        'b: 'a,
+    {
+        Some(Box::new(|txn, key| {
+            Box::pin(async move {
+                let (shape, start, stop, dtype) = if key.matches::<(Vec<u64>, Number, Number)>() {
+                    let (shape, start, stop): (Vec<u64>, Number, Number) =
+                        key.opt_cast_into().unwrap();
+
+                    (shape, start, stop, None)
+                } else if key.matches::<(Vec<u64>, Number, Number, ValueType)>() {
+                    let (shape, start, stop, dtype): (Vec<u64>, Number, Number, ValueType) =
+                        key.opt_cast_into().unwrap();
+
+                    (shape, start, stop, Some(dtype.try_into()?))
+                } else {
+                    return Err(TCError::bad_request("invalid schema for range tensor", key));
+                };
+
+                let file = create_file(&txn).await?;
+
+                DenseTensor::range(file, *txn.id(), shape, start, stop, dtype)
+                    .map_ok(Tensor::from)
+                    .map_ok(Collection::from)
+                    .map_ok(State::from)
+                    .await
+            })
+        }))
+    }
+}
+
+struct RepeatHandler {
+    tensor: Tensor,
+}
+
+impl<'a> Handler<'a> for RepeatHandler {
+    fn post<'b>(self: Box<Self>) -> Option<PostHandler<'a, 'b>>
+    where
+        'b: 'a,
+    {
+        Some(Box::new(|txn, mut params| {
+            Box::pin(async move {
+                let axis = params.require(&AXIS.into())?;
+                let axis = cast_axis(axis, self.tensor.ndim())?;
+                let repeats: Value = params.require(&label("repeats").into())?;
+                params.expect_empty()?;
+
+                let dim = self.tensor.shape()[axis];
+                let repeats: Vec<Number> = match repeats {
+                    Value::Number(n) => Ok(vec![n; dim as usize]),
+                    Value::Tuple(repeats) if repeats.len() as u64 == dim => repeats
+                        .try_cast_into(|v| TCError::bad_request("invalid list of repeats", v)),
+                    Value::Tuple(repeats) => Err(TCError::unsupported(format!(
+                        "expected {} repeats for axis {} but found {}",
+                        dim,
+                        axis,
+                        repeats.len()
+                    ))),
+                    other => Err(TCError::bad_request("invalid repeats", other)),
+                }?;
+
+                let repeats = repeats
+                    .into_iter()
+                    .map(|n| {
+                        let is_integer =
+                            matches!(n.class(), NumberType::Int(_) | NumberType::UInt(_));
+
+                        if is_integer && n >= Number::from(1) {
+                            Ok(n.cast_into())
+                        } else {
+                            Err(TCError::bad_request(
+                                "repeat count must be a positive integer, not",
+                                n,
+                            ))
+                        }
+                    })
+                    .collect::<TCResult<Vec<u64>>>()?;
+
+                let mut shape = self.tensor.shape().clone();
+                shape[axis] = repeats.iter().sum();
+
+                let output = create_like(txn, &self.tensor, shape).await?;
+
+                let mut offset = 0;
+                for (i, repeat) in repeats.into_iter().enumerate() {
+                    let i = i as u64;
+
+                    let mut source = Bounds::all(self.tensor.shape());
+                    source[axis] = AxisBounds::In(i..(i + 1));
+
+                    let mut dest = Bounds::all(output.shape());
+                    dest[axis] = AxisBounds::In(offset..(offset + repeat));
+
+                    let mut slice_shape = self.tensor.shape().clone();
+                    slice_shape[axis] = repeat;
+
+                    let value = self.tensor.clone().slice(source)?.broadcast(slice_shape)?;
+                    output.clone().write(txn.clone(), dest, value).await?;
+
+                    offset += repeat;
+                }
+
+                Ok(State::Collection(output.into()))
+            })
+        }))
+    }
+}
+
+impl<T> From<T> for RepeatHandler
+where
+    Tensor: From<T>,
+{
+    fn from(tensor: T) -> Self {
+        Self {
+            tensor: tensor.into(),
+        }
+    }
+}
+
+struct ReshapeHandler<T> {
+    tensor: T,
 }
 
 impl<'a, T> Handler<'a> for ReshapeHandler<T>
 where
-    T: TensorAccess + TensorTransform + Send + 'a,
-    Tensor: From<T::Reshape>,
+    T: TensorAccess + TensorTransform + Send + 'a,
+    Tensor: From<T::Reshape>,
+{
+    fn get<'b>(self: Box<Self>) -> Option<GetHandler<'a, 'b>>
+    where
+        'b: 'a,
+    {
+        Some(Box::new(|_txn, key| {
+            Box::pin(async move {
+                let shape = key.try_into()?;
+                let shape = cast_shape(shape, self.tensor.size())?;
+                self.tensor
+                    .reshape(shape.into())
+                    .map(Tensor::from)
+                    .map(Collection::from)
+                    .map(State::from)
+            })
+        }))
+    }
+}
+
+impl<T> From<T> for ReshapeHandler<T> {
+    fn from(tensor: T) -> Self {
+        Self { tensor }
+    }
+}
+
+struct RollHandler {
+    tensor: Tensor,
+}
+
+impl RollHandler {
+    async fn roll(txn: &Txn, tensor: Tensor, shift: i64, axis: usize) -> TCResult<Tensor> {
+        let shape = tensor.shape().clone();
+        let dim = shape[axis];
+
+        let output = create_like(txn, &tensor, shape.clone()).await?;
+
+        let shift = shift.rem_euclid(dim as i64) as u64;
+
+        let mut writes = FuturesUnordered::new();
+        for (source, dest) in vec![
+            (0..(dim - shift), shift..dim),
+            ((dim - shift)..dim, 0..shift),
+        ] {
+            if source.start == source.end {
+                continue;
+            }
+
+            let mut source_bounds = Bounds::all(&shape);
+            source_bounds[axis] = AxisBounds::In(source);
+
+            let mut dest_bounds = Bounds::all(&shape);
+            dest_bounds[axis] = AxisBounds::In(dest);
+
+            let slice = tensor.clone().slice(source_bounds)?;
+            writes.push(output.clone().write(txn.clone(), dest_bounds, slice));
+        }
+
+        while let Some(()) = writes.try_next().await? {
+            // no-op
+        }
+
+        Ok(output)
+    }
+}
+
+impl<'a> Handler<'a> for RollHandler {
+    fn post<'b>(self: Box<Self>) -> Option<PostHandler<'a, 'b>>
+    where
+        'b: 'a,
+    {
+        Some(Box::new(|txn, mut params| {
+            Box::pin(async move {
+                let shift: Value = params.require(&label("shift").into())?;
+                let axis: Value = params.or_default(&AXIS.into())?;
+                params.expect_empty()?;
+
+                let shift = i64::try_cast_from(shift, |v| {
+                    TCError::bad_request("invalid shift for roll", v)
+                })?;
+
+                self.tensor.shape().validate("roll")?;
+
+                let rolled = if axis.is_some() {
+                    let axis = cast_axis(axis, self.tensor.ndim())?;
+                    Self::roll(txn, self.tensor, shift, axis).await?
+                } else {
+                    // roll the flattened tensor, then restore its shape
+                    let shape = self.tensor.shape().clone();
+                    let flat = self.tensor.reshape(vec![shape.size()].into())?;
+                    Self::roll(txn, flat, shift, 0).await?.reshape(shape)?
+                };
+
+                Ok(State::Collection(rolled.into()))
+            })
+        }))
+    }
+}
+
+impl<T> From<T> for RollHandler
+where
+    Tensor: From<T>,
 {
-    fn get<'b>(self: Box<Self>) -> Option<GetHandler<'a, 'b>>
+    fn from(tensor: T) -> Self {
+        Self {
+            tensor: tensor.into(),
+        }
+    }
+}
+
+struct ScatterHandler {
+    tensor: Tensor,
+}
+
+impl<'a> Handler<'a> for ScatterHandler {
+    fn post<'b>(self: Box<Self>) -> Option<PostHandler<'a, 'b>>
     where
         'b: 'a,
     {
-        Some(Box::new(|_txn, key| {
+        Some(Box::new(|txn, mut params| {
             Box::pin(async move {
-                let shape = key.try_into()?;
-                let shape = cast_shape(shape, self.tensor.size())?;
-                self.tensor
-                    .reshape(shape.into())
-                    .map(Tensor::from)
-                    .map(Collection::from)
-                    .map(State::from)
+                let axis = params.require(&AXIS.into())?;
+                let axis = cast_axis(axis, self.tensor.ndim())?;
+                let indices: Tensor = params.require(&label("indices").into())?;
+                let values: Tensor = params.require(&label("values").into())?;
+                params.expect_empty()?;
+
+                let dim = self.tensor.shape()[axis];
+                let indices = read_indices(txn, indices, dim).await?;
+
+                let mut shape = self.tensor.shape().clone();
+                shape[axis] = indices.len() as u64;
+                if values.shape() != &shape {
+                    return Err(TCError::bad_request(
+                        format!("expected values to scatter with shape {}, found", shape),
+                        values.shape(),
+                    ));
+                }
+
+                let output = copy(txn, self.tensor).await?;
+
+                // write each slice in order, so that the last write to a duplicate index wins
+                for (i, index) in indices.into_iter().enumerate() {
+                    let i = i as u64;
+
+                    let mut source = Bounds::all(values.shape());
+                    source[axis] = AxisBounds::In(i..(i + 1));
+
+                    let mut dest = Bounds::all(output.shape());
+                    dest[axis] = AxisBounds::In(index..(index + 1));
+
+                    let slice = values.clone().slice(source)?;
+                    output.clone().write(txn.clone(), dest, slice).await?;
+                }
+
+                Ok(State::Collection(output.into()))
             })
         }))
     }
 }
 
-impl<T> From<T> for ReshapeHandler<T> {
+impl<T> From<T> for ScatterHandler
+where
+    Tensor: From<T>,
+{
     fn from(tensor: T) -> Self {
-        Self { tensor }
+        Self {
+            tensor: tensor.into(),
+        }
+    }
+}
+
+struct SoftmaxHandler {
+    tensor: Tensor,
+}
+
+impl<'a> Handler<'a> for SoftmaxHandler {
+    fn post<'b>(self: Box<Self>) -> Option<PostHandler<'a, 'b>>
+    where
+        'b: 'a,
+    {
+        Some(Box::new(|_txn, mut params| {
+            Box::pin(async move {
+                let axis = params.require(&AXIS.into())?;
+                let axis = cast_axis(axis, self.tensor.ndim())?;
+                params.expect_empty()?;
+
+                self.tensor.shape().validate("softmax")?;
+
+                let shape = self.tensor.shape().clone();
+                let tensor = match self.tensor.dtype() {
+                    NumberType::Float(_) => self.tensor,
+                    _ => TensorTransform::cast_into(self.tensor, FloatType::F64.into())?,
+                };
+
+                // subtract the max of each slice so that exp never overflows
+                let max = tensor
+                    .clone()
+                    .max(axis)?
+                    .expand_dims(axis)?
+                    .broadcast(shape.clone())?;
+
+                let exp = tensor.sub(max)?.exp()?;
+
+                let sum = exp.clone().sum(axis)?.expand_dims(axis)?.broadcast(shape)?;
+
+                exp.div(sum).map(Collection::from).map(State::from)
+            })
+        }))
+    }
+}
+
+impl<T> From<T> for SoftmaxHandler
+where
+    Tensor: From<T>,
+{
+    fn from(tensor: T) -> Self {
+        Self {
+            tensor: tensor.into(),
+        }
     }
 }
 
@@ -828,92 +2380,357 @@ where
                     ))),
                 }?;
 
-                if sizes.iter().sum::<u64>() != dim {
+                if sizes.iter().sum::<u64>() != dim {
+                    return Err(TCError::bad_request(
+                        "invalid split sizes",
+                        sizes.into_iter().collect::<Tuple<u64>>(),
+                    ));
+                }
+
+                let mut split = Vec::with_capacity(sizes.len());
+                let mut i = 0;
+                for size in sizes.into_iter() {
+                    let mut bounds = Bounds::all(self.tensor.shape());
+                    bounds[axis] = AxisBounds::In(i..(i + size));
+
+                    let slice = self
+                        .tensor
+                        .clone()
+                        .slice(bounds)
+                        .map(Tensor::from)
+                        .map(State::from)?;
+
+                    split.push(slice);
+                    i += size;
+                }
+
+                Ok(State::Tuple(split.into()))
+            })
+        }))
+    }
+}
+
+impl<T> From<T> for SplitHandler<T> {
+    fn from(tensor: T) -> Self {
+        Self { tensor }
+    }
+}
+
+struct TileHandler;
+
+impl<'a> Handler<'a> for TileHandler {
+    fn post<'b>(self: Box<Self>) -> Option<PostHandler<'a, 'b>>
+    where
+        'b: 'a,
+    {
+        Some(Box::new(|txn, mut params| {
+            Box::pin(async move {
+                let tensor: Tensor = params.require(&TENSOR.into())?;
+                let multiples: Value = params.require(&label("multiples").into())?;
+                params.expect_empty()?;
+
+                let multiples: Vec<u64> = match multiples {
+                    Value::Number(n) if n >= Number::from(1) => {
+                        assert!(tensor.ndim() > 0);
+                        let mut multiples = vec![1; tensor.ndim() - 1];
+                        multiples.push(n.cast_into());
+                        Ok(multiples)
+                    }
+                    Value::Number(n) => Err(TCError::unsupported(format!(
+                        "cannot tile a Tensor {} times",
+                        n
+                    )))?,
+                    Value::Tuple(multiples) if multiples.len() == tensor.ndim() => multiples
+                        .try_cast_into(|v| {
+                            TCError::bad_request("invalid list of multiples for tile", v)
+                        }),
+                    other => Err(TCError::bad_request("invalid multiples for tile", other)),
+                }?;
+
+                match tensor {
+                    Tensor::Dense(dense) => {
+                        DenseTensor::tile(txn.clone(), dense, multiples)
+                            .map_ok(Tensor::from)
+                            .map_ok(State::from)
+                            .await
+                    }
+                    Tensor::Sparse(sparse) => {
+                        SparseTensor::tile(txn.clone(), sparse, multiples)
+                            .map_ok(Tensor::from)
+                            .map_ok(State::from)
+                            .await
+                    }
+                }
+            })
+        }))
+    }
+}
+
+struct ToSparseHandler {
+    tensor: Tensor,
+}
+
+impl<'a> Handler<'a> for ToSparseHandler {
+    fn get<'b>(self: Box<Self>) -> Option<GetHandler<'a, 'b>>
+    where
+        'b: 'a,
+    {
+        Some(Box::new(|txn, key| {
+            Box::pin(async move {
+                let threshold = if key.is_none() {
+                    Number::from(0)
+                } else {
+                    Number::try_cast_from(key, |v| {
+                        TCError::bad_request("invalid threshold for Tensor::to_sparse", v)
+                    })?
+                };
+
+                if threshold < Number::from(0) {
+                    return Err(TCError::bad_request(
+                        "to_sparse threshold must be non-negative, not",
+                        threshold,
+                    ));
+                }
+
+                let tensor = self.tensor;
+                let dtype = tensor.dtype();
+
+                let sparse = if threshold == Number::from(0) {
+                    tensor.into_sparse()
+                } else {
+                    let mask = tensor.abs()?.gt_const(threshold)?;
+                    let mask = TensorTransform::cast_into(mask, dtype)?;
+                    TensorMath::mul(tensor, mask)?.into_sparse()
+                };
+
+                copy(txn, sparse)
+                    .map_ok(Collection::from)
+                    .map_ok(State::from)
+                    .await
+            })
+        }))
+    }
+}
+
+impl<T> From<T> for ToSparseHandler
+where
+    Tensor: From<T>,
+{
+    fn from(tensor: T) -> Self {
+        Self {
+            tensor: tensor.into(),
+        }
+    }
+}
+
+struct ToTableHandler {
+    tensor: Tensor,
+}
+
+impl<'a> Handler<'a> for ToTableHandler {
+    fn get<'b>(self: Box<Self>) -> Option<GetHandler<'a, 'b>>
+    where
+        'b: 'a,
+    {
+        Some(Box::new(|txn, key| {
+            Box::pin(async move {
+                let include_zeros = if key.is_none() {
+                    false
+                } else {
+                    bool::try_cast_from(key, |v| {
+                        TCError::bad_request("invalid flag to include zeros", v)
+                    })?
+                };
+
+                let txn_id = *txn.id();
+                let tensor = self.tensor;
+
+                let mut key = Vec::with_capacity(tensor.ndim());
+                for x in 0..tensor.ndim() {
+                    let name: Id = format!("dim_{}", x).parse()?;
+                    key.push(Column::from((name, NumberType::UInt(UIntType::U64))));
+                }
+
+                let values = vec![Column::from((label("value"), tensor.dtype()))];
+                let schema = TableSchema::new(IndexSchema::from((key, values)), vec![]);
+
+                let dir = txn.context().create_dir_unique(txn_id).await?;
+                let table = TableIndex::create(&dir, schema, txn_id).await?;
+
+                let elements: TCBoxTryStream<(Coord, Number)> = match tensor {
+                    Tensor::Dense(dense) => dense_elements(txn, dense.into_inner()).await?,
+                    Tensor::Sparse(sparse) if include_zeros => {
+                        let dense = sparse.into_dense().into_inner().accessor();
+                        dense_elements(txn, dense).await?
+                    }
+                    Tensor::Sparse(sparse) => {
+                        let filled = sparse.into_inner().filled(txn.clone()).await?;
+                        Box::pin(filled)
+                    }
+                };
+
+                elements
+                    .map_ok(|(coord, value)| {
+                        let key = coord.into_iter().map(Number::from).map(Value::from);
+                        table.upsert(txn_id, key.collect(), vec![value.into()])
+                    })
+                    .try_buffer_unordered(num_cpus::get())
+                    .try_fold((), |(), ()| future::ready(Ok(())))
+                    .await?;
+
+                Ok(Collection::from(table).into())
+            })
+        }))
+    }
+}
+
+impl<T> From<T> for ToTableHandler
+where
+    Tensor: From<T>,
+{
+    fn from(tensor: T) -> Self {
+        Self {
+            tensor: tensor.into(),
+        }
+    }
+}
+
+struct TopKHandler {
+    tensor: Tensor,
+}
+
+impl<'a> Handler<'a> for TopKHandler {
+    fn post<'b>(self: Box<Self>) -> Option<PostHandler<'a, 'b>>
+    where
+        'b: 'a,
+    {
+        Some(Box::new(|txn, mut params| {
+            Box::pin(async move {
+                let k: u64 = params.require(&label("k").into())?;
+                let axis: Value = params.or_default(&AXIS.into())?;
+                let largest: bool = params.option(&label("largest").into(), || true)?;
+                params.expect_empty()?;
+
+                if let NumberType::Complex(_) = self.tensor.dtype() {
+                    return Err(TCError::not_implemented("topk for a complex Tensor"));
+                }
+
+                self.tensor.shape().validate("topk")?;
+
+                let ndim = self.tensor.ndim();
+                if ndim == 0 {
                     return Err(TCError::bad_request(
-                        "invalid split sizes",
-                        sizes.into_iter().collect::<Tuple<u64>>(),
+                        "topk requires at least one axis, found shape",
+                        self.tensor.shape(),
                     ));
                 }
 
-                let mut split = Vec::with_capacity(sizes.len());
-                let mut i = 0;
-                for size in sizes.into_iter() {
-                    let mut bounds = Bounds::all(self.tensor.shape());
-                    bounds[axis] = AxisBounds::In(i..(i + size));
-
-                    let slice = self
-                        .tensor
-                        .clone()
-                        .slice(bounds)
-                        .map(Tensor::from)
-                        .map(State::from)?;
+                let axis = if axis.is_none() {
+                    ndim - 1
+                } else {
+                    cast_axis(axis, ndim)?
+                };
 
-                    split.push(slice);
-                    i += size;
+                let dim = self.tensor.shape()[axis];
+                if k == 0 || k > dim {
+                    return Err(TCError::bad_request(
+                        format!(
+                            "topk requires k between 1 and {} (the dimension of axis {}), not",
+                            dim, axis
+                        ),
+                        k,
+                    ));
                 }
 
-                Ok(State::Tuple(split.into()))
+                let (values, indices) = top_k(txn, self.tensor, axis, k as usize, largest).await?;
+
+                Ok(State::Tuple(
+                    vec![
+                        State::Collection(values.into()),
+                        State::Collection(indices.into()),
+                    ]
+                    .into(),
+                ))
             })
         }))
     }
 }
 
-impl<T> From<T> for SplitHandler<T> {
+impl<T> From<T> for TopKHandler
+where
+    Tensor: From<T>,
+{
     fn from(tensor: T) -> Self {
-        Self { tensor }
+        Self {
+            tensor: tensor.into(),
+        }
     }
 }
 
-struct TileHandler;
+struct TraceHandler {
+    tensor: Tensor,
+}
 
-impl<'a> Handler<'a> for TileHandler {
-    fn post<'b>(self: Box<Self>) -> Option<PostHandler<'a, 'b>>
+impl<'a> Handler<'a> for TraceHandler {
+    fn get<'b>(self: Box<Self>) -> Option<GetHandler<'a, 'b>>
     where
         'b: 'a,
     {
-        Some(Box::new(|txn, mut params| {
+        Some(Box::new(|txn, key| {
             Box::pin(async move {
-                let tensor: Tensor = params.require(&TENSOR.into())?;
-                let multiples: Value = params.require(&label("multiples").into())?;
-                params.expect_empty()?;
+                let offset = if key.is_none() {
+                    0
+                } else {
+                    i64::try_cast_from(key, |v| TCError::bad_request("invalid diagonal offset", v))?
+                };
 
-                let multiples: Vec<u64> = match multiples {
-                    Value::Number(n) if n >= Number::from(1) => {
-                        assert!(tensor.ndim() > 0);
-                        let mut multiples = vec![1; tensor.ndim() - 1];
-                        multiples.push(n.cast_into());
-                        Ok(multiples)
-                    }
-                    Value::Number(n) => Err(TCError::unsupported(format!(
-                        "cannot tile a Tensor {} times",
-                        n
-                    )))?,
-                    Value::Tuple(multiples) if multiples.len() == tensor.ndim() => multiples
-                        .try_cast_into(|v| {
-                            TCError::bad_request("invalid list of multiples for tile", v)
-                        }),
-                    other => Err(TCError::bad_request("invalid multiples for tile", other)),
-                }?;
+                let tensor = self.tensor;
+                if tensor.ndim() != 2 {
+                    return Err(TCError::unsupported(format!(
+                        "trace requires a matrix, not a Tensor with shape {}",
+                        tensor.shape()
+                    )));
+                }
 
-                match tensor {
-                    Tensor::Dense(dense) => {
-                        DenseTensor::tile(txn.clone(), dense, multiples)
-                            .map_ok(Tensor::from)
-                            .map_ok(State::from)
-                            .await
-                    }
-                    Tensor::Sparse(sparse) => {
-                        SparseTensor::tile(txn.clone(), sparse, multiples)
-                            .map_ok(Tensor::from)
-                            .map_ok(State::from)
-                            .await
-                    }
+                let (rows, cols) = (tensor.shape()[0] as i64, tensor.shape()[1] as i64);
+
+                // the diagonal starts at (0, offset) above the main diagonal,
+                // or at (-offset, 0) below it
+                let (row, col) = if offset >= 0 {
+                    (0, offset)
+                } else {
+                    (-offset, 0)
+                };
+                let len = Ord::min(rows - row, cols - col);
+                if len <= 0 {
+                    return Ok(Value::from(tensor.dtype().additive_identity()).into());
                 }
+
+                let offsets = (0..len)
+                    .map(|i| ((row + i) * cols) + col + i)
+                    .map(|offset| offset as u64)
+                    .collect();
+
+                let flat = tensor.reshape(vec![(rows * cols) as u64].into())?;
+                let diagonal = flat.slice(Bounds::from(vec![AxisBounds::Of(offsets)]))?;
+                let trace = diagonal.sum_all(txn.clone()).await?;
+                Ok(Value::from(trace).into())
             })
         }))
     }
 }
 
+impl<T> From<T> for TraceHandler
+where
+    Tensor: From<T>,
+{
+    fn from(tensor: T) -> Self {
+        Self {
+            tensor: tensor.into(),
+        }
+    }
+}
+
 struct TransposeHandler<T> {
     tensor: T,
 }
@@ -946,6 +2763,41 @@ where
             })
         }))
     }
+
+    fn post<'b>(self: Box<Self>) -> Option<PostHandler<'a, 'b>>
+    where
+        'b: 'a,
+    {
+        Some(Box::new(|txn, mut params| {
+            Box::pin(async move {
+                let permutation: Value = params.or_default(&label("permutation").into())?;
+                let materialize: bool = params.or_default(&label("copy").into())?;
+                params.expect_empty()?;
+
+                let permutation = if permutation.is_none() {
+                    None
+                } else {
+                    let permutation = permutation.try_cast_into(|v| {
+                        TCError::bad_request("invalid permutation for transpose", v)
+                    })?;
+
+                    Some(permutation)
+                };
+
+                let transpose = self.tensor.transpose(permutation).map(Tensor::from)?;
+
+                // a copy costs as much memory (or disk) as the source tensor,
+                // but avoids permuting every coordinate on each subsequent read
+                let transpose = if materialize {
+                    copy(txn, transpose).await?
+                } else {
+                    transpose
+                };
+
+                Ok(State::Collection(Collection::Tensor(transpose)))
+            })
+        }))
+    }
 }
 
 impl<T> From<T> for TransposeHandler<T> {
@@ -966,11 +2818,14 @@ impl Route for TensorType {
                     "copy_from" => Some(Box::new(CopyDenseHandler)),
                     "concatenate" => Some(Box::new(ConcatenateHandler)),
                     "constant" => Some(Box::new(ConstantHandler)),
+                    "linspace" => Some(Box::new(LinspaceHandler)),
+                    "meshgrid" => Some(Box::new(MeshgridHandler)),
                     "range" => Some(Box::new(RangeHandler)),
                     "random" if path.len() == 1 => Some(Box::new(RandomUniformHandler)),
                     _ => None,
                 },
                 Self::Sparse => match path[0].as_str() {
+                    "adjacency" => Some(Box::new(AdjacencyHandler)),
                     "copy_from" => Some(Box::new(CopySparseHandler)),
                     _ => None,
                 },
@@ -993,6 +2848,150 @@ impl Route for TensorType {
     }
 }
 
+struct UniqueHandler {
+    tensor: Tensor,
+}
+
+impl<'a> Handler<'a> for UniqueHandler {
+    fn get<'b>(self: Box<Self>) -> Option<GetHandler<'a, 'b>>
+    where
+        'b: 'a,
+    {
+        Some(Box::new(|txn, key| {
+            Box::pin(async move {
+                let return_counts = if key.is_none() {
+                    false
+                } else {
+                    bool::try_cast_from(key, |v| {
+                        TCError::bad_request("invalid flag to return counts", v)
+                    })?
+                };
+
+                let dtype = self.tensor.dtype();
+                let unique = unique(txn, self.tensor).await?;
+                let shape = Shape::from(vec![unique.len() as u64]);
+
+                let (values, counts): (Vec<Number>, Vec<Number>) = unique
+                    .into_iter()
+                    .map(|(value, count)| (value, Number::from(count)))
+                    .unzip();
+
+                let values = stream::iter(values.into_iter().map(Ok));
+                let file = create_file(txn).await?;
+                let values = DenseTensorFile::from_values(
+                    file,
+                    *txn.id(),
+                    shape.clone(),
+                    dtype,
+                    values,
+                    PER_BLOCK,
+                )
+                .map_ok(DenseTensor::from)
+                .map_ok(Tensor::from)
+                .map_ok(Collection::from)
+                .await?;
+
+                if !return_counts {
+                    return Ok(State::Collection(values));
+                }
+
+                let counts = stream::iter(counts.into_iter().map(Ok));
+                let file = create_file(txn).await?;
+                let counts = DenseTensorFile::from_values(
+                    file,
+                    *txn.id(),
+                    shape,
+                    UIntType::U64.into(),
+                    counts,
+                    PER_BLOCK,
+                )
+                .map_ok(DenseTensor::from)
+                .map_ok(Tensor::from)
+                .map_ok(Collection::from)
+                .await?;
+
+                Ok(State::Tuple(
+                    vec![State::Collection(values), State::Collection(counts)].into(),
+                ))
+            })
+        }))
+    }
+}
+
+impl<T> From<T> for UniqueHandler
+where
+    Tensor: From<T>,
+{
+    fn from(tensor: T) -> Self {
+        Self {
+            tensor: tensor.into(),
+        }
+    }
+}
+
+struct CumulativeHandler {
+    tensor: Tensor,
+    op: fn(Number, Number) -> Number,
+    op_name: &'static str,
+}
+
+impl CumulativeHandler {
+    fn new(tensor: Tensor, op: fn(Number, Number) -> Number, op_name: &'static str) -> Self {
+        Self {
+            tensor,
+            op,
+            op_name,
+        }
+    }
+
+    // the running maximum, which stays NaN once a NaN value is seen
+    fn max(acc: Number, n: Number) -> Number {
+        if acc.is_nan() {
+            acc
+        } else if n.is_nan() || n > acc {
+            n
+        } else {
+            acc
+        }
+    }
+
+    // the running minimum, which stays NaN once a NaN value is seen
+    fn min(acc: Number, n: Number) -> Number {
+        if acc.is_nan() {
+            acc
+        } else if n.is_nan() || n < acc {
+            n
+        } else {
+            acc
+        }
+    }
+}
+
+impl<'a> Handler<'a> for CumulativeHandler {
+    fn get<'b>(self: Box<Self>) -> Option<GetHandler<'a, 'b>>
+    where
+        'b: 'a,
+    {
+        Some(Box::new(|txn, key| {
+            Box::pin(async move {
+                self.tensor.shape().validate(self.op_name)?;
+
+                let scanned = if key.is_some() {
+                    let axis = cast_axis(key, self.tensor.ndim())?;
+                    scan_along_axis(txn, self.tensor, axis, self.op).await?
+                } else {
+                    // scan the flattened tensor
+                    let size = self.tensor.size();
+                    let flat = self.tensor.reshape(vec![size].into())?;
+                    scan_along_axis(txn, flat, 0, self.op).await?
+                };
+
+                Ok(State::Collection(scanned.into()))
+            })
+        }))
+    }
+}
+
 struct DualHandler {
     tensor: Tensor,
     op: fn(Tensor, Tensor) -> TCResult<Tensor>,
@@ -1054,26 +3053,103 @@ impl<'a> Handler<'a> for DualHandler {
 
                 l.shape().validate(self.op_name)?;
 
-                match r {
-                    State::Collection(Collection::Tensor(r)) => {
-                        r.shape().validate(self.op_name)?;
+                match r {
+                    State::Collection(Collection::Tensor(r)) => {
+                        r.shape().validate(self.op_name)?;
+
+                        let (l, r) = if l.dtype() == r.dtype() {
+                            (l, r)
+                        } else {
+                            let dtype = promote(l.dtype(), r.dtype());
+                            let l = TensorTransform::cast_into(l, dtype)?;
+                            let r = TensorTransform::cast_into(r, dtype)?;
+                            (l, r)
+                        };
+
+                        if l.shape() == r.shape() {
+                            (self.op)(l, r).map(Collection::from).map(State::from)
+                        } else {
+                            let (l, r) = broadcast(l, r)?;
+                            (self.op)(l, r).map(Collection::from).map(State::from)
+                        }
+                    }
+                    State::Scalar(Scalar::Value(r)) if r.matches::<Number>() => {
+                        let r = r.opt_cast_into().expect("numeric constant");
+                        (self.op_const)(l, r).map(Collection::from).map(State::from)
+                    }
+                    other => Err(TCError::bad_request(
+                        "expected a Tensor or Number, found",
+                        other,
+                    )),
+                }
+            })
+        }))
+    }
+}
+
+// a `DualHandler` for "div" which rejects integer division by zero;
+// floating-point division by a constant zero follows IEEE 754, but dividing by a `Tensor`
+// which contains a zero fails, since every `Tensor` combination rejects a non-finite result
+struct DivHandler {
+    tensor: Tensor,
+}
+
+impl DivHandler {
+    fn new<T>(tensor: T) -> Self
+    where
+        Tensor: From<T>,
+    {
+        Self {
+            tensor: tensor.into(),
+        }
+    }
+
+    fn into_dual(self) -> Box<DualHandler> {
+        Box::new(DualHandler::new(
+            self.tensor,
+            TensorMath::div,
+            TensorMathConst::div_const,
+            "div",
+        ))
+    }
+}
+
+impl<'a> Handler<'a> for DivHandler {
+    fn get<'b>(self: Box<Self>) -> Option<GetHandler<'a, 'b>>
+    where
+        'b: 'a,
+    {
+        // dividing by a constant zero is rejected by `div_const`
+        self.into_dual().get()
+    }
 
-                        if l.shape() == r.shape() {
-                            (self.op)(l, r).map(Collection::from).map(State::from)
-                        } else {
-                            let (l, r) = broadcast(l, r)?;
-                            (self.op)(l, r).map(Collection::from).map(State::from)
+    fn post<'b>(self: Box<Self>) -> Option<PostHandler<'a, 'b>>
+    where
+        'b: 'a,
+    {
+        Some(Box::new(|txn, params| {
+            Box::pin(async move {
+                if let Some(State::Collection(Collection::Tensor(r))) =
+                    params.get(&label("r").into())
+                {
+                    // a sparse dividend ignores zeros in the divisor, so only check a dense one
+                    if let Tensor::Dense(_) = self.tensor {
+                        match promote(self.tensor.dtype(), r.dtype()) {
+                            NumberType::Float(_) | NumberType::Complex(_) => {}
+                            dtype => {
+                                if !r.clone().all(txn.clone()).await? {
+                                    return Err(TCError::bad_request(
+                                        "division by zero in a Tensor of type",
+                                        dtype,
+                                    ));
+                                }
+                            }
                         }
                     }
-                    State::Scalar(Scalar::Value(r)) if r.matches::<Number>() => {
-                        let r = r.opt_cast_into().expect("numeric constant");
-                        (self.op_const)(l, r).map(Collection::from).map(State::from)
-                    }
-                    other => Err(TCError::bad_request(
-                        "expected a Tensor or Number, found",
-                        other,
-                    )),
                 }
+
+                let div = self.into_dual().post().expect("Tensor div");
+                div(txn, params).await
             })
         }))
     }
@@ -1195,7 +3271,27 @@ where
     {
         Some(Box::new(|txn, key| {
             Box::pin(async move {
+                // the key is either an axis, or an (axis, keepdims) tuple
+                let (key, keepdims) = match key {
+                    Value::Tuple(mut key) if key.len() == 2 => {
+                        let keepdims = key.pop().expect("keepdims");
+                        let keepdims = bool::try_cast_from(keepdims, |v| {
+                            TCError::bad_request("invalid value for keepdims", v)
+                        })?;
+
+                        (key.pop().expect("axis"), keepdims)
+                    }
+                    key => (key, false),
+                };
+
                 let axis = if key.is_none() {
+                    if keepdims {
+                        return Err(TCError::bad_request(
+                            "keepdims requires an axis to reduce",
+                            key,
+                        ));
+                    }
+
                     None
                 } else {
                     let axis = cast_axis(key, self.tensor.ndim())?;
@@ -1207,10 +3303,22 @@ where
                 };
 
                 if let Some(axis) = axis {
-                    (self.reduce)(self.tensor.clone(), axis)
-                        .map(Tensor::from)
-                        .map(Collection::from)
-                        .map(State::from)
+                    let reduced = (self.reduce)(self.tensor.clone(), axis).map(Tensor::from)?;
+                    let reduced = if keepdims {
+                        reduced.expand_dims(axis)?
+                    } else {
+                        reduced
+                    };
+
+                    Ok(State::Collection(reduced.into()))
+                } else if keepdims {
+                    // the reduced axis of a 1-D tensor is its only axis
+                    let reduced = (self.reduce_all)(self.tensor, txn.clone()).await?;
+                    constant(txn, vec![1].into(), reduced, PER_BLOCK)
+                        .map_ok(Tensor::from)
+                        .map_ok(Collection::from)
+                        .map_ok(State::from)
+                        .await
                 } else {
                     (self.reduce_all)(self.tensor, txn.clone())
                         .map_ok(Value::from)
@@ -1333,14 +3441,21 @@ impl<'a> Handler<'a> for UnaryHandler {
 struct UnaryHandlerAsync<F: Send> {
     tensor: Tensor,
     op: fn(Tensor, Txn) -> F,
+    op_axis: fn(Tensor, usize) -> TCResult<Tensor>,
     op_name: &'static str,
 }
 
 impl<'a, F: Send> UnaryHandlerAsync<F> {
-    fn new(tensor: Tensor, op: fn(Tensor, Txn) -> F, op_name: &'static str) -> Self {
+    fn new(
+        tensor: Tensor,
+        op: fn(Tensor, Txn) -> F,
+        op_axis: fn(Tensor, usize) -> TCResult<Tensor>,
+        op_name: &'static str,
+    ) -> Self {
         Self {
             tensor,
             op,
+            op_axis,
             op_name,
         }
     }
@@ -1370,6 +3485,33 @@ where
             })
         }))
     }
+
+    fn post<'b>(self: Box<Self>) -> Option<PostHandler<'a, 'b>>
+    where
+        'b: 'a,
+    {
+        Some(Box::new(|_txn, mut params| {
+            Box::pin(async move {
+                let axis: Value = params.require(&AXIS.into())?;
+                params.expect_empty()?;
+
+                self.tensor.shape().validate(self.op_name)?;
+
+                // reducing a 1-D Tensor along an axis would return a scalar, not a Tensor
+                if self.tensor.ndim() == 1 {
+                    return Err(TCError::bad_request(
+                        format!("to reduce a 1-D Tensor, call {} without an axis", self.op_name),
+                        axis,
+                    ));
+                }
+
+                let axis = cast_axis(axis, self.tensor.ndim())?;
+                (self.op_axis)(self.tensor, axis)
+                    .map(Collection::from)
+                    .map(State::from)
+            })
+        }))
+    }
 }
 
 impl<B: DenseWrite<fs::File<Array>, fs::File<Node>, fs::Dir, Txn>> Route for DenseTensor<B> {
@@ -1456,6 +3598,20 @@ where
             }
 
             // reduce ops (which require borrowing)
+            "max" => {
+                return Some(Box::new(ReduceHandler::new(
+                    tensor,
+                    TensorReduce::max,
+                    TensorReduce::max_all,
+                )))
+            }
+            "min" => {
+                return Some(Box::new(ReduceHandler::new(
+                    tensor,
+                    TensorReduce::min,
+                    TensorReduce::min_all,
+                )))
+            }
             "product" => {
                 return Some(Box::new(ReduceHandler::new(
                     tensor,
@@ -1477,6 +3633,7 @@ where
 
         match path[0].as_str() {
             // to stream
+            "chunk" => Some(Box::new(ChunkHandler::from(tensor))),
             "elements" => Some(Box::new(ElementsHandler::new(tensor))),
 
             // views
@@ -1492,6 +3649,9 @@ where
                 ))));
             }
 
+            "to_sparse" => return Some(Box::new(ToSparseHandler::from(tensor))),
+            "to_table" => return Some(Box::new(ToTableHandler::from(tensor))),
+
             // boolean ops
             "and" => Some(Box::new(DualHandler::new(
                 tensor,
@@ -1608,10 +3768,34 @@ where
                 TensorTrig::atanh,
                 "atanh",
             ))),
-            "tanh" => Some(Box::new(UnaryHandler::new(
+            "tanh" => Some(Box::new(UnaryHandler::new(tensor.into(), tanh, "tanh"))),
+
+            // activation functions
+            "relu" => Some(Box::new(UnaryHandler::new(tensor.into(), relu, "relu"))),
+            "sigmoid" => Some(Box::new(UnaryHandler::new(
+                tensor.into(),
+                sigmoid,
+                "sigmoid",
+            ))),
+
+            // floating-point predicates
+            "isfinite" => Some(Box::new(PredicateHandler::new(
+                tensor.into(),
+                is_finite,
+                true,
+                "isfinite",
+            ))),
+            "isinf" => Some(Box::new(PredicateHandler::new(
+                tensor.into(),
+                is_inf,
+                false,
+                "isinf",
+            ))),
+            "isnan" => Some(Box::new(PredicateHandler::new(
                 tensor.into(),
-                TensorTrig::tanh,
-                "tanh",
+                is_nan,
+                false,
+                "isnan",
             ))),
 
             // unary ops
@@ -1623,11 +3807,13 @@ where
             "all" => Some(Box::new(UnaryHandlerAsync::new(
                 tensor.into(),
                 TensorUnary::all,
+                all_along_axis,
                 "all",
             ))),
             "any" => Some(Box::new(UnaryHandlerAsync::new(
                 tensor.into(),
                 TensorUnary::any,
+                any_along_axis,
                 "any",
             ))),
             "exp" => Some(Box::new(UnaryHandler::new(
@@ -1653,12 +3839,7 @@ where
                 TensorMathConst::add_const,
                 "add",
             ))),
-            "div" => Some(Box::new(DualHandler::new(
-                tensor,
-                TensorMath::div,
-                TensorMathConst::div_const,
-                "div",
-            ))),
+            "div" => Some(Box::new(DivHandler::new(tensor))),
             "log" => Some(Box::new(LogHandler::new(tensor))),
             "mul" => Some(Box::new(DualHandler::new(
                 tensor,
@@ -1695,9 +3876,46 @@ where
 
             // linear algebra
             "diagonal" => Some(Box::new(DiagonalHandler::from(tensor))),
+            "dot" => Some(Box::new(DotHandler::from(tensor))),
+            "outer" => Some(Box::new(OuterHandler::from(tensor))),
+            "trace" => Some(Box::new(TraceHandler::from(tensor))),
+
+            // reduce ops (which don't require borrowing)
+            "cummax" => Some(Box::new(CumulativeHandler::new(
+                tensor.into(),
+                CumulativeHandler::max,
+                "cummax",
+            ))),
+            "cummin" => Some(Box::new(CumulativeHandler::new(
+                tensor.into(),
+                CumulativeHandler::min,
+                "cummin",
+            ))),
+            "count_nonzero" => Some(Box::new(CountNonzeroHandler::from(tensor))),
+
+            // accumulate
+            "accumulate" => Some(Box::new(AccumulateHandler::from(tensor))),
+
+            // slicewise ops
+            "apply_along_axis" => Some(Box::new(ApplyAlongAxisHandler::from(tensor))),
+            "softmax" => Some(Box::new(SoftmaxHandler::from(tensor))),
 
             // other
+            "copy" => Some(Box::new(CopyHandler::from(tensor))),
+            "diff" => Some(Box::new(DiffHandler::from(tensor))),
+            "gather" => Some(Box::new(GatherHandler::from(tensor))),
+            "masked_fill" => Some(Box::new(MaskedFillHandler::from(tensor))),
+            "masked_mean" => Some(Box::new(MaskedReduceHandler::new(tensor, true))),
+            "masked_sum" => Some(Box::new(MaskedReduceHandler::new(tensor, false))),
+            "moving_average" => Some(Box::new(MovingAverageHandler::from(tensor))),
+            "nan_to_num" => Some(Box::new(NanToNumHandler::from(tensor))),
+            "quantile" => Some(Box::new(QuantileHandler::from(tensor))),
+            "repeat" => Some(Box::new(RepeatHandler::from(tensor))),
+            "roll" => Some(Box::new(RollHandler::from(tensor))),
+            "scatter" => Some(Box::new(ScatterHandler::from(tensor))),
             "split" => Some(Box::new(SplitHandler::from(tensor))),
+            "topk" => Some(Box::new(TopKHandler::from(tensor))),
+            "unique" => Some(Box::new(UniqueHandler::from(tensor))),
 
             _ => None,
         }
@@ -1729,9 +3947,25 @@ async fn constant(
     txn: &Txn,
     shape: Shape,
     value: Number,
+    block_size: usize,
 ) -> TCResult<DenseTensor<DenseTensorFile>> {
     let file = create_file(txn).await?;
-    DenseTensor::constant(file, *txn.id(), shape, value).await
+    DenseTensor::constant(file, *txn.id(), shape, value, block_size).await
+}
+
+async fn copy(txn: &Txn, source: Tensor) -> TCResult<Tensor> {
+    match source {
+        Tensor::Dense(source) => {
+            let file = create_file(txn).await?;
+            let blocks = BlockListFile::copy_from(source.into_inner(), file, txn).await?;
+            Ok(DenseTensor::from(blocks.accessor()).into())
+        }
+        Tensor::Sparse(source) => {
+            let dir = txn.context().create_dir_unique(*txn.id()).await?;
+            let table = SparseTable::copy_from(source, dir, txn).await?;
+            Ok(SparseTensor::from(table.accessor()).into())
+        }
+    }
 }
 
 async fn create_sparse(txn: &Txn, schema: Schema) -> TCResult<SparseTensor<SparseTable>> {
@@ -1740,6 +3974,24 @@ async fn create_sparse(txn: &Txn, schema: Schema) -> TCResult<SparseTensor<Spars
     SparseTensor::create(&dir, schema, txn_id).await
 }
 
+// create a new, zero-valued `Tensor` with the given `shape`, which is dense or sparse like `tensor`
+async fn create_like(txn: &Txn, tensor: &Tensor, shape: Shape) -> TCResult<Tensor> {
+    let dtype = tensor.dtype();
+
+    match tensor {
+        Tensor::Dense(_) => {
+            constant(txn, shape, dtype.additive_identity(), PER_BLOCK)
+                .map_ok(Tensor::from)
+                .await
+        }
+        Tensor::Sparse(_) => {
+            create_sparse(txn, Schema { shape, dtype })
+                .map_ok(Tensor::from)
+                .await
+        }
+    }
+}
+
 async fn write<T>(tensor: T, txn: &Txn, key: Value, value: State) -> TCResult<()>
 where
     T: TensorAccess
@@ -1754,6 +4006,19 @@ where
 
     match value {
         State::Collection(Collection::Tensor(value)) => {
+            let shape = bounds.to_shape(tensor.shape())?;
+            let value = if value.shape() == &shape {
+                value
+            } else {
+                let value_shape = value.shape().clone();
+                value.broadcast(shape.clone()).map_err(|_| {
+                    TCError::unsupported(format!(
+                        "cannot broadcast a Tensor with shape {} to write to a slice with shape {}",
+                        value_shape, shape
+                    ))
+                })?
+            };
+
             tensor.write(txn.clone(), bounds, value).await
         }
         State::Scalar(scalar) => {
@@ -1769,12 +4034,475 @@ where
     }
 }
 
+// write `value` to `output` at every coordinate where the boolean `mask` is `true`
+async fn fill_where(txn: &Txn, output: &Tensor, mask: Tensor, value: Number) -> TCResult<()> {
+    let masked = match mask.into_sparse() {
+        Tensor::Sparse(sparse) => sparse.into_inner().filled(txn.clone()).await?,
+        Tensor::Dense(_) => unreachable!("sparse Tensor"),
+    };
+
+    let txn_id = *txn.id();
+
+    masked
+        .map_ok(|(coord, _)| output.write_value_at(txn_id, coord, value))
+        .try_buffer_unordered(num_cpus::get())
+        .try_fold((), |(), ()| future::ready(Ok(())))
+        .await
+}
+
+// stream every element of a dense tensor, with its coordinate, in row-major order
+async fn dense_elements<'a>(
+    txn: &Txn,
+    dense: DenseAccessor,
+) -> TCResult<TCBoxTryStream<'a, (Coord, Number)>> {
+    let coords = stream::iter(Bounds::all(dense.shape()).affected());
+    let values = dense.value_stream(txn.clone()).await?;
+    let elements = coords
+        .zip(values)
+        .map(|(coord, value)| value.map(|value| (coord, value)));
+
+    Ok(Box::pin(elements))
+}
+
+// max(x, 0), which preserves the dtype and the sparsity of `tensor` since relu(0) = 0
+fn relu(tensor: &Tensor) -> TCResult<Tensor> {
+    let positive = tensor.clone().gt_const(tensor.dtype().additive_identity())?;
+    TensorMath::mul(tensor.clone(), positive)
+}
+
+// 1 / (1 + exp(-x)), which is always dense since sigmoid(0) = 0.5
+fn sigmoid(tensor: &Tensor) -> TCResult<Tensor> {
+    // (1 + tanh(x / 2)) / 2 is equivalent but can't overflow for large negative x
+    cast_into_float(tensor.clone())?
+        .into_dense()
+        .div_const(2.0.into())?
+        .tanh()?
+        .add_const(1.0.into())?
+        .div_const(2.0.into())
+}
+
+// like `TensorTrig::tanh`, but promotes an integer `tensor` to F64
+fn tanh(tensor: &Tensor) -> TCResult<Tensor> {
+    cast_into_float(tensor.clone())?.tanh()
+}
+
+// `true` where every element along `axis` is nonzero, i.e. the minimum of `tensor` as booleans
+fn all_along_axis(tensor: Tensor, axis: usize) -> TCResult<Tensor> {
+    TensorTransform::cast_into(tensor, NumberType::Bool)?.min(axis)
+}
+
+// `true` where any element along `axis` is nonzero, i.e. the maximum of `tensor` as booleans
+fn any_along_axis(tensor: Tensor, axis: usize) -> TCResult<Tensor> {
+    TensorTransform::cast_into(tensor, NumberType::Bool)?.max(axis)
+}
+
+// |x| < inf, which is always dense since zero is finite
+fn is_finite(tensor: &Tensor) -> TCResult<Tensor> {
+    let inf = Number::from(f64::INFINITY);
+    tensor.abs()?.into_dense().lt_const(inf)
+}
+
+// |x| == inf
+fn is_inf(tensor: &Tensor) -> TCResult<Tensor> {
+    let inf = Number::from(f64::INFINITY);
+    tensor.abs()?.eq_const(inf)
+}
+
+fn is_nan(tensor: &Tensor) -> TCResult<Tensor> {
+    TensorUnary::is_nan(tensor)
+}
+
+fn cast_into_float(tensor: Tensor) -> TCResult<Tensor> {
+    match tensor.dtype() {
+        NumberType::Float(_) | NumberType::Complex(_) => Ok(tensor),
+        _ => TensorTransform::cast_into(tensor, FloatType::F64.into()),
+    }
+}
+
+// compute the mean of each `window` of consecutive elements of `tensor` along `axis` with a running sum;
+// in "full" mode, the first `window - 1` outputs are the means of the shorter windows seen so far
+async fn moving_average(
+    txn: &Txn,
+    tensor: Tensor,
+    axis: usize,
+    window: usize,
+    full: bool,
+) -> TCResult<Tensor> {
+    let dtype = tensor.dtype();
+    let ndim = tensor.ndim();
+    let permutation = axis_last(ndim, axis);
+    let mut shape: Shape = permutation.iter().map(|x| tensor.shape()[*x]).collect();
+    if !full {
+        shape[ndim - 1] -= (window - 1) as u64;
+    }
+
+    let values = slices_along_axis(txn, tensor, axis)
+        .await?
+        .map_ok(move |slice| {
+            let mut sum = dtype.zero();
+            let mut averages = Vec::with_capacity(slice.len());
+            for i in 0..slice.len() {
+                sum = sum + slice[i];
+                if i >= window {
+                    sum = sum - slice[i - window];
+                }
+
+                if i + 1 >= window {
+                    averages.push(sum / Number::from(window as u64));
+                } else if full {
+                    averages.push(sum / Number::from((i + 1) as u64));
+                }
+            }
+
+            stream::iter(averages.into_iter().map(move |n| Ok(n.into_type(dtype))))
+        })
+        .try_flatten();
+
+    let file = create_file(txn).await?;
+    let averages = DenseTensorFile::from_values(file, *txn.id(), shape, dtype, values, PER_BLOCK)
+        .map_ok(DenseTensor::from)
+        .map_ok(Tensor::from)
+        .await?;
+
+    // restore the original order of the axes
+    let mut inverse = vec![0; ndim];
+    for (i, x) in permutation.into_iter().enumerate() {
+        inverse[x] = i;
+    }
+
+    averages.transpose(Some(inverse))
+}
+
+// compute a running `op` over each 1-D slice of `tensor` along `axis`
+async fn scan_along_axis(
+    txn: &Txn,
+    tensor: Tensor,
+    axis: usize,
+    op: fn(Number, Number) -> Number,
+) -> TCResult<Tensor> {
+    let dtype = tensor.dtype();
+    let ndim = tensor.ndim();
+    let permutation = axis_last(ndim, axis);
+    let shape: Shape = permutation.iter().map(|x| tensor.shape()[*x]).collect();
+
+    let values = slices_along_axis(txn, tensor, axis)
+        .await?
+        .map_ok(move |slice| {
+            let mut acc = None;
+            let scanned = slice.into_iter().map(move |n| {
+                let n = acc.map(|acc| op(acc, n)).unwrap_or(n);
+                acc = Some(n);
+                Ok(n)
+            });
+
+            stream::iter(scanned)
+        })
+        .try_flatten();
+
+    let file = create_file(txn).await?;
+    let scanned = DenseTensorFile::from_values(file, *txn.id(), shape, dtype, values, PER_BLOCK)
+        .map_ok(DenseTensor::from)
+        .map_ok(Tensor::from)
+        .await?;
+
+    // restore the original order of the axes
+    let mut inverse = vec![0; ndim];
+    for (i, x) in permutation.into_iter().enumerate() {
+        inverse[x] = i;
+    }
+
+    scanned.transpose(Some(inverse))
+}
+
+// select the `k` largest (or smallest) elements of each 1-D slice of `tensor` along `axis`,
+// in sorted order, together with their indices; ties resolve to the lower index and NaN sorts last
+async fn top_k(
+    txn: &Txn,
+    tensor: Tensor,
+    axis: usize,
+    k: usize,
+    largest: bool,
+) -> TCResult<(Tensor, Tensor)> {
+    let dtype = tensor.dtype();
+    let ndim = tensor.ndim();
+    let permutation = axis_last(ndim, axis);
+    let mut shape: Shape = permutation.iter().map(|x| tensor.shape()[*x]).collect();
+    shape[ndim - 1] = k as u64;
+
+    let selected: Vec<(u64, Number)> = slices_along_axis(txn, tensor, axis)
+        .await?
+        .and_then(move |slice| {
+            let mut slice: Vec<(u64, Number)> = (0..slice.len() as u64).zip(slice).collect();
+
+            // a stable sort keeps equal values in the order of their indices,
+            // and NaN is ordered last so that it's only selected if there's nothing else
+            let selected = sort_numbers(&mut slice, |(_, n)| n, largest).map(|()| {
+                slice.truncate(k);
+                stream::iter(slice.into_iter().map(Ok))
+            });
+
+            future::ready(selected)
+        })
+        .try_flatten()
+        .try_collect()
+        .await?;
+
+    let (indices, values): (Vec<u64>, Vec<Number>) = selected.into_iter().unzip();
+
+    let file = create_file(txn).await?;
+    let values = stream::iter(values.into_iter().map(Ok));
+    let values =
+        DenseTensorFile::from_values(file, *txn.id(), shape.clone(), dtype, values, PER_BLOCK)
+            .map_ok(DenseTensor::from)
+            .map_ok(Tensor::from)
+            .await?;
+
+    let file = create_file(txn).await?;
+    let indices = stream::iter(indices.into_iter().map(Number::from).map(Ok));
+    let indices = DenseTensorFile::from_values(
+        file,
+        *txn.id(),
+        shape,
+        UIntType::U64.into(),
+        indices,
+        PER_BLOCK,
+    )
+    .map_ok(DenseTensor::from)
+    .map_ok(Tensor::from)
+    .await?;
+
+    // restore the original order of the axes
+    let mut inverse = vec![0; ndim];
+    for (i, x) in permutation.into_iter().enumerate() {
+        inverse[x] = i;
+    }
+
+    Ok((
+        values.transpose(Some(inverse.clone()))?,
+        indices.transpose(Some(inverse))?,
+    ))
+}
+
+// stream the values of each 1-D slice of `tensor` along `axis`
+async fn slices_along_axis<'a>(
+    txn: &Txn,
+    tensor: Tensor,
+    axis: usize,
+) -> TCResult<TCBoxTryStream<'a, Vec<Number>>> {
+    let dim = tensor.shape()[axis];
+    let permutation = axis_last(tensor.ndim(), axis);
+
+    let transposed = match tensor.transpose(Some(permutation))?.into_dense() {
+        Tensor::Dense(dense) => dense.into_inner(),
+        Tensor::Sparse(_) => unreachable!("dense Tensor"),
+    };
+
+    let slices = transposed
+        .value_stream(txn.clone())
+        .await?
+        .chunks(dim as usize)
+        .map(|values| values.into_iter().collect::<TCResult<Vec<Number>>>());
+
+    Ok(Box::pin(slices))
+}
+
+// collect the distinct values of `tensor`, in ascending order, with the number of times each occurs
+async fn unique(txn: &Txn, tensor: Tensor) -> TCResult<Vec<(Number, u64)>> {
+    if let NumberType::Complex(_) = tensor.dtype() {
+        return Err(TCError::not_implemented("unique for a complex Tensor"));
+    }
+
+    let zero = tensor.dtype().zero();
+    let size = tensor.size();
+
+    let (values, implicit_zeros): (TCBoxTryStream<Number>, u64) = match tensor {
+        Tensor::Dense(dense) => (dense.into_inner().value_stream(txn.clone()).await?, 0),
+        Tensor::Sparse(sparse) => {
+            // a sparse tensor doesn't store its zeros, so count them separately
+            let filled = sparse
+                .clone()
+                .into_inner()
+                .filled_count(txn.clone())
+                .await?;
+            let values = sparse.into_inner().filled(txn.clone()).await?;
+            (Box::pin(values.map_ok(|(_, value)| value)), size - filled)
+        }
+    };
+
+    // keep only the distinct values, in order, so that memory use is bounded by their number
+    let mut unique = values
+        .try_fold(Vec::new(), |mut unique, value| {
+            future::ready(if value.is_nan() {
+                Err(TCError::bad_request(
+                    "cannot find the unique values of a Tensor which contains",
+                    value,
+                ))
+            } else {
+                count_unique(&mut unique, value, 1);
+                Ok(unique)
+            })
+        })
+        .await?;
+
+    if implicit_zeros > 0 {
+        count_unique(&mut unique, zero, implicit_zeros);
+    }
+
+    Ok(unique)
+}
+
+// add `count` occurrences of `value` to the sorted list of distinct values in `unique`
+fn count_unique(unique: &mut Vec<(Number, u64)>, value: Number, count: u64) {
+    // `value` is neither complex nor NaN, so it has a defined order
+    match unique.binary_search_by(|(n, _)| n.partial_cmp(&value).expect("ordered value")) {
+        Ok(i) => unique[i].1 += count,
+        Err(i) => unique.insert(i, (value, count)),
+    }
+}
+
+// linearly interpolate each quantile in `q` between the ranks of the 1-D `slice`,
+// which is sorted by the same `arg_sort` as the argsort handler
+async fn interpolate_quantiles(txn: &Txn, slice: Tensor, q: &[f64]) -> TCResult<Vec<Number>> {
+    if slice.is_nan()?.any(txn.clone()).await? {
+        return Ok(vec![Number::from(f64::NAN); q.len()]);
+    }
+
+    let slice = match slice.into_dense() {
+        Tensor::Dense(dense) => dense,
+        Tensor::Sparse(_) => unreachable!("dense Tensor"),
+    };
+
+    let indices = tc_tensor::arg_sort(slice.clone().into_inner(), txn.clone()).await?;
+    let indices = Tensor::Dense(indices.accessor().into());
+    let slice = Tensor::Dense(slice);
+
+    let last = (slice.size() - 1) as f64;
+    let mut quantiles = Vec::with_capacity(q.len());
+    for q in q {
+        let rank = q * last;
+        let (lo, hi) = (rank.floor(), rank.ceil());
+        let lo_value = read_ranked(txn, &slice, &indices, lo as u64).await?;
+        let hi_value = read_ranked(txn, &slice, &indices, hi as u64).await?;
+        quantiles.push(Number::from(lo_value + (hi_value - lo_value) * (rank - lo)));
+    }
+
+    Ok(quantiles)
+}
+
+// read the element of the 1-D `slice` at the given `rank` in the order of the sorted `indices`
+async fn read_ranked(txn: &Txn, slice: &Tensor, indices: &Tensor, rank: u64) -> TCResult<f64> {
+    let index = indices.clone().read_value(txn.clone(), vec![rank]).await?;
+    let value = slice.clone().read_value(txn.clone(), vec![u64::cast_from(index)]).await?;
+    Ok(f64::cast_from(value))
+}
+
+// sort `items` by the `Number` each one contains, in ascending (or descending) order with NaN last,
+// keeping equal numbers in their original order
+fn sort_numbers<T, F>(items: &mut [T], number: F, descending: bool) -> TCResult<()>
+where
+    F: Fn(&T) -> &Number,
+{
+    let mut unordered = None;
+
+    items.sort_by(|l, r| {
+        let (l, r) = (number(l), number(r));
+
+        match (l.is_nan(), r.is_nan()) {
+            (true, true) => Ordering::Equal,
+            (true, false) => Ordering::Greater,
+            (false, true) => Ordering::Less,
+            (false, false) => {
+                let order = if descending {
+                    r.partial_cmp(l)
+                } else {
+                    l.partial_cmp(r)
+                };
+
+                order.unwrap_or_else(|| {
+                    unordered = Some((*l, *r));
+                    Ordering::Equal
+                })
+            }
+        }
+    });
+
+    if let Some((l, r)) = unordered {
+        Err(TCError::unsupported(format!(
+            "cannot sort {} and {}, which have no defined order",
+            l, r
+        )))
+    } else {
+        Ok(())
+    }
+}
+
+// read a 1-D Tensor of indices into an axis with dimension `dim`
+async fn read_indices(txn: &Txn, indices: Tensor, dim: u64) -> TCResult<Vec<u64>> {
+    if indices.ndim() != 1 {
+        return Err(TCError::bad_request(
+            "expected a 1-D Tensor of indices but found one with shape",
+            indices.shape(),
+        ));
+    }
+
+    match indices.dtype() {
+        NumberType::Int(_) | NumberType::UInt(_) => {}
+        other => return Err(TCError::bad_request("indices must be integers, not", other)),
+    }
+
+    let indices = match indices.into_dense() {
+        Tensor::Dense(dense) => dense.into_inner(),
+        Tensor::Sparse(_) => unreachable!("dense Tensor"),
+    };
+
+    indices
+        .value_stream(txn.clone())
+        .await?
+        .map(|index| {
+            let index = index?;
+            let i = i64::cast_from(index);
+            if i >= 0 && (i as u64) < dim {
+                Ok(i as u64)
+            } else {
+                Err(TCError::bad_request(
+                    format!("index out of bounds for an axis with dimension {}:", dim),
+                    index,
+                ))
+            }
+        })
+        .try_collect()
+        .await
+}
+
+// a permutation which moves `axis` to the end, so that each slice along it is contiguous
+fn axis_last(ndim: usize, axis: usize) -> Vec<usize> {
+    let mut permutation: Vec<usize> = (0..ndim).filter(|x| *x != axis).collect();
+    permutation.push(axis);
+    permutation
+}
+
 async fn create_file(txn: &Txn) -> TCResult<fs::File<Array>> {
     txn.context()
         .create_file_unique(*txn.id(), TensorType::Dense)
         .await
 }
 
+// split an optional trailing block size off of the key of a request to create a dense Tensor
+fn split_block_size(key: Value) -> TCResult<(Value, Option<usize>)> {
+    match key {
+        Value::Tuple(mut key) if key.len() == 3 => {
+            let block_size = key.pop().expect("block size");
+            let block_size = u64::try_cast_from(block_size, |v| {
+                TCError::bad_request("invalid dense Tensor block size", v)
+            })?;
+
+            Ok((Value::Tuple(key), Some(block_size as usize)))
+        }
+        key => Ok((key, None)),
+    }
+}
+
+// cast the exclusive end of a range, which may be equal to `dim`
 fn cast_bound(dim: u64, bound: Value) -> TCResult<u64> {
     let bound = i64::try_cast_from(bound, |v| TCError::bad_request("invalid bound", v))?;
     if bound.abs() as u64 > dim {
@@ -1791,6 +4519,29 @@ fn cast_bound(dim: u64, bound: Value) -> TCResult<u64> {
     }
 }
 
+// cast a point index, which must be less than `dim`
+fn cast_index(dim: u64, index: Value) -> TCResult<u64> {
+    let index = i64::try_cast_from(index, |v| TCError::bad_request("invalid index", v))?;
+    let in_bounds = if index < 0 {
+        index.abs() as u64 <= dim
+    } else {
+        (index as u64) < dim
+    };
+
+    if !in_bounds {
+        return Err(TCError::bad_request(
+            format!("Index out of bounds for dimension {}", dim),
+            index,
+        ));
+    }
+
+    if index < 0 {
+        Ok(dim - index.abs() as u64)
+    } else {
+        Ok(index as u64)
+    }
+}
+
 fn cast_axis(axis: Value, ndim: usize) -> TCResult<usize> {
     debug!("cast axis {} with ndim {}", axis, ndim);
 
@@ -1808,18 +4559,32 @@ fn cast_axis(axis: Value, ndim: usize) -> TCResult<usize> {
     }
 }
 
+fn cast_quantile(q: Value) -> TCResult<f64> {
+    let q = Number::try_cast_from(q, |v| TCError::bad_request("invalid quantile", v))?;
+    let q = f64::cast_from(q);
+
+    if (0.0..=1.0).contains(&q) {
+        Ok(q)
+    } else {
+        Err(TCError::bad_request(
+            "a quantile must be between 0 and 1, not",
+            q,
+        ))
+    }
+}
+
 fn cast_range(dim: u64, range: Range) -> TCResult<AxisBounds> {
     debug!("cast range from {} with dimension {}", range, dim);
 
     let start = match range.start {
         Bound::Un => 0,
         Bound::In(start) => cast_bound(dim, start)?,
-        Bound::Ex(start) => cast_bound(dim, start)? + 1,
+        Bound::Ex(start) => cast_index(dim, start)? + 1,
     };
 
     let end = match range.end {
         Bound::Un => dim,
-        Bound::In(end) => cast_bound(dim, end)? + 1,
+        Bound::In(end) => cast_index(dim, end)? + 1,
         Bound::Ex(end) => cast_bound(dim, end)?,
     };
 
@@ -1839,7 +4604,7 @@ pub fn cast_bounds(shape: &Shape, value: Value) -> TCResult<Bounds> {
     match value {
         Value::None => Ok(Bounds::all(shape)),
         Value::Number(i) => {
-            let bound = cast_bound(shape[0], i.into())?;
+            let bound = cast_index(shape[0], i.into())?;
             Ok(Bounds::from(vec![bound]))
         }
         Value::Tuple(range) if range.matches::<(Bound, Bound)>() => {
@@ -1878,7 +4643,7 @@ pub fn cast_bounds(shape: &Shape, value: Value) -> TCResult<Bounds> {
                 } else if bound.matches::<Vec<u64>>() {
                     bound.opt_cast_into().map(AxisBounds::Of).unwrap()
                 } else if let Value::Number(value) = bound {
-                    cast_bound(shape[axis], value.into()).map(AxisBounds::At)?
+                    cast_index(shape[axis], value.into()).map(AxisBounds::At)?
                 } else {
                     return Err(TCError::bad_request(
                         format!("invalid bound for axis {}", axis),
@@ -110,33 +110,19 @@ where
     where
         'b: 'a,
     {
-        Some(Box::new(|_txn, mut params| {
+        Some(Box::new(|txn, mut params| {
             Box::pin(async move {
                 let other: Map<State> = params.require(&label("eq").into())?;
                 params.expect_empty()?;
 
-                if self.map.len() != other.len() {
-                    return Ok(false.into());
-                }
-
-                const ERR: &str = "cannot cast into Value from";
-                let eq = self
-                    .map
-                    .into_iter()
-                    .zip(other)
-                    .map(|((this_id, this), (that_id, that))| {
-                        if this_id != that_id {
-                            return Ok(false);
-                        }
-
-                        let this = State::from(this);
-                        let this = Value::try_cast_from(this, |s| TCError::bad_request(ERR, s))?;
-                        let that = Value::try_cast_from(that, |s| TCError::bad_request(ERR, s))?;
-                        Ok(this == that)
-                    })
-                    .collect::<TCResult<Vec<bool>>>()?;
+                let this = State::Map(
+                    self.map
+                        .into_iter()
+                        .map(|(id, state)| (id, State::from(state)))
+                        .collect(),
+                );
 
-                Ok(eq.into_iter().all(|eq| eq).into())
+                this.eq(&State::Map(other), txn).map_ok(State::from).await
             })
         }))
     }
@@ -160,29 +146,13 @@ where
     where
         'b: 'a,
     {
-        Some(Box::new(|_txn, mut params| {
+        Some(Box::new(|txn, mut params| {
             Box::pin(async move {
                 let other: Tuple<State> = params.require(&label("eq").into())?;
                 params.expect_empty()?;
 
-                if self.tuple.len() != other.len() {
-                    return Ok(false.into());
-                }
-
-                const ERR: &str = "cannot cast into Value from";
-                let eq = self
-                    .tuple
-                    .into_iter()
-                    .zip(other)
-                    .map(|(this, that)| {
-                        let this = State::from(this);
-                        let this = Value::try_cast_from(this, |s| TCError::bad_request(ERR, s))?;
-                        let that = Value::try_cast_from(that, |s| TCError::bad_request(ERR, s))?;
-                        Ok(this == that)
-                    })
-                    .collect::<TCResult<Vec<bool>>>()?;
-
-                Ok(eq.into_iter().all(|eq| eq).into())
+                let this = State::Tuple(self.tuple.into_iter().map(State::from).collect());
+                this.eq(&State::Tuple(other), txn).map_ok(State::from).await
             })
         }))
     }
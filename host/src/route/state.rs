@@ -74,6 +74,36 @@ impl<'a> Handler<'a> for HashHandler {
     }
 }
 
+struct EqHandler {
+    state: State,
+}
+
+impl<'a> Handler<'a> for EqHandler {
+    fn post<'b>(self: Box<Self>) -> Option<PostHandler<'a, 'b>>
+    where
+        'b: 'a,
+    {
+        Some(Box::new(|txn, mut params| {
+            Box::pin(async move {
+                let other: State = params.require(&label("eq").into())?;
+                params.expect_empty()?;
+
+                self.state
+                    .eq(&other, txn)
+                    .map_ok(Value::from)
+                    .map_ok(State::from)
+                    .await
+            })
+        }))
+    }
+}
+
+impl From<State> for EqHandler {
+    fn from(state: State) -> EqHandler {
+        Self { state }
+    }
+}
+
 impl From<State> for HashHandler {
     fn from(state: State) -> HashHandler {
         Self { state }
@@ -174,6 +204,7 @@ impl Route for State {
         } else if path.len() == 1 {
             match path[0].as_str() {
                 "class" => Some(Box::new(ClassHandler::from(self.class()))),
+                "eq" => Some(Box::new(EqHandler::from(self.clone()))),
                 "hash" => Some(Box::new(HashHandler::from(self.clone()))),
                 "is_none" => Some(Box::new(AttributeHandler::from(Number::Bool(
                     self.is_none().into(),
@@ -1,12 +1,14 @@
 use futures::{TryFutureExt, TryStreamExt};
 use safecast::{Match, TryCastInto};
 
-use tc_error::TCError;
+use tc_error::{TCError, TCResult};
 use tc_value::Number;
 use tcgeneric::TCPathBuf;
 
+use crate::closure::Closure;
 use crate::generic::{label, PathSegment};
 use crate::route::{GetHandler, Handler, PostHandler, Public, Route};
+use crate::scalar::OpDef;
 use crate::state::State;
 use crate::stream::{Source, TCStream};
 
@@ -151,6 +153,50 @@ impl<'a> Handler<'a> for Map {
     }
 }
 
+struct Reduce {
+    source: TCStream,
+}
+
+impl<'a> Handler<'a> for Reduce {
+    fn post<'b>(self: Box<Self>) -> Option<PostHandler<'a, 'b>>
+    where
+        'b: 'a,
+    {
+        Some(Box::new(|txn, mut params| {
+            Box::pin(async move {
+                let op = params.require(&label("op").into())?;
+                let initial = params.require(&label("initial").into())?;
+                params.expect_empty()?;
+
+                let op = require_binary_op(op)?;
+                self.source.reduce(txn.clone(), initial, op).await
+            })
+        }))
+    }
+}
+
+struct Scan {
+    source: TCStream,
+}
+
+impl<'a> Handler<'a> for Scan {
+    fn post<'b>(self: Box<Self>) -> Option<PostHandler<'a, 'b>>
+    where
+        'b: 'a,
+    {
+        Some(Box::new(|_txn, mut params| {
+            Box::pin(async move {
+                let op = params.require(&label("op").into())?;
+                let initial = params.require(&label("initial").into())?;
+                params.expect_empty()?;
+
+                let op = require_binary_op(op)?;
+                Ok(State::Stream(self.source.scan(initial, op)))
+            })
+        }))
+    }
+}
+
 impl Route for TCStream {
     fn route<'a>(&'a self, path: &'a [PathSegment]) -> Option<Box<dyn Handler<'a> + 'a>> {
         if path.len() != 1 {
@@ -166,6 +212,8 @@ impl Route for TCStream {
             "fold" => Some(Box::new(Fold { source })),
             "for_each" => Some(Box::new(ForEach { source })),
             "map" => Some(Box::new(Map { source })),
+            "reduce" => Some(Box::new(Reduce { source })),
+            "scan" => Some(Box::new(Scan { source })),
             _ => None,
         }
     }
@@ -211,3 +259,16 @@ impl Route for Static {
         }
     }
 }
+
+// a binary op is a PUT op, which takes exactly two arguments: a key and a value
+fn require_binary_op(op: Closure) -> TCResult<Closure> {
+    let (context, op_def) = op.into_inner();
+
+    match op_def {
+        OpDef::Put(_) => Ok(Closure::new(context, op_def)),
+        other => Err(TCError::bad_request(
+            "expected a PUT Op with two arguments (a key and a value) but found",
+            other,
+        )),
+    }
+}
@@ -245,6 +245,69 @@ impl State {
         })
     }
 
+    /// Return `true` if this `State` is structurally equal to `other`.
+    ///
+    /// Returns a "not implemented" error if the two states cannot be meaningfully compared.
+    pub fn eq<'a>(&'a self, other: &'a Self, txn: &'a Txn) -> TCBoxTryFuture<'a, bool> {
+        Box::pin(async move {
+            match (self, other) {
+                (Self::Collection(this), Self::Collection(that)) => {
+                    this.clone().eq(that.clone(), txn.clone()).await
+                }
+                (Self::Scalar(Scalar::Value(this)), Self::Scalar(Scalar::Value(that))) => {
+                    Ok(this == that)
+                }
+                (this, that) if this.is_map() && that.is_map() => {
+                    let this = this.clone().try_into_map(|s| {
+                        TCError::internal(format!("expected a Map but found {}", s))
+                    })?;
+
+                    let that = that.clone().try_into_map(|s| {
+                        TCError::internal(format!("expected a Map but found {}", s))
+                    })?;
+
+                    if this.len() != that.len() {
+                        return Ok(false);
+                    }
+
+                    for ((this_id, this), (that_id, that)) in this.iter().zip(that.iter()) {
+                        if this_id != that_id || !this.eq(that, txn).await? {
+                            return Ok(false);
+                        }
+                    }
+
+                    Ok(true)
+                }
+                (this, that) if this.is_tuple() && that.is_tuple() => {
+                    let this = this.clone().try_into_tuple(|s| {
+                        TCError::internal(format!("expected a Tuple but found {}", s))
+                    })?;
+
+                    let that = that.clone().try_into_tuple(|s| {
+                        TCError::internal(format!("expected a Tuple but found {}", s))
+                    })?;
+
+                    if this.len() != that.len() {
+                        return Ok(false);
+                    }
+
+                    for (this, that) in this.iter().zip(that.iter()) {
+                        if !this.eq(that, txn).await? {
+                            return Ok(false);
+                        }
+                    }
+
+                    Ok(true)
+                }
+                (this, that) => Err(TCError::not_implemented(format!(
+                    "compare {} with {}",
+                    this.class(),
+                    that.class()
+                ))),
+            }
+        })
+    }
+
     /// Return true if this `State` is an empty [`Tuple`] or [`Map`], default [`Link`], or `Value::None`
     pub fn is_none(&self) -> bool {
         match self {
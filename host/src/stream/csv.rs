@@ -0,0 +1,134 @@
+//! The CSV encoding shared by [`super::ExportFormat::CSV`] and the `Table` CSV loader
+
+use tc_error::*;
+use tc_value::Value;
+
+/// Encode the given `row` as a single CSV record, without a trailing newline.
+///
+/// `Value::None` is encoded as an empty cell, an empty string as a quoted empty cell,
+/// and `Value::Bytes` as a hexadecimal string.
+pub fn encode_record<'a, I: IntoIterator<Item = &'a Value>>(row: I) -> String {
+    row.into_iter()
+        .map(|value| match value {
+            Value::None => String::new(),
+            Value::Bytes(bytes) => encode_cell(&hex::encode(bytes)),
+            value => encode_cell(&value.to_string()),
+        })
+        .collect::<Vec<String>>()
+        .join(",")
+}
+
+/// Quote the given `cell` if it's empty, has surrounding whitespace, or contains a special character.
+pub fn encode_cell(cell: &str) -> String {
+    if cell.is_empty() || cell.trim() != cell || cell.contains(&[',', '"', '\n', '\r'][..]) {
+        format!("\"{}\"", cell.replace('"', "\"\""))
+    } else {
+        cell.to_string()
+    }
+}
+
+/// Split the complete CSV records off the front of the given `buffer`.
+///
+/// An incomplete trailing record is left in the `buffer`, unless `eof` is `true`.
+pub fn take_records(buffer: &mut String, eof: bool) -> Vec<String> {
+    let mut records = Vec::new();
+    let mut quoted = false;
+    let mut start = 0;
+
+    for (i, c) in buffer.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            '\n' if !quoted => {
+                records.push(trim_newline(&buffer[start..i]).to_string());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+
+    if eof {
+        if start < buffer.len() {
+            records.push(trim_newline(&buffer[start..]).to_string());
+        }
+
+        buffer.clear();
+    } else {
+        buffer.drain(..start);
+    }
+
+    records
+}
+
+/// Parse a single CSV `record`.
+///
+/// An empty unquoted cell is parsed as `Value::None` and every other cell as a `Value::String`.
+/// Whitespace around an unquoted cell is ignored.
+pub fn parse_record(record: &str, delimiter: char) -> TCResult<Vec<Value>> {
+    let mut cells = Vec::new();
+    let mut chars = record.chars().peekable();
+
+    loop {
+        while chars.peek().map_or(false, |c| *c != delimiter && c.is_whitespace()) {
+            chars.next();
+        }
+
+        let cell = if chars.peek() == Some(&'"') {
+            chars.next();
+
+            let mut cell = String::new();
+            loop {
+                match chars.next() {
+                    Some('"') if chars.peek() == Some(&'"') => {
+                        chars.next();
+                        cell.push('"');
+                    }
+                    Some('"') => break,
+                    Some(c) => cell.push(c),
+                    None => return Err(TCError::bad_request("unterminated quote in CSV", record)),
+                }
+            }
+
+            while chars.peek().map_or(false, |c| *c != delimiter && c.is_whitespace()) {
+                chars.next();
+            }
+
+            match chars.peek() {
+                Some(c) if *c != delimiter => {
+                    return Err(TCError::bad_request(
+                        "expected a delimiter after a quoted CSV cell",
+                        record,
+                    ))
+                }
+                _ => {}
+            }
+
+            Value::String(cell.into())
+        } else {
+            let mut cell = String::new();
+            while let Some(c) = chars.peek() {
+                if *c == delimiter {
+                    break;
+                } else {
+                    cell.push(*c);
+                    chars.next();
+                }
+            }
+
+            match cell.trim() {
+                "" => Value::None,
+                cell => Value::String(cell.to_string().into()),
+            }
+        };
+
+        cells.push(cell);
+
+        if chars.next().is_none() {
+            break Ok(cells);
+        }
+    }
+}
+
+#[inline]
+fn trim_newline(record: &str) -> &str {
+    record.strip_suffix('\r').unwrap_or(record)
+}
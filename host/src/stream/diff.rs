@@ -0,0 +1,64 @@
+use async_trait::async_trait;
+use futures::future::{self, TryFutureExt};
+use futures::stream::TryStreamExt;
+
+use tc_error::*;
+use tc_tensor::{SparseAccess, TensorIO, TensorInstance};
+use tc_value::{Number, Value};
+use tcgeneric::TCBoxTryStream;
+
+use crate::collection::Tensor;
+use crate::state::State;
+use crate::txn::Txn;
+
+use super::source::Source;
+use super::TCStream;
+
+/// A stream of the `(coord, left, right)` elements where two `Tensor`s differ
+///
+/// Only the coordinates filled in the given `mask` are read, in order.
+#[derive(Clone)]
+pub struct Diff {
+    left: Tensor,
+    right: Tensor,
+    mask: Tensor,
+}
+
+impl Diff {
+    pub fn new(left: Tensor, right: Tensor, mask: Tensor) -> Self {
+        Self { left, right, mask }
+    }
+}
+
+#[async_trait]
+impl Source for Diff {
+    async fn into_stream(self, txn: Txn) -> TCResult<TCBoxTryStream<'static, State>> {
+        let coords = match self.mask.into_sparse() {
+            Tensor::Sparse(sparse) => sparse.into_inner().filled(txn.clone()).await?,
+            Tensor::Dense(_) => unreachable!("sparse Tensor"),
+        };
+
+        let left = self.left;
+        let right = self.right;
+
+        let diffs = coords
+            .map_ok(move |(coord, _)| {
+                let l = left.clone().read_value(txn.clone(), coord.clone());
+                let r = right.clone().read_value(txn.clone(), coord.clone());
+                future::try_join(l, r).map_ok(move |(l, r)| {
+                    let coord = coord.into_iter().map(Number::from).map(Value::from);
+                    let coord = Value::Tuple(coord.collect());
+                    State::from(Value::Tuple(vec![coord, l.into(), r.into()].into()))
+                })
+            })
+            .try_buffered(num_cpus::get());
+
+        Ok(Box::pin(diffs))
+    }
+}
+
+impl From<Diff> for TCStream {
+    fn from(diff: Diff) -> Self {
+        TCStream::Diff(Box::new(diff))
+    }
+}
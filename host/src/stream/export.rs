@@ -0,0 +1,152 @@
+use std::fmt;
+use std::iter::FromIterator;
+use std::str::FromStr;
+
+use async_trait::async_trait;
+use futures::stream::{self, StreamExt, TryStreamExt};
+use safecast::TryCastFrom;
+
+use tc_btree::BTreeInstance;
+use tc_error::*;
+use tc_table::TableInstance;
+use tc_value::Value;
+use tcgeneric::{Id, TCBoxTryStream, Tuple};
+
+use crate::collection::Collection;
+use crate::state::State;
+use crate::txn::Txn;
+
+use super::csv;
+use super::source::Source;
+use super::TCStream;
+
+/// The textual format of an [`Export`] stream
+#[derive(Clone, Copy, Eq, PartialEq)]
+pub enum ExportFormat {
+    CSV,
+    NDJSON,
+}
+
+impl ExportFormat {
+    fn header(&self, columns: &[Id]) -> Option<String> {
+        match self {
+            Self::CSV => Some(
+                columns
+                    .iter()
+                    .map(|name| csv::encode_cell(name.as_str()))
+                    .collect::<Vec<String>>()
+                    .join(","),
+            ),
+            Self::NDJSON => None,
+        }
+    }
+
+    fn encode(&self, columns: &[Id], row: Vec<Value>) -> TCResult<String> {
+        if row.len() != columns.len() {
+            return Err(TCError::bad_request(
+                format!("cannot export a row with {} columns from", row.len()),
+                Value::from_iter(columns.iter().cloned().map(Value::from)),
+            ));
+        }
+
+        match self {
+            Self::CSV => Ok(csv::encode_record(&row)),
+
+            Self::NDJSON => {
+                let mut entries = Vec::with_capacity(columns.len());
+                for (name, value) in columns.iter().zip(&row) {
+                    let name = serde_json::to_string(name.as_str()).map_err(json_err)?;
+                    let value = serde_json::to_string(value).map_err(json_err)?;
+                    entries.push(format!("{}:{}", name, value));
+                }
+
+                Ok(format!("{{{}}}", entries.join(",")))
+            }
+        }
+    }
+}
+
+impl FromStr for ExportFormat {
+    type Err = TCError;
+
+    fn from_str(format: &str) -> TCResult<Self> {
+        match format {
+            "csv" => Ok(Self::CSV),
+            "ndjson" => Ok(Self::NDJSON),
+            other => Err(TCError::bad_request("unsupported export format", other)),
+        }
+    }
+}
+
+impl fmt::Display for ExportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::CSV => f.write_str("csv"),
+            Self::NDJSON => f.write_str("ndjson"),
+        }
+    }
+}
+
+/// A stream of the rows of a [`Collection`], encoded as lines of text
+#[derive(Clone)]
+pub struct Export {
+    source: Collection,
+    format: ExportFormat,
+}
+
+impl Export {
+    pub fn new(source: Collection, format: ExportFormat) -> Self {
+        Self { source, format }
+    }
+}
+
+#[async_trait]
+impl Source for Export {
+    async fn into_stream(self, txn: Txn) -> TCResult<TCBoxTryStream<'static, State>> {
+        let columns: Vec<Id> = match &self.source {
+            Collection::BTree(btree) => btree.schema().iter().map(|c| c.name.clone()).collect(),
+            Collection::Table(table) => table.primary_schema().column_names().cloned().collect(),
+
+            #[cfg(feature = "tensor")]
+            Collection::Tensor(_) => {
+                return Err(TCError::unsupported(
+                    "export a Tensor by calling its elements() method",
+                ))
+            }
+        };
+
+        let format = self.format;
+        let header = format.header(&columns);
+
+        let rows = TCStream::from(self.source).into_stream(txn).await?;
+        let lines = rows.map(move |row| {
+            let row = row.and_then(|state| {
+                Value::try_cast_from(state, |s| {
+                    TCError::bad_request("cannot export an invalid row", s)
+                })
+            })?;
+
+            let row = Tuple::<Value>::try_cast_from(row, |v| {
+                TCError::bad_request("cannot export an invalid row", v)
+            })?;
+
+            format.encode(&columns, row.into_inner())
+        });
+
+        let lines = stream::iter(header.into_iter().map(Ok)).chain(lines);
+        let lines = lines.map_ok(|line| format!("{}\n", line));
+        let lines = lines.map_ok(|line| Value::String(line.into()));
+        Ok(Box::pin(lines.map_ok(State::from)))
+    }
+}
+
+impl From<Export> for TCStream {
+    fn from(export: Export) -> Self {
+        TCStream::Export(Box::new(export))
+    }
+}
+
+#[inline]
+fn json_err(cause: serde_json::Error) -> TCError {
+    TCError::bad_request("export failed to encode JSON", cause)
+}
@@ -20,14 +20,25 @@ use crate::fs;
 use crate::state::{State, StateView};
 use crate::txn::Txn;
 
+#[cfg(feature = "tensor")]
+use diff::Diff;
+use export::Export;
 use group::Aggregate;
 use range::Range;
+use sample::Sample;
 use source::*;
 
+pub use export::ExportFormat;
 pub use source::Source;
 
+pub mod csv;
+
+#[cfg(feature = "tensor")]
+mod diff;
+mod export;
 mod group;
 mod range;
+mod sample;
 mod source;
 
 /// A stream generator such as a `Collection` or a mapping or aggregation of its items
@@ -35,10 +46,16 @@ mod source;
 pub enum TCStream {
     Aggregate(Box<Aggregate>),
     Collection(Collection),
+    #[cfg(feature = "tensor")]
+    Diff(Box<Diff>),
+    Export(Box<Export>),
     Filter(Box<Filter>),
     Flatten(Box<Flatten>),
+    KeyedRows(Box<KeyedRows>),
     Map(Box<Map>),
     Range(Range),
+    Sample(Box<Sample>),
+    Scan(Box<Scan>),
 }
 
 impl TCStream {
@@ -50,6 +67,22 @@ impl TCStream {
         Aggregate::new(self).into()
     }
 
+    /// Stream the `(coord, left, right)` elements of `left` and `right` at each coordinate filled
+    /// in the given `mask`.
+    #[cfg(feature = "tensor")]
+    pub fn diff(
+        left: crate::collection::Tensor,
+        right: crate::collection::Tensor,
+        mask: crate::collection::Tensor,
+    ) -> Self {
+        Diff::new(left, right, mask).into()
+    }
+
+    /// Encode the rows of the given `collection` as a stream of lines of text in the given `format`.
+    pub fn export(collection: crate::collection::Collection, format: ExportFormat) -> Self {
+        Export::new(collection, format).into()
+    }
+
     /// Return a new stream with only the elements in this stream which match the given `filter`.
     pub fn filter(self, filter: Closure) -> Self {
         Filter::new(self, filter).into()
@@ -113,15 +146,52 @@ impl TCStream {
         Ok(hasher.finalize())
     }
 
+    /// Stream the rows of the given `table` as `Map`s keyed by column name.
+    pub fn keyed_rows(table: crate::collection::Table) -> Self {
+        KeyedRows::new(table).into()
+    }
+
     /// Return a `TCStream` produced by calling the given [`Closure`] on each item in this stream.
     pub fn map(self, op: Closure) -> Self {
         Map::new(self, op).into()
     }
 
+    /// Reduce this stream to a single `State` by calling the given PUT `op` with the result so far
+    /// as its key and the next item as its value, starting with the given `initial` `State`.
+    /// Since the key of a PUT op is a `Value`, so must be `initial` and each result of `op`.
+    ///
+    /// For example, reducing `[1, 2, 3]` with `0` and an op which adds its key and value will produce `6`.
+    pub async fn reduce(self, txn: Txn, initial: State, op: Closure) -> TCResult<State> {
+        let mut source = self.into_stream(txn.clone()).await?;
+
+        let mut state = initial;
+        while let Some(item) = source.try_next().await? {
+            let args = State::Tuple(vec![state, item].into());
+            state = op.clone().call(&txn, args).await?;
+        }
+
+        Ok(state)
+    }
+
     /// Return a `TCStream` of numbers at the given `step` within the given range.
     pub fn range(start: Number, stop: Number, step: Number) -> Self {
         Range::new(start, stop, step).into()
     }
+
+    /// Return a `TCStream` of each intermediate result of [`Self::reduce`].
+    ///
+    /// For example, scanning `[1, 2, 3]` with `0` and an op which adds its key and value will produce
+    /// `[1, 3, 6]`.
+    pub fn scan(self, initial: State, op: Closure) -> Self {
+        Scan::new(self, initial, op).into()
+    }
+
+    /// Return a random sample of up to `size` items in this stream.
+    ///
+    /// This reads the entire stream once, even if the sample size is small.
+    pub fn sample(self, size: usize) -> Self {
+        Sample::new(self, size).into()
+    }
 }
 
 #[async_trait]
@@ -130,10 +200,16 @@ impl Source for TCStream {
         match self {
             Self::Aggregate(aggregate) => aggregate.into_stream(txn).await,
             Self::Collection(collection) => collection.into_stream(txn).await,
+            #[cfg(feature = "tensor")]
+            Self::Diff(diff) => diff.into_stream(txn).await,
+            Self::Export(export) => export.into_stream(txn).await,
             Self::Filter(filter) => filter.into_stream(txn).await,
             Self::Flatten(source) => source.into_stream(txn).await,
+            Self::KeyedRows(rows) => rows.into_stream(txn).await,
             Self::Map(map) => map.into_stream(txn).await,
             Self::Range(range) => range.into_stream(txn).await,
+            Self::Sample(sample) => sample.into_stream(txn).await,
+            Self::Scan(scan) => scan.into_stream(txn).await,
         }
     }
 }
@@ -0,0 +1,96 @@
+use async_trait::async_trait;
+use futures::stream::{self, TryStreamExt};
+use sha2::{Digest, Sha256};
+
+use tc_error::*;
+use tcgeneric::TCBoxTryStream;
+
+use crate::state::State;
+use crate::txn::Txn;
+
+use super::source::Source;
+use super::TCStream;
+
+/// A uniform random sample of up to `size` items of a source stream, chosen by reservoir sampling
+///
+/// This reads the entire source stream once, even if the sample size is small.
+/// The sample is seeded with the current transaction ID, so replaying a transaction
+/// will select the same sample.
+#[derive(Clone)]
+pub struct Sample {
+    source: TCStream,
+    size: usize,
+}
+
+impl Sample {
+    pub fn new(source: TCStream, size: usize) -> Self {
+        Self { source, size }
+    }
+}
+
+#[async_trait]
+impl Source for Sample {
+    async fn into_stream(self, txn: Txn) -> TCResult<TCBoxTryStream<'static, State>> {
+        let mut rng = SplitMix64::seed(&txn.id().to_string());
+        let size = self.size;
+
+        let mut source = self.source.into_stream(txn).await?;
+        let mut reservoir: Vec<(u64, State)> = Vec::with_capacity(size);
+        let mut seen = 0u64;
+
+        while let Some(item) = source.try_next().await? {
+            if reservoir.len() < size {
+                reservoir.push((seen, item));
+            } else if size > 0 {
+                let i = rng.next_below(seen + 1);
+                if i < size as u64 {
+                    reservoir[i as usize] = (seen, item);
+                }
+            }
+
+            seen += 1;
+        }
+
+        // emit the sample in the same order as the source
+        reservoir.sort_by_key(|(i, _)| *i);
+
+        let sample = stream::iter(reservoir.into_iter().map(|(_, item)| Ok(item)));
+        Ok(Box::pin(sample))
+    }
+}
+
+impl From<Sample> for TCStream {
+    fn from(sample: Sample) -> Self {
+        TCStream::Sample(Box::new(sample))
+    }
+}
+
+// a small, fast pseudo-random number generator which is reproducible given the same seed
+struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    fn seed(seed: &str) -> Self {
+        let hash = Sha256::digest(seed.as_bytes());
+        let mut state = [0u8; 8];
+        state.copy_from_slice(&hash[..8]);
+
+        Self {
+            state: u64::from_be_bytes(state),
+        }
+    }
+
+    fn next(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E3779B97F4A7C15);
+
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^ (z >> 31)
+    }
+
+    fn next_below(&mut self, bound: u64) -> u64 {
+        self.next() % bound
+    }
+}
@@ -1,6 +1,6 @@
 use async_trait::async_trait;
 use futures::future::{self, TryFutureExt};
-use futures::stream::{StreamExt, TryStreamExt};
+use futures::stream::{self, StreamExt, TryStreamExt};
 use safecast::TryCastFrom;
 
 use tc_btree::BTreeInstance;
@@ -11,6 +11,7 @@ use tc_value::Value;
 use tcgeneric::TCBoxTryStream;
 
 use crate::closure::Closure;
+use crate::collection::Table;
 use crate::state::State;
 use crate::txn::Txn;
 
@@ -93,6 +94,38 @@ impl From<Collection> for TCStream {
     }
 }
 
+/// A stream of the rows of a [`Table`], as `Map`s keyed by column name
+#[derive(Clone)]
+pub struct KeyedRows {
+    table: Table,
+}
+
+impl KeyedRows {
+    pub fn new(table: Table) -> Self {
+        Self { table }
+    }
+}
+
+#[async_trait]
+impl Source for KeyedRows {
+    async fn into_stream(self, txn: Txn) -> TCResult<TCBoxTryStream<'static, State>> {
+        let rows = self.table.keyed_rows(*txn.id()).await?;
+        let rows = rows.map_ok(|row| {
+            row.into_iter()
+                .map(|(name, value)| (name, State::from(value)))
+                .collect::<tcgeneric::Map<State>>()
+        });
+
+        Ok(Box::pin(rows.map_ok(State::Map)))
+    }
+}
+
+impl From<KeyedRows> for TCStream {
+    fn from(rows: KeyedRows) -> Self {
+        TCStream::KeyedRows(Box::new(rows))
+    }
+}
+
 #[derive(Clone)]
 pub struct Filter {
     source: TCStream,
@@ -215,3 +248,51 @@ impl From<Map> for TCStream {
         TCStream::Map(Box::new(map))
     }
 }
+
+#[derive(Clone)]
+pub struct Scan {
+    source: TCStream,
+    initial: State,
+    op: Closure,
+}
+
+impl Scan {
+    pub fn new(source: TCStream, initial: State, op: Closure) -> Self {
+        Self {
+            source,
+            initial,
+            op,
+        }
+    }
+}
+
+#[async_trait]
+impl Source for Scan {
+    async fn into_stream(self, txn: Txn) -> TCResult<TCBoxTryStream<'static, State>> {
+        let source = self.source.into_stream(txn.clone()).await?;
+        let op = self.op;
+
+        let scan = stream::try_unfold((source, self.initial), move |(mut source, state)| {
+            let txn = txn.clone();
+            let op = op.clone();
+
+            Box::pin(async move {
+                if let Some(item) = source.try_next().await? {
+                    let args = State::Tuple(vec![state, item].into());
+                    let state = op.call(&txn, args).await?;
+                    Ok(Some((state.clone(), (source, state))))
+                } else {
+                    Ok(None)
+                }
+            })
+        });
+
+        Ok(Box::pin(scan))
+    }
+}
+
+impl From<Scan> for TCStream {
+    fn from(scan: Scan) -> TCStream {
+        TCStream::Scan(Box::new(scan))
+    }
+}
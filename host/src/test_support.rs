@@ -0,0 +1,26 @@
+//! Fixtures shared by the unit tests of this crate.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::gateway::{Config, Gateway};
+use crate::kernel::Kernel;
+use crate::txn::TxnServer;
+
+/// Construct a new [`Gateway`] with an empty [`Kernel`] and a workspace unique to `name`.
+pub async fn gateway(name: &str) -> Arc<Gateway> {
+    let path = std::env::temp_dir().join(format!("tc_test_{}_{}", name, std::process::id()));
+    std::fs::create_dir_all(&path).expect("workspace");
+
+    let cache = freqfs::Cache::new(1_000_000, Duration::from_secs(1), None);
+    let workspace = cache.load(path).await.expect("workspace");
+    let txn_server = TxnServer::new(workspace).await;
+
+    let config = Config {
+        addr: "127.0.0.1".parse().expect("address"),
+        http_port: 8702,
+        request_ttl: Duration::from_secs(30),
+    };
+
+    Gateway::new(config, Kernel::new(vec![]), txn_server)
+}
@@ -20,7 +20,6 @@ use super::request::*;
 use super::{Active, Txn, TxnId};
 
 const GRACE: Duration = Duration::from_secs(3);
-const INTERVAL: Duration = Duration::from_millis(100);
 
 /// Server to keep track of the transactions currently active for this host.
 #[derive(Clone)]
@@ -33,7 +32,6 @@ impl TxnServer {
     /// Construct a new `TxnServer`.
     pub async fn new(workspace: DirLock<fs::CacheBlock>) -> Self {
         let active = Arc::new(RwLock::new(HashMap::new()));
-        spawn_cleanup_thread(workspace.clone(), active.clone());
         Self { active, workspace }
     }
 
@@ -87,6 +85,36 @@ impl TxnServer {
         .await?
     }
 
+    /// Remove every transaction which has outlived its auth token (plus a grace period) from this
+    /// `TxnServer`, delete its workspace, and return its [`TxnId`].
+    ///
+    /// The grace period ensures that no request in an expired transaction is still being handled.
+    pub async fn expire(&self) -> Vec<TxnId> {
+        let now = Gateway::time();
+        let mut txn_pool = self.active.write().await;
+        let expired: Vec<TxnId> = txn_pool
+            .iter()
+            .filter_map(|(txn_id, active)| {
+                if active.expires() + GRACE < now {
+                    Some(txn_id)
+                } else {
+                    None
+                }
+            })
+            .cloned()
+            .collect();
+
+        let mut workspace = self.workspace.write().await;
+        for txn_id in &expired {
+            if let Some(_active) = txn_pool.remove(txn_id) {
+                debug!("clean up txn {}", txn_id);
+                workspace.delete(txn_id.to_string());
+            }
+        }
+
+        expired
+    }
+
     async fn txn_dir(&self, txn_id: TxnId) -> TCResult<fs::Dir> {
         let mut workspace = self.workspace.write().await;
         let cache = workspace
@@ -96,46 +124,3 @@ impl TxnServer {
         fs::Dir::new(cache).await
     }
 }
-
-fn spawn_cleanup_thread(
-    workspace: DirLock<fs::CacheBlock>,
-    active: Arc<RwLock<HashMap<TxnId, Arc<Active>>>>,
-) {
-    let mut interval = tokio::time::interval(tokio::time::Duration::from_millis(
-        INTERVAL.as_millis() as u64,
-    ));
-
-    tokio::spawn(async move {
-        loop {
-            interval.tick().await;
-            cleanup(&workspace, &active).await;
-        }
-    });
-}
-
-async fn cleanup(
-    workspace: &DirLock<fs::CacheBlock>,
-    txn_pool: &RwLock<HashMap<TxnId, Arc<Active>>>,
-) {
-    let now = Gateway::time();
-    let mut txn_pool = txn_pool.write().await;
-    let expired: Vec<TxnId> = txn_pool
-        .iter()
-        .filter_map(|(txn_id, active)| {
-            if active.expires() + GRACE < now {
-                Some(txn_id)
-            } else {
-                None
-            }
-        })
-        .cloned()
-        .collect();
-
-    let mut workspace = workspace.write().await;
-    for txn_id in expired.into_iter() {
-        if let Some(_active) = txn_pool.remove(&txn_id) {
-            debug!("clean up txn {}", txn_id);
-            workspace.delete(txn_id.to_string());
-        }
-    }
-}
@@ -6,9 +6,10 @@ use std::ops::{Deref, DerefMut};
 
 use collate::Collate;
 use log::debug;
+use safecast::*;
 
 use tc_error::*;
-use tc_value::{Bound, Range, Value, ValueCollator, ValueType};
+use tc_value::{Bound, Range, StringCollator, Value, ValueCollator, ValueType};
 use tcgeneric::{Id, Map, Tuple};
 
 use super::Column;
@@ -117,7 +118,53 @@ impl Bounds {
         Self { inner }
     }
 
+    /// Cast the given `object` into `Bounds` and validate it against the given `columns`.
+    ///
+    /// Each entry may be a single value, a [`Range`], or (for a column which does not itself
+    /// hold a `Tuple`) a compact `[start, end]` pair which is interpreted as `[start, end)`.
+    pub fn from_object_checked(object: Map<Value>, columns: &[Column]) -> TCResult<Bounds> {
+        let columns: HashMap<&Id, &Column> = columns.iter().map(|c| (&c.name, c)).collect();
+
+        let mut validated = HashMap::with_capacity(object.len());
+        for (name, bound) in object.into_iter() {
+            let column = *columns.get(&name).ok_or_else(|| {
+                TCError::not_found(format!(
+                    "column {} (available columns are {})",
+                    name,
+                    Tuple::<&Id>::from_iter(columns.keys().copied())
+                ))
+            })?;
+
+            let is_range = bound.matches::<(Bound, Bound)>()
+                || bound.matches::<(Bound, Value)>()
+                || bound.matches::<(Value, Bound)>()
+                || (bound.matches::<Range>()
+                    && !matches!(column.dtype, ValueType::Tuple | ValueType::Value));
+
+            let bound = if is_range {
+                ColumnBound::In(bound.opt_cast_into().unwrap())
+            } else {
+                ColumnBound::Is(bound)
+            };
+
+            let bounds = Bounds::from_iter(vec![(name.clone(), bound)]);
+            let bounds = bounds.validate(&[column.clone()]).map_err(|cause| {
+                TCError::bad_request(
+                    format!("invalid bound for column {} of type {}", name, column.dtype),
+                    cause,
+                )
+            })?;
+
+            validated.extend(bounds.inner);
+        }
+
+        Ok(validated.into())
+    }
+
     /// Convert these `Bounds` into an equivalent [`tc_btree::Range`] according to the given schema.
+    ///
+    /// A string bound on the rightmost bounded column, if that column is case-insensitive,
+    /// matches every string which differs from it only by case.
     pub fn into_btree_range(mut self, columns: &[Column]) -> TCResult<tc_btree::Range> {
         let on_err = |bounds: &HashMap<Id, ColumnBound>| {
             TCError::bad_request(
@@ -137,6 +184,13 @@ impl Bounds {
                 Some(ColumnBound::In(Range { start, end })) => {
                     break (prefix, start.into(), end.into()).into()
                 }
+                Some(ColumnBound::Is(Value::String(value)))
+                    if column.case_insensitive && self.is_empty() =>
+                {
+                    // match every string which differs from the given value only by case
+                    let Range { start, end } = StringCollator::case_insensitive_range(&value);
+                    break (prefix, start.into(), end.into()).into();
+                }
                 Some(ColumnBound::Is(value)) => prefix.push(value),
             }
 
@@ -150,11 +204,40 @@ impl Bounds {
         }
     }
 
-    /// Merge these `Bounds` with the given `other`.
-    pub fn merge(&mut self, other: Self, collator: &ValueCollator) -> TCResult<()> {
+    /// Return the first of the given `columns` whose bound in `inner` falls outside its bound
+    /// in these `Bounds`, if any.
+    pub fn find_uncontained<'a>(
+        &self,
+        inner: &Bounds,
+        columns: &'a [Column],
+    ) -> Option<&'a Column> {
+        columns.iter().find(
+            |column| match (self.get(column.name()), inner.get(column.name())) {
+                (Some(outer), Some(inner)) => !outer.contains(inner, &column.collator()),
+                (Some(outer), None) => !matches!(
+                    outer,
+                    ColumnBound::In(Range {
+                        start: Bound::Un,
+                        end: Bound::Un
+                    })
+                ),
+                (None, _) => false,
+            },
+        )
+    }
+
+    /// Merge these `Bounds` with the given `other`, collating each bound according to the
+    /// matching one of the given `columns`.
+    pub fn merge(&mut self, other: Self, columns: &[Column]) -> TCResult<()> {
         for (col_name, inner) in other.inner.into_iter() {
             if let Some(outer) = self.get(&col_name) {
-                if !outer.contains(&inner, collator) {
+                let collator = columns
+                    .iter()
+                    .find(|column| column.name() == &col_name)
+                    .map(Column::collator)
+                    .unwrap_or_default();
+
+                if !outer.contains(&inner, &collator) {
                     debug!("{} does not contain {}", outer, inner);
                     return Err(TCError::bad_request("Out of bounds", inner));
                 }
@@ -168,21 +251,22 @@ impl Bounds {
 
     /// Cast these `Bounds` to match the given schema, or return an error.
     pub fn validate(self, columns: &[Column]) -> TCResult<Bounds> {
-        let try_cast_bound = |bound: Bound, dtype: ValueType| match bound {
-            Bound::In(val) => dtype.try_cast(val).map(Bound::In),
-            Bound::Ex(val) => dtype.try_cast(val).map(Bound::Ex),
+        let try_cast_bound = |bound: Bound, column: &Column| match bound {
+            Bound::In(val) => column.cast(val).map(Bound::In),
+            Bound::Ex(val) => column.cast(val).map(Bound::Ex),
             Bound::Un => Ok(Bound::Un),
         };
 
         let mut validated = HashMap::new();
-        let columns: HashMap<&Id, ValueType> = columns.iter().map(|c| c.into()).collect();
+        let columns: HashMap<&Id, &Column> = columns.iter().map(|c| (&c.name, c)).collect();
         for (name, bound) in self.inner.into_iter() {
-            if let Some(dtype) = columns.get(&name) {
+            if let Some(column) = columns.get(&name) {
                 let bound = match bound {
-                    ColumnBound::Is(value) => dtype.try_cast(value).map(ColumnBound::Is)?,
+                    // for a nullable column, `Value::None` matches "is null"
+                    ColumnBound::Is(value) => column.cast(value).map(ColumnBound::Is)?,
                     ColumnBound::In(Range { start, end }) => {
-                        let start = try_cast_bound(start, *dtype)?;
-                        let end = try_cast_bound(end, *dtype)?;
+                        let start = try_cast_bound(start, column)?;
+                        let end = try_cast_bound(end, column)?;
                         ColumnBound::In(Range { start, end })
                     }
                 };
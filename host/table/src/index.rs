@@ -5,24 +5,30 @@ use std::sync::Arc;
 
 use async_trait::async_trait;
 use futures::future::{self, join_all, try_join_all, TryFutureExt};
-use futures::stream::TryStreamExt;
+use futures::join;
+use futures::stream::{StreamExt, TryStreamExt};
 use log::debug;
-use safecast::AsType;
+use safecast::{AsType, CastFrom, TryCastFrom};
 
 use tc_btree::{BTreeFile, BTreeInstance, BTreeType, BTreeWrite, Node};
 use tc_error::*;
 use tc_transact::fs::{CopyFrom, Dir, File, Persist, Restore};
+use tc_transact::lock::{TxnLock, TxnLockWriteGuard};
 use tc_transact::{Transact, Transaction, TxnId};
-use tc_value::Value;
+use tc_value::{Number, Value, ValueType};
 use tcgeneric::{label, Id, Instance, Label, TCBoxTryStream, Tuple};
 
-use super::view::{Limited, MergeSource, Merged, Selection, TableSlice as Slice};
+use super::view::{Aggregate, Limited, MergeSource, Merged, Selection, TableSlice as Slice};
 use super::{
     Bounds, Column, ColumnBound, IndexSchema, IndexSlice, Key, Row, Table, TableInstance,
     TableOrder, TableRead, TableSchema, TableSlice, TableStream, TableType, TableWrite, Values,
 };
 
 const PRIMARY_INDEX: Label = label("primary");
+const SCHEMA: Label = label("schema");
+
+// the maximum encoded size of the schema of a table
+const SCHEMA_MAX_LEN: usize = 65_536;
 
 #[derive(Clone)]
 pub struct Index<F, D, Txn> {
@@ -60,7 +66,7 @@ impl<F: File<Node>, D: Dir, Txn: Transaction<D>> Index<F, D, Txn> {
         let outer = outer.validate(columns)?.into_btree_range(columns)?;
         let inner = inner.validate(columns)?.into_btree_range(columns)?;
 
-        if outer.contains(&inner, self.btree.collator()) {
+        if self.btree.collator().contains(&outer, &inner) {
             Ok(())
         } else {
             Err(TCError::unsupported(
@@ -80,6 +86,11 @@ impl<F: File<Node>, D: Dir, Txn: Transaction<D>> Index<F, D, Txn> {
         self.btree.slice(range, reverse)?.keys(txn_id).await
     }
 
+    async fn truncate(&self, txn_id: TxnId) -> TCResult<()> {
+        debug!("Index::truncate");
+        self.btree.delete(txn_id, tc_btree::Range::default()).await
+    }
+
     async fn delete_inner(&self, txn_id: TxnId, key: Key) -> TCResult<()> {
         debug!("Index::delete {:?}", key);
         let range = tc_btree::Range::with_prefix(key.to_vec());
@@ -162,7 +173,7 @@ impl<F: File<Node>, D: Dir, Txn: Transaction<D>> TableOrder for Index<F, D, Txn>
     type Reverse = IndexSlice<F, D, Txn>;
 
     fn order_by(self, order: Vec<Id>, reverse: bool) -> TCResult<Self::OrderBy> {
-        if self.schema.starts_with(&order) {
+        if self.schema.supports_order(&order) {
             Ok(IndexSlice::all(self.btree, self.schema, reverse))
         } else {
             Err(TCError::bad_request(
@@ -177,7 +188,7 @@ impl<F: File<Node>, D: Dir, Txn: Transaction<D>> TableOrder for Index<F, D, Txn>
     }
 
     fn validate_order(&self, order: &[Id]) -> TCResult<()> {
-        if !self.schema.starts_with(&order) {
+        if !self.schema.supports_order(order) {
             let order: Vec<String> = order.iter().map(|c| c.to_string()).collect();
             Err(TCError::bad_request(
                 &format!("cannot order index with schema {} by", self.schema),
@@ -198,6 +209,11 @@ impl<F: File<Node>, D: Dir, Txn: Transaction<D>> TableStream for Index<F, D, Txn
         self.btree.count(txn_id).await
     }
 
+    async fn exists(self, txn_id: TxnId) -> TCResult<bool> {
+        let is_empty = self.btree.is_empty(txn_id).await?;
+        Ok(!is_empty)
+    }
+
     fn limit(self, limit: u64) -> Self::Limit {
         Limited::new(self, limit)
     }
@@ -308,10 +324,29 @@ impl<F, D, Txn> From<Index<F, D, Txn>> for Table<F, D, Txn> {
     }
 }
 
+// the current schema and indices of a table, shared by every handle to it
+#[derive(Clone)]
+struct Version<F, D, Txn> {
+    schema: TableSchema,
+    primary: Index<F, D, Txn>,
+    auxiliary: Vec<(Id, Index<F, D, Txn>)>,
+    // the file which records `schema`, once it differs from the schema the table was created with
+    stored: Option<BTreeFile<F, D, Txn>>,
+}
+
+// two versions of a table with the same schema have the same indices
+impl<F, D, Txn> PartialEq for Version<F, D, Txn> {
+    fn eq(&self, other: &Self) -> bool {
+        self.schema == other.schema
+    }
+}
+
 struct Inner<F, D, Txn> {
+    dir: D,
     schema: TableSchema,
     primary: Index<F, D, Txn>,
     auxiliary: Vec<(Id, Index<F, D, Txn>)>,
+    latest: TxnLock<Version<F, D, Txn>>,
 }
 
 /// The base type of a [`Table`].
@@ -331,6 +366,13 @@ impl<F: File<Node>, D: Dir, Txn: Transaction<D>> TableIndex<F, D, Txn> {
         D::File: AsType<F>,
         D::FileClass: From<BTreeType>,
     {
+        if let Some(col) = schema.primary().key().iter().find(|col| col.nullable) {
+            return Err(TCError::bad_request(
+                "a Table key column cannot be nullable",
+                &col.name,
+            ));
+        }
+
         let primary_file = context
             .create_file(txn_id, PRIMARY_INDEX.into(), BTreeType::default())
             .await?;
@@ -344,10 +386,10 @@ impl<F: File<Node>, D: Dir, Txn: Transaction<D>> TableIndex<F, D, Txn> {
                 .iter()
                 .map(|(name, column_names)| (name.clone(), column_names.to_vec()))
                 .map(|(name, column_names)| async {
-                    if name == PRIMARY_INDEX {
+                    if name == PRIMARY_INDEX || name == SCHEMA {
                         return Err(TCError::bad_request(
                             "cannot create an auxiliary index with reserved name",
-                            PRIMARY_INDEX,
+                            name,
                         ));
                     }
 
@@ -355,7 +397,7 @@ impl<F: File<Node>, D: Dir, Txn: Transaction<D>> TableIndex<F, D, Txn> {
                         .create_file(txn_id, name.clone(), BTreeType::default())
                         .await?;
 
-                    Self::create_index(file, primary_schema, column_names, txn_id)
+                    create_index(file, primary_schema, column_names, txn_id)
                         .map_ok(move |index| (name, index))
                         .await
                 }),
@@ -364,24 +406,345 @@ impl<F: File<Node>, D: Dir, Txn: Transaction<D>> TableIndex<F, D, Txn> {
         .into_iter()
         .collect();
 
-        Ok(TableIndex {
+        Ok(Self::new(context.clone(), schema, primary, auxiliary, None))
+    }
+
+    fn new(
+        dir: D,
+        schema: TableSchema,
+        primary: Index<F, D, Txn>,
+        auxiliary: Vec<(Id, Index<F, D, Txn>)>,
+        stored: Option<BTreeFile<F, D, Txn>>,
+    ) -> Self {
+        let version = Version {
+            schema: schema.clone(),
+            primary: primary.clone(),
+            auxiliary: auxiliary.clone(),
+            stored,
+        };
+
+        TableIndex {
             inner: Arc::new(Inner {
+                dir,
                 schema,
                 primary,
                 auxiliary,
+                latest: TxnLock::new("Table schema", version),
             }),
-        })
+        }
+    }
+
+    /// Return a handle to the current version of this table as of the given transaction,
+    /// including any change to its schema made through another handle.
+    pub async fn current(&self, txn_id: TxnId) -> TCResult<Self> {
+        let version = self.inner.latest.read(txn_id).await?;
+        Ok(self.at_version(&version))
+    }
+
+    /// Add the value `column` to this `TableIndex`, backfilling each existing row with the given
+    /// `default` value, and return this table with its updated schema.
+    ///
+    /// The rows of the primary index are rewritten in place and no auxiliary index is affected.
+    /// Every change is made in the given transaction, so if it fails the old schema remains intact;
+    /// once it commits, every handle to this table sees the new schema.
+    pub async fn add_column(
+        &self,
+        txn: &Txn,
+        column: Column,
+        default: Value,
+    ) -> TCResult<TableIndex<F, D, Txn>>
+    where
+        D::File: AsType<F>,
+        D::FileClass: From<BTreeType>,
+    {
+        let txn_id = *txn.id();
+        let (mut version, table) = self.write_version(txn_id).await?;
+        let primary = table.inner.schema.primary();
+
+        if primary.key().iter().any(|col| col.name == column.name) {
+            return Err(TCError::unsupported(format!(
+                "cannot add key column {} to an existing Table",
+                column.name
+            )));
+        } else if primary.values().iter().any(|col| col.name == column.name) {
+            return Err(TCError::bad_request(
+                "Table already has a column named",
+                column.name,
+            ));
+        }
+
+        let default = column.cast(default)?;
+
+        let mut values = primary.values().to_vec();
+        values.push(column);
+
+        let primary_schema = IndexSchema::from((primary.key().to_vec(), values));
+        let primary = table
+            .rewrite_primary(txn, primary_schema, move |mut row| {
+                row.push(default.clone());
+                row
+            })
+            .await?;
+
+        let schema = TableSchema::new(
+            primary.schema.clone(),
+            table.inner.schema.indices().to_vec(),
+        );
+
+        let table = table.with_indices(schema, primary, table.inner.auxiliary.clone());
+        table.set_version(txn_id, &mut version).await?;
+        Ok(table)
+    }
+
+    /// Build a new auxiliary index `name` on the given `columns` from the current rows of this
+    /// table, and return this table with the new index added to its schema.
+    ///
+    /// The primary index is streamed once and no other index is affected. If a dropped index
+    /// left a file named `name` behind, that file is truncated first.
+    pub async fn create_index(
+        &self,
+        txn: &Txn,
+        name: Id,
+        columns: Vec<Id>,
+    ) -> TCResult<TableIndex<F, D, Txn>>
+    where
+        D::File: AsType<F>,
+        D::FileClass: From<BTreeType>,
+    {
+        let txn_id = *txn.id();
+        let (mut version, table) = self.write_version(txn_id).await?;
+
+        if name == PRIMARY_INDEX || name == SCHEMA {
+            return Err(TCError::bad_request(
+                "cannot create an auxiliary index with reserved name",
+                name,
+            ));
+        } else if table.inner.schema.indices().iter().any(|(n, _)| n == &name) {
+            return Err(TCError::bad_request(
+                "this table already has an index named",
+                name,
+            ));
+        }
+
+        let primary = &table.inner.primary;
+        primary.schema.validate_columns(&columns)?;
+
+        let file: F = match table.inner.dir.get_file(txn_id, &name).await? {
+            Some(file) => {
+                file.truncate(txn_id).await?;
+                file
+            }
+            None => {
+                table
+                    .inner
+                    .dir
+                    .create_file(txn_id, name.clone(), BTreeType::default())
+                    .await?
+            }
+        };
+
+        let index = create_index(file, &primary.schema, columns.clone(), txn_id).await?;
+
+        let rows = primary.clone().keyed_rows(txn_id).await?;
+        let keys = rows.map(|row| index_key(index.schema.key(), &row?));
+
+        index.btree.try_insert_from(txn_id, keys).await?;
+
+        let mut indices = table.inner.schema.indices().to_vec();
+        indices.push((name.clone(), columns));
+
+        let mut auxiliary = table.inner.auxiliary.clone();
+        auxiliary.push((name, index));
+
+        let schema = TableSchema::new(primary.schema.clone(), indices);
+        let table = table.with_indices(schema, primary.clone(), auxiliary);
+        table.set_version(txn_id, &mut version).await?;
+        Ok(table)
+    }
+
+    /// Drop the value column `name` from this `TableIndex` and return this table with its updated
+    /// schema.
+    ///
+    /// Any auxiliary index on `name` alone is dropped; an index which depends on `name` together
+    /// with other columns must be dropped first. The rows of the primary index are rewritten in
+    /// place, within the given transaction; once it commits, every handle to this table sees the
+    /// new schema.
+    pub async fn drop_column(&self, txn: &Txn, name: Id) -> TCResult<TableIndex<F, D, Txn>>
+    where
+        D::File: AsType<F>,
+        D::FileClass: From<BTreeType>,
+    {
+        let txn_id = *txn.id();
+        let (mut version, table) = self.write_version(txn_id).await?;
+        let primary = table.inner.schema.primary();
+
+        if primary.key().iter().any(|col| col.name == name) {
+            return Err(TCError::bad_request(
+                "cannot drop a key column from a Table",
+                name,
+            ));
+        }
+
+        let index = primary
+            .values()
+            .iter()
+            .position(|col| col.name == name)
+            .ok_or_else(|| TCError::not_found(format!("Column {}", name)))?;
+
+        let mut indices = Vec::with_capacity(table.inner.schema.indices().len());
+        for (index_name, columns) in table.inner.schema.indices() {
+            if columns.as_slice() == &[name.clone()] {
+                // the file of a dropped index is reset if an index with its name is created later
+                continue;
+            } else if columns.contains(&name) {
+                return Err(TCError::bad_request(
+                    format!("cannot drop column {} which is used by index", name),
+                    index_name,
+                ));
+            } else {
+                indices.push((index_name.clone(), columns.to_vec()));
+            }
+        }
+
+        let mut values = primary.values().to_vec();
+        values.remove(index);
+
+        let primary_schema = IndexSchema::from((primary.key().to_vec(), values));
+
+        let auxiliary = table.inner.auxiliary.iter();
+        let auxiliary = auxiliary
+            .filter(|(index_name, _)| indices.iter().any(|(n, _)| n == index_name))
+            .cloned()
+            .collect();
+
+        let index = primary.key().len() + index;
+        let primary = table
+            .rewrite_primary(txn, primary_schema, move |mut row| {
+                row.remove(index);
+                row
+            })
+            .await?;
+
+        let schema = TableSchema::new(primary.schema.clone(), indices);
+        let table = table.with_indices(schema, primary, auxiliary);
+        table.set_version(txn_id, &mut version).await?;
+        Ok(table)
+    }
+
+    /// Drop the auxiliary index `name` and return this table without it in its schema.
+    ///
+    /// The file of the dropped index is reset if an index with the same name is created later.
+    pub async fn drop_index(&self, txn_id: TxnId, name: Id) -> TCResult<TableIndex<F, D, Txn>>
+    where
+        D::File: AsType<F>,
+        D::FileClass: From<BTreeType>,
+    {
+        let (mut version, table) = self.write_version(txn_id).await?;
+
+        if !table.inner.auxiliary.iter().any(|(n, _)| n == &name) {
+            return Err(TCError::not_found(format!("index {}", name)));
+        }
+
+        let indices = table.inner.schema.indices().iter();
+        let indices = indices.filter(|(n, _)| n != &name).cloned();
+        let schema = TableSchema::new(table.inner.schema.primary().clone(), indices);
+
+        let auxiliary = table.inner.auxiliary.iter();
+        let auxiliary = auxiliary.filter(|(n, _)| n != &name).cloned().collect();
+
+        let table = table.with_indices(schema, table.inner.primary.clone(), auxiliary);
+        table.set_version(txn_id, &mut version).await?;
+        Ok(table)
     }
 
-    async fn create_index(
-        file: F,
-        primary: &IndexSchema,
-        key: Vec<Id>,
+    /// Rename the column `old` to `new` in the schema of this table, its primary index, and every
+    /// auxiliary index which contains it, and return this table with its updated schema.
+    ///
+    /// Rows are stored positionally, so this only changes the schema; no row is rewritten.
+    pub async fn rename_column(
+        &self,
         txn_id: TxnId,
-    ) -> TCResult<Index<F, D, Txn>> {
-        let schema = primary.auxiliary(&key)?;
-        let btree = BTreeFile::create(file, schema.clone().into(), txn_id).await?;
-        Ok(Index { btree, schema })
+        old: Id,
+        new: Id,
+    ) -> TCResult<TableIndex<F, D, Txn>>
+    where
+        D::File: AsType<F>,
+        D::FileClass: From<BTreeType>,
+    {
+        debug!("rename column {} to {} at {}", old, new, txn_id);
+
+        let (mut version, table) = self.write_version(txn_id).await?;
+        let primary = table.inner.schema.primary();
+
+        if !primary.column_names().any(|name| name == &old) {
+            return Err(TCError::not_found(format!("Column {}", old)));
+        } else if primary.column_names().any(|name| name == &new) {
+            return Err(TCError::bad_request(
+                "Table already has a column named",
+                new,
+            ));
+        }
+
+        let rename_schema = |schema: &IndexSchema| {
+            IndexSchema::from((
+                rename_columns(schema.key(), &old, &new),
+                rename_columns(schema.values(), &old, &new),
+            ))
+        };
+
+        let indices = table
+            .inner
+            .schema
+            .indices()
+            .iter()
+            .map(|(name, columns)| {
+                let columns = columns
+                    .iter()
+                    .map(|col| {
+                        if col == &old {
+                            new.clone()
+                        } else {
+                            col.clone()
+                        }
+                    })
+                    .collect();
+
+                (name.clone(), columns)
+            })
+            .collect::<Vec<(Id, Vec<Id>)>>();
+
+        let schema = TableSchema::new(rename_schema(primary), indices);
+
+        let primary = Index {
+            btree: table
+                .inner
+                .primary
+                .btree
+                .rename(schema.primary().clone().into())?,
+            schema: schema.primary().clone(),
+        };
+
+        let mut auxiliary = Vec::with_capacity(table.inner.auxiliary.len());
+        for (name, index) in &table.inner.auxiliary {
+            let index = if index.schema.column_names().any(|col| col == &old) {
+                let schema = rename_schema(&index.schema);
+                let btree = index.btree.rename(schema.clone().into())?;
+                Index { btree, schema }
+            } else {
+                index.clone()
+            };
+
+            auxiliary.push((name.clone(), index));
+        }
+
+        let table = table.with_indices(schema, primary, auxiliary);
+        table.set_version(txn_id, &mut version).await?;
+        Ok(table)
+    }
+
+    /// Group this table by the given `columns`, emitting one row per unique value.
+    pub fn group_by(self, columns: Vec<Id>) -> TCResult<Aggregate<F, D, Txn, Merged<F, D, Txn>>> {
+        Aggregate::new(self, columns)
     }
 
     /// Return `true` if this table has zero rows.
@@ -393,30 +756,262 @@ impl<F: File<Node>, D: Dir, Txn: Transaction<D>> TableIndex<F, D, Txn> {
     ///
     /// Returns an error in the case that later [`Bounds`] are larger than earlier [`Bounds`].
     pub fn merge_bounds(&self, all_bounds: Vec<Bounds>) -> TCResult<Bounds> {
-        let collator = self.inner.primary.btree().collator();
+        let columns = self.inner.primary.schema().columns();
 
         let mut merged = Bounds::default();
         for bounds in all_bounds {
-            merged.merge(bounds, collator)?;
+            merged.merge(bounds, &columns)?;
         }
 
         Ok(merged)
     }
 
+    // rewrite every row of the primary index in place with the given `schema`,
+    // reading the existing rows from a copy in the transaction's workspace
+    async fn rewrite_primary<M>(
+        &self,
+        txn: &Txn,
+        schema: IndexSchema,
+        migrate_row: M,
+    ) -> TCResult<Index<F, D, Txn>>
+    where
+        D::File: AsType<F>,
+        D::FileClass: From<BTreeType>,
+        M: Fn(Vec<Value>) -> Vec<Value> + Send + Unpin,
+    {
+        let txn_id = *txn.id();
+        let primary = &self.inner.primary;
+
+        let file: F = txn
+            .context()
+            .create_file_unique(txn_id, BTreeType::default())
+            .await?;
+
+        let copy = <BTreeFile<F, D, Txn> as CopyFrom<D, BTreeFile<F, D, Txn>>>::copy_from(
+            primary.btree.clone(),
+            file,
+            txn,
+        )
+        .await?;
+
+        // the order of the rows is unaffected, since it's determined by the primary key
+        let btree = primary.btree.reset(txn_id, schema.clone().into()).await?;
+        let rows = copy.keys(txn_id).await?;
+        btree.merge_insert_from(txn_id, rows.map_ok(migrate_row)).await?;
+
+        Ok(Index { btree, schema })
+    }
+
+    // construct a handle to this table with the given schema and indices, in the same directory
+    fn with_indices(
+        &self,
+        schema: TableSchema,
+        primary: Index<F, D, Txn>,
+        auxiliary: Vec<(Id, Index<F, D, Txn>)>,
+    ) -> Self {
+        TableIndex {
+            inner: Arc::new(Inner {
+                dir: self.inner.dir.clone(),
+                schema,
+                primary,
+                auxiliary,
+                latest: self.inner.latest.clone(),
+            }),
+        }
+    }
+
+    // construct a handle to the given `version` of this table
+    fn at_version(&self, version: &Version<F, D, Txn>) -> Self {
+        if version.schema == self.inner.schema {
+            self.clone()
+        } else {
+            let auxiliary = version.auxiliary.clone();
+            self.with_indices(version.schema.clone(), version.primary.clone(), auxiliary)
+        }
+    }
+
+    // lock the schema of this table for writing and return a handle to its current version
+    async fn write_version(
+        &self,
+        txn_id: TxnId,
+    ) -> TCResult<(TxnLockWriteGuard<Version<F, D, Txn>>, Self)> {
+        let version = self.inner.latest.write(txn_id).await?;
+        let table = self.at_version(&version);
+        Ok((version, table))
+    }
+
+    // make this handle the current version of its table and record its schema,
+    // so that the table is loaded with it when its host restarts
+    async fn set_version(&self, txn_id: TxnId, version: &mut Version<F, D, Txn>) -> TCResult<()>
+    where
+        D::File: AsType<F>,
+        D::FileClass: From<BTreeType>,
+    {
+        let stored = if let Some(stored) = &version.stored {
+            stored.delete(txn_id, tc_btree::Range::default()).await?;
+            stored.clone()
+        } else {
+            let file = self
+                .inner
+                .dir
+                .create_file(txn_id, SCHEMA.into(), BTreeType::default())
+                .await?;
+
+            BTreeFile::create(file, schema_file(), txn_id).await?
+        };
+
+        let schema = Value::cast_from(self.inner.schema.clone());
+        stored.insert(txn_id, vec![schema]).await?;
+
+        *version = Version {
+            schema: self.inner.schema.clone(),
+            primary: self.inner.primary.clone(),
+            auxiliary: self.inner.auxiliary.clone(),
+            stored: Some(stored),
+        };
+
+        Ok(())
+    }
+
+    /// Delete every row in this table, one range deletion per index rather than row-by-row.
+    pub async fn truncate(&self, txn_id: TxnId) -> TCResult<()> {
+        let mut deletes = Vec::with_capacity(self.inner.auxiliary.len() + 1);
+        deletes.push(self.inner.primary.truncate(txn_id));
+        for (_, index) in &self.inner.auxiliary {
+            deletes.push(index.truncate(txn_id));
+        }
+
+        try_join_all(deletes).await?;
+        Ok(())
+    }
+
+    /// Delete every row of this table within the given `bounds`.
+    ///
+    /// Each index which supports `bounds`, including the primary index if possible, has its matching
+    /// entries deleted as a single range. Rows are only read one at a time to remove their entries
+    /// from any other index.
+    pub async fn delete_where(&self, txn_id: TxnId, bounds: Bounds) -> TCResult<()> {
+        let primary = &self.inner.primary;
+
+        validate_bound_columns(primary.schema(), &bounds)?;
+
+        let columns = primary.schema.columns();
+        let bounds = bounds.validate(&columns)?;
+
+        let primary_by_range = primary.validate_bounds(&bounds).is_ok();
+        let (by_range, by_row): (Vec<&Index<F, D, Txn>>, Vec<&Index<F, D, Txn>>) = self
+            .inner
+            .auxiliary
+            .iter()
+            .map(|(_, index)| index)
+            .partition(|index| index.validate_bounds(&bounds).is_ok());
+
+        debug!(
+            "delete rows within {}: {} index ranges and {} indices row-by-row",
+            bounds,
+            by_range.len() + primary_by_range as usize,
+            by_row.len() + !primary_by_range as usize
+        );
+
+        // the rows to delete are read from an index which supports the bounds,
+        // so none of those indices can be modified until every row has been read
+        if primary_by_range && !by_row.is_empty() {
+            let by_row = by_row.as_slice();
+            let rows = primary.clone().index_slice(bounds.clone())?;
+            let rows = rows.keyed_rows(txn_id).await?;
+
+            rows.map_ok(|row| self.delete_row(txn_id, by_row, row))
+                .try_buffer_unordered(num_cpus::get())
+                .try_fold((), |(), ()| future::ready(Ok(())))
+                .await?;
+        } else if !primary_by_range {
+            let rows = self
+                .clone()
+                .slice(bounds.clone())?
+                .keyed_rows(txn_id)
+                .await?;
+
+            let mut by_row = by_row;
+            by_row.push(primary);
+            let by_row = by_row.as_slice();
+
+            rows.map_ok(|row| self.delete_row(txn_id, by_row, row))
+                .try_buffer_unordered(2)
+                .try_fold((), |(), ()| future::ready(Ok(())))
+                .await?;
+        }
+
+        let mut deletes = Vec::with_capacity(by_range.len() + 1);
+        for index in by_range {
+            let columns = index.schema.columns();
+            let range = bounds.clone().into_btree_range(&columns)?;
+            deletes.push(index.btree.delete(txn_id, range));
+        }
+
+        if primary_by_range {
+            let range = bounds.into_btree_range(&columns)?;
+            deletes.push(primary.btree.delete(txn_id, range));
+        }
+
+        try_join_all(deletes).await?;
+        Ok(())
+    }
+
+    // delete the given `row` from each of the given `indices`
+    async fn delete_row(
+        &self,
+        txn_id: TxnId,
+        indices: &[&Index<F, D, Txn>],
+        row: Row,
+    ) -> TCResult<()> {
+        let deletes = indices.iter().map(|index| index.delete(txn_id, row.clone()));
+        try_join_all(deletes).await?;
+        Ok(())
+    }
+
     /// Borrow the primary `Index` of this `TableIndex`.
     pub fn primary(&self) -> &Index<F, D, Txn> {
         &self.inner.primary
     }
 
+    /// Return the name and columns of the index which [`Self::supporting_index`] would choose
+    /// for the given [`Bounds`].
+    pub fn explain(&self, bounds: &Bounds) -> TCResult<(Id, Vec<Id>)> {
+        let (name, index) = self.find_supporting_index(bounds)?;
+        Ok((name, index.schema().column_names().cloned().collect()))
+    }
+
+    /// Return the name and columns of the index of this `TableIndex` with the given schema.
+    pub fn explain_index(&self, schema: &IndexSchema) -> TCResult<(Id, Vec<Id>)> {
+        let columns = schema.column_names().cloned().collect();
+
+        if self.inner.primary.schema() == schema {
+            return Ok((PRIMARY_INDEX.into(), columns));
+        }
+
+        for (name, index) in &self.inner.auxiliary {
+            if index.schema() == schema {
+                return Ok((name.clone(), columns));
+            }
+        }
+
+        Err(TCError::bad_request("this table has no index with schema", schema))
+    }
+
     /// Return an index which supports the given [`Bounds`], or an error if there is none.
     pub fn supporting_index(&self, bounds: &Bounds) -> TCResult<Index<F, D, Txn>> {
+        self.find_supporting_index(bounds)
+            .map(|(_name, index)| index.clone())
+    }
+
+    fn find_supporting_index(&self, bounds: &Bounds) -> TCResult<(Id, &Index<F, D, Txn>)> {
         if self.inner.primary.validate_bounds(bounds).is_ok() {
-            return Ok(self.inner.primary.clone());
+            return Ok((PRIMARY_INDEX.into(), &self.inner.primary));
         }
 
-        for (_, index) in &self.inner.auxiliary {
+        for (name, index) in &self.inner.auxiliary {
             if index.validate_bounds(bounds).is_ok() {
-                return Ok(index.clone());
+                return Ok((name.clone(), index));
             }
         }
 
@@ -439,6 +1034,116 @@ impl<F: File<Node>, D: Dir, Txn: Transaction<D>> TableIndex<F, D, Txn> {
             .slice_rows(txn_id, bounds, reverse)
             .await
     }
+
+    /// Check that every auxiliary index of this `TableIndex` agrees with its primary index.
+    ///
+    /// This does not stop at the first inconsistency; it returns all of them, if any.
+    pub async fn verify_indices(&self, txn_id: TxnId) -> TCResult<Vec<IndexDiscrepancy>> {
+        let primary = &self.inner.primary;
+        let mut discrepancies = Vec::new();
+
+        // every row of the primary index should have an entry in each auxiliary index
+        let mut rows = primary.clone().keyed_rows(txn_id).await?;
+        while let Some(row) = rows.try_next().await? {
+            for (name, index) in &self.inner.auxiliary {
+                let key = index_key(index.schema.key(), &row)?;
+                let range = tc_btree::Range::with_prefix(key.to_vec());
+                if index
+                    .btree
+                    .clone()
+                    .slice(range, false)?
+                    .is_empty(txn_id)
+                    .await?
+                {
+                    discrepancies.push(IndexDiscrepancy::Missing(name.clone(), key));
+                }
+            }
+        }
+
+        // every entry in an auxiliary index should refer to a primary row with the same values
+        for (name, index) in &self.inner.auxiliary {
+            let mut keys = index.btree.clone().keys(txn_id).await?;
+            while let Some(key) = keys.try_next().await? {
+                let entry = index.schema.row_from_values(key.to_vec())?;
+                let primary_key = index_key(primary.schema.key(), &entry)?;
+
+                let live = match self.read(&txn_id, &primary_key).await? {
+                    Some(row) => {
+                        let row = primary.schema.row_from_values(row)?;
+                        entry.iter().all(|(col, value)| row.get(col) == Some(value))
+                    }
+                    None => false,
+                };
+
+                if !live {
+                    discrepancies.push(IndexDiscrepancy::Orphaned(name.clone(), key));
+                }
+            }
+        }
+
+        Ok(discrepancies)
+    }
+}
+
+/// An inconsistency between an auxiliary index of a [`TableIndex`] and its primary index
+pub enum IndexDiscrepancy {
+    /// The key of a primary row which is missing from the named auxiliary index
+    Missing(Id, Key),
+    /// A key in the named auxiliary index which does not match any primary row
+    Orphaned(Id, Key),
+}
+
+impl fmt::Display for IndexDiscrepancy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Missing(name, key) => write!(
+                f,
+                "index {} is missing key {}",
+                name,
+                Value::from_iter(key.to_vec())
+            ),
+            Self::Orphaned(name, key) => write!(
+                f,
+                "index {} has key {} with no matching row",
+                name,
+                Value::from_iter(key.to_vec())
+            ),
+        }
+    }
+}
+
+async fn create_index<F: File<Node>, D: Dir, Txn: Transaction<D>>(
+    file: F,
+    primary: &IndexSchema,
+    key: Vec<Id>,
+    txn_id: TxnId,
+) -> TCResult<Index<F, D, Txn>> {
+    let schema = primary.auxiliary(&key)?;
+    let btree = BTreeFile::create(file, schema.clone().into(), txn_id).await?;
+    Ok(Index { btree, schema })
+}
+
+// the schema of the file which records the schema of an altered table
+fn schema_file() -> Vec<Column> {
+    vec![Column {
+        name: SCHEMA.into(),
+        dtype: ValueType::Value,
+        max_len: Some(SCHEMA_MAX_LEN),
+        nullable: false,
+        case_insensitive: false,
+    }]
+}
+
+// select the values of the given `columns` from `row`, in order
+fn index_key(columns: &[Column], row: &Row) -> TCResult<Key> {
+    columns
+        .iter()
+        .map(|col| {
+            row.get(&col.name)
+                .cloned()
+                .ok_or_else(|| TCError::bad_request("missing value for column", &col.name))
+        })
+        .collect()
 }
 
 impl<F: File<Node>, D: Dir, Txn: Transaction<D>> Instance for TableIndex<F, D, Txn> {
@@ -579,6 +1284,10 @@ impl<F: File<Node>, D: Dir, Txn: Transaction<D>> TableStream for TableIndex<F, D
         self.inner.primary.clone().count(txn_id).await
     }
 
+    async fn exists(self, txn_id: TxnId) -> TCResult<bool> {
+        self.inner.primary.clone().exists(txn_id).await
+    }
+
     fn limit(self, limit: u64) -> Self::Limit {
         Limited::new(self, limit)
     }
@@ -601,6 +1310,8 @@ impl<F: File<Node>, D: Dir, Txn: Transaction<D>> TableSlice for TableIndex<F, D,
         let primary = &self.inner.primary;
         let auxiliary = &self.inner.auxiliary;
 
+        validate_bound_columns(primary.schema(), &bounds)?;
+
         let columns: Vec<Id> = primary
             .schema()
             .columns()
@@ -685,6 +1396,8 @@ impl<F: File<Node>, D: Dir, Txn: Transaction<D>> TableSlice for TableIndex<F, D,
         let primary = &self.inner.primary;
         let auxiliary = &self.inner.auxiliary;
 
+        validate_bound_columns(primary.schema(), bounds)?;
+
         if primary.validate_bounds(bounds).is_ok() {
             return Ok(());
         }
@@ -768,6 +1481,18 @@ impl<F: File<Node>, D: Dir, Txn: Transaction<D>> TableWrite for TableIndex<F, D,
         Ok(())
     }
 
+    async fn insert(&self, txn_id: TxnId, key: Key, values: Values) -> TCResult<()> {
+        let key = self.inner.primary.schema.validate_key(key)?;
+        if self.read(&txn_id, &key).await?.is_some() {
+            return Err(TCError::bad_request(
+                "there is already a row with key",
+                Value::from_iter(key),
+            ));
+        }
+
+        self.upsert(txn_id, key, values).await
+    }
+
     async fn update(&self, txn_id: TxnId, key: Key, values: Row) -> TCResult<()> {
         let columns_updated: HashSet<Id> = values.keys().cloned().collect();
 
@@ -799,6 +1524,69 @@ impl<F: File<Node>, D: Dir, Txn: Transaction<D>> TableWrite for TableIndex<F, D,
         Ok(())
     }
 
+    async fn update_if_version(
+        &self,
+        txn_id: TxnId,
+        key: Key,
+        version: Id,
+        expected: Value,
+        mut values: Row,
+    ) -> TCResult<()> {
+        let primary = &self.inner.primary;
+        let key_len = primary.schema.key().len();
+
+        let (index, column) = primary
+            .schema
+            .values()
+            .iter()
+            .enumerate()
+            .find(|(_, col)| col.name == version)
+            .ok_or_else(|| TCError::not_found(format!("version column {}", version)))?;
+
+        if !matches!(column.dtype, ValueType::Number(_)) {
+            return Err(TCError::bad_request(
+                format!("version column {} must be numeric, not", version),
+                column.dtype,
+            ));
+        }
+
+        if values.contains_key(&version) {
+            return Err(TCError::bad_request(
+                "cannot update the version column directly",
+                version,
+            ));
+        }
+
+        let expected = column.cast(expected)?;
+        let next = match &expected {
+            Value::Number(n) => column.cast(Value::Number(*n + Number::from(1)))?,
+            other => return Err(TCError::bad_request("invalid version", other)),
+        };
+
+        // read the row by its exact key--the read and the update both hold locks for this
+        // transaction, so a concurrent transaction which updates the same row can't commit
+        // in between them
+        let key = primary.schema.validate_key(key)?;
+        let row = self.read(&txn_id, &key).await?.ok_or_else(|| {
+            TCError::not_found(format!("row with key {}", Value::from_iter(key.to_vec())))
+        })?;
+
+        if row[key_len + index] != expected {
+            return Err(TCError::new(
+                ErrorType::Conflict,
+                format!(
+                    "expected version {} of row {} but found {}",
+                    expected,
+                    Value::from_iter(key),
+                    row[key_len + index]
+                ),
+            ));
+        }
+
+        values.insert(version, next);
+        self.update(txn_id, key, values).await
+    }
+
     async fn upsert(&self, txn_id: TxnId, key: Key, values: Values) -> TCResult<()> {
         let primary = &self.inner.primary;
         let aux = &self.inner.auxiliary;
@@ -834,25 +1622,52 @@ impl<F: File<Node>, D: Dir, Txn: Transaction<D>> TableWrite for TableIndex<F, D,
 }
 
 #[async_trait]
-impl<F: File<Node> + Transact, D: Dir, Txn: Transaction<D>> Transact for TableIndex<F, D, Txn> {
+impl<F, D, Txn> Transact for TableIndex<F, D, Txn>
+where
+    F: File<Node> + Transact,
+    D: Dir + Transact,
+    Txn: Transaction<D>,
+{
     async fn commit(&self, txn_id: &TxnId) {
-        let mut commits = Vec::with_capacity(self.inner.auxiliary.len() + 1);
-        commits.push(self.inner.primary.commit(txn_id));
-        for (_, index) in &self.inner.auxiliary {
+        join!(self.inner.dir.commit(txn_id), self.inner.latest.commit(txn_id));
+
+        // a schema change made through another handle may have replaced this handle's indices
+        let version = self.inner.latest.read(*txn_id).await.expect("Table schema");
+
+        let mut commits = Vec::with_capacity(version.auxiliary.len() + 2);
+        commits.push(version.primary.commit(txn_id));
+        for (_, index) in &version.auxiliary {
             commits.push(index.commit(txn_id));
         }
 
+        if let Some(stored) = &version.stored {
+            commits.push(stored.commit(txn_id));
+        }
+
         join_all(commits).await;
     }
 
     async fn finalize(&self, txn_id: &TxnId) {
-        let mut cleanups = Vec::with_capacity(self.inner.auxiliary.len() + 1);
-        cleanups.push(self.inner.primary.finalize(txn_id));
-        for (_, index) in &self.inner.auxiliary {
-            cleanups.push(index.finalize(txn_id));
+        {
+            let version = self.inner.latest.read(*txn_id).await.expect("Table schema");
+
+            let mut cleanups = Vec::with_capacity(version.auxiliary.len() + 2);
+            cleanups.push(version.primary.finalize(txn_id));
+            for (_, index) in &version.auxiliary {
+                cleanups.push(index.finalize(txn_id));
+            }
+
+            if let Some(stored) = &version.stored {
+                cleanups.push(stored.finalize(txn_id));
+            }
+
+            join_all(cleanups).await;
         }
 
-        join_all(cleanups).await;
+        join!(
+            self.inner.dir.finalize(txn_id),
+            self.inner.latest.finalize(txn_id)
+        );
     }
 }
 
@@ -871,6 +1686,26 @@ where
     }
 
     async fn load(txn: &Txn, schema: Self::Schema, store: Self::Store) -> TCResult<Self> {
+        let txn_id = *txn.id();
+
+        // if this table's schema has been altered since it was created, load the altered schema
+        let (schema, stored) = if let Some(file) = store.get_file(txn_id, &SCHEMA.into()).await? {
+            let stored: BTreeFile<F, D, Txn> = BTreeFile::load(txn, schema_file(), file).await?;
+            let mut keys = stored.clone().keys(txn_id).await?;
+            let schema = match keys.try_next().await? {
+                Some(mut key) if key.len() == 1 => {
+                    TableSchema::try_cast_from(key.remove(0), |v| {
+                        TCError::internal(format!("cannot load Table: invalid schema {}", v))
+                    })?
+                }
+                _ => return Err(TCError::internal("cannot load Table: schema is missing")),
+            };
+
+            (schema, Some(stored))
+        } else {
+            (schema, None)
+        };
+
         let file = store
             .get_file(*txn.id(), &PRIMARY_INDEX.into())
             .await?
@@ -890,13 +1725,7 @@ where
             auxiliary.push((name.clone(), index));
         }
 
-        Ok(Self {
-            inner: Arc::new(Inner {
-                schema,
-                primary,
-                auxiliary,
-            }),
-        })
+        Ok(Self::new(store, schema, primary, auxiliary, stored))
     }
 }
 
@@ -970,3 +1799,25 @@ impl<F: File<Node>, D: Dir, Txn: Transaction<D>> fmt::Display for TableIndex<F,
         f.write_str("a Table")
     }
 }
+
+// return a `NotFound` error for the first column in `bounds` which is not in the given `schema`
+#[inline]
+fn validate_bound_columns(schema: &IndexSchema, bounds: &Bounds) -> TCResult<()> {
+    let columns: Vec<Id> = bounds.keys().cloned().collect();
+    schema.validate_columns(&columns)
+}
+
+// rename the column `old` to `new`, if present
+fn rename_columns(columns: &[Column], old: &Id, new: &Id) -> Vec<Column> {
+    columns
+        .iter()
+        .cloned()
+        .map(|mut col| {
+            if &col.name == old {
+                col.name = new.clone();
+            }
+
+            col
+        })
+        .collect()
+}
@@ -22,9 +22,9 @@ use index::*;
 use view::*;
 
 pub use bounds::*;
-pub use index::TableIndex;
+pub use index::{IndexDiscrepancy, TableIndex};
 pub use schema::*;
-pub use view::Merged;
+pub use view::{Aggregate, Merged};
 
 mod bounds;
 mod index;
@@ -49,6 +49,11 @@ pub trait TableInstance: Instance<Class = TableType> {
 
     /// Return the schema of this `Table`.
     fn schema(&self) -> TableSchema;
+
+    /// Return the [`IndexSchema`] of this `Table`'s key and values, without its indices.
+    fn primary_schema(&self) -> IndexSchema {
+        (self.key().to_vec(), self.values().to_vec()).into()
+    }
 }
 
 /// [`Table`] sort methods
@@ -100,6 +105,30 @@ pub trait TableStream: TableInstance + Sized {
             .await
     }
 
+    /// Return `true` if this `Table` has at least one row, without counting every row.
+    async fn exists(self, txn_id: TxnId) -> TCResult<bool> {
+        let mut rows = self.rows(txn_id).await?;
+        rows.try_next().map_ok(|row| row.is_some()).await
+    }
+
+    /// Return the first row of this `Table`, in its current order.
+    async fn first(self, txn_id: TxnId) -> TCResult<Vec<Value>> {
+        let mut rows = self.rows(txn_id).await?;
+        rows.try_next()
+            .await?
+            .ok_or_else(|| TCError::not_found("this Table is empty"))
+    }
+
+    /// Return the last row of this `Table`, in its current order.
+    ///
+    /// By default this reads every row; implementors which support `reverse` should override it.
+    async fn last(self, txn_id: TxnId) -> TCResult<Vec<Value>> {
+        let rows = self.rows(txn_id).await?;
+        rows.try_fold(None, |_, row| future::ready(Ok(Some(row))))
+            .await?
+            .ok_or_else(|| TCError::not_found("this Table is empty"))
+    }
+
     /// Limit the number of rows returned by `rows`.
     fn limit(self, limit: u64) -> Self::Limit;
 
@@ -108,6 +137,23 @@ pub trait TableStream: TableInstance + Sized {
 
     /// Return a stream of the rows in this `Table`.
     async fn rows<'a>(self, txn_id: TxnId) -> TCResult<TCBoxTryStream<'a, Vec<Value>>>;
+
+    /// Return a stream of the rows in this `Table`, each keyed by column name.
+    ///
+    /// Prefer `rows` where the column names are not needed, since this allocates a [`Row`] each.
+    async fn keyed_rows<'a>(self, txn_id: TxnId) -> TCResult<TCBoxTryStream<'a, Row>> {
+        let schema = self.primary_schema();
+        let rows = self.rows(txn_id).await?;
+        let rows = rows.and_then(move |row| {
+            future::ready(if row.len() == schema.len() {
+                schema.row_from_values(row)
+            } else {
+                Err(TCError::internal("the rows of this Table do not match its schema"))
+            })
+        });
+
+        Ok(Box::pin(rows))
+    }
 }
 
 /// [`Table`] write methods
@@ -116,9 +162,23 @@ pub trait TableWrite: TableInstance {
     /// Delete the given [`Row`] from this table, if present.
     async fn delete(&self, txn_id: TxnId, key: Key) -> TCResult<()>;
 
+    /// Insert a new row into this table, or return an error if the given key is already present.
+    async fn insert(&self, txn_id: TxnId, key: Key, values: Values) -> TCResult<()>;
+
     /// Update one row of this table.
     async fn update(&self, txn_id: TxnId, key: Key, values: Row) -> TCResult<()>;
 
+    /// Update one row of this table only if its numeric `version` column holds the `expected`
+    /// value, and increment its version, or return a conflict error if it doesn't.
+    async fn update_if_version(
+        &self,
+        txn_id: TxnId,
+        key: Key,
+        version: Id,
+        expected: Value,
+        values: Row,
+    ) -> TCResult<()>;
+
     /// Insert or update the given row.
     async fn upsert(&self, txn_id: TxnId, key: Key, values: Values) -> TCResult<()>;
 }
@@ -127,6 +187,7 @@ pub trait TableWrite: TableInstance {
 #[derive(Clone, Copy, Hash, Eq, PartialEq)]
 pub enum TableType {
     Table,
+    Aggregate,
     Index,
     IndexSlice,
     Limit,
@@ -163,6 +224,7 @@ impl fmt::Display for TableType {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             Self::Table => write!(f, "type Table"),
+            Self::Aggregate => write!(f, "type Aggregate"),
             Self::Index => write!(f, "type Index"),
             Self::IndexSlice => write!(f, "type Index slice"),
             Self::Limit => write!(f, "type Limit selection"),
@@ -177,6 +239,7 @@ impl fmt::Display for TableType {
 #[derive(Clone)]
 pub enum Table<F, D, Txn> {
     Table(TableIndex<F, D, Txn>),
+    Aggregate(Box<Aggregate<F, D, Txn, Table<F, D, Txn>>>),
     Index(Index<F, D, Txn>),
     IndexSlice(IndexSlice<F, D, Txn>),
     Limit(Box<Limited<F, D, Txn>>),
@@ -194,6 +257,7 @@ where
     fn class(&self) -> Self::Class {
         match self {
             Self::Table(_) => TableType::Table,
+            Self::Aggregate(_) => TableType::Aggregate,
             Self::Index(_) => TableType::Index,
             Self::IndexSlice(_) => TableType::IndexSlice,
             Self::Limit(_) => TableType::Limit,
@@ -204,6 +268,19 @@ where
     }
 }
 
+impl<F: File<Node>, D: Dir, Txn: Transaction<D>> Table<F, D, Txn>
+where
+    Self: Send + Sync,
+{
+    /// Group this `Table` by the given `columns`, emitting one row per unique value.
+    pub fn group_by(self, columns: Vec<Id>) -> TCResult<Self> {
+        match self {
+            Self::Aggregate(aggregate) => aggregate.group_by(columns).map(Self::from),
+            other => Aggregate::new(other, columns).map(Self::from),
+        }
+    }
+}
+
 impl<F: File<Node>, D: Dir, Txn: Transaction<D>> TableInstance for Table<F, D, Txn>
 where
     Self: Send + Sync,
@@ -211,6 +288,7 @@ where
     fn key(&self) -> &[Column] {
         match self {
             Self::Table(table) => table.key(),
+            Self::Aggregate(aggregate) => aggregate.key(),
             Self::Index(index) => index.key(),
             Self::IndexSlice(slice) => slice.key(),
             Self::Limit(limit) => limit.key(),
@@ -223,6 +301,7 @@ where
     fn values(&self) -> &[Column] {
         match self {
             Self::Table(table) => table.values(),
+            Self::Aggregate(aggregate) => aggregate.values(),
             Self::Index(slice) => slice.values(),
             Self::IndexSlice(slice) => slice.values(),
             Self::Limit(limit) => limit.values(),
@@ -235,6 +314,7 @@ where
     fn schema(&self) -> TableSchema {
         match self {
             Self::Table(table) => table.schema(),
+            Self::Aggregate(aggregate) => aggregate.schema(),
             Self::Index(slice) => TableInstance::schema(slice),
             Self::IndexSlice(slice) => TableInstance::schema(slice),
             Self::Limit(limit) => limit.schema(),
@@ -256,6 +336,7 @@ where
     fn order_by(self, order: Vec<Id>, reverse: bool) -> TCResult<Self::OrderBy> {
         match self {
             Self::Table(table) => table.order_by(order, reverse).map(Self::from),
+            Self::Aggregate(aggregate) => aggregate.order_by(order, reverse).map(Self::from),
             Self::Index(index) => index.order_by(order, reverse).map(Self::from),
             Self::IndexSlice(slice) => slice.order_by(order, reverse).map(Self::from),
             Self::Merge(merge) => merge.order_by(order, reverse).map(Self::from),
@@ -271,6 +352,7 @@ where
     fn reverse(self) -> TCResult<Self::Reverse> {
         match self {
             Self::Table(table) => table.reverse().map(Self::from),
+            Self::Aggregate(aggregate) => aggregate.reverse().map(Self::from),
             Self::Index(index) => index.reverse().map(Self::from),
             Self::IndexSlice(slice) => slice.reverse().map(Self::from),
             Self::Merge(merge) => merge.reverse().map(Self::from),
@@ -286,6 +368,7 @@ where
     fn validate_order(&self, order: &[Id]) -> TCResult<()> {
         match self {
             Self::Table(table) => table.validate_order(order),
+            Self::Aggregate(aggregate) => aggregate.validate_order(order),
             Self::Index(index) => index.validate_order(order),
             Self::IndexSlice(slice) => slice.validate_order(order),
             Self::Merge(merge) => merge.validate_order(order),
@@ -325,6 +408,7 @@ where
     async fn count(self, txn_id: TxnId) -> TCResult<u64> {
         match self {
             Self::Table(table) => table.count(txn_id).await,
+            Self::Aggregate(aggregate) => aggregate.count(txn_id).await,
             Self::Index(index) => index.count(txn_id).await,
             Self::IndexSlice(slice) => slice.count(txn_id).await,
             Self::Limit(limit) => limit.count(txn_id).await,
@@ -334,9 +418,31 @@ where
         }
     }
 
+    async fn exists(self, txn_id: TxnId) -> TCResult<bool> {
+        match self {
+            Self::Table(table) => table.exists(txn_id).await,
+            Self::Index(index) => index.exists(txn_id).await,
+            Self::IndexSlice(slice) => slice.exists(txn_id).await,
+            other => {
+                let mut rows = other.rows(txn_id).await?;
+                rows.try_next().map_ok(|row| row.is_some()).await
+            }
+        }
+    }
+
+    async fn last(self, txn_id: TxnId) -> TCResult<Vec<Value>> {
+        match self {
+            // the last row of a limited table is not the first row of its reversed source
+            Self::Limit(limited) => limited.last(txn_id).await,
+            Self::Aggregate(aggregate) => aggregate.last(txn_id).await,
+            other => other.reverse()?.first(txn_id).await,
+        }
+    }
+
     fn limit(self, limit: u64) -> <Self as TableStream>::Limit {
         match self {
             Self::Table(table) => table.limit(limit).into(),
+            Self::Aggregate(aggregate) => aggregate.limit(limit).into(),
             Self::Index(index) => index.limit(limit).into(),
             Self::IndexSlice(slice) => slice.limit(limit).into(),
             Self::Limit(limited) => limited.limit(limit).into(),
@@ -349,6 +455,7 @@ where
     fn select(self, columns: Vec<Id>) -> TCResult<<Self as TableStream>::Selection> {
         match self {
             Self::Table(table) => table.select(columns).map(Self::from),
+            Self::Aggregate(aggregate) => aggregate.select(columns).map(Self::from),
             Self::Index(index) => index.select(columns).map(Self::from),
             Self::IndexSlice(slice) => slice.select(columns).map(Self::from),
             Self::Limit(limited) => limited.select(columns).map(Self::from),
@@ -361,6 +468,7 @@ where
     async fn rows<'a>(self, txn_id: TxnId) -> TCResult<TCBoxTryStream<'a, Vec<Value>>> {
         match self {
             Self::Table(table) => table.rows(txn_id).await,
+            Self::Aggregate(aggregate) => aggregate.rows(txn_id).await,
             Self::Index(index) => index.rows(txn_id).await,
             Self::IndexSlice(slice) => slice.rows(txn_id).await,
             Self::Limit(limited) => limited.rows(txn_id).await,
@@ -369,6 +477,19 @@ where
             Self::TableSlice(slice) => slice.rows(txn_id).await,
         }
     }
+
+    async fn keyed_rows<'a>(self, txn_id: TxnId) -> TCResult<TCBoxTryStream<'a, Row>> {
+        match self {
+            Self::Table(table) => table.keyed_rows(txn_id).await,
+            Self::Aggregate(aggregate) => aggregate.keyed_rows(txn_id).await,
+            Self::Index(index) => index.keyed_rows(txn_id).await,
+            Self::IndexSlice(slice) => slice.keyed_rows(txn_id).await,
+            Self::Limit(limited) => limited.keyed_rows(txn_id).await,
+            Self::Merge(merge) => merge.keyed_rows(txn_id).await,
+            Self::Selection(selection) => selection.keyed_rows(txn_id).await,
+            Self::TableSlice(slice) => slice.keyed_rows(txn_id).await,
+        }
+    }
 }
 
 #[async_trait]
@@ -419,6 +540,17 @@ where
         }
     }
 
+    async fn insert(&self, txn_id: TxnId, key: Key, values: Values) -> TCResult<()> {
+        if let Self::Table(table) = self {
+            table.insert(txn_id, key, values).await
+        } else {
+            Err(TCError::unsupported(format!(
+                "instance of {} does not support insert",
+                self.class()
+            )))
+        }
+    }
+
     async fn update(&self, txn_id: TxnId, key: Key, values: Row) -> TCResult<()> {
         if let Self::Table(table) = self {
             table.update(txn_id, key, values).await
@@ -430,6 +562,26 @@ where
         }
     }
 
+    async fn update_if_version(
+        &self,
+        txn_id: TxnId,
+        key: Key,
+        version: Id,
+        expected: Value,
+        values: Row,
+    ) -> TCResult<()> {
+        if let Self::Table(table) = self {
+            table
+                .update_if_version(txn_id, key, version, expected, values)
+                .await
+        } else {
+            Err(TCError::unsupported(format!(
+                "instance of {} does not support update",
+                self.class()
+            )))
+        }
+    }
+
     async fn upsert(&self, txn_id: TxnId, key: Key, values: Values) -> TCResult<()> {
         if let Self::Table(table) = self {
             table.upsert(txn_id, key, values).await
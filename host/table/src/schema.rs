@@ -8,7 +8,7 @@ use futures::TryFutureExt;
 use safecast::*;
 
 use tc_error::*;
-use tc_value::{Value, ValueType};
+use tc_value::Value;
 use tcgeneric::{Id, Map, Tuple};
 
 use super::{Key, Values};
@@ -100,9 +100,11 @@ impl IndexSchema {
 
         let mut values = Vec::with_capacity(self.values().len());
         for col in self.values() {
-            let value = row
-                .remove(col.name())
-                .ok_or(TCError::not_found(col.name()))?;
+            let value = match row.remove(col.name()) {
+                Some(value) => value,
+                None if col.nullable => Value::None,
+                None => return Err(TCError::not_found(col.name())),
+            };
 
             values.push(value);
         }
@@ -140,7 +142,7 @@ impl IndexSchema {
 
         let mut row = Map::new();
         for (column, value) in self.columns().into_iter().zip(values.into_iter()) {
-            let value = column.dtype.try_cast(value)?;
+            let value = column.cast(value)?;
             row.insert(column.name, value);
         }
 
@@ -164,6 +166,24 @@ impl IndexSchema {
         true
     }
 
+    /// Return `true` if an index with this schema can return rows in the given `order`.
+    ///
+    /// This is the case if `order` is a prefix of this schema, or if this schema is a prefix of
+    /// `order`: every row of an index is unique, so columns after a complete index row are only
+    /// tie-breakers which can never apply.
+    pub fn supports_order(&self, order: &[Id]) -> bool {
+        if self.starts_with(order) {
+            return true;
+        }
+
+        let columns = self.columns();
+        order.len() > columns.len()
+            && columns
+                .iter()
+                .zip(order)
+                .all(|(col, name)| col.name() == name)
+    }
+
     /// Return the `IndexSchema` needed to index the given columns.
     pub fn auxiliary(&self, key: &[Id]) -> TCResult<IndexSchema> {
         let subset: HashSet<&Id> = key.iter().collect();
@@ -220,7 +240,7 @@ impl IndexSchema {
 
         let mut validated = Vec::with_capacity(key.len());
         for (val, col) in key.into_iter().zip(self.key.iter()) {
-            let value = col.dtype.try_cast(val)?;
+            let value = col.cast(val)?;
             validated.push(value);
         }
 
@@ -240,7 +260,7 @@ impl IndexSchema {
 
         let mut validated = Vec::with_capacity(values.len());
         for (val, col) in values.into_iter().zip(self.values.iter()) {
-            let value = col.dtype.try_cast(val)?;
+            let value = col.cast(val)?;
             validated.push(value);
         }
 
@@ -250,18 +270,18 @@ impl IndexSchema {
     /// Return an error if the given [`Row`] has any extra fields or incompatible values.
     pub fn validate_row_partial(&self, row: Row) -> TCResult<Row> {
         let mut validated = Row::new();
-        let columns: HashMap<Id, ValueType> = self
+        let columns: HashMap<Id, Column> = self
             .columns()
             .into_iter()
-            .map(|c| (c.name, c.dtype))
+            .map(|c| (c.name.clone(), c))
             .collect();
 
         for (col_name, value) in row.into_iter() {
-            let dtype = columns
+            let column = columns
                 .get(&col_name)
                 .ok_or(TCError::bad_request("No such column", &col_name))?;
 
-            let value = dtype.try_cast(value)?;
+            let value = column.cast(value)?;
             validated.insert(col_name, value);
         }
 
@@ -269,7 +289,13 @@ impl IndexSchema {
     }
 
     /// Return an error if the given [`Row`] does not have a compatible value for every column.
-    pub fn validate_row(&self, row: Row) -> TCResult<Row> {
+    pub fn validate_row(&self, mut row: Row) -> TCResult<Row> {
+        for col in &self.values {
+            if col.nullable && !row.contains_key(col.name()) {
+                row.insert(col.name.clone(), Value::None);
+            }
+        }
+
         let expected: HashSet<Id> = self.columns().iter().map(|c| c.name()).cloned().collect();
         let actual: HashSet<Id> = row.keys().cloned().collect();
         let missing: Vec<&Id> = expected.difference(&actual).collect();
@@ -304,11 +330,18 @@ impl IndexSchema {
     pub fn values_from_row(&self, mut row: Row, reject_extras: bool) -> TCResult<Vec<Value>> {
         let mut key = Vec::with_capacity(self.len());
         for column in self.columns() {
-            let value = row
-                .remove(&column.name)
-                .ok_or_else(|| TCError::bad_request("missing value for column", &column.name))?;
-
-            let value = column.dtype.try_cast(value)?;
+            let value = match row.remove(&column.name) {
+                Some(value) => value,
+                None if column.nullable => Value::None,
+                None => {
+                    return Err(TCError::bad_request(
+                        "missing value for column",
+                        &column.name,
+                    ))
+                }
+            };
+
+            let value = column.cast(value)?;
             key.push(value);
         }
 
@@ -1,10 +1,11 @@
+use std::cmp::Ordering;
 use std::collections::{HashMap, HashSet};
 use std::iter::FromIterator;
 use std::marker::PhantomData;
 
 use async_trait::async_trait;
 use futures::future;
-use futures::stream::{StreamExt, TryStreamExt};
+use futures::stream::{self, StreamExt, TryStreamExt};
 use log::debug;
 
 use tc_btree::{BTreeFile, BTreeInstance, Node};
@@ -16,10 +17,221 @@ use tcgeneric::{Id, Instance, TCBoxTryStream};
 
 use super::index::TableIndex;
 use super::{
-    Bounds, Column, IndexSchema, Table, TableInstance, TableOrder, TableSchema, TableStream,
+    Bounds, Column, IndexSchema, Row, Table, TableInstance, TableOrder, TableSchema, TableStream,
     TableType,
 };
 
+/// A view of the unique values of the given columns of a [`Table`], in order
+#[derive(Clone)]
+pub struct Aggregate<F, D, Txn, T> {
+    source: Selection<F, D, Txn, T>,
+}
+
+impl<F: File<Node>, D: Dir, Txn: Transaction<D>, T: TableInstance> Aggregate<F, D, Txn, T> {
+    /// Group the given `source` by the given `columns`.
+    pub fn new<S: TableOrder<OrderBy = T>>(source: S, columns: Vec<Id>) -> TCResult<Self> {
+        let schema = source.schema();
+        for name in &columns {
+            if !schema.primary().column_names().any(|col| col == name) {
+                return Err(TCError::not_found(format!("Column {}", name)));
+            }
+        }
+
+        let source = source.order_by(columns.to_vec(), false)?;
+        let source = Selection::new(source, columns)?;
+        Ok(Self { source })
+    }
+
+    /// Return an error, since an `Aggregate` is already grouped.
+    pub fn group_by(self, columns: Vec<Id>) -> TCResult<Self> {
+        Err(TCError::unsupported(format!(
+            "cannot group an aggregate of columns {} by {}",
+            Value::from_iter(self.source.columns),
+            Value::from_iter(columns)
+        )))
+    }
+}
+
+impl<F, D, Txn, T> Instance for Aggregate<F, D, Txn, T>
+where
+    Self: Send + Sync,
+{
+    type Class = TableType;
+
+    fn class(&self) -> Self::Class {
+        TableType::Aggregate
+    }
+}
+
+impl<F, D, Txn, T> TableInstance for Aggregate<F, D, Txn, T>
+where
+    F: File<Node>,
+    D: Dir,
+    Txn: Transaction<D>,
+    T: TableInstance,
+{
+    fn key(&self) -> &[Column] {
+        self.source.key()
+    }
+
+    fn values(&self) -> &[Column] {
+        self.source.values()
+    }
+
+    fn schema(&self) -> TableSchema {
+        self.source.schema()
+    }
+}
+
+impl<F, D, Txn, T> TableOrder for Aggregate<F, D, Txn, T>
+where
+    F: File<Node>,
+    D: Dir,
+    Txn: Transaction<D>,
+    T: TableInstance,
+{
+    type OrderBy = Self;
+    type Reverse = Self;
+
+    fn order_by(self, order: Vec<Id>, reverse: bool) -> TCResult<Self::OrderBy> {
+        self.validate_order(&order)?;
+
+        if reverse {
+            self.reverse()
+        } else {
+            Ok(self)
+        }
+    }
+
+    fn reverse(self) -> TCResult<Self::Reverse> {
+        Err(TCError::unsupported(
+            "cannot reverse an aggregate (consider reversing its source instead)",
+        ))
+    }
+
+    fn validate_order(&self, order: &[Id]) -> TCResult<()> {
+        if self.source.columns.starts_with(order) {
+            Ok(())
+        } else {
+            Err(TCError::bad_request(
+                &format!(
+                    "aggregate of columns {} does not support order",
+                    Value::from_iter(self.source.columns.to_vec())
+                ),
+                Value::from_iter(order.to_vec()),
+            ))
+        }
+    }
+}
+
+#[async_trait]
+impl<F, D, Txn, T> TableStream for Aggregate<F, D, Txn, T>
+where
+    F: File<Node>,
+    D: Dir,
+    Txn: Transaction<D>,
+    T: TableStream,
+    Table<F, D, Txn>: From<T>,
+{
+    type Limit = Limited<F, D, Txn>;
+    type Selection = Selection<F, D, Txn, Self>;
+
+    fn limit(self, limit: u64) -> Self::Limit {
+        Limited::new(self, limit)
+    }
+
+    fn select(self, columns: Vec<Id>) -> TCResult<Self::Selection> {
+        Selection::new(self, columns)
+    }
+
+    async fn rows<'a>(self, txn_id: TxnId) -> TCResult<TCBoxTryStream<'a, Vec<Value>>> {
+        // compare only the group-by columns, so that rows in the same group always collapse
+        let group_len = self.source.columns.len();
+
+        let mut last: Option<Vec<Value>> = None;
+        let rows = self.source.rows(txn_id).await?;
+        let rows = rows.try_filter(move |row| {
+            let group = &row[..group_len];
+            let unique = last.as_deref() != Some(group);
+            if unique {
+                last = Some(group.to_vec());
+            }
+
+            future::ready(unique)
+        });
+
+        Ok(Box::pin(rows))
+    }
+
+    async fn keyed_rows<'a>(self, txn_id: TxnId) -> TCResult<TCBoxTryStream<'a, Row>> {
+        let columns = self.source.columns.to_vec();
+        let rows = self.rows(txn_id).await?;
+        Ok(Box::pin(rows.map_ok(move |row| label_row(&columns, row))))
+    }
+}
+
+impl<F, D, Txn, T> Aggregate<F, D, Txn, T>
+where
+    F: File<Node>,
+    D: Dir,
+    Txn: Transaction<D>,
+    T: TableStream,
+    Table<F, D, Txn>: From<T>,
+{
+    /// Stream each unique group in this aggregate together with the number of rows in it.
+    pub async fn counts<'a>(
+        self,
+        txn_id: TxnId,
+    ) -> TCResult<TCBoxTryStream<'a, (Vec<Value>, u64)>> {
+        let group_len = self.source.columns.len();
+
+        // a trailing `None` marks the end of the last group
+        let rows = self.source.rows(txn_id).await?;
+        let rows = rows.map_ok(Some).chain(stream::once(future::ready(Ok(None))));
+
+        let mut last: Option<(Vec<Value>, u64)> = None;
+        let counts = rows.try_filter_map(move |row| {
+            let counted = match row {
+                Some(row) => match last.as_mut() {
+                    Some((group, count)) if group[..] == row[..group_len] => {
+                        *count += 1;
+                        None
+                    }
+                    _ => last.replace((row[..group_len].to_vec(), 1)),
+                },
+                None => last.take(),
+            };
+
+            future::ready(Ok(counted))
+        });
+
+        Ok(Box::pin(counts))
+    }
+}
+
+impl<F, D, Txn, T> From<Aggregate<F, D, Txn, T>> for Table<F, D, Txn>
+where
+    F: File<Node>,
+    D: Dir,
+    Txn: Transaction<D>,
+    T: TableInstance,
+    Table<F, D, Txn>: From<T>,
+{
+    fn from(aggregate: Aggregate<F, D, Txn, T>) -> Self {
+        let selection = aggregate.source;
+
+        Table::Aggregate(Box::new(Aggregate {
+            source: Selection {
+                source: selection.source.into(),
+                schema: selection.schema,
+                columns: selection.columns,
+                indices: selection.indices,
+                phantom: Phantom::default(),
+            },
+        }))
+    }
+}
+
 #[derive(Clone)]
 pub struct IndexSlice<F, D, Txn> {
     source: BTreeFile<F, D, Txn>,
@@ -69,6 +281,33 @@ impl<F: File<Node>, D: Dir, Txn: Transaction<D>> IndexSlice<F, D, Txn> {
         &self.bounds
     }
 
+    /// Stream only the given `key` columns of each row in this slice, directly from its `BTree`.
+    pub async fn stream_keys<'a>(
+        self,
+        txn_id: TxnId,
+        key: &[Id],
+    ) -> TCResult<TCBoxTryStream<'a, Vec<Value>>> {
+        let columns = self.schema.columns();
+        let indices = key
+            .iter()
+            .map(|name| {
+                columns
+                    .iter()
+                    .position(|col| &col.name == name)
+                    .ok_or_else(|| TCError::not_found(format!("Column {}", name)))
+            })
+            .collect::<TCResult<Vec<usize>>>()?;
+
+        let rows = self
+            .source
+            .slice(self.range, self.reverse)?
+            .keys(txn_id)
+            .await?;
+
+        let keys = rows.map_ok(move |row| indices.iter().map(|i| row[*i].clone()).collect());
+        Ok(Box::pin(keys))
+    }
+
     pub fn schema(&'_ self) -> &'_ IndexSchema {
         &self.schema
     }
@@ -88,13 +327,25 @@ impl<F: File<Node>, D: Dir, Txn: Transaction<D>> IndexSlice<F, D, Txn> {
 
     pub fn slice_index(self, bounds: Bounds) -> TCResult<Self> {
         let columns = self.schema().columns();
-        let outer = bounds.clone().into_btree_range(&columns)?;
+        let bounds = bounds.validate(&columns)?;
         let inner = bounds.clone().into_btree_range(&columns)?;
 
-        if outer.contains(&inner, self.source.collator()) {
+        if self.source.collator().contains(&self.range, &inner) {
             let mut slice = self;
             slice.bounds = bounds;
+            slice.range = inner;
             Ok(slice)
+        } else if let Some(column) = self.bounds.find_uncontained(&bounds, &columns) {
+            let outer = self.bounds.get(column.name()).expect("outer bound");
+            let inner = bounds.get(column.name()).cloned().unwrap_or_default();
+
+            Err(TCError::bad_request(
+                &format!(
+                    "bound {} on column {} of type {} is outside of the IndexSlice bound",
+                    inner, column.name, column.dtype
+                ),
+                outer,
+            ))
         } else {
             Err(TCError::bad_request(
                 &format!("IndexSlice with bounds {} does not contain", self.bounds),
@@ -169,7 +420,7 @@ where
     }
 
     fn validate_order(&self, order: &[Id]) -> TCResult<()> {
-        if self.schema.starts_with(order) {
+        if self.schema.supports_order(order) {
             Ok(())
         } else {
             Err(TCError::bad_request(
@@ -191,10 +442,21 @@ where
     type Selection = Selection<F, D, Txn, Self>;
 
     async fn count(self, txn_id: TxnId) -> TCResult<u64> {
-        self.source
-            .slice(self.range.clone(), false)?
-            .count(txn_id)
-            .await
+        if self.range == tc_btree::Range::default() {
+            // an unbounded slice can be counted without streaming its rows
+            self.source.count(txn_id).await
+        } else {
+            self.source
+                .slice(self.range.clone(), false)?
+                .count(txn_id)
+                .await
+        }
+    }
+
+    async fn exists(self, txn_id: TxnId) -> TCResult<bool> {
+        let slice = self.source.slice(self.range, false)?;
+        let is_empty = slice.is_empty(txn_id).await?;
+        Ok(!is_empty)
     }
 
     fn limit(self, limit: u64) -> Self::Limit {
@@ -282,6 +544,12 @@ impl<F: File<Node>, D: Dir, Txn: Transaction<D>> TableStream for Limited<F, D, T
         let rows: TCBoxTryStream<Vec<Value>> = Box::pin(rows.take(self.limit as usize));
         Ok(rows)
     }
+
+    async fn keyed_rows<'a>(self, txn_id: TxnId) -> TCResult<TCBoxTryStream<'a, Row>> {
+        let rows = self.source.keyed_rows(txn_id).await?;
+        let rows: TCBoxTryStream<Row> = Box::pin(rows.take(self.limit as usize));
+        Ok(rows)
+    }
 }
 
 impl<F, D, Txn> From<Limited<F, D, Txn>> for Table<F, D, Txn> {
@@ -374,6 +642,22 @@ impl<F: File<Node>, D: Dir, Txn: Transaction<D>> Merged<F, D, Txn> {
         })
     }
 
+    /// Borrow the [`Bounds`] of this merge.
+    pub fn bounds(&'_ self) -> &'_ Bounds {
+        &self.bounds
+    }
+
+    /// Return the name and columns of each index which this merge reads, innermost first.
+    pub fn explain(&self) -> TCResult<Vec<(Id, Vec<Id>)>> {
+        let mut indices = match &self.left {
+            MergeSource::Table(table) => vec![table.source().explain(table.bounds())?],
+            MergeSource::Merge(merged) => merged.explain()?,
+        };
+
+        indices.push(self.source().explain_index(&self.right.schema)?);
+        Ok(indices)
+    }
+
     fn source(&'_ self) -> &'_ TableIndex<F, D, Txn> {
         self.left.source()
     }
@@ -382,6 +666,95 @@ impl<F: File<Node>, D: Dir, Txn: Transaction<D>> Merged<F, D, Txn> {
         self.left.into_source()
     }
 
+    // return `true` if both sides of this merge are slices of the primary index
+    // with the same direction, i.e. they can be merged without re-slicing per key
+    fn is_sorted_by_key(&self) -> bool {
+        let primary = self.source().primary().schema();
+
+        match &self.left {
+            MergeSource::Table(left) => {
+                &left.slice.schema == primary
+                    && &self.right.schema == primary
+                    && left.slice.reverse == self.right.reverse
+            }
+            MergeSource::Merge(_) => false,
+        }
+    }
+
+    // advance cursors over the (sorted) left rows and right keys in lockstep,
+    // emitting each left row whose key is also present on the right
+    async fn merge_join<'a>(self, txn_id: TxnId) -> TCResult<TCBoxTryStream<'a, Vec<Value>>> {
+        let collator = self.source().primary().btree().collator().clone();
+        let key_len = self.key().len();
+        let reverse = self.right.reverse;
+
+        let left = match self.left {
+            MergeSource::Table(left) if left.bounds().is_empty() => {
+                // the right side is already a slice of the primary index
+                return self.right.rows(txn_id).await;
+            }
+            MergeSource::Table(left) => left.rows(txn_id).await?,
+            MergeSource::Merge(_) => unreachable!("merge join of a nested merge"),
+        };
+
+        let right = self.right.rows(txn_id).await?;
+
+        let merge = stream::try_unfold(
+            (left, right, None, None),
+            move |(mut left, mut right, mut row, mut key): (
+                TCBoxTryStream<'a, Vec<Value>>,
+                TCBoxTryStream<'a, Vec<Value>>,
+                Option<Vec<Value>>,
+                Option<Vec<Value>>,
+            )| {
+                let collator = collator.clone();
+
+                async move {
+                    loop {
+                        if row.is_none() {
+                            row = left.try_next().await?;
+                        }
+
+                        if key.is_none() {
+                            key = right.try_next().await?;
+                        }
+
+                        let (this_row, this_key) = match (&row, &key) {
+                            (Some(row), Some(key)) => (row, key),
+                            _ => return Ok(None),
+                        };
+
+                        let order =
+                            collator.compare_slice(&this_row[..key_len], &this_key[..key_len]);
+
+                        match if reverse { order.reverse() } else { order } {
+                            Ordering::Less => row = None,
+                            Ordering::Greater => key = None,
+                            Ordering::Equal => {
+                                let found = row.take();
+                                key = None;
+                                return Ok(found.map(|row| (row, (left, right, None, None))));
+                            }
+                        }
+                    }
+                }
+            },
+        );
+
+        Ok(Box::pin(merge))
+    }
+
+    // merge the given `bounds` with the bounds of every level of this merge, i.e. the bounds of
+    // the left source (which recursively include those of a nested merge) and of the right index,
+    // returning an error if the given `bounds` fall outside any of them
+    fn merge_bounds(&self, bounds: Bounds) -> TCResult<Bounds> {
+        self.source().merge_bounds(vec![
+            self.left.bounds().clone(),
+            self.right.bounds().clone(),
+            bounds,
+        ])
+    }
+
     /// Stream the rows within the given [`Bounds`] of this merge
     pub async fn slice_rows<'a>(
         self,
@@ -432,6 +805,16 @@ impl<F: File<Node>, D: Dir, Txn: Transaction<D>> TableStream for Merged<F, D, Tx
     type Limit = Limited<F, D, Txn>;
     type Selection = Selection<F, D, Txn, Self>;
 
+    async fn count(self, txn_id: TxnId) -> TCResult<u64> {
+        if self.bounds.is_empty() {
+            self.into_source().count(txn_id).await
+        } else {
+            let rows = self.rows(txn_id).await?;
+            rows.try_fold(0, |count, _| future::ready(Ok(count + 1)))
+                .await
+        }
+    }
+
     fn limit(self, limit: u64) -> Self::Limit {
         Limited::new(self, limit)
     }
@@ -441,9 +824,16 @@ impl<F: File<Node>, D: Dir, Txn: Transaction<D>> TableStream for Merged<F, D, Tx
     }
 
     async fn rows<'a>(self, txn_id: TxnId) -> TCResult<TCBoxTryStream<'a, Vec<Value>>> {
+        if self.is_sorted_by_key() {
+            return self.merge_join(txn_id).await;
+        }
+
+        // every nested read below must use the captured `txn_id` so that the whole merge
+        // reads a single consistent snapshot, even though the left source is sliced lazily
+        // (`validate_bounds` only checks the schema and does not read any data)
         let key_columns = self.key().to_vec();
-        let key_names = key_columns.iter().map(|col| &col.name).cloned().collect();
-        let keys = self.right.select(key_names)?.rows(txn_id).await?;
+        let key_names: Vec<Id> = key_columns.iter().map(|col| &col.name).cloned().collect();
+        let keys = self.right.stream_keys(txn_id, &key_names).await?;
 
         let left = self.left;
         let left_clone = left.clone();
@@ -489,16 +879,19 @@ impl<F: File<Node>, D: Dir, Txn: Transaction<D>> super::TableSlice for Merged<F,
     type Slice = Self;
 
     fn slice(self, bounds: Bounds) -> TCResult<Self::Slice> {
-        let source = self.left.into_source();
-        let bounds = source.merge_bounds(vec![self.bounds, bounds])?;
-        source.slice(bounds)
+        let reverse = self.right.reverse;
+        let bounds = self.merge_bounds(bounds)?;
+
+        let slice = self.into_source().slice(bounds)?;
+        if reverse {
+            slice.reverse()
+        } else {
+            Ok(slice)
+        }
     }
 
     fn validate_bounds(&self, bounds: &Bounds) -> TCResult<()> {
-        let bounds = self
-            .source()
-            .merge_bounds(vec![self.bounds.clone(), bounds.clone()])?;
-
+        let bounds = self.merge_bounds(bounds.clone())?;
         self.source().validate_bounds(&bounds)
     }
 }
@@ -523,7 +916,7 @@ impl<F: File<Node>, D: Dir, Txn: Transaction<D>, T: TableInstance> Selection<F,
         let column_set: HashSet<&Id> = columns.iter().collect();
         let mut indices: Vec<usize> = Vec::with_capacity(columns.len());
 
-        let source_columns = source.schema().primary().columns();
+        let source_columns = source.primary_schema().columns();
         let source_indices: HashMap<&Id, usize> = source_columns
             .iter()
             .enumerate()
@@ -591,20 +984,7 @@ where
     }
 
     fn schema(&self) -> TableSchema {
-        let source = self.source.schema();
-        let source = source.primary();
-
-        let select = |columns: &[Column]| {
-            columns
-                .iter()
-                .filter(|col| self.columns.contains(&col.name))
-                .cloned()
-                .collect()
-        };
-
-        let key = select(source.key());
-        let values = select(source.values());
-        IndexSchema::from((key, values)).into()
+        self.primary_schema().into()
     }
 }
 
@@ -647,7 +1027,7 @@ where
             .cloned()
             .collect();
 
-        let mut unknown: HashSet<&Id> = selected.difference(&order_columns).collect();
+        let mut unknown: HashSet<&Id> = order_columns.difference(&selected).collect();
         if !unknown.is_empty() {
             let unknown: Vec<String> = unknown.drain().map(|c| c.to_string()).collect();
             return Err(TCError::bad_request(
@@ -694,6 +1074,12 @@ where
         let selected: TCBoxTryStream<Vec<Value>> = Box::pin(selected);
         Ok(selected)
     }
+
+    async fn keyed_rows<'a>(self, txn_id: TxnId) -> TCResult<TCBoxTryStream<'a, Row>> {
+        let columns = self.columns.to_vec();
+        let rows = self.rows(txn_id).await?;
+        Ok(Box::pin(rows.map_ok(move |row| label_row(&columns, row))))
+    }
 }
 
 impl<F, D, Txn, T> From<Selection<F, D, Txn, T>> for Table<F, D, Txn>
@@ -736,6 +1122,11 @@ impl<F: File<Node>, D: Dir, Txn: Transaction<D>> TableSlice<F, D, Txn> {
         self.slice.bounds()
     }
 
+    /// Group this slice by the given `columns`, emitting one row per unique value.
+    pub fn group_by(self, columns: Vec<Id>) -> TCResult<Aggregate<F, D, Txn, Merged<F, D, Txn>>> {
+        Aggregate::new(self, columns)
+    }
+
     pub fn index_slice(self, bounds: Bounds) -> TCResult<IndexSlice<F, D, Txn>> {
         self.slice.slice_index(bounds)
     }
@@ -851,11 +1242,18 @@ where
     type Slice = Merged<F, D, Txn>;
 
     fn slice(self, bounds: Bounds) -> TCResult<Merged<F, D, Txn>> {
+        let reverse = self.slice.reverse;
         let slice_bounds = self.slice.bounds().clone();
         let source = self.into_source();
         let bounds = source.merge_bounds(vec![slice_bounds, bounds])?;
         source.validate_bounds(&bounds)?;
-        source.slice(bounds)
+
+        let slice = source.slice(bounds)?;
+        if reverse {
+            slice.reverse()
+        } else {
+            Ok(slice)
+        }
     }
 
     fn validate_bounds(&self, bounds: &Bounds) -> TCResult<()> {
@@ -888,3 +1286,9 @@ impl<F: File<Node>, D: Dir, Txn: Transaction<D>> Default for Phantom<F, D, Txn>
         }
     }
 }
+
+// label each value of a row with the selected column it belongs to, in selection order
+#[inline]
+fn label_row(columns: &[Id], row: Vec<Value>) -> Row {
+    columns.iter().cloned().zip(row).collect()
+}
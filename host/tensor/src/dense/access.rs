@@ -11,6 +11,7 @@ use safecast::AsType;
 use tc_btree::*;
 use tc_error::*;
 use tc_transact::fs::{BlockId, Dir, File};
+use tc_transact::lock::TxnLockWriteGuard;
 use tc_transact::{Transaction, TxnId};
 use tc_value::{FloatInstance, Number, NumberClass, NumberInstance, NumberType, UIntType};
 use tcgeneric::{TCBoxStream, TCBoxTryFuture, TCBoxTryStream, Tuple};
@@ -18,8 +19,8 @@ use tcgeneric::{TCBoxStream, TCBoxTryFuture, TCBoxTryStream, Tuple};
 use crate::sparse::{SparseAccess, SparseAccessor};
 use crate::stream::{Read, ReadValueAt};
 use crate::{
-    transform, Bounds, Coord, Phantom, Shape, TensorAccess, TensorReduce, TensorType, ERR_INF,
-    ERR_NAN,
+    max_value, min_value, reduce_values, sum_dtype, transform, Bounds, Coord, Phantom, Shape,
+    TensorAccess, TensorReduce, TensorType, ERR_INF, ERR_NAN,
 };
 
 use super::file::{BlockListFile, BlockListFileSlice};
@@ -99,6 +100,19 @@ pub trait DenseWrite<FD: File<Array>, FS: File<Node>, D: Dir, T: Transaction<D>>
 
     /// Write a value to the slice of this [`DenseTensor`] with the given [`Bounds`].
     async fn write_value(&self, txn_id: TxnId, bounds: Bounds, number: Number) -> TCResult<()>;
+
+    /// Add the given `number` to the element of this [`DenseTensor`] at the given `coord`,
+    /// holding the write lock on its block across the read and the write.
+    async fn accumulate_value_at(
+        &self,
+        txn_id: TxnId,
+        coord: Coord,
+        number: Number,
+    ) -> TCResult<()>;
+
+    /// Lock this [`DenseTensor`] against any other accumulation in the same transaction,
+    /// until the returned guard is dropped.
+    async fn accumulate_lock(&self, txn_id: TxnId) -> TCResult<TxnLockWriteGuard<()>>;
 }
 
 /// A generic enum which can contain any [`DenseAccess`] impl
@@ -236,6 +250,25 @@ where
             _ => Err(TCError::unsupported("cannot write to a Tensor view")),
         }
     }
+
+    async fn accumulate_value_at(
+        &self,
+        txn_id: TxnId,
+        coord: Coord,
+        number: Number,
+    ) -> TCResult<()> {
+        match self {
+            Self::File(file) => file.accumulate_value_at(txn_id, coord, number).await,
+            _ => Err(TCError::unsupported("cannot write to a Tensor view")),
+        }
+    }
+
+    async fn accumulate_lock(&self, txn_id: TxnId) -> TCResult<TxnLockWriteGuard<()>> {
+        match self {
+            Self::File(file) => file.accumulate_lock(txn_id).await,
+            _ => Err(TCError::unsupported("cannot write to a Tensor view")),
+        }
+    }
 }
 
 impl<FD, FS, D, T> ReadValueAt<D> for DenseAccessor<FD, FS, D, T>
@@ -1258,6 +1291,8 @@ impl<FD, FS, D, T, B> fmt::Display for BlockListFlip<FD, FS, D, T, B> {
 
 #[derive(Copy, Clone)]
 pub enum Reductor {
+    Max(NumberType, u64),
+    Min(NumberType, u64),
     Product(NumberType, u64),
     Sum(NumberType, u64),
 }
@@ -1265,6 +1300,8 @@ pub enum Reductor {
 impl Reductor {
     fn dtype(&self) -> NumberType {
         match self {
+            Self::Max(dtype, _) => *dtype,
+            Self::Min(dtype, _) => *dtype,
             Self::Product(dtype, _) => *dtype,
             Self::Sum(dtype, _) => *dtype,
         }
@@ -1272,25 +1309,66 @@ impl Reductor {
 
     fn reduce_block(self, block: Array) -> TCResult<Array> {
         match self {
+            Self::Max(_dtype, stride) => Ok(reduce_strides(block.to_vec(), stride, max_value)),
+            Self::Min(_dtype, stride) => Ok(reduce_strides(block.to_vec(), stride, min_value)),
             Self::Product(_dtype, stride) => {
                 block.reduce_product(stride).map_err(TCError::unsupported)
             }
-            Self::Sum(_dtype, stride) => block.reduce_sum(stride).map_err(TCError::unsupported),
+            Self::Sum(dtype, stride) => block
+                .cast_into(dtype)
+                .reduce_sum(stride)
+                .map_err(TCError::unsupported),
         }
     }
 
     fn reduce_stream(self, blocks: TCBoxTryStream<Array>) -> TCBoxTryStream<Array> {
         let reduced = match self {
+            Self::Max(_dtype, stride) => return reduce_stream_strides(blocks, stride, max_value),
+            Self::Min(_dtype, stride) => return reduce_stream_strides(blocks, stride, min_value),
             Self::Product(dtype, stride) => {
                 afarray::reduce_product(blocks, dtype, PER_BLOCK, stride)
             }
-            Self::Sum(dtype, stride) => afarray::reduce_sum(blocks, dtype, PER_BLOCK, stride),
+            Self::Sum(dtype, stride) => {
+                // cast each block before summing so that the sum does not overflow
+                let blocks = Box::pin(blocks.map_ok(move |block| block.cast_into(dtype)));
+                afarray::reduce_sum(blocks, dtype, PER_BLOCK, stride)
+            }
         };
 
         std::pin::Pin::new(reduced)
     }
 }
 
+// reduce each consecutive run of `stride` values to a single value
+fn reduce_strides(values: Vec<Number>, stride: u64, reduce: fn(Number, Number) -> Number) -> Array {
+    let reduced: Vec<Number> = values
+        .chunks(stride as usize)
+        .filter_map(|chunk| reduce_values(chunk.iter().copied(), reduce))
+        .collect();
+
+    Array::from(reduced)
+}
+
+// reduce each consecutive run of `stride` values in `blocks` to a single value
+fn reduce_stream_strides<'a>(
+    blocks: TCBoxTryStream<'a, Array>,
+    stride: u64,
+    reduce: fn(Number, Number) -> Number,
+) -> TCBoxTryStream<'a, Array> {
+    let reduced = blocks
+        .map_ok(|array| array.to_vec())
+        .map_ok(|values| stream::iter(values.into_iter().map(Ok)))
+        .try_flatten()
+        .chunks(stride as usize)
+        .map(|values| values.into_iter().collect::<TCResult<Vec<Number>>>())
+        .map_ok(move |values| reduce_values(values, reduce).expect("reduced value"))
+        .chunks(PER_BLOCK)
+        .map(|values| values.into_iter().collect::<TCResult<Vec<Number>>>())
+        .map_ok(Array::from);
+
+    Box::pin(reduced)
+}
+
 type ReduceAll<FD, FS, D, T> =
     fn(&DenseTensor<FD, FS, D, T, DenseAccessor<FD, FS, D, T>>, T) -> TCBoxTryFuture<Number>;
 
@@ -1312,6 +1390,32 @@ where
     T: Transaction<D>,
     B: DenseAccess<FD, FS, D, T>,
 {
+    pub fn max(source: B, axis: usize) -> TCResult<Self> {
+        let rebase = transform::Reduce::new(source.shape().clone(), axis)?;
+        let dtype = source.dtype();
+        let stride = Self::extremum_stride(&source, axis)?;
+
+        Ok(BlockListReduce {
+            source,
+            rebase,
+            reductor: Reductor::Max(dtype, stride),
+            reduce_all: TensorReduce::max_all,
+        })
+    }
+
+    pub fn min(source: B, axis: usize) -> TCResult<Self> {
+        let rebase = transform::Reduce::new(source.shape().clone(), axis)?;
+        let dtype = source.dtype();
+        let stride = Self::extremum_stride(&source, axis)?;
+
+        Ok(BlockListReduce {
+            source,
+            rebase,
+            reductor: Reductor::Min(dtype, stride),
+            reduce_all: TensorReduce::min_all,
+        })
+    }
+
     pub fn product(source: B, axis: usize) -> TCResult<Self> {
         let rebase = transform::Reduce::new(source.shape().clone(), axis)?;
         let dtype = afarray::product_dtype(source.dtype());
@@ -1327,7 +1431,7 @@ where
 
     pub fn sum(source: B, axis: usize) -> TCResult<Self> {
         let rebase = transform::Reduce::new(source.shape().clone(), axis)?;
-        let dtype = afarray::sum_dtype(source.dtype());
+        let dtype = sum_dtype(source.dtype());
         let stride = source.size() / (source.size() / source.shape()[axis]);
 
         Ok(BlockListReduce {
@@ -1337,6 +1441,18 @@ where
             reduce_all: TensorReduce::sum_all,
         })
     }
+
+    // there is no maximum or minimum of an empty axis
+    fn extremum_stride(source: &B, axis: usize) -> TCResult<u64> {
+        if source.size() == 0 {
+            Err(TCError::bad_request(
+                "cannot compute the extremum of an empty Tensor with shape",
+                source.shape(),
+            ))
+        } else {
+            Ok(source.size() / (source.size() / source.shape()[axis]))
+        }
+    }
 }
 
 impl<FD, FS, D, T, B> TensorAccess for BlockListReduce<FD, FS, D, T, B>
@@ -1419,6 +1535,8 @@ where
         let slice = self.source.slice(source_bounds)?;
 
         match reductor {
+            Reductor::Max(_, _) => BlockListReduce::max(slice, reduce_axis),
+            Reductor::Min(_, _) => BlockListReduce::min(slice, reduce_axis),
             Reductor::Product(_, _) => BlockListReduce::product(slice, reduce_axis),
             Reductor::Sum(_, _) => BlockListReduce::sum(slice, reduce_axis),
         }
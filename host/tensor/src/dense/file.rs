@@ -16,6 +16,7 @@ use strided::Stride;
 use tc_btree::Node;
 use tc_error::*;
 use tc_transact::fs::{BlockId, CopyFrom, Dir, File, Persist, Restore};
+use tc_transact::lock::{TxnLock, TxnLockWriteGuard};
 use tc_transact::{Transact, Transaction, TxnId};
 use tc_value::{Float, Number, NumberClass, NumberInstance, NumberType};
 use tcgeneric::{TCBoxTryFuture, TCBoxTryStream};
@@ -32,25 +33,54 @@ use super::{array_err, div_ceil, DenseAccess, DenseAccessor, DenseWrite, MEBIBYT
 const BLOCK_SIZE: usize = MEBIBYTE + 5;
 
 /// A wrapper around a `DenseTensor` [`File`]
+///
+/// Each block stores `block_size` elements (except the last, which may be shorter).
+/// A larger block size means fewer reads to stream the whole tensor, at the cost of more I/O
+/// (and a larger cache footprint) to read or write a single element.
+/// Regardless of its block size on disk, a `BlockListFile` always emits blocks of [`PER_BLOCK`]
+/// elements from `block_stream`, so that it can be combined with other dense tensors.
 #[derive(Clone)]
 pub struct BlockListFile<FD, FS, D, T> {
     file: FD,
     schema: Schema,
+    block_size: usize,
+    accumulate: TxnLock<()>,
     sparse: PhantomData<FS>,
     dir: PhantomData<D>,
     txn: PhantomData<T>,
 }
 
 impl<FD, FS, D, T> BlockListFile<FD, FS, D, T> {
-    fn new(file: FD, schema: Schema) -> Self {
+    fn new(file: FD, schema: Schema, block_size: usize) -> Self {
+        debug_assert!(block_size > 0);
+
         Self {
             file,
             schema,
+            block_size,
+            accumulate: TxnLock::new("dense Tensor accumulator", ()),
             sparse: PhantomData,
             dir: PhantomData,
             txn: PhantomData,
         }
     }
+
+    /// The number of elements stored in each block of this `BlockListFile`.
+    pub fn block_size(&self) -> usize {
+        self.block_size
+    }
+}
+
+// raise an error if the given `block_size` is not a positive power of two
+fn validate_block_size(block_size: usize) -> TCResult<()> {
+    if block_size.is_power_of_two() {
+        Ok(())
+    } else {
+        Err(TCError::bad_request(
+            "dense Tensor block size must be a positive power of two, not",
+            block_size,
+        ))
+    }
 }
 
 impl<FD, FS, D, T> BlockListFile<FD, FS, D, T>
@@ -60,13 +90,21 @@ where
     D: Dir,
     T: Transaction<D>,
 {
-    /// Construct a new `BlockListFile` with the given [`Shape`], filled with the given `value`.
-    pub async fn constant(file: FD, txn_id: TxnId, shape: Shape, value: Number) -> TCResult<Self> {
+    /// Construct a new `BlockListFile` with the given [`Shape`], filled with the given `value`,
+    /// with `block_size` elements per block.
+    pub async fn constant(
+        file: FD,
+        txn_id: TxnId,
+        shape: Shape,
+        value: Number,
+        block_size: usize,
+    ) -> TCResult<Self> {
         debug!("BlockListFile::constant {} {}", shape, value);
+        validate_block_size(block_size)?;
 
         let value_clone = value.clone();
         let generator = |length| Array::constant(value_clone.clone(), length);
-        Self::from_blocks_generator(file, txn_id, shape, value.class(), generator).await
+        Self::from_blocks_generator(file, txn_id, shape, value.class(), generator, block_size).await
     }
 
     /// Construct a new `BlockListFile` with the given [`Shape`], with a random normal distribution.
@@ -86,7 +124,7 @@ where
             Array::random_normal(dtype, length).mul(&std).add(&mean)
         };
 
-        Self::from_blocks_generator(file, txn_id, shape, dtype.into(), generator).await
+        Self::from_blocks_generator(file, txn_id, shape, dtype.into(), generator, PER_BLOCK).await
     }
 
     /// Construct a new `BlockListFile` with the given [`Shape`], with a random normal distribution.
@@ -98,7 +136,7 @@ where
     ) -> TCResult<Self> {
         debug!("BlockListFile::random_normal {}", shape);
         let generator = |length| Array::random_uniform(dtype, length);
-        Self::from_blocks_generator(file, txn_id, shape, dtype.into(), generator).await
+        Self::from_blocks_generator(file, txn_id, shape, dtype.into(), generator, PER_BLOCK).await
     }
 
     async fn from_blocks_generator<G>(
@@ -107,6 +145,7 @@ where
         shape: Shape,
         dtype: NumberType,
         generator: G,
+        block_size: usize,
     ) -> TCResult<Self>
     where
         G: Fn(usize) -> Array + Send + Copy,
@@ -119,18 +158,22 @@ where
 
         let size = shape.size();
 
-        let blocks = (0..(size / PER_BLOCK as u64)).map(move |_| Ok(generator(PER_BLOCK)));
+        let blocks = (0..(size / block_size as u64)).map(move |_| Ok(generator(block_size)));
 
-        let trailing_len = (size % PER_BLOCK as u64) as usize;
-        if trailing_len > 0 {
+        let trailing_len = (size % block_size as u64) as usize;
+        let blocks = if trailing_len > 0 {
             let blocks = blocks.chain(iter::once(Ok(generator(trailing_len))));
             Self::from_blocks(file, txn_id, Some(shape), dtype, stream::iter(blocks)).await
         } else {
             Self::from_blocks(file, txn_id, Some(shape), dtype, stream::iter(blocks)).await
-        }
+        }?;
+
+        Ok(Self::new(blocks.file, blocks.schema, block_size))
     }
 
     /// Construct a new `BlockListFile` from the given `Stream` of [`Array`] blocks.
+    ///
+    /// Every block except the last must contain exactly [`PER_BLOCK`] elements.
     pub async fn from_blocks<S: Stream<Item = TCResult<Array>> + Send + Unpin>(
         file: FD,
         txn_id: TxnId,
@@ -173,43 +216,61 @@ where
             vec![size].into()
         };
 
-        Ok(Self::new(file, Schema { shape, dtype }))
+        Ok(Self::new(file, Schema { shape, dtype }, PER_BLOCK))
     }
 
-    /// Construct a new `BlockListFile` from the given `Stream` of elements.
+    /// Construct a new `BlockListFile` from the given `Stream` of elements,
+    /// with `block_size` elements per block.
     pub async fn from_values<S: Stream<Item = TCResult<Number>> + Send + Unpin>(
         file: FD,
         txn_id: TxnId,
         shape: Shape,
         dtype: NumberType,
         values: S,
+        block_size: usize,
     ) -> TCResult<Self> {
         debug!("BlockListFile::from_values {}", shape);
+        validate_block_size(block_size)?;
 
+        let bytes_per_block = block_size * dtype.size();
+        let expected = shape.size();
         let mut i = 0u64;
         let mut size = 0u64;
-        let mut values = values.chunks(PER_BLOCK);
+        let mut values = values.chunks(block_size);
         while let Some(chunk) = values.next().await {
             let chunk = chunk.into_iter().collect::<TCResult<Vec<Number>>>()?;
             size += chunk.len() as u64;
+
+            // stop consuming the stream as soon as it's known to be too long
+            if size > expected {
+                return Err(TCError::bad_request(
+                    format!(
+                        "DenseTensor of shape {} requires {} values, received too many",
+                        shape, expected
+                    ),
+                    size,
+                ));
+            }
+
             let block_id = BlockId::from(i);
             let block = Array::from(chunk).cast_into(dtype);
-            file.create_block(txn_id, block_id, block, BLOCK_SIZE)
+            file.create_block(txn_id, block_id, block, bytes_per_block)
                 .await?;
 
             i += 1;
         }
 
-        if size != shape.size() {
-            return Err(TCError::unsupported(format!(
-                "DenseTensor of shape {} requires {} values, found {}",
-                shape,
-                shape.size(),
+        if size < expected {
+            return Err(TCError::bad_request(
+                format!(
+                    "DenseTensor of shape {} requires {} values, received",
+                    shape, expected
+                ),
                 size,
-            )));
+            ));
         }
 
-        Ok(Self::new(file, Schema { shape, dtype }))
+        Ok(Self::new(file, Schema { shape, dtype }, block_size))
     }
 
     /// Construct a new `BlockListFile` of elements evenly distributed between `start` and `stop`.
@@ -219,8 +280,8 @@ where
         shape: Shape,
         start: Number,
         stop: Number,
+        dtype: NumberType,
     ) -> TCResult<Self> {
-        let dtype = Ord::max(start.class(), stop.class());
         let step = (stop - start) / Number::from(shape.size() as f32);
 
         debug!(
@@ -231,14 +292,16 @@ where
         let values = stream::iter(0..shape.size())
             .map(Number::from)
             .map(|i| start + (i * step))
+            .map(move |n| n.into_type(dtype))
             .map(Ok);
 
-        Self::from_values(file, txn_id, shape, dtype, values).await
+        Self::from_values(file, txn_id, shape, dtype, values, PER_BLOCK).await
     }
 
-    /// Consume this `BlockListFile` handle and return a `Stream` of `Array` blocks.
+    /// Consume this `BlockListFile` handle and return a `Stream` of its `Array` blocks,
+    /// as stored on disk.
     pub fn into_stream(self, txn_id: TxnId) -> impl Stream<Item = TCResult<Array>> + Unpin {
-        let num_blocks = div_ceil(self.size(), PER_BLOCK as u64);
+        let num_blocks = div_ceil(self.size(), self.block_size as u64);
 
         let blocks = stream::iter((0..num_blocks).into_iter().map(BlockId::from))
             .map(move |block_id| self.file.clone().read_block_owned(txn_id, block_id))
@@ -255,7 +318,7 @@ where
 
     /// Sort the elements in this `BlockListFile`.
     pub async fn merge_sort(&self, txn_id: TxnId) -> TCResult<()> {
-        let num_blocks = div_ceil(self.size(), PER_BLOCK as u64);
+        let num_blocks = div_ceil(self.size(), self.block_size as u64);
         if num_blocks == 0 {
             return Ok(());
         } else if num_blocks == 1 {
@@ -281,7 +344,8 @@ where
                 let mut block = Array::concatenate(&left, &right);
                 block.sort(true).map_err(array_err)?;
 
-                let (left_sorted, right_sorted) = block.split(PER_BLOCK).map_err(array_err)?;
+                let (left_sorted, right_sorted) =
+                    block.split(self.block_size).map_err(array_err)?;
                 sorted = sorted && left.deref() == &left_sorted && right.deref() == &right_sorted;
 
                 *left = left_sorted;
@@ -305,10 +369,10 @@ where
             .map(|(d, x)| d * x)
             .sum();
 
-        let block_id = BlockId::from(offset / PER_BLOCK as u64);
+        let block_id = BlockId::from(offset / self.block_size as u64);
         let mut block = self.file.write_block(txn_id, block_id).await?;
 
-        let offset = offset % PER_BLOCK as u64;
+        let offset = offset % self.block_size as u64;
 
         (*block)
             .set_value(offset as usize, value)
@@ -325,8 +389,9 @@ where
         }
 
         let txn_id = *txn.id();
-        let num_blocks = div_ceil(value.size(), PER_BLOCK as u64);
+        let num_blocks = div_ceil(value.size(), self.block_size as u64);
         let contents = value.block_stream(txn).await?;
+        let contents = rechunk(contents, self.block_size);
         stream::iter((0..num_blocks).map(BlockId::from))
             .zip(contents)
             .map(|(block_id, r)| r.map(|array| (block_id, array)))
@@ -384,16 +449,23 @@ where
                 return Ok(blocks);
             }
 
+            let block_size = self.block_size;
             let file = self.file;
-            let block_stream = Box::pin(
-                stream::iter(0..(div_ceil(size, PER_BLOCK as u64)))
+            let block_stream: TCBoxTryStream<Array> = Box::pin(
+                stream::iter(0..(div_ceil(size, block_size as u64)))
                     .map(BlockId::from)
                     .then(move |block_id| file.clone().read_block_owned(*txn.id(), block_id))
                     .map_ok(|block| (*block).clone()),
             );
 
-            let block_stream: TCBoxTryStream<Array> = Box::pin(block_stream);
-            Ok(block_stream)
+            if block_size == PER_BLOCK {
+                Ok(block_stream)
+            } else {
+                let block_stream: TCBoxTryStream<Array> =
+                    Box::pin(rechunk(block_stream, PER_BLOCK));
+
+                Ok(block_stream)
+            }
         })
     }
 
@@ -407,7 +479,7 @@ where
 
     async fn read_values(self, txn: Self::Txn, coords: Coords) -> TCResult<Array> {
         let txn_id = *txn.id();
-        let per_block = ArrayExt::from(&[PER_BLOCK as u64][..]);
+        let per_block = ArrayExt::from(&[self.block_size as u64][..]);
 
         let offsets = coords.to_offsets(self.shape());
         let block_offsets = &offsets / &per_block;
@@ -484,7 +556,7 @@ where
 
         let blocks = value.block_stream(txn).await?;
 
-        let af_per_block = af::constant(PER_BLOCK as u64, af::Dim4::new(&[1, 1, 1, 1]));
+        let af_per_block = af::constant(self.block_size as u64, af::Dim4::new(&[1, 1, 1, 1]));
         stream::iter(offsets)
             .zip(blocks)
             .map(|(offsets, r)| r.map(|array| (offsets, array)))
@@ -535,7 +607,8 @@ where
         let coords = stream::iter(bounds.affected().map(TCResult::Ok));
         CoordBlocks::new(coords, bounds.len(), PER_BLOCK)
             .map_ok(|coords| {
-                let (block_ids, af_indices, af_offsets) = coord_block(coords, self.shape());
+                let (block_ids, af_indices, af_offsets) =
+                    coord_block(coords, self.shape(), self.block_size);
 
                 let file = &self.file;
                 let value = value.clone();
@@ -566,6 +639,35 @@ where
             .try_fold((), |(), ()| future::ready(Ok(())))
             .await
     }
+
+    async fn accumulate_value_at(
+        &self,
+        txn_id: TxnId,
+        coord: Coord,
+        number: Number,
+    ) -> TCResult<()> {
+        self.shape().validate_coord(&coord)?;
+
+        let _lock = self.accumulate.write(txn_id).await?;
+
+        let offset: u64 = coord_bounds(self.shape())
+            .iter()
+            .zip(coord.iter())
+            .map(|(d, x)| d * x)
+            .sum();
+
+        let block_id = BlockId::from(offset / self.block_size as u64);
+        let mut block = self.file.write_block(txn_id, block_id).await?;
+
+        let offset = (offset % self.block_size as u64) as usize;
+        let sum = (block.get_value(offset) + number).into_type(self.dtype());
+
+        (*block).set_value(offset, sum).map_err(array_err)
+    }
+
+    async fn accumulate_lock(&self, txn_id: TxnId) -> TCResult<TxnLockWriteGuard<()>> {
+        self.accumulate.write(txn_id).await
+    }
 }
 
 impl<FD, FS, D, T> ReadValueAt<D> for BlockListFile<FD, FS, D, T>
@@ -589,10 +691,10 @@ where
                 .map(|(d, x)| d * x)
                 .sum();
 
-            let block_id = BlockId::from(offset / PER_BLOCK as u64);
+            let block_id = BlockId::from(offset / self.block_size as u64);
             let block = self.file.read_block(*txn.id(), block_id).await?;
 
-            let value = block.get_value((offset % PER_BLOCK as u64) as usize);
+            let value = block.get_value((offset % self.block_size as u64) as usize);
 
             Ok((coord, value))
         })
@@ -608,10 +710,12 @@ where
     T: Transaction<D>,
 {
     async fn commit(&self, txn_id: &TxnId) {
+        self.accumulate.commit(txn_id).await;
         self.file.commit(txn_id).await
     }
 
     async fn finalize(&self, txn_id: &TxnId) {
+        self.accumulate.finalize(txn_id).await;
         self.file.finalize(txn_id).await
     }
 }
@@ -650,8 +754,9 @@ where
         &self.schema
     }
 
-    async fn load(_txn: &T, schema: Self::Schema, file: Self::Store) -> TCResult<Self> {
-        Ok(Self::new(file, schema))
+    async fn load(txn: &T, schema: Self::Schema, file: Self::Store) -> TCResult<Self> {
+        let block_size = stored_block_size(&file, *txn.id(), schema.shape.size()).await?;
+        Ok(Self::new(file, schema, block_size))
     }
 }
 
@@ -679,7 +784,31 @@ where
         }
 
         self.file.truncate(txn_id).await?;
-        self.file.copy_from(&backup.file, txn_id).await
+
+        if self.block_size == backup.block_size {
+            return self.file.copy_from(&backup.file, txn_id).await;
+        }
+
+        // otherwise, re-divide the backup into blocks of this tensor's own block size
+        let file = backup.file.clone();
+        let num_blocks = div_ceil(backup.size(), backup.block_size as u64);
+        let blocks = stream::iter(0..num_blocks)
+            .map(BlockId::from)
+            .then(move |block_id| file.clone().read_block_owned(txn_id, block_id))
+            .map_ok(|block| (*block).clone());
+
+        let bytes_per_block = self.block_size * self.dtype().size();
+        rechunk(Box::pin(blocks), self.block_size)
+            .enumerate()
+            .map(|(i, r)| r.map(|block| (BlockId::from(i), block)))
+            .map_ok(|(block_id, block)| {
+                self.file
+                    .create_block(txn_id, block_id, block, bytes_per_block)
+                    .map_ok(|_| ())
+            })
+            .try_buffer_unordered(num_cpus::get())
+            .try_fold((), |(), ()| future::ready(Ok(())))
+            .await
     }
 }
 
@@ -755,7 +884,11 @@ where
         }?;
 
         if size == schema.shape.size() {
-            Ok(Self::new(file, schema))
+            let block_size = stored_block_size(&file, txn_id, size)
+                .map_err(de::Error::custom)
+                .await?;
+
+            Ok(Self::new(file, schema, block_size))
         } else {
             Err(de::Error::custom(format!(
                 "tensor data has the wrong number of elements ({}) for shape {}",
@@ -1053,6 +1186,7 @@ where
         }
 
         let txn_id = *txn.id();
+        let block_size = self.source.block_size;
         let file = self.source.file;
         let shape = self.source.schema.shape;
         let mut bounds = self.rebase.bounds().clone();
@@ -1062,7 +1196,7 @@ where
         let coords = stream::iter(bounds.affected().map(TCResult::Ok));
         let values = CoordBlocks::new(coords, ndim, PER_BLOCK).and_then(move |coords| {
             let file_clone = file.clone();
-            let (block_ids, af_indices, af_offsets) = coord_block(coords, &shape);
+            let (block_ids, af_indices, af_offsets) = coord_block(coords, &shape, block_size);
 
             Box::pin(async move {
                 let mut start = 0;
@@ -1153,11 +1287,69 @@ fn block_offsets(
     (block_offsets, end)
 }
 
-fn coord_block(coords: Coords, shape: &[u64]) -> (Vec<u64>, ArrayExt<u64>, Offsets) {
-    let af_per_block = af::constant(PER_BLOCK as u64, af::Dim4::new(&[1, 1, 1, 1]));
+fn coord_block(
+    coords: Coords,
+    shape: &[u64],
+    block_size: usize,
+) -> (Vec<u64>, ArrayExt<u64>, Offsets) {
+    let af_per_block = af::constant(block_size as u64, af::Dim4::new(&[1, 1, 1, 1]));
 
     let offsets = coords.to_offsets(shape);
     let block_offsets = ArrayExt::from(af::div(offsets.deref(), &af_per_block, true));
     let block_ids = block_offsets.unique(true);
     (block_ids.to_vec(), block_offsets, offsets)
 }
+
+// infer the block size of an existing dense tensor file from the length of its first block
+async fn stored_block_size<F: File<Array>>(file: &F, txn_id: TxnId, size: u64) -> TCResult<usize> {
+    if size == 0 {
+        return Ok(PER_BLOCK);
+    }
+
+    let first = file.read_block(txn_id, BlockId::from(0u64)).await?;
+    let len = first.len();
+
+    if (len as u64) < size {
+        Ok(len)
+    } else {
+        // there is only one block, so any block size at least as large as the tensor will do
+        Ok(Ord::max(len, PER_BLOCK))
+    }
+}
+
+// re-divide a stream of blocks of any length into blocks of `block_size` elements
+fn rechunk<'a, S>(blocks: S, block_size: usize) -> impl Stream<Item = TCResult<Array>> + 'a
+where
+    S: Stream<Item = TCResult<Array>> + Send + Unpin + 'a,
+{
+    stream::try_unfold(
+        (blocks, None),
+        move |(mut blocks, mut pending): (S, Option<Array>)| async move {
+            loop {
+                if pending
+                    .as_ref()
+                    .map(|p| p.len() >= block_size)
+                    .unwrap_or(false)
+                {
+                    let buffer = pending.take().expect("pending block");
+                    if buffer.len() == block_size {
+                        return Ok(Some((buffer, (blocks, None))));
+                    }
+
+                    let (block, remainder) = buffer.split(block_size).map_err(array_err)?;
+                    return Ok(Some((block, (blocks, Some(remainder)))));
+                }
+
+                match blocks.try_next().await? {
+                    Some(next) => {
+                        pending = Some(match pending.take() {
+                            Some(buffer) => Array::concatenate(&buffer, &next),
+                            None => next,
+                        });
+                    }
+                    None => return Ok(pending.map(|block| (block, (blocks, None)))),
+                }
+            }
+        },
+    )
+}
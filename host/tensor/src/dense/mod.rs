@@ -14,19 +14,22 @@ use safecast::{AsType, CastFrom, CastInto};
 use tc_btree::Node;
 use tc_error::*;
 use tc_transact::fs::{CopyFrom, Dir, File, Persist, Restore};
+use tc_transact::lock::TxnLockWriteGuard;
 use tc_transact::{IntoView, Transact, Transaction, TxnId};
 use tc_value::{
-    Float, FloatType, Number, NumberClass, NumberInstance, NumberType, Trigonometry, UIntType,
+    Float, FloatType, Number, NumberClass, NumberIdentity, NumberInstance, NumberType,
+    Trigonometry, UIntType,
 };
 use tcgeneric::{Instance, TCBoxTryFuture, TCBoxTryStream};
 
 use super::sparse::{DenseToSparse, SparseTensor};
 use super::stream::{Read, ReadValueAt};
 use super::{
-    tile, trig_dtype, Bounds, Coord, Phantom, Schema, Shape, Tensor, TensorAccess, TensorBoolean,
-    TensorBooleanConst, TensorCompare, TensorCompareConst, TensorDiagonal, TensorDualIO, TensorIO,
-    TensorIndex, TensorInstance, TensorMath, TensorMathConst, TensorPersist, TensorReduce,
-    TensorTransform, TensorTrig, TensorType, TensorUnary, ERR_COMPLEX_EXPONENT,
+    check_divisor, is_nan, max_value, min_value, promote, sum_dtype, tile, trig_dtype, Bounds,
+    Coord, Phantom, Schema, Shape, Tensor, TensorAccess, TensorBoolean, TensorBooleanConst,
+    TensorCompare, TensorCompareConst, TensorDiagonal, TensorDualIO, TensorIO, TensorIndex,
+    TensorInstance, TensorMath, TensorMathConst, TensorPersist, TensorReduce, TensorTransform,
+    TensorTrig, TensorType, TensorUnary, ERR_COMPLEX_EXPONENT,
 };
 
 use access::*;
@@ -118,13 +121,23 @@ where
         schema.validate("create Dense")?;
 
         let Schema { shape, dtype } = schema;
-        BlockListFile::constant(file, txn_id, shape, dtype.zero())
+        BlockListFile::constant(file, txn_id, shape, dtype.additive_identity(), PER_BLOCK)
             .map_ok(Self::from)
             .await
     }
 
     /// Create a new `DenseTensor` filled with the given `value`.
-    pub async fn constant<S>(file: FD, txn_id: TxnId, shape: S, value: Number) -> TCResult<Self>
+    ///
+    /// `block_size` is the number of elements per block on disk, which must be a power of two.
+    /// A larger block size is faster to stream but slower to read or write at random coordinates;
+    /// [`PER_BLOCK`] is a sensible default.
+    pub async fn constant<S>(
+        file: FD,
+        txn_id: TxnId,
+        shape: S,
+        value: Number,
+        block_size: usize,
+    ) -> TCResult<Self>
     where
         Shape: From<S>,
     {
@@ -134,30 +147,42 @@ where
         };
         schema.validate("create Dense constant")?;
 
-        BlockListFile::constant(file, txn_id, schema.shape, value)
+        BlockListFile::constant(file, txn_id, schema.shape, value, block_size)
             .map_ok(Self::from)
             .await
     }
 
     /// Create a new `DenseTensor` filled with a range evenly distributed between `start` and `stop`.
+    ///
+    /// If no `dtype` is given, it's inferred from `start` and `stop`.
     pub async fn range<S>(
         file: FD,
         txn_id: TxnId,
         shape: S,
         start: Number,
         stop: Number,
+        dtype: Option<NumberType>,
     ) -> TCResult<Self>
     where
         Shape: From<S>,
     {
         let schema = Schema {
             shape: shape.into(),
-            dtype: Ord::max(start.class(), stop.class()),
+            dtype: dtype.unwrap_or_else(|| promote(start.class(), stop.class())),
         };
 
         schema.validate("create Dense range")?;
 
-        BlockListFile::range(file, txn_id, schema.shape, start, stop)
+        for n in &[start, stop] {
+            if n.into_type(schema.dtype).into_type(n.class()) != *n {
+                return Err(TCError::bad_request(
+                    format!("range bound is not representable as {}", schema.dtype),
+                    n,
+                ));
+            }
+        }
+
+        BlockListFile::range(file, txn_id, schema.shape, start, stop, schema.dtype)
             .map_ok(Self::from)
             .await
     }
@@ -200,7 +225,14 @@ where
             }
         };
 
-        let output = Self::constant(output_file, txn_id, shape, dtype.zero()).await?;
+        let output = Self::constant(
+            output_file,
+            txn_id,
+            shape,
+            dtype.additive_identity(),
+            PER_BLOCK,
+        )
+        .await?;
 
         tile(txn, input, output, multiples).await
     }
@@ -299,7 +331,8 @@ where
     fn and(self, other: Tensor<FD, FS, D, T>) -> TCResult<Self::Combine> {
         match other {
             Tensor::Dense(dense) => self.and(dense).map(Tensor::from),
-            Tensor::Sparse(sparse) => self.into_sparse().and(sparse).map(Tensor::from),
+            // iterate over only the filled coordinates of the sparse tensor
+            Tensor::Sparse(sparse) => sparse.and(self.into_sparse()).map(Tensor::from),
         }
     }
 
@@ -642,6 +675,16 @@ where
             .write_value(txn_id, Bounds::from(coord), value)
             .await
     }
+
+    async fn accumulate_value_at(&self, txn: T, coord: Coord, value: Number) -> TCResult<()> {
+        self.blocks
+            .accumulate_value_at(*txn.id(), coord, value)
+            .await
+    }
+
+    async fn accumulate_lock(&self, txn_id: TxnId) -> TCResult<TxnLockWriteGuard<()>> {
+        self.blocks.accumulate_lock(txn_id).await
+    }
 }
 
 #[async_trait]
@@ -719,7 +762,7 @@ where
                 Number::from(i as u64)
             });
 
-        BlockListFile::from_values(file, txn_id, shape, dtype, values)
+        BlockListFile::from_values(file, txn_id, shape, dtype, values, PER_BLOCK)
             .map_ok(DenseTensor::from)
             .await
     }
@@ -814,7 +857,7 @@ where
             l + r
         }
 
-        let dtype = Ord::max(self.dtype(), other.dtype());
+        let dtype = promote(self.dtype(), other.dtype());
         self.combine(other, add_array, Add::add, dtype)
     }
 
@@ -828,7 +871,7 @@ where
             l / r
         }
 
-        let dtype = Ord::max(self.dtype(), other.dtype());
+        let dtype = promote(self.dtype(), other.dtype());
         self.combine(other, div_array, Div::div, dtype)
     }
 
@@ -847,7 +890,7 @@ where
             l * r
         }
 
-        let dtype = Ord::max(self.dtype(), other.dtype());
+        let dtype = promote(self.dtype(), other.dtype());
         self.combine(other, mul_array, Mul::mul, dtype)
     }
 
@@ -861,7 +904,7 @@ where
             l.pow(r)
         }
 
-        let dtype = Ord::max(self.dtype(), other.dtype());
+        let dtype = promote(self.dtype(), other.dtype());
         self.combine(other, pow_array, Number::pow, dtype)
     }
 
@@ -871,7 +914,7 @@ where
             l - r
         }
 
-        let dtype = Ord::max(self.dtype(), other.dtype());
+        let dtype = promote(self.dtype(), other.dtype());
         self.combine(other, sub_array, Sub::sub, dtype)
     }
 }
@@ -913,6 +956,7 @@ where
     fn mul(self, other: Tensor<FD, FS, D, T>) -> TCResult<Self::Combine> {
         match other {
             Tensor::Dense(dense) => self.mul(dense).map(Tensor::from),
+            // iterate over only the filled coordinates of the sparse tensor
             Tensor::Sparse(sparse) => sparse.mul(self.into_sparse()).map(Tensor::from),
         }
     }
@@ -955,6 +999,8 @@ where
             &l / r
         }
 
+        check_divisor(self.dtype(), other)?;
+
         Ok(BlockListConst::new(self.blocks, other, div_array, Number::div).into())
     }
 
@@ -1026,6 +1072,39 @@ where
     }
 }
 
+impl<FD, FS, D, T, B> DenseTensor<FD, FS, D, T, B>
+where
+    D: Dir,
+    T: Transaction<D>,
+    FD: File<Array>,
+    FS: File<Node>,
+    D::File: AsType<FD> + AsType<FS>,
+    B: DenseAccess<FD, FS, D, T>,
+    D::FileClass: From<TensorType>,
+{
+    async fn extremum_all(self, txn: T, reduce: fn(Number, Number) -> Number) -> TCResult<Number> {
+        let shape = self.shape().clone();
+        let values = self.blocks.value_stream(txn).await?;
+
+        values
+            .try_fold(None, move |extremum, n| {
+                let extremum = match extremum {
+                    Some(extremum) => reduce(extremum, n),
+                    None => n,
+                };
+
+                future::ready(Ok(Some(extremum)))
+            })
+            .await?
+            .ok_or_else(|| {
+                TCError::bad_request(
+                    "cannot compute the extremum of an empty Tensor with shape",
+                    shape,
+                )
+            })
+    }
+}
+
 impl<FD, FS, D, T, B> TensorReduce<D> for DenseTensor<FD, FS, D, T, B>
 where
     D: Dir,
@@ -1039,6 +1118,22 @@ where
     type Txn = T;
     type Reduce = DenseTensor<FD, FS, D, T, BlockListReduce<FD, FS, D, T, B>>;
 
+    fn max(self, axis: usize) -> TCResult<Self::Reduce> {
+        BlockListReduce::max(self.blocks, axis).map(DenseTensor::from)
+    }
+
+    fn max_all(&self, txn: T) -> TCBoxTryFuture<Number> {
+        Box::pin(self.clone().extremum_all(txn, max_value))
+    }
+
+    fn min(self, axis: usize) -> TCResult<Self::Reduce> {
+        BlockListReduce::min(self.blocks, axis).map(DenseTensor::from)
+    }
+
+    fn min_all(&self, txn: T) -> TCBoxTryFuture<Number> {
+        Box::pin(self.clone().extremum_all(txn, min_value))
+    }
+
     fn product(self, axis: usize) -> TCResult<Self::Reduce> {
         BlockListReduce::product(self.blocks, axis).map(DenseTensor::from)
     }
@@ -1069,11 +1164,12 @@ where
 
     fn sum_all(&self, txn: T) -> TCBoxTryFuture<Number> {
         Box::pin(async move {
-            let zero = self.dtype().zero();
+            let dtype = sum_dtype(self.dtype());
+            let zero = dtype.zero();
             let blocks = self.blocks.clone().block_stream(txn).await?;
 
             blocks
-                .map_ok(|array| array.sum())
+                .map_ok(|array| array.cast_into(dtype).sum())
                 .try_fold(zero, |sum, block_sum| future::ready(Ok(sum + block_sum)))
                 .await
         })
@@ -1224,6 +1320,17 @@ where
         Ok(DenseTensor::from(blocks))
     }
 
+    fn is_nan(&self) -> TCResult<Self::Unary> {
+        let blocks = BlockListUnary::new(
+            self.blocks.clone(),
+            Array::is_nan,
+            is_nan,
+            NumberType::Bool,
+        );
+
+        Ok(DenseTensor::from(blocks))
+    }
+
     async fn all(self, txn: T) -> TCResult<bool> {
         let mut blocks = self.blocks.block_stream(txn).await?;
 
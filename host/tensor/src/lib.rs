@@ -13,8 +13,12 @@ use safecast::*;
 use tc_btree::{BTreeType, Node};
 use tc_error::*;
 use tc_transact::fs::{Dir, File};
+use tc_transact::lock::TxnLockWriteGuard;
 use tc_transact::{IntoView, Transaction, TxnId};
-use tc_value::{FloatType, IntType, Number, NumberType, UIntType, Value, ValueType};
+use tc_value::{
+    ComplexType, FloatInstance, FloatType, IntType, Number, NumberClass, NumberInstance,
+    NumberType, UIntType, Value, ValueType,
+};
 use tcgeneric::{
     label, path_label, Class, Instance, NativeClass, PathLabel, PathSegment, TCBoxTryFuture,
     TCPathBuf, Tuple,
@@ -24,7 +28,9 @@ use stream::ReadValueAt;
 
 pub use afarray::{print_af_info, Array};
 pub use bounds::{AxisBounds, Bounds, Shape};
-pub use dense::{arg_sort, BlockListFile, DenseAccess, DenseAccessor, DenseTensor, DenseWrite};
+pub use dense::{
+    arg_sort, BlockListFile, DenseAccess, DenseAccessor, DenseTensor, DenseWrite, PER_BLOCK,
+};
 pub use sparse::{SparseAccess, SparseAccessor, SparseTable, SparseTensor, SparseWrite};
 
 mod bounds;
@@ -56,8 +62,58 @@ impl Schema {
     pub fn validate(&self, debug_info: &'static str) -> TCResult<()> {
         self.shape.validate(debug_info)
     }
+
+    /// Parse a `(shape, dtype)` tuple into a [`Schema`], reporting which part is invalid, if any.
+    pub fn try_from_value(value: Value) -> TCResult<Self> {
+        let (shape, dtype): (Value, Value) = value.try_cast_into(|v| {
+            TCError::bad_request("expected a Tensor schema (shape, dtype) but found", v)
+        })?;
+
+        let shape: Vec<u64> = shape.try_cast_into(|v| {
+            TCError::bad_request("expected a Tensor shape like [2, 3] but found", v)
+        })?;
+
+        let dtype = TCPathBuf::try_cast_from(dtype, |v| {
+            TCError::bad_request("expected a Tensor dtype link but found", v)
+        })?;
+
+        match ValueType::from_path(&dtype) {
+            Some(ValueType::Number(dtype)) => Ok(Schema {
+                shape: shape.into(),
+                dtype,
+            }),
+            _ => {
+                let valid = NUMBER_TYPES
+                    .iter()
+                    .map(|dtype| ValueType::from(*dtype).path().to_string())
+                    .join(", ");
+
+                Err(TCError::bad_request(
+                    format!("expected a Tensor dtype (one of {}) but found", valid),
+                    dtype,
+                ))
+            }
+        }
+    }
 }
 
+// the valid dtypes of a [`Tensor`], for error messages
+const NUMBER_TYPES: [NumberType; 13] = [
+    NumberType::Bool,
+    NumberType::Complex(ComplexType::C32),
+    NumberType::Complex(ComplexType::C64),
+    NumberType::Float(FloatType::F32),
+    NumberType::Float(FloatType::F64),
+    NumberType::Int(IntType::I8),
+    NumberType::Int(IntType::I16),
+    NumberType::Int(IntType::I32),
+    NumberType::Int(IntType::I64),
+    NumberType::UInt(UIntType::U8),
+    NumberType::UInt(UIntType::U16),
+    NumberType::UInt(UIntType::U32),
+    NumberType::UInt(UIntType::U64),
+];
+
 impl TryCastFrom<Value> for Schema {
     fn can_cast_from(value: &Value) -> bool {
         match value {
@@ -260,6 +316,20 @@ pub trait TensorIO<D: Dir> {
 
     /// Overwrite a single element of this [`Tensor`].
     async fn write_value_at(&self, txn_id: TxnId, coord: Coord, value: Number) -> TCResult<()>;
+
+    /// Add the given `value` to a single element of this [`Tensor`], as a single write.
+    async fn accumulate_value_at(
+        &self,
+        txn: Self::Txn,
+        coord: Coord,
+        value: Number,
+    ) -> TCResult<()>;
+
+    /// Lock this [`Tensor`] against any other accumulation in the same transaction, such as
+    /// `accumulate_value_at`, until the returned guard is dropped.
+    ///
+    /// Hold this lock to add a value to a slice of this [`Tensor`] as a single write.
+    async fn accumulate_lock(&self, txn_id: TxnId) -> TCResult<TxnLockWriteGuard<()>>;
 }
 
 /// [`Tensor`] I/O operations which accept another [`Tensor`] as an argument
@@ -356,6 +426,18 @@ pub trait TensorReduce<D: Dir> {
     /// The result type of a reduce operation
     type Reduce: TensorInstance;
 
+    /// Return the maximum of this [`Tensor`] along the given `axis`.
+    fn max(self, axis: usize) -> TCResult<Self::Reduce>;
+
+    /// Return the maximum of all elements in this [`Tensor`].
+    fn max_all(&self, txn: Self::Txn) -> TCBoxTryFuture<Number>;
+
+    /// Return the minimum of this [`Tensor`] along the given `axis`.
+    fn min(self, axis: usize) -> TCResult<Self::Reduce>;
+
+    /// Return the minimum of all elements in this [`Tensor`].
+    fn min_all(&self, txn: Self::Txn) -> TCBoxTryFuture<Number>;
+
     /// Return the product of this [`Tensor`] along the given `axis`.
     fn product(self, axis: usize) -> TCResult<Self::Reduce>;
 
@@ -363,9 +445,11 @@ pub trait TensorReduce<D: Dir> {
     fn product_all(&self, txn: Self::Txn) -> TCBoxTryFuture<Number>;
 
     /// Return the sum of this [`Tensor`] along the given `axis`.
+    ///
+    /// The sum is accumulated in, and returned as, the wider [`sum_dtype`] of this `Tensor`.
     fn sum(self, axis: usize) -> TCResult<Self::Reduce>;
 
-    /// Return the sum of all elements in this [`Tensor`].
+    /// Return the sum of all elements in this [`Tensor`], as a [`Number`] of its [`sum_dtype`].
     fn sum_all(&self, txn: Self::Txn) -> TCBoxTryFuture<Number>;
 }
 
@@ -436,6 +520,9 @@ pub trait TensorUnary<D: Dir> {
     /// Element-wise round to the nearest integer
     fn round(&self) -> TCResult<Self::Unary>;
 
+    /// Element-wise check for a floating-point NaN
+    fn is_nan(&self) -> TCResult<Self::Unary>;
+
     /// Return `true` if all elements in this [`Tensor`] are nonzero.
     async fn all(self, txn: Self::Txn) -> TCResult<bool>;
 
@@ -489,6 +576,56 @@ pub trait TensorTrig {
     fn atanh(&self) -> TCResult<Self::Unary>;
 }
 
+// integer division by zero is undefined, so reject it up front rather than at the element level
+fn check_divisor(dtype: NumberType, divisor: Number) -> TCResult<()> {
+    match promote(dtype, divisor.class()) {
+        NumberType::Float(_) | NumberType::Complex(_) => Ok(()),
+        dtype if divisor == divisor.class().zero() => Err(TCError::bad_request(
+            "division by zero in a Tensor of type",
+            dtype,
+        )),
+        _ => Ok(()),
+    }
+}
+
+// `true` if `n` is a floating-point NaN
+fn is_nan(n: Number) -> Number {
+    n.is_nan().into()
+}
+
+// the greater of `l` and `r`, or NaN if either is NaN
+fn max_value(l: Number, r: Number) -> Number {
+    if l.is_nan() {
+        l
+    } else if r.is_nan() || r > l {
+        r
+    } else {
+        l
+    }
+}
+
+// the lesser of `l` and `r`, or NaN if either is NaN
+fn min_value(l: Number, r: Number) -> Number {
+    if l.is_nan() {
+        l
+    } else if r.is_nan() || r < l {
+        r
+    } else {
+        l
+    }
+}
+
+// fold `values` with `reduce`, or return `None` if there are no values
+fn reduce_values<I: IntoIterator<Item = Number>>(
+    values: I,
+    reduce: fn(Number, Number) -> Number,
+) -> Option<Number> {
+    values.into_iter().fold(None, |acc, n| match acc {
+        Some(acc) => Some(reduce(acc, n)),
+        None => Some(n),
+    })
+}
+
 fn trig_dtype(dtype: NumberType) -> NumberType {
     match dtype {
         NumberType::Int(it) => match it {
@@ -503,6 +640,75 @@ fn trig_dtype(dtype: NumberType) -> NumberType {
     }
 }
 
+/// Return the smallest [`NumberType`] able to represent every value of both `a` and `b`.
+///
+/// The promotion rules are:
+///  - `Bool` promotes to the other type
+///  - two types in the same category (e.g. `I16` and `I32`) promote to the wider of the two
+///  - an unsigned integer and a signed integer promote to a signed integer wide enough for both
+///    (e.g. `U8` + `I8` -> `I16`), or to `F64` if one of them is 64 bits wide
+///  - an integer and a float promote to `F32` if the integer has at most 16 bits, otherwise `F64`
+///    (e.g. `I32` + `F32` -> `F64`)
+///  - a complex number and a real number promote to `C32` if the real type fits in an `F32`,
+///    otherwise `C64`
+pub fn promote(a: NumberType, b: NumberType) -> NumberType {
+    use tc_value::{ComplexType as CT, FloatType as FT, IntType as IT, NumberType as NT};
+
+    fn fits_f32(dtype: NT) -> bool {
+        match dtype {
+            NT::Bool | NT::Float(FT::F32) => true,
+            NT::Int(IT::I8) | NT::Int(IT::I16) => true,
+            NT::UInt(UIntType::U8) | NT::UInt(UIntType::U16) => true,
+            _ => false,
+        }
+    }
+
+    match (a, b) {
+        (NT::Number, _) | (_, NT::Number) => NT::Number,
+        (NT::Bool, other) | (other, NT::Bool) => other,
+
+        (NT::Complex(_), NT::Complex(_)) => Ord::max(a, b),
+        (NT::Complex(ct), other) | (other, NT::Complex(ct)) => match ct {
+            CT::C32 if fits_f32(other) => CT::C32.into(),
+            _ => CT::C64.into(),
+        },
+
+        (NT::Float(_), NT::Float(_)) => Ord::max(a, b),
+        (NT::Float(ft), other) | (other, NT::Float(ft)) => match ft {
+            FT::F32 if fits_f32(other) => FT::F32.into(),
+            _ => FT::F64.into(),
+        },
+
+        (NT::Int(_), NT::Int(_)) | (NT::UInt(_), NT::UInt(_)) => Ord::max(a, b),
+        (NT::Int(it), NT::UInt(ut)) | (NT::UInt(ut), NT::Int(it)) => {
+            let signed = match ut {
+                UIntType::U8 => IT::I16,
+                UIntType::U16 => IT::I32,
+                UIntType::U32 => IT::I64,
+                _ => return FT::F64.into(),
+            };
+
+            Ord::max(NT::Int(it), NT::Int(signed))
+        }
+    }
+}
+
+/// Return the [`NumberType`] in which to accumulate the sum of elements of the given `dtype`.
+///
+/// Like numpy, this widens the accumulator so that the sum of a large `Tensor` does not overflow:
+///  - `Bool` and unsigned integers sum to `U64`
+///  - signed integers sum to `I64`
+///  - floats sum to `F64` and complex numbers to `C64`
+pub fn sum_dtype(dtype: NumberType) -> NumberType {
+    match dtype {
+        NumberType::Bool | NumberType::UInt(_) => UIntType::U64.into(),
+        NumberType::Int(_) => IntType::I64.into(),
+        NumberType::Float(_) => FloatType::F64.into(),
+        NumberType::Complex(_) => ComplexType::C64.into(),
+        NumberType::Number => NumberType::Number,
+    }
+}
+
 /// The [`Class`] of [`Tensor`]
 #[derive(Clone, Copy, Eq, PartialEq)]
 pub enum TensorType {
@@ -872,6 +1078,20 @@ where
             Self::Sparse(sparse) => sparse.write_value_at(txn_id, coord, value).await,
         }
     }
+
+    async fn accumulate_value_at(&self, txn: T, coord: Coord, value: Number) -> TCResult<()> {
+        match self {
+            Self::Dense(dense) => dense.accumulate_value_at(txn, coord, value).await,
+            Self::Sparse(sparse) => sparse.accumulate_value_at(txn, coord, value).await,
+        }
+    }
+
+    async fn accumulate_lock(&self, txn_id: TxnId) -> TCResult<TxnLockWriteGuard<()>> {
+        match self {
+            Self::Dense(dense) => dense.accumulate_lock(txn_id).await,
+            Self::Sparse(sparse) => sparse.accumulate_lock(txn_id).await,
+        }
+    }
 }
 
 #[async_trait]
@@ -1045,6 +1265,34 @@ where
     type Txn = T;
     type Reduce = Self;
 
+    fn max(self, axis: usize) -> TCResult<Self::Reduce> {
+        match self {
+            Self::Dense(dense) => dense.max(axis).map(Self::from),
+            Self::Sparse(sparse) => sparse.max(axis).map(Self::from),
+        }
+    }
+
+    fn max_all(&self, txn: T) -> TCBoxTryFuture<Number> {
+        match self {
+            Self::Dense(dense) => dense.max_all(txn),
+            Self::Sparse(sparse) => sparse.max_all(txn),
+        }
+    }
+
+    fn min(self, axis: usize) -> TCResult<Self::Reduce> {
+        match self {
+            Self::Dense(dense) => dense.min(axis).map(Self::from),
+            Self::Sparse(sparse) => sparse.min(axis).map(Self::from),
+        }
+    }
+
+    fn min_all(&self, txn: T) -> TCBoxTryFuture<Number> {
+        match self {
+            Self::Dense(dense) => dense.min_all(txn),
+            Self::Sparse(sparse) => sparse.min_all(txn),
+        }
+    }
+
     fn product(self, axis: usize) -> TCResult<Self::Reduce> {
         match self {
             Self::Dense(dense) => dense.product(axis).map(Self::from),
@@ -1103,11 +1351,20 @@ where
     }
 
     fn cast_into(self, dtype: NumberType) -> TCResult<Self> {
-        if dtype == self.dtype() {
+        let source = self.dtype();
+        if dtype == source {
             return Ok(self);
         }
 
-        match self {
+        let tensor = if dtype == NumberType::Bool {
+            // a number casts to true if and only if it's nonzero, so NaN is true
+            self.ne_const(source.zero())?
+        } else {
+            // false and true cast to exactly 0 and 1 in every numeric type
+            self
+        };
+
+        match tensor {
             Self::Dense(dense) => TensorTransform::cast_into(dense, dtype).map(Self::from),
             Self::Sparse(sparse) => TensorTransform::cast_into(sparse, dtype).map(Self::from),
         }
@@ -1238,6 +1495,13 @@ where
         }
     }
 
+    fn is_nan(&self) -> TCResult<Self::Unary> {
+        match self {
+            Self::Dense(dense) => dense.is_nan().map(Self::from),
+            Self::Sparse(sparse) => sparse.is_nan().map(Self::from),
+        }
+    }
+
     async fn all(self, txn: T) -> TCResult<bool> {
         match self {
             Self::Dense(dense) => dense.all(txn).await,
@@ -1552,3 +1816,102 @@ fn coord_bounds(shape: &[u64]) -> Vec<u64> {
         .map(|axis| shape[axis + 1..].iter().product())
         .collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_schema_from_value() {
+        let shape: Tuple<Value> = vec![Value::from(2u64), Value::from(3u64)].into();
+        let dtype = ValueType::from(NumberType::from(FloatType::F32)).path();
+        let schema = Schema::try_from_value((shape.clone(), dtype).cast_into()).expect("schema");
+        assert_eq!(schema.shape.to_vec(), vec![2, 3]);
+        assert_eq!(schema.dtype, NumberType::from(FloatType::F32));
+
+        let bad_shape: Tuple<Value> = vec![Value::from(2u64), Value::None].into();
+        let dtype = ValueType::from(NumberType::from(FloatType::F32)).path();
+        let err = Schema::try_from_value((bad_shape, dtype).cast_into())
+            .err()
+            .expect("error");
+        assert!(err.code() == ErrorType::BadRequest);
+        assert!(err.message().contains("shape"));
+
+        let dtype = ValueType::String.path();
+        let err = Schema::try_from_value((shape, dtype).cast_into())
+            .err()
+            .expect("error");
+        assert!(err.code() == ErrorType::BadRequest);
+        assert!(err.message().contains("one of"));
+        assert!(err
+            .message()
+            .contains(&ValueType::from(NumberType::Bool).path().to_string()));
+
+        let err = Schema::try_from_value(Value::None).err().expect("error");
+        assert!(err.code() == ErrorType::BadRequest);
+        assert!(err.message().contains("(shape, dtype)"));
+    }
+
+    #[test]
+    fn test_promote() {
+        let pairs: Vec<(NumberType, NumberType, NumberType)> = vec![
+            (NumberType::Bool, IntType::I16.into(), IntType::I16.into()),
+            (
+                IntType::I16.into(),
+                IntType::I64.into(),
+                IntType::I64.into(),
+            ),
+            (
+                UIntType::U8.into(),
+                UIntType::U32.into(),
+                UIntType::U32.into(),
+            ),
+            (UIntType::U8.into(), IntType::I8.into(), IntType::I16.into()),
+            (
+                UIntType::U16.into(),
+                IntType::I8.into(),
+                IntType::I32.into(),
+            ),
+            (
+                UIntType::U32.into(),
+                IntType::I64.into(),
+                IntType::I64.into(),
+            ),
+            (
+                UIntType::U64.into(),
+                IntType::I8.into(),
+                FloatType::F64.into(),
+            ),
+            (
+                IntType::I16.into(),
+                FloatType::F32.into(),
+                FloatType::F32.into(),
+            ),
+            (
+                IntType::I32.into(),
+                FloatType::F32.into(),
+                FloatType::F64.into(),
+            ),
+            (
+                FloatType::F32.into(),
+                FloatType::F64.into(),
+                FloatType::F64.into(),
+            ),
+            (
+                FloatType::F32.into(),
+                ComplexType::C32.into(),
+                ComplexType::C32.into(),
+            ),
+            (
+                FloatType::F64.into(),
+                ComplexType::C32.into(),
+                ComplexType::C64.into(),
+            ),
+        ];
+
+        for (a, b, expected) in pairs {
+            assert_eq!(promote(a, b), expected);
+            assert_eq!(promote(b, a), expected);
+        }
+    }
+}
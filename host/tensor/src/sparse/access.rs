@@ -11,6 +11,7 @@ use safecast::AsType;
 use tc_btree::Node;
 use tc_error::*;
 use tc_transact::fs::{Dir, File};
+use tc_transact::lock::TxnLockWriteGuard;
 use tc_transact::{Transaction, TxnId};
 use tc_value::{FloatInstance, Number, NumberClass, NumberInstance, NumberType};
 use tcgeneric::{TCBoxTryFuture, TCBoxTryStream, Tuple};
@@ -18,8 +19,8 @@ use tcgeneric::{TCBoxTryFuture, TCBoxTryStream, Tuple};
 use crate::dense::{DenseAccess, DenseAccessor, DenseTensor, PER_BLOCK};
 use crate::stream::{sorted_coords, sorted_values, Read, ReadValueAt};
 use crate::{
-    coord_bounds, transform, AxisBounds, Bounds, Coord, Phantom, Shape, TensorAccess, TensorType,
-    TensorUnary, ERR_INF, ERR_NAN,
+    coord_bounds, promote, transform, AxisBounds, Bounds, Coord, Phantom, Shape, TensorAccess,
+    TensorType, TensorUnary, ERR_INF, ERR_NAN,
 };
 
 use super::combine::{coord_to_offset, SparseCombine};
@@ -70,6 +71,13 @@ pub trait SparseWrite<FD: File<Array>, FS: File<Node>, D: Dir, T: Transaction<D>
 {
     /// Write the given `value` at the given `coord` of this [`SparseTensor`].
     async fn write_value(&self, txn_id: TxnId, coord: Coord, value: Number) -> TCResult<()>;
+
+    /// Add the given `value` to the element at the given `coord` of this [`SparseTensor`].
+    async fn accumulate_value(&self, txn: T, coord: Coord, value: Number) -> TCResult<()>;
+
+    /// Lock this [`SparseTensor`] against any other accumulation in the same transaction,
+    /// until the returned guard is dropped.
+    async fn accumulate_lock(&self, txn_id: TxnId) -> TCResult<TxnLockWriteGuard<()>>;
 }
 
 /// A generic [`SparseAccess`] type
@@ -196,6 +204,20 @@ where
             _ => Err(TCError::unsupported("cannot write to a Tensor view")),
         }
     }
+
+    async fn accumulate_value(&self, txn: T, coord: Coord, value: Number) -> TCResult<()> {
+        match self {
+            Self::Table(table) => table.accumulate_value(txn, coord, value).await,
+            _ => Err(TCError::unsupported("cannot write to a Tensor view")),
+        }
+    }
+
+    async fn accumulate_lock(&self, txn_id: TxnId) -> TCResult<TxnLockWriteGuard<()>> {
+        match self {
+            Self::Table(table) => table.accumulate_lock(txn_id).await,
+            _ => Err(TCError::unsupported("cannot write to a Tensor view")),
+        }
+    }
 }
 
 impl<FD, FS, D, T> ReadValueAt<D> for SparseAccessor<FD, FS, D, T>
@@ -948,7 +970,7 @@ where
     A: SparseAccess<FD, FS, D, T>,
 {
     fn dtype(&self) -> NumberType {
-        Ord::max(self.source.dtype(), self.other.class())
+        promote(self.source.dtype(), self.other.class())
     }
 
     fn ndim(&self) -> usize {
@@ -1637,6 +1659,7 @@ type Reductor<FD, FS, D, T> =
 pub struct SparseReduce<FD, FS, D, T> {
     source: SparseAccessor<FD, FS, D, T>,
     rebase: transform::Reduce,
+    dtype: NumberType,
     reductor: Reductor<FD, FS, D, T>,
 }
 
@@ -1652,11 +1675,13 @@ where
     pub fn new(
         source: SparseAccessor<FD, FS, D, T>,
         axis: usize,
+        dtype: NumberType,
         reductor: Reductor<FD, FS, D, T>,
     ) -> TCResult<Self> {
         transform::Reduce::new(source.shape().clone(), axis).map(|rebase| SparseReduce {
             source,
             rebase,
+            dtype,
             reductor,
         })
     }
@@ -1672,7 +1697,7 @@ where
     D::FileClass: From<TensorType>,
 {
     fn dtype(&self) -> NumberType {
-        self.source.dtype()
+        self.dtype
     }
 
     fn ndim(&self) -> usize {
@@ -1767,7 +1792,7 @@ where
         );
 
         let source = self.source.slice(source_bounds)?;
-        let reduced = SparseReduce::new(source.into(), reduce_axis, self.reductor)?;
+        let reduced = SparseReduce::new(source.into(), reduce_axis, self.dtype, self.reductor)?;
         if reduced.ndim() == 0 {
             Err(TCError::unsupported(
                 "cannot return a zero-dimensional slice from a reduced Tensor",
@@ -14,6 +14,7 @@ use safecast::{AsType, CastFrom, CastInto};
 use tc_btree::{BTreeType, Node};
 use tc_error::*;
 use tc_transact::fs::{CopyFrom, Dir, File, Persist, Restore};
+use tc_transact::lock::TxnLockWriteGuard;
 use tc_transact::{IntoView, Transact, Transaction, TxnId};
 use tc_value::{
     Float, FloatType, Number, NumberClass, NumberInstance, NumberType, Trigonometry, UIntType,
@@ -24,11 +25,11 @@ use super::dense::{BlockListSparse, DenseTensor, PER_BLOCK};
 use super::stream::ReadValueAt;
 use super::transform;
 use super::{
-    coord_bounds, tile, trig_dtype, AxisBounds, Bounds, Coord, Phantom, Schema, Shape, Tensor,
-    TensorAccess, TensorBoolean, TensorBooleanConst, TensorCompare, TensorCompareConst,
-    TensorDiagonal, TensorDualIO, TensorIO, TensorIndex, TensorInstance, TensorMath,
-    TensorMathConst, TensorPersist, TensorReduce, TensorTransform, TensorTrig, TensorType,
-    TensorUnary, ERR_COMPLEX_EXPONENT,
+    check_divisor, coord_bounds, is_nan, promote, sum_dtype, tile, trig_dtype, AxisBounds, Bounds,
+    Coord, Phantom, Schema, Shape, Tensor, TensorAccess, TensorBoolean, TensorBooleanConst,
+    TensorCompare, TensorCompareConst, TensorDiagonal, TensorDualIO, TensorIO, TensorIndex,
+    TensorInstance, TensorMath, TensorMathConst, TensorPersist, TensorReduce, TensorTransform,
+    TensorTrig, TensorType, TensorUnary, ERR_COMPLEX_EXPONENT,
 };
 
 use access::*;
@@ -824,6 +825,14 @@ where
     async fn write_value_at(&self, txn_id: TxnId, coord: Coord, value: Number) -> TCResult<()> {
         self.accessor.write_value(txn_id, coord, value).await
     }
+
+    async fn accumulate_value_at(&self, txn: T, coord: Coord, value: Number) -> TCResult<()> {
+        self.accessor.accumulate_value(txn, coord, value).await
+    }
+
+    async fn accumulate_lock(&self, txn_id: TxnId) -> TCResult<TxnLockWriteGuard<()>> {
+        self.accessor.accumulate_lock(txn_id).await
+    }
 }
 
 impl<FD, FS, D, T, L, R> TensorMath<D, SparseTensor<FD, FS, D, T, R>>
@@ -849,7 +858,7 @@ where
         fn div(l: Number, r: Number) -> Number {
             // to prevent a divide-by-zero error, treat the right-hand side as if it doesn't exist
             if r == r.class().zero() {
-                Ord::max(l.class(), r.class()).zero()
+                promote(l.class(), r.class()).zero()
             } else {
                 l / r
             }
@@ -954,6 +963,7 @@ impl<FD, FS, D, T, A> TensorMathConst for SparseTensor<FD, FS, D, T, A> {
     }
 
     fn div_const(self, other: Number) -> TCResult<Self::Combine> {
+        check_divisor(self.dtype(), other)?;
         Ok(SparseConstCombinator::new(self.accessor, other, Number::div).into())
     }
 
@@ -1006,10 +1016,44 @@ where
     type Txn = T;
     type Reduce = SparseTensor<FD, FS, D, T, SparseReduce<FD, FS, D, T>>;
 
+    fn max(self, axis: usize) -> TCResult<Self::Reduce> {
+        let dtype = self.dtype();
+        let accessor = SparseReduce::new(
+            self.accessor.accessor(),
+            axis,
+            dtype,
+            SparseTensor::<FD, FS, D, T, SparseAccessor<FD, FS, D, T>>::max_all,
+        )?;
+
+        Ok(SparseTensor::from(accessor))
+    }
+
+    fn max_all(&self, txn: T) -> TCBoxTryFuture<Number> {
+        Box::pin(async move { self.clone().into_dense().max_all(txn).await })
+    }
+
+    fn min(self, axis: usize) -> TCResult<Self::Reduce> {
+        let dtype = self.dtype();
+        let accessor = SparseReduce::new(
+            self.accessor.accessor(),
+            axis,
+            dtype,
+            SparseTensor::<FD, FS, D, T, SparseAccessor<FD, FS, D, T>>::min_all,
+        )?;
+
+        Ok(SparseTensor::from(accessor))
+    }
+
+    fn min_all(&self, txn: T) -> TCBoxTryFuture<Number> {
+        Box::pin(async move { self.clone().into_dense().min_all(txn).await })
+    }
+
     fn product(self, axis: usize) -> TCResult<Self::Reduce> {
+        let dtype = self.dtype();
         let accessor = SparseReduce::new(
             self.accessor.accessor(),
             axis,
+            dtype,
             SparseTensor::<FD, FS, D, T, SparseAccessor<FD, FS, D, T>>::product_all,
         )?;
 
@@ -1021,9 +1065,11 @@ where
     }
 
     fn sum(self, axis: usize) -> TCResult<Self::Reduce> {
+        let dtype = sum_dtype(self.dtype());
         let accessor = SparseReduce::new(
             self.accessor.accessor(),
             axis,
+            dtype,
             SparseTensor::<FD, FS, D, T, SparseAccessor<FD, FS, D, T>>::sum_all,
         )?;
 
@@ -1032,20 +1078,21 @@ where
 
     fn sum_all(&self, txn: T) -> TCBoxTryFuture<Number> {
         Box::pin(async move {
-            let mut sum = self.dtype().zero();
+            let dtype = sum_dtype(self.dtype());
+            let mut sum = dtype.zero();
             let mut filled = self.accessor.clone().filled(txn).await?;
             let mut buffer = Vec::with_capacity(PER_BLOCK);
             while let Some((_coord, value)) = filled.try_next().await? {
                 buffer.push(value);
 
                 if buffer.len() == PER_BLOCK {
-                    sum += Array::from(buffer.to_vec()).sum();
+                    sum += Array::from(buffer.to_vec()).cast_into(dtype).sum();
                     buffer.clear()
                 }
             }
 
             if !buffer.is_empty() {
-                sum += Array::from(buffer).sum();
+                sum += Array::from(buffer).cast_into(dtype).sum();
             }
 
             Ok(sum)
@@ -1191,6 +1238,12 @@ where
         Ok(SparseTensor::from(accessor))
     }
 
+    fn is_nan(&self) -> TCResult<Self::Unary> {
+        let source = self.accessor.clone().accessor();
+        let accessor = SparseUnary::new(source, is_nan, NumberType::Bool);
+        Ok(SparseTensor::from(accessor))
+    }
+
     async fn all(self, txn: Self::Txn) -> TCResult<bool> {
         let affected = stream::iter(Bounds::all(self.shape()).affected());
         let filled = self.accessor.filled(txn).await?;
@@ -17,6 +17,7 @@ use tc_table::{
     Column, ColumnBound, Merged, TableIndex, TableSchema, TableSlice, TableStream, TableWrite,
 };
 use tc_transact::fs::{CopyFrom, Dir, File, Persist, Restore};
+use tc_transact::lock::{TxnLock, TxnLockWriteGuard};
 use tc_transact::{Transact, Transaction, TxnId};
 use tc_value::{Bound, Number, NumberClass, NumberInstance, NumberType, UInt, Value, ValueType};
 use tcgeneric::{label, Id, Label, TCBoxTryStream, Tuple};
@@ -37,6 +38,7 @@ const ERR_CORRUPT: &str = "SparseTensor corrupted! Please file a bug report.";
 pub struct SparseTable<FD, FS, D, T> {
     table: TableIndex<FS, D, T>,
     schema: Schema,
+    accumulate: TxnLock<()>,
     dense: PhantomData<FD>,
 }
 
@@ -59,6 +61,7 @@ where
         Ok(Self {
             table,
             schema,
+            accumulate: TxnLock::new("sparse Tensor accumulator", ()),
             dense: PhantomData,
         })
     }
@@ -186,6 +189,20 @@ where
         self.shape().validate_coord(&coord)?;
         upsert_value(&self.table, txn_id, coord, value).await
     }
+
+    async fn accumulate_value(&self, txn: T, coord: Coord, value: Number) -> TCResult<()> {
+        self.shape().validate_coord(&coord)?;
+
+        let dtype = self.dtype();
+        let txn_id = *txn.id();
+        let _lock = self.accumulate.write(txn_id).await?;
+        let (coord, current) = read_value_at(self.table.clone(), txn, coord, dtype).await?;
+        upsert_value(&self.table, txn_id, coord, (current + value).into_type(dtype)).await
+    }
+
+    async fn accumulate_lock(&self, txn_id: TxnId) -> TCResult<TxnLockWriteGuard<()>> {
+        self.accumulate.write(txn_id).await
+    }
 }
 
 impl<FD, FS, D, T> ReadValueAt<D> for SparseTable<FD, FS, D, T>
@@ -224,10 +241,12 @@ where
     TableIndex<FS, D, T>: Transact,
 {
     async fn commit(&self, txn_id: &TxnId) {
+        self.accumulate.commit(txn_id).await;
         self.table.commit(txn_id).await
     }
 
     async fn finalize(&self, txn_id: &TxnId) {
+        self.accumulate.finalize(txn_id).await;
         self.table.finalize(txn_id).await
     }
 }
@@ -292,6 +311,7 @@ where
         Ok(Self {
             table,
             schema,
+            accumulate: TxnLock::new("sparse Tensor accumulator", ()),
             dense: PhantomData,
         })
     }
@@ -11,12 +11,14 @@ use number_general::NumberCollator;
 use tcgeneric::Instance;
 
 pub use link::*;
+pub use number::*;
 pub use slice::*;
 pub use string::*;
 pub use value::*;
 pub use version::*;
 
 mod link;
+mod number;
 mod slice;
 mod string;
 mod value;
@@ -32,6 +34,21 @@ pub struct ValueCollator {
     version: Collator<Version>,
 }
 
+impl ValueCollator {
+    /// Construct a new `ValueCollator` which orders string values without regard to case.
+    pub fn case_insensitive() -> Self {
+        Self {
+            string: StringCollator::case_insensitive(),
+            ..Self::default()
+        }
+    }
+
+    /// Return `true` if this `ValueCollator` orders string values without regard to case.
+    pub fn is_case_insensitive(&self) -> bool {
+        self.string.is_case_insensitive()
+    }
+}
+
 impl Collate for ValueCollator {
     type Value = Value;
 
@@ -0,0 +1,59 @@
+use number_general::{Number, NumberInstance, NumberType};
+
+/// Explicit identity elements for each [`NumberType`].
+pub trait NumberIdentity {
+    /// The value `x` such that `n + x == n` for every `n` of this type.
+    fn additive_identity(&self) -> Number;
+
+    /// The value `x` such that `n * x == n` for every `n` of this type.
+    fn multiplicative_identity(&self) -> Number;
+}
+
+impl NumberIdentity for NumberType {
+    fn additive_identity(&self) -> Number {
+        match self {
+            Self::Bool => Number::from(false),
+            Self::Complex(_) | Self::Float(_) | Self::Int(_) | Self::UInt(_) => {
+                Number::from(0).into_type(*self)
+            }
+            Self::Number => Number::from(0),
+        }
+    }
+
+    fn multiplicative_identity(&self) -> Number {
+        match self {
+            Self::Bool => Number::from(true),
+            Self::Complex(_) | Self::Float(_) | Self::Int(_) | Self::UInt(_) => {
+                Number::from(1).into_type(*self)
+            }
+            Self::Number => Number::from(1),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use number_general::{FloatType, UIntType};
+
+    use super::*;
+
+    #[test]
+    fn test_bool_identity() {
+        assert_eq!(NumberType::Bool.additive_identity(), Number::from(false));
+        assert_eq!(
+            NumberType::Bool.multiplicative_identity(),
+            Number::from(true)
+        );
+    }
+
+    #[test]
+    fn test_numeric_identity() {
+        let dtype = NumberType::Float(FloatType::F32);
+        assert_eq!(dtype.additive_identity().class(), dtype);
+        assert_eq!(dtype.multiplicative_identity(), Number::from(1f32));
+
+        let dtype = NumberType::UInt(UIntType::U8);
+        assert_eq!(dtype.additive_identity().class(), dtype);
+        assert_eq!(dtype.multiplicative_identity().class(), dtype);
+    }
+}
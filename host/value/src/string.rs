@@ -17,7 +17,7 @@ use serde_json::json;
 use tc_error::*;
 use tcgeneric::Id;
 
-use super::{Link, Number};
+use super::{Bound, Link, Number, Range, Value};
 
 /// A TinyChain String
 #[derive(Clone, Default, Eq, PartialEq)]
@@ -154,12 +154,86 @@ impl fmt::Display for TCString {
 #[derive(Clone, Default)]
 pub struct StringCollator {
     collator: Collator<String>,
+    case_insensitive: bool,
+}
+
+impl StringCollator {
+    /// Construct a new `StringCollator` which orders strings without regard to case.
+    ///
+    /// Strings which differ only by case are still distinct, but are ordered adjacently,
+    /// with the all-lowercase form first.
+    pub fn case_insensitive() -> Self {
+        Self {
+            collator: Collator::default(),
+            case_insensitive: true,
+        }
+    }
+
+    /// Return `true` if this `StringCollator` ignores case.
+    pub fn is_case_insensitive(&self) -> bool {
+        self.case_insensitive
+    }
+
+    /// Return the [`Range`] of every string which differs from the given `string` only by case,
+    /// according to a case-insensitive `StringCollator`.
+    pub fn case_insensitive_range(string: &str) -> Range {
+        let lower: String = lowercase(string).collect();
+
+        // no string which differs only by case can sort after the lowercase form plus a null char
+        let mut end = lower.clone();
+        end.push('\0');
+
+        Range {
+            start: Bound::In(Value::String(lower.into())),
+            end: Bound::Ex(Value::String(end.into())),
+        }
+    }
 }
 
 impl Collate for StringCollator {
     type Value = TCString;
 
     fn compare(&self, left: &Self::Value, right: &Self::Value) -> Ordering {
-        self.collator.compare(&left.0, &right.0)
+        if self.case_insensitive {
+            match lowercase(&left.0).cmp(lowercase(&right.0)) {
+                Ordering::Equal => match (is_lowercase(&left.0), is_lowercase(&right.0)) {
+                    (true, false) => Ordering::Less,
+                    (false, true) => Ordering::Greater,
+                    _ => self.collator.compare(&left.0, &right.0),
+                },
+                order => order,
+            }
+        } else {
+            self.collator.compare(&left.0, &right.0)
+        }
+    }
+}
+
+#[inline]
+fn lowercase(string: &str) -> impl Iterator<Item = char> + '_ {
+    string.chars().flat_map(char::to_lowercase)
+}
+
+#[inline]
+fn is_lowercase(string: &str) -> bool {
+    lowercase(string).eq(string.chars())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_case_insensitive_order() {
+        let collator = StringCollator::case_insensitive();
+        let mut strings: Vec<TCString> = ["banana", "Apple", "cherry", "apple", "Banana"]
+            .iter()
+            .map(|s| s.to_string().into())
+            .collect();
+
+        strings.sort_by(|l, r| collator.compare(l, r));
+
+        let strings: Vec<&str> = strings.iter().map(|s| s.as_str()).collect();
+        assert_eq!(strings, ["apple", "Apple", "banana", "Banana", "cherry"]);
     }
 }
@@ -1073,6 +1073,51 @@ impl<T1: TryCastFrom<Value>, T2: TryCastFrom<Value>, T3: TryCastFrom<Value>> Try
     }
 }
 
+impl<
+        T1: TryCastFrom<Value>,
+        T2: TryCastFrom<Value>,
+        T3: TryCastFrom<Value>,
+        T4: TryCastFrom<Value>,
+    > TryCastFrom<Value> for (T1, T2, T3, T4)
+{
+    fn can_cast_from(value: &Value) -> bool {
+        match value {
+            Value::Tuple(tuple) => Self::can_cast_from(tuple),
+            _ => false,
+        }
+    }
+
+    fn opt_cast_from(value: Value) -> Option<Self> {
+        match value {
+            Value::Tuple(tuple) => Self::opt_cast_from(tuple),
+            _ => None,
+        }
+    }
+}
+
+impl<
+        T1: TryCastFrom<Value>,
+        T2: TryCastFrom<Value>,
+        T3: TryCastFrom<Value>,
+        T4: TryCastFrom<Value>,
+        T5: TryCastFrom<Value>,
+    > TryCastFrom<Value> for (T1, T2, T3, T4, T5)
+{
+    fn can_cast_from(value: &Value) -> bool {
+        match value {
+            Value::Tuple(tuple) => Self::can_cast_from(tuple),
+            _ => false,
+        }
+    }
+
+    fn opt_cast_from(value: Value) -> Option<Self> {
+        match value {
+            Value::Tuple(tuple) => Self::opt_cast_from(tuple),
+            _ => None,
+        }
+    }
+}
+
 impl<T: Clone + TryCastFrom<Value>> TryCastFrom<Value> for Map<T> {
     fn can_cast_from(value: &Value) -> bool {
         Vec::<(Id, T)>::can_cast_from(value)
@@ -3,6 +3,7 @@ from test_chain import *
 from test_class import *
 from test_einsum import *
 from test_graph import *
+from test_stream import *
 from test_table import *
 from test_table_demo import *
 from test_tensor import *
@@ -1,4 +1,5 @@
 import random
+import time
 import tinychain as tc
 import unittest
 
@@ -38,6 +39,71 @@ class BTreeTests(unittest.TestCase):
             result = self.host.post(ENDPOINT, cxt)
             self.assertEqual(result, x)
 
+    def testInsertUnique(self):
+        cxt = tc.Context()
+        cxt.tree = tc.btree.BTree(SCHEMA)
+        cxt.first = cxt.tree.insert_unique((1, "one"))
+        cxt.second = tc.After(cxt.first, cxt.tree.insert_unique((1, "one")))
+        cxt.result = tc.After(cxt.second, tc.Tuple([cxt.first, cxt.second, cxt.tree.count()]))
+
+        result = self.host.post(ENDPOINT, cxt)
+        self.assertEqual(result, [True, False, 1])
+
+    def testMergeInsert(self):
+        keys = [(i, num2words(i)) for i in range(10000)]
+
+        cxt = tc.Context()
+        cxt.source = tc.btree.BTree.load(SCHEMA, keys)
+        cxt.merged = tc.btree.BTree(SCHEMA)
+        cxt.generic = tc.btree.BTree(SCHEMA)
+        cxt.inserts = [cxt.merged.merge_insert(cxt.source), cxt.generic.insert(cxt.source)]
+        cxt.result = tc.After(cxt.inserts, tc.Tuple([cxt.merged.count(), cxt.merged.eq(cxt.generic)]))
+
+        self.assertEqual(self.host.post(ENDPOINT, cxt), [len(keys), True])
+
+    def testMergeInsertOutOfOrder(self):
+        keys = [(i, num2words(i)) for i in range(3)]
+
+        cxt = tc.Context()
+        cxt.tree = tc.btree.BTree(SCHEMA)
+        cxt.result = cxt.tree.merge_insert(list(reversed(keys)))
+
+        self.assertRaises(tc.error.BadRequest, lambda: self.host.post(ENDPOINT, cxt))
+
+    def testInsertValidated(self):
+        keys = [(i, num2words(i)) for i in range(10)]
+
+        cxt = tc.Context()
+        cxt.source = tc.btree.BTree.load(SCHEMA, keys)
+        cxt.tree = tc.btree.BTree(SCHEMA)
+        cxt.result = tc.After(cxt.tree.insert_validated(cxt.source.keys(), len(keys)), cxt.tree.eq(cxt.source))
+
+        self.assertTrue(self.host.post(ENDPOINT, cxt))
+
+    def testInsertValidatedInvalidKey(self):
+        # the second key has no value for the non-nullable "word" column
+        schema = tc.btree.Schema((tc.Column("number", tc.Int), tc.Column("word", tc.String, 100, nullable=True)))
+
+        cxt = tc.Context()
+        cxt.source = tc.btree.BTree.load(schema, [(0, "zero"), (1, None)])
+        cxt.tree = tc.btree.BTree(SCHEMA)
+        cxt.result = cxt.tree.insert_validated(cxt.source.keys(), 10)
+
+        with self.assertRaises(tc.error.BadRequest) as context:
+            self.host.post(ENDPOINT, cxt)
+
+        self.assertIn("index 1", str(context.exception))
+
+    def testInsertValidatedTooManyKeys(self):
+        keys = [(i, num2words(i)) for i in range(10)]
+
+        cxt = tc.Context()
+        cxt.source = tc.btree.BTree.load(SCHEMA, keys)
+        cxt.tree = tc.btree.BTree(SCHEMA)
+        cxt.result = cxt.tree.insert_validated(cxt.source.keys(), 5)
+
+        self.assertRaises(tc.error.BadRequest, lambda: self.host.post(ENDPOINT, cxt))
+
     def testSlice(self):
         keys = [[i, num2words(i)] for i in range(50)]
 
@@ -60,6 +126,110 @@ class BTreeTests(unittest.TestCase):
         result = self.host.post(ENDPOINT, cxt)
         self.assertEqual(result, expected(list(reversed(keys))))
 
+    def testReverseCount(self):
+        keys = [[i, num2words(i)] for i in range(50)]
+
+        cxt = tc.Context()
+        cxt.tree = tc.btree.BTree(SCHEMA)
+        cxt.inserts = [cxt.tree.insert(key) for key in keys]
+        cxt.result = tc.After(cxt.inserts, tc.Tuple([
+            cxt.tree[10:30].count(),
+            cxt.tree[10:30].reverse().count(),
+            cxt.tree.reverse().count(),
+        ]))
+
+        forward, reverse, reverse_all = self.host.post(ENDPOINT, cxt)
+        self.assertEqual(forward, 20)
+        self.assertEqual(reverse, forward)
+        self.assertEqual(reverse_all, len(keys))
+
+    def testCompare(self):
+        cxt = tc.Context()
+        cxt.tree = tc.btree.BTree(SCHEMA)
+        cxt.result = tc.Tuple([
+            cxt.tree.compare((1, "one"), (1, "two")),
+            cxt.tree.compare((1, "two"), (1, "one")),
+            cxt.tree.compare((1, "one"), (1, "one")),
+        ])
+
+        self.assertEqual(self.host.post(ENDPOINT, cxt), [-1, 1, 0])
+
+    def testEq(self):
+        keys = [(i, num2words(i)) for i in range(10)]
+
+        cxt = tc.Context()
+        cxt.tree = tc.btree.BTree(SCHEMA)
+        cxt.same = tc.btree.BTree(SCHEMA)
+        cxt.other = tc.btree.BTree(SCHEMA)
+        cxt.inserts = [
+            [cxt.tree.insert(key) for key in keys],
+            [cxt.same.insert(key) for key in reversed(keys)],
+            [cxt.other.insert(key) for key in keys[1:]],
+        ]
+
+        cxt.result = tc.After(cxt.inserts, tc.Tuple([
+            cxt.tree.eq(cxt.same),
+            cxt.tree.eq(cxt.other),
+            tc.Tuple([1, cxt.tree]).eq([1, cxt.same]),
+            tc.Tuple([1, cxt.tree]).eq([2, cxt.same]),
+        ]))
+
+        self.assertEqual(self.host.post(ENDPOINT, cxt), [True, False, True, False])
+
+    def testContains(self):
+        keys = [[i, num2words(i)] for i in range(10)]
+
+        cxt = tc.Context()
+        cxt.tree = tc.btree.BTree(SCHEMA)
+        cxt.inserts = [cxt.tree.insert(key) for key in keys]
+        cxt.result = tc.After(cxt.inserts, tc.Tuple([
+            cxt.tree.contains((3, "three")),
+            cxt.tree.contains((3, "four")),
+            cxt.tree.contains((11, "eleven")),
+        ]))
+
+        self.assertEqual(self.host.post(ENDPOINT, cxt), [True, False, False])
+
+    def testContainsPartialKey(self):
+        cxt = tc.Context()
+        cxt.tree = tc.btree.BTree(SCHEMA)
+        cxt.result = tc.After(cxt.tree.insert((1, "one")), cxt.tree.contains((1,)))
+
+        self.assertRaises(tc.error.BadRequest, lambda: self.host.post(ENDPOINT, cxt))
+
+    def testPop(self):
+        keys = [[i, num2words(i)] for i in range(10)]
+
+        cxt = tc.Context()
+        cxt.tree = tc.btree.BTree(SCHEMA)
+        cxt.inserts = [cxt.tree.insert(key) for key in keys]
+        cxt.popped = tc.After(cxt.inserts, cxt.tree.pop(limit=3))
+        cxt.result = tc.After(cxt.popped, tc.Tuple([cxt.popped, cxt.tree.count()]))
+
+        popped, count = self.host.post(ENDPOINT, cxt)
+        self.assertEqual(popped, keys[:3])
+        self.assertEqual(count, 7)
+
+    def testSlicePrefix(self):
+        keys = [[i // 5, num2words(i)] for i in range(50)]
+
+        cxt = tc.Context()
+        cxt.tree = tc.btree.BTree(SCHEMA)
+        cxt.inserts = [cxt.tree.insert(key) for key in keys]
+        cxt.result = tc.After(cxt.inserts, tc.Map(
+            first=cxt.tree[(0,)],
+            last=cxt.tree[(9,)],
+            missing=cxt.tree[(10,)]))
+
+        result = self.host.post(ENDPOINT, cxt)
+
+        def sorted_keys(prefix):
+            return sorted((key for key in keys if key[0] == prefix), key=lambda key: key[1])
+
+        self.assertEqual(result["first"], expected(sorted_keys(0)))
+        self.assertEqual(result["last"], expected(sorted_keys(9)))
+        self.assertEqual(result["missing"], expected([]))
+
     def testSliceRange(self):
         keys = [[i, num2words(i)] for i in range(50)]
 
@@ -71,6 +241,51 @@ class BTreeTests(unittest.TestCase):
         result = self.host.post(ENDPOINT, cxt)
         self.assertEqual(result, expected(keys[29:32]))
 
+    def testCaseInsensitive(self):
+        schema = tc.btree.Schema((tc.Column("word", tc.String, 100, case_insensitive=True),))
+        keys = [["banana"], ["Apple"], ["cherry"], ["apple"], ["Banana"]]
+
+        cxt = tc.Context()
+        cxt.tree = tc.btree.BTree(schema)
+        cxt.inserts = [cxt.tree.insert(key) for key in keys]
+        cxt.result = tc.After(cxt.inserts, tc.Map(
+            all=cxt.tree,
+            slice=cxt.tree[("apple",)],
+            range=cxt.tree["a":"c"]))
+
+        result = self.host.post(ENDPOINT, cxt)
+
+        def rows(keys):
+            return {str(tc.uri(tc.btree.BTree)): [tc.to_json(schema), keys]}
+
+        self.assertEqual(result["all"], rows([["apple"], ["Apple"], ["banana"], ["Banana"], ["cherry"]]))
+        self.assertEqual(result["slice"], rows([["apple"]]))
+        self.assertEqual(result["range"], rows([["apple"], ["Apple"], ["banana"], ["Banana"]]))
+
+    def testCaseInsensitiveMixed(self):
+        schema = tc.btree.Schema((
+            tc.Column("word", tc.String, 100, case_insensitive=True),
+            tc.Column("code", tc.String, 100)))
+
+        keys = [["cherry", "c"], ["apple", "a"], ["Banana", "B"]]
+
+        cxt = tc.Context()
+        cxt.tree = tc.btree.BTree(schema)
+        cxt.inserts = [cxt.tree.insert(key) for key in keys]
+        cxt.result = tc.After(cxt.inserts, tc.Map(
+            range=cxt.tree["a":"c"],
+            exact=cxt.tree[("Banana", "B")],
+            missing=cxt.tree[("Banana", "b")]))
+
+        result = self.host.post(ENDPOINT, cxt)
+
+        def rows(keys):
+            return {str(tc.uri(tc.btree.BTree)): [tc.to_json(schema), keys]}
+
+        self.assertEqual(result["range"], rows([["apple", "a"], ["Banana", "B"]]))
+        self.assertEqual(result["exact"], rows([["Banana", "B"]]))
+        self.assertEqual(result["missing"], rows([]))
+
     def testDeleteAll(self):
         keys = [(i, num2words(i)) for i in range(100)]
 
@@ -148,6 +363,38 @@ class ChainTests(PersistenceTest, unittest.TestCase):
 
         for host in hosts:
             self.assertEqual(host.get("/test/btree/tree/count"), n)
+            self.assertEqual(host.get("/test/btree/tree/subject/count"), n)
+
+        self.assertEqual(hosts[0].get("/test/btree/tree/subject"), hosts[0].get("/test/btree/tree"))
+
+
+class TimeoutTests(unittest.TestCase):
+    def setUp(self):
+        class Persistent(tc.Cluster, metaclass=tc.Meta):
+            __uri__ = tc.URI("/test/btree")
+
+            def _configure(self):
+                self.tree = tc.chain.Sync(tc.btree.BTree(SCHEMA))
+
+            @tc.put_method
+            def insert_slowly(self, txn):
+                txn.inserted = self.tree.insert((1, "one"))
+                return tc.After(txn.inserted, tc.Stream.range((0, 10**7)).for_each(tc.get_op(lambda i: i * 2)))
+
+        self.host = start_host("test_btree_timeout", [Persistent], timeout=1)
+
+    def testExpiredTxnIsRolledBack(self):
+        self.assertRaises(tc.error.Timeout, lambda: self.host.put("/test/btree/insert_slowly"))
+
+        # wait for the transaction's auth token, plus a grace period, to expire
+        time.sleep(5)
+
+        self.assertEqual(self.host.get("/test/btree/tree/count"), 0)
+        self.host.put("/test/btree/tree", None, (2, "two"))
+        self.assertEqual(self.host.get("/test/btree/tree/count"), 1)
+
+    def tearDown(self):
+        self.host.stop()
 
 
 def expected(rows):
@@ -1,7 +1,7 @@
 import unittest
 import tinychain as tc
 
-from testutils import DEFAULT_PORT, PersistenceTest
+from testutils import DEFAULT_PORT, PersistenceTest, start_host
 
 
 class ChainTests(PersistenceTest, unittest.TestCase):
@@ -45,5 +45,26 @@ class ChainTests(PersistenceTest, unittest.TestCase):
             self.assertEqual(sum, 4)
 
 
+class ListTests(unittest.TestCase):
+    def testList(self):
+        class Hosted(tc.Cluster, metaclass=tc.Meta):
+            __uri__ = tc.URI(f"http://127.0.0.1:{DEFAULT_PORT}/test/hosted")
+
+            def _configure(self):
+                self.map = tc.chain.Sync(tc.Map({}))
+                self.tree = tc.chain.Sync(tc.btree.BTree(tc.btree.Schema((tc.Column("key", tc.U64),))))
+
+        class Empty(tc.Cluster, metaclass=tc.Meta):
+            __uri__ = tc.URI(f"http://127.0.0.1:{DEFAULT_PORT}/test/empty")
+
+        host = start_host("test_list", [Hosted, Empty])
+
+        try:
+            self.assertEqual(host.get("/test/hosted/list"), ["map", "tree"])
+            self.assertEqual(host.get("/test/empty/list"), [])
+        finally:
+            host.stop()
+
+
 if __name__ == "__main__":
     unittest.main()
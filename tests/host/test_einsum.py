@@ -45,6 +45,13 @@ class Tests(unittest.TestCase):
         B = np.array([3, 2, 1])
         self.execute('ii,j->ij', A, B)
 
+    def testImplicitOutput(self):
+        A = np.array([[1, 2], [3, 4]])
+        B = np.array([[5, 6], [7, 8]])
+        self.execute('ij,jk', A, B)
+        self.execute('ii', A)
+        self.execute('ji', A)
+
     def test2D(self):
         A = np.array([[1, 1], [2, 2], [3, 3]])
         self.execute('ij->', A)
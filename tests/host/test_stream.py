@@ -0,0 +1,39 @@
+import tinychain as tc
+import unittest
+
+from testutils import start_host
+
+ENDPOINT = "/transact/hypothetical"
+
+
+class StreamTests(unittest.TestCase):
+    @classmethod
+    def setUpClass(cls):
+        cls.host = start_host("test_stream")
+
+    def testReduce(self):
+        @tc.put_op
+        def add(key: tc.Number, value: tc.Number):
+            return key + value
+
+        cxt = tc.Context()
+        cxt.numbers = tc.Stream.range((0, 5))
+        cxt.result = tc.Tuple([cxt.numbers.reduce(0, add), cxt.numbers.scan(0, add)])
+
+        reduced, scanned = self.host.post(ENDPOINT, cxt)
+        self.assertEqual(reduced, 10)
+        self.assertEqual(scanned, [0, 1, 3, 6, 10])
+
+    def testReduceNotBinary(self):
+        cxt = tc.Context()
+        cxt.result = tc.Stream.range((0, 5)).reduce(0, tc.get_op(lambda x: x))
+
+        self.assertRaises(tc.error.BadRequest, lambda: self.host.post(ENDPOINT, cxt))
+
+    @classmethod
+    def tearDownClass(cls):
+        cls.host.stop()
+
+
+if __name__ == "__main__":
+    unittest.main()
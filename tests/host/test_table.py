@@ -2,8 +2,9 @@ import random
 import tinychain as tc
 import unittest
 
+from concurrent.futures import ThreadPoolExecutor
 from num2words import num2words
-from testutils import DEFAULT_PORT, start_host, PersistenceTest
+from testutils import DEFAULT_PORT, expect_dense, start_host, PersistenceTest
 
 ENDPOINT = "/transact/hypothetical"
 SCHEMA = tc.table.Schema(
@@ -15,6 +16,53 @@ class TableTests(unittest.TestCase):
     def setUpClass(cls):
         cls.host = start_host("test_table")
 
+    def testAddColumn(self):
+        count = 5
+        values = [(v,) for v in range(count)]
+        keys = [(num2words(i),) for i in range(count)]
+
+        cxt = tc.Context()
+        cxt.table = tc.table.Table(SCHEMA)
+        cxt.inserts = [cxt.table.insert(k, v) for k, v in zip(keys, values)]
+        cxt.migrated = tc.After(cxt.inserts, cxt.table.add_column(tc.Column("likes", tc.UInt), 0))
+        cxt.result = cxt.migrated[("two",)]
+
+        result = self.host.post(ENDPOINT, cxt)
+        self.assertEqual(result, ["two", 2, 0])
+
+    def testCount(self):
+        count = 100
+        values = [(v,) for v in range(count)]
+        keys = [(num2words(i),) for i in range(count)]
+
+        cxt = tc.Context()
+        cxt.table = tc.table.Table(SCHEMA)
+        cxt.inserts = [cxt.table.insert(k, v) for k, v in zip(keys, values)]
+        cxt.result = tc.After(cxt.inserts, tc.Tuple([
+            cxt.table.count(),
+            cxt.table.order_by(["views"]).count(),
+            cxt.table.where({"views": slice(50)}).count(),
+        ]))
+
+        result = self.host.post(ENDPOINT, cxt)
+        self.assertEqual(result, [count, count, 50])
+
+    def testOrderByIndexPrefix(self):
+        count = 10
+        values = [(v, v % 3) for v in range(count)]
+        keys = [(num2words(i),) for i in range(count)]
+        schema = tc.table.Schema(
+            [tc.Column("name", tc.String, 512)],
+            [tc.Column("views", tc.UInt), tc.Column("likes", tc.UInt)]).create_index("views", ["views"])
+
+        cxt = tc.Context()
+        cxt.table = tc.table.Table(schema)
+        cxt.inserts = [cxt.table.insert(k, v) for k, v in zip(keys, values)]
+        cxt.result = tc.After(cxt.inserts, cxt.table.order_by(["views", "name", "likes"]).first())
+
+        result = self.host.post(ENDPOINT, cxt)
+        self.assertEqual(result, ["zero", 0, 0])
+
     def testCreate(self):
         cxt = tc.Context()
         cxt.table = tc.table.Table(SCHEMA)
@@ -23,6 +71,28 @@ class TableTests(unittest.TestCase):
         count = self.host.post(ENDPOINT, cxt)
         self.assertEqual(count, 1)
 
+    def testCreateIndex(self):
+        count = 50
+        values = [(v,) for v in range(count)]
+        keys = [(num2words(i),) for i in range(count)]
+        schema = tc.table.Schema([tc.Column("name", tc.String, 512)], [tc.Column("views", tc.UInt)])
+
+        cxt = tc.Context()
+        cxt.table = tc.table.Table(schema)
+        cxt.inserts = [cxt.table.insert(k, v) for k, v in zip(keys, values)]
+        cxt.indexed = tc.After(cxt.inserts, cxt.table.create_index("views", ["views"]))
+        cxt.result = cxt.indexed.where({"views": slice(10, 20)}).count()
+
+        result = self.host.post(ENDPOINT, cxt)
+        self.assertEqual(result, 10)
+
+    def testCreateDuplicateIndex(self):
+        cxt = tc.Context()
+        cxt.table = tc.table.Table(SCHEMA)
+        cxt.result = cxt.table.create_index("views", ["views"])
+
+        self.assertRaises(tc.error.BadRequest, lambda: self.host.post(ENDPOINT, cxt))
+
     def testDelete(self):
         count = 2
         values = [(v,) for v in range(count)]
@@ -37,6 +107,434 @@ class TableTests(unittest.TestCase):
         result = self.host.post(ENDPOINT, cxt)
         self.assertEqual(result, expected(SCHEMA, []))
 
+    def testDeleteWhere(self):
+        count = 50
+        values = [(v,) for v in range(count)]
+        keys = [(num2words(i),) for i in range(count)]
+        remaining = [k for k in keys if not "f" <= k[0] < "t"]
+
+        cxt = tc.Context()
+        cxt.table = tc.table.Table(SCHEMA)
+        cxt.inserts = [cxt.table.insert(k, v) for k, v in zip(keys, values)]
+        cxt.delete = tc.After(cxt.inserts, cxt.table.delete_where({"name": slice("f", "t")}))
+        cxt.result = tc.After(cxt.delete, tc.Tuple([
+            cxt.table.count(),
+            cxt.table.where({"name": slice("f", "t")}).count(),
+            cxt.table.order_by(["views"]).count(),
+        ]))
+
+        result = self.host.post(ENDPOINT, cxt)
+        self.assertEqual(result, [len(remaining), 0, len(remaining)])
+
+    def testDeleteWhereIndex(self):
+        count = 50
+        values = [(v,) for v in range(count)]
+        keys = [(num2words(i),) for i in range(count)]
+
+        cxt = tc.Context()
+        cxt.table = tc.table.Table(SCHEMA)
+        cxt.inserts = [cxt.table.insert(k, v) for k, v in zip(keys, values)]
+        cxt.delete = tc.After(cxt.inserts, cxt.table.delete_where({"views": slice(10, 20)}))
+        cxt.result = tc.After(cxt.delete, tc.Tuple([
+            cxt.table.count(),
+            cxt.table.where({"views": slice(10, 20)}).count(),
+        ]))
+
+        result = self.host.post(ENDPOINT, cxt)
+        self.assertEqual(result, [count - 10, 0])
+
+    def testVerifyIndices(self):
+        count = 50
+        values = [(v,) for v in range(count)]
+        keys = [(num2words(i),) for i in range(count)]
+
+        cxt = tc.Context()
+        cxt.table = tc.table.Table(SCHEMA)
+        cxt.inserts = [cxt.table.insert(k, v) for k, v in zip(keys, values)]
+        cxt.delete = tc.After(cxt.inserts, cxt.table.delete_where({"views": slice(10, 20)}))
+        cxt.update = tc.After(cxt.delete, cxt.table.update_row(keys[0], (100,)))
+        cxt.result = tc.After(cxt.update, cxt.table.verify_indices())
+
+        self.assertEqual(self.host.post(ENDPOINT, cxt), [])
+
+    def testDropColumn(self):
+        count = 5
+        values = [(v,) for v in range(count)]
+        keys = [(num2words(i),) for i in range(count)]
+
+        cxt = tc.Context()
+        cxt.table = tc.table.Table(SCHEMA)
+        cxt.inserts = [cxt.table.insert(k, v) for k, v in zip(keys, values)]
+        cxt.dropped = tc.After(cxt.inserts, cxt.table.drop_column("views"))
+        cxt.result = cxt.dropped[("two",)]
+
+        result = self.host.post(ENDPOINT, cxt)
+        self.assertEqual(result, ["two"])
+
+    def testDropKeyColumn(self):
+        cxt = tc.Context()
+        cxt.table = tc.table.Table(SCHEMA)
+        cxt.result = cxt.table.drop_column("name")
+
+        self.assertRaises(tc.error.BadRequest, lambda: self.host.post(ENDPOINT, cxt))
+
+    def testDropIndex(self):
+        cxt = tc.Context()
+        cxt.table = tc.table.Table(SCHEMA)
+        cxt.result = cxt.table.drop_index("views").where({"views": slice(10)}).count()
+
+        self.assertRaises(tc.error.BadRequest, lambda: self.host.post(ENDPOINT, cxt))
+
+    def testExplain(self):
+        cxt = tc.Context()
+        cxt.table = tc.table.Table(SCHEMA)
+        cxt.result = tc.Tuple([cxt.table.explain(), cxt.table.where({"views": slice(10)}).explain()])
+
+        primary = {"index": "primary", "columns": ["name", "views"]}
+        views = {"index": "views", "columns": ["views", "name"]}
+
+        full, sliced = self.host.post(ENDPOINT, cxt)
+        self.assertEqual(full, {"indices": [primary], "full_scan": True})
+        self.assertEqual(sliced, {"indices": [primary, views], "full_scan": False})
+
+    def testExportCSVRoundTrip(self):
+        schema = tc.table.Schema(
+            [tc.Column("name", tc.String, 100)],
+            [tc.Column("count", tc.I64), tc.Column("score", tc.F64, nullable=True)])
+
+        rows = [
+            ["", 3, -1.25],
+            [" padded ", 1, 0.5],
+            ['a, "quoted"\nvalue', -2, None],
+        ]
+
+        cxt = tc.Context()
+        cxt.table = tc.table.Table.load(schema, rows)
+        cxt.loaded = tc.table.Table.load_csv(schema, cxt.table.export("csv"))
+        cxt.result = tc.Tuple([cxt.loaded["errors"], cxt.table.eq(cxt.loaded["table"])])
+
+        self.assertEqual(self.host.post(ENDPOINT, cxt), [0, True])
+
+    def testTruncate(self):
+        count = 1000
+        keys = [(i,) for i in range(count)]
+        schema = tc.table.Schema([tc.Column("id", tc.U64)], [tc.Column("views", tc.UInt)])
+
+        cxt = tc.Context()
+        cxt.table = tc.table.Table.load(schema, [k + (0,) for k in keys])
+        cxt.truncate = cxt.table.truncate()
+        cxt.result = tc.After(cxt.truncate, cxt.table.count())
+
+        result = self.host.post(ENDPOINT, cxt)
+        self.assertEqual(result, 0)
+
+    def testInsertRows(self):
+        count = 50
+        rows = [[i, i * 2] for i in range(count)]
+        schema = tc.table.Schema([tc.Column("id", tc.U64)], [tc.Column("views", tc.UInt)])
+
+        cxt = tc.Context()
+        cxt.source = tc.table.Table.load(schema, rows)
+        cxt.table = tc.table.Table(schema)
+        cxt.insert = cxt.table.insert_rows(cxt.source.rows())
+        cxt.result = tc.After(cxt.insert, cxt.table)
+
+        result = self.host.post(ENDPOINT, cxt)
+        self.assertEqual(result, expected(schema, rows))
+
+    def testInsertInvalidRow(self):
+        schema = tc.table.Schema([tc.Column("id", tc.U64)], [tc.Column("views", tc.UInt)])
+        keys_only = tc.table.Schema([tc.Column("id", tc.U64)])
+
+        cxt = tc.Context()
+        cxt.source = tc.table.Table.load(keys_only, [[i] for i in range(10)])
+        cxt.table = tc.table.Table(schema)
+        cxt.insert = cxt.table.insert_rows(cxt.source.rows())
+        cxt.result = tc.After(cxt.insert, cxt.table.count())
+
+        self.assertRaises(tc.error.BadRequest, lambda: self.host.post(ENDPOINT, cxt))
+
+    def testInsertExistingRow(self):
+        schema = tc.table.Schema([tc.Column("id", tc.U64)], [tc.Column("views", tc.UInt)])
+
+        cxt = tc.Context()
+        cxt.source = tc.table.Table.load(schema, [[i, i] for i in range(5)])
+        cxt.table = tc.table.Table.load(schema, [[3, 0]])
+        cxt.insert = cxt.table.insert_rows(cxt.source.rows())
+        cxt.result = tc.After(cxt.insert, cxt.table.count())
+
+        self.assertRaises(tc.error.BadRequest, lambda: self.host.post(ENDPOINT, cxt))
+
+    def testUpsertRow(self):
+        schema = tc.table.Schema([tc.Column("id", tc.U64)], [tc.Column("views", tc.UInt)])
+
+        cxt = tc.Context()
+        cxt.table = tc.table.Table.load(schema, [[0, 0], [1, 1]])
+        cxt.updated = cxt.table.upsert_row([1], [10])
+        cxt.inserted = cxt.table.upsert_row([2], [20])
+        cxt.result = tc.After([cxt.updated, cxt.inserted], tc.Tuple([
+            cxt.updated,
+            cxt.inserted,
+            cxt.table.values_at([1]),
+            cxt.table.values_at([2]),
+            cxt.table.count(),
+        ]))
+
+        self.assertEqual(self.host.post(ENDPOINT, cxt), [False, True, [10], [20], 3])
+
+    def testUpsertRowInvalid(self):
+        schema = tc.table.Schema([tc.Column("id", tc.U64)], [tc.Column("views", tc.UInt)])
+
+        cxt = tc.Context()
+        cxt.table = tc.table.Table.load(schema, [[0, 0]])
+        cxt.result = cxt.table.upsert_row([0], [1, 2])
+
+        self.assertRaises(tc.error.BadRequest, lambda: self.host.post(ENDPOINT, cxt))
+
+    def testSample(self):
+        count = 50
+        schema = tc.table.Schema([tc.Column("id", tc.U64)], [tc.Column("views", tc.UInt)])
+        rows = [[i, i * 2] for i in range(count)]
+
+        cxt = tc.Context()
+        cxt.table = tc.table.Table.load(schema, rows)
+        cxt.result = tc.Map(
+            first=cxt.table.sample(10),
+            second=cxt.table.sample(10),
+            all=cxt.table.sample(count * 2))
+
+        result = self.host.post(ENDPOINT, cxt)
+
+        # two samples in the same transaction select the same rows
+        self.assertEqual(result["first"], result["second"])
+
+        [_schema, sample] = result["first"][str(tc.uri(tc.table.Table))]
+        self.assertEqual(len(sample), 10)
+        self.assertTrue(all(row in rows for row in sample))
+        self.assertEqual(result["first"], expected(schema, sorted(sample)))
+        self.assertEqual(result["all"], expected(schema, rows))
+
+    def testToTensor(self):
+        count = 20
+        schema = tc.table.Schema(
+            [tc.Column("id", tc.U64)],
+            [tc.Column("name", tc.String, 32), tc.Column("score", tc.F64)])
+
+        rows = [[i, num2words(i), i / 2] for i in range(count)]
+
+        cxt = tc.Context()
+        cxt.table = tc.table.Table.load(schema, rows)
+        cxt.result = cxt.table.to_tensor(["id", "score"])
+
+        result = self.host.post(ENDPOINT, cxt)
+        flat = [value for i, _name, score in rows for value in (i, score)]
+        self.assertEqual(result, expect_dense(tc.F64, [count, 2], flat))
+
+    def testToTensorRoundTrip(self):
+        count = 20
+        schema = tc.table.Schema(
+            [tc.Column("id", tc.U64)],
+            [tc.Column("views", tc.U64), tc.Column("score", tc.F64)])
+
+        rows = [[i, i * 3, i / 4] for i in range(count)]
+
+        cxt = tc.Context()
+        cxt.table = tc.table.Table.load(schema, rows)
+        cxt.slice = cxt.table.where({"id": slice(5, 15)})
+        cxt.tensor = cxt.slice.to_tensor(["views", "score"])
+        cxt.result = tc.Map(
+            shape=cxt.tensor.shape,
+            sums=cxt.tensor.sum(0),
+            row=cxt.tensor[3],
+            views=cxt.slice.sum_column("views"),
+            score=cxt.slice.sum_column("score"))
+
+        result = self.host.post(ENDPOINT, cxt)
+
+        self.assertEqual(result["shape"], [10, 2])
+        self.assertEqual(result["row"], expect_dense(tc.F64, [2], [24, 2.0]))
+        self.assertEqual(result["sums"], expect_dense(tc.F64, [2], [result["views"], result["score"]]))
+        self.assertEqual(result["views"], sum(views for _, views, _ in rows[5:15]))
+
+    def testToTensorNullable(self):
+        schema = tc.table.Schema(
+            [tc.Column("id", tc.U64)],
+            [tc.Column("score", tc.F64, nullable=True)])
+
+        cxt = tc.Context()
+        cxt.table = tc.table.Table.load(schema, [[0, 1.], [1, None]])
+        cxt.result = cxt.table.to_tensor(["score"])
+
+        self.assertRaises(tc.error.BadRequest, lambda: self.host.post(ENDPOINT, cxt))
+
+    def testToTensorNonNumeric(self):
+        schema = tc.table.Schema([tc.Column("id", tc.U64)], [tc.Column("name", tc.String, 32)])
+
+        cxt = tc.Context()
+        cxt.table = tc.table.Table.load(schema, [[1, "one"]])
+        cxt.result = cxt.table.to_tensor(["id", "name"])
+
+        self.assertRaises(tc.error.BadRequest, lambda: self.host.post(ENDPOINT, cxt))
+
+    def testRenameColumn(self):
+        count = 5
+        values = [(v,) for v in range(count)]
+        keys = [(num2words(i),) for i in range(count)]
+
+        cxt = tc.Context()
+        cxt.table = tc.table.Table(SCHEMA)
+        cxt.inserts = [cxt.table.insert(k, v) for k, v in zip(keys, values)]
+        cxt.renamed = tc.After(cxt.inserts, cxt.table.rename_column("views", "likes"))
+        cxt.result = cxt.renamed.where({"likes": 2}).select(["name"]).rows()
+
+        result = self.host.post(ENDPOINT, cxt)
+        self.assertEqual(result, [["two"]])
+
+    def testRenameColumnCollision(self):
+        cxt = tc.Context()
+        cxt.table = tc.table.Table(SCHEMA)
+        cxt.result = cxt.table.rename_column("views", "name")
+
+        self.assertRaises(tc.error.BadRequest, lambda: self.host.post(ENDPOINT, cxt))
+
+    def testExists(self):
+        count = 50
+        values = [(v,) for v in range(count)]
+        keys = [(num2words(i),) for i in range(count)]
+
+        cxt = tc.Context()
+        cxt.table = tc.table.Table(SCHEMA)
+        cxt.inserts = [cxt.table.insert(k, v) for k, v in zip(keys, values)]
+        cxt.result = tc.After(cxt.inserts, tc.Tuple([
+            cxt.table.exists(),
+            cxt.table.exists({"views": 25}),
+            cxt.table.exists({"views": count + 1}),
+            cxt.table.where({"views": count + 1}).exists(),
+        ]))
+
+        self.assertEqual(self.host.post(ENDPOINT, cxt), [True, True, False, False])
+
+    def testFirstLast(self):
+        count = 10
+        values = [(v,) for v in range(count)]
+        keys = [(num2words(i),) for i in range(count)]
+
+        cxt = tc.Context()
+        cxt.table = tc.table.Table(SCHEMA)
+        cxt.inserts = [cxt.table.insert(k, v) for k, v in zip(keys, values)]
+        cxt.ordered = cxt.table.order_by(["views"])
+        cxt.result = tc.After(cxt.inserts, tc.Tuple([
+            cxt.ordered.first(),
+            cxt.ordered.last(),
+            cxt.table.order_by(["views"], True).first(),
+            cxt.ordered.limit(3).last(),
+        ]))
+
+        first, last, reversed_first, limited_last = self.host.post(ENDPOINT, cxt)
+        self.assertEqual(first, ["zero", 0])
+        self.assertEqual(last, ["nine", 9])
+        self.assertEqual(reversed_first, ["nine", 9])
+        self.assertEqual(limited_last, ["two", 2])
+
+    def testFirstEmpty(self):
+        cxt = tc.Context()
+        cxt.table = tc.table.Table(SCHEMA)
+        cxt.result = cxt.table.first()
+
+        self.assertRaises(tc.error.NotFound, lambda: self.host.post(ENDPOINT, cxt))
+
+    def testValuesAt(self):
+        count = 5
+        values = [(v,) for v in range(count)]
+        keys = [(num2words(i),) for i in range(count)]
+
+        cxt = tc.Context()
+        cxt.table = tc.table.Table(SCHEMA)
+        cxt.inserts = [cxt.table.insert(k, v) for k, v in zip(keys, values)]
+        cxt.result = tc.After(cxt.inserts, cxt.table.values_at(("three",)))
+
+        self.assertEqual(self.host.post(ENDPOINT, cxt), [3])
+
+        cxt = tc.Context()
+        cxt.table = tc.table.Table(SCHEMA)
+        cxt.result = cxt.table.values_at(("three",))
+
+        self.assertRaises(tc.error.NotFound, lambda: self.host.post(ENDPOINT, cxt))
+
+    def testValuesAtPartialKey(self):
+        schema = tc.table.Schema([tc.Column("x", tc.U64), tc.Column("y", tc.U64)], [tc.Column("views", tc.UInt)])
+
+        cxt = tc.Context()
+        cxt.table = tc.table.Table.load(schema, [[1, 2, 3]])
+        cxt.result = cxt.table.values_at((1,))
+
+        self.assertRaises(tc.error.BadRequest, lambda: self.host.post(ENDPOINT, cxt))
+
+    def testSumColumn(self):
+        count = 10
+        values = [(v,) for v in range(count)]
+        keys = [(num2words(i),) for i in range(count)]
+
+        cxt = tc.Context()
+        cxt.table = tc.table.Table(SCHEMA)
+        cxt.inserts = [cxt.table.insert(k, v) for k, v in zip(keys, values)]
+        cxt.result = tc.After(cxt.inserts, tc.Tuple([
+            cxt.table.sum_column("views"),
+            cxt.table.mean_column("views"),
+            cxt.table.where({"views": slice(5, 10)}).sum_column("views"),
+        ]))
+
+        self.assertEqual(self.host.post(ENDPOINT, cxt), [45, 4.5, 35])
+
+    def testSumColumnNotNumeric(self):
+        cxt = tc.Context()
+        cxt.table = tc.table.Table(SCHEMA)
+        cxt.result = cxt.table.sum_column("name")
+
+        self.assertRaises(tc.error.BadRequest, lambda: self.host.post(ENDPOINT, cxt))
+
+    def testGroupBy(self):
+        count = 10
+        values = [(v % 3,) for v in range(count)]
+        keys = [(num2words(i),) for i in range(count)]
+
+        cxt = tc.Context()
+        cxt.table = tc.table.Table(SCHEMA)
+        cxt.inserts = [cxt.table.insert(k, v) for k, v in zip(keys, values)]
+        groups = cxt.table._get("group_by", ["views"], tc.table.Table)
+        cxt.result = tc.After(cxt.inserts, groups.count())
+
+        result = self.host.post(ENDPOINT, cxt)
+        self.assertEqual(result, 3)
+
+    def testCountBy(self):
+        count = 10
+        values = [(v % 3,) for v in range(count)]
+        keys = [(num2words(i),) for i in range(count)]
+
+        cxt = tc.Context()
+        cxt.table = tc.table.Table(SCHEMA)
+        cxt.inserts = [cxt.table.insert(k, v) for k, v in zip(keys, values)]
+        cxt.result = tc.After(cxt.inserts, cxt.table.count_by(["views"]))
+
+        schema = tc.table.Schema([tc.Column("views", tc.UInt)], [tc.Column("count", tc.U64)])
+        result = self.host.post(ENDPOINT, cxt)
+        self.assertEqual(result, expected(schema, [[0, 4], [1, 3], [2, 3]]))
+
+    def testGroupByRows(self):
+        count = 10
+        values = [(v % 3,) for v in range(count)]
+        keys = [(num2words(i),) for i in range(count)]
+
+        cxt = tc.Context()
+        cxt.table = tc.table.Table(SCHEMA)
+        cxt.inserts = [cxt.table.insert(k, v) for k, v in zip(keys, values)]
+        groups = cxt.table._get("group_by", ["views"], tc.table.Table)
+        cxt.result = tc.After(cxt.inserts, groups.rows())
+
+        result = self.host.post(ENDPOINT, cxt)
+        self.assertEqual(result, [[0], [1], [2]])
+
     def testInsert(self):
         for x in range(0, 100, 10):
             keys = list(range(x))
@@ -53,6 +551,38 @@ class TableTests(unittest.TestCase):
             result = self.host.post(ENDPOINT, cxt)
             self.assertEqual(result, x)
 
+    def testKeyedRows(self):
+        count = 10
+        values = [(v,) for v in range(count)]
+        keys = [(num2words(i),) for i in range(count)]
+
+        cxt = tc.Context()
+        cxt.table = tc.table.Table(SCHEMA)
+        cxt.inserts = [cxt.table.insert(k, v) for k, v in zip(keys, values)]
+        cxt.result = tc.After(cxt.inserts, cxt.table.keyed_rows({"views": slice(2, 5)}))
+
+        result = self.host.post(ENDPOINT, cxt)
+        self.assertEqual(sorted(row["views"] for row in result), [2, 3, 4])
+        self.assertEqual(
+            sorted(row["name"] for row in result),
+            sorted(num2words(i) for i in range(2, 5)))
+
+    def testKeyedRowsSelection(self):
+        count = 3
+        values = [(v,) for v in range(count)]
+        keys = [(num2words(i),) for i in range(count)]
+
+        cxt = tc.Context()
+        cxt.table = tc.table.Table(SCHEMA)
+        cxt.inserts = [cxt.table.insert(k, v) for k, v in zip(keys, values)]
+        cxt.selection = cxt.table.select(["views", "name"])
+        cxt.result = tc.After(cxt.inserts, (cxt.selection.keyed_rows(), cxt.selection.limit(1).keyed_rows()))
+
+        rows, limited = self.host.post(ENDPOINT, cxt)
+        expected = sorted([{"name": num2words(i), "views": i} for i in range(count)], key=lambda row: row["name"])
+        self.assertEqual(rows, expected)
+        self.assertEqual(limited, expected[:1])
+
     def testLimit(self):
         count = 50
         values = [(v,) for v in range(count)]
@@ -67,6 +597,169 @@ class TableTests(unittest.TestCase):
         first_row = sorted(list(k + v) for k, v in zip(keys, values))[0]
         self.assertEqual(result, expected(SCHEMA, [first_row]))
 
+    def testSlicePrimaryRange(self):
+        count = 50
+        values = [(v,) for v in range(count)]
+        keys = [(num2words(i),) for i in range(count)]
+        rows = sorted([list(k + v) for k, v in zip(keys, values) if "f" <= k[0] < "t"])
+
+        cxt = tc.Context()
+        cxt.table = tc.table.Table(SCHEMA)
+        cxt.inserts = [cxt.table.insert(k, v) for k, v in zip(keys, values)]
+        cxt.result = tc.After(cxt.inserts, cxt.table.where({"name": slice("f", "t")}))
+
+        result = self.host.post(ENDPOINT, cxt)
+        self.assertEqual(result, expected(SCHEMA, rows))
+
+    def testSliceNestedMerge(self):
+        count = 50
+        values = [(v, v % 10) for v in range(count)]
+        keys = [(num2words(i),) for i in range(count)]
+        schema = tc.table.Schema(
+            [tc.Column("name", tc.String, 512)],
+            [tc.Column("views", tc.UInt), tc.Column("likes", tc.UInt)]
+        ).create_index("views", ["views"]).create_index("likes", ["likes"])
+
+        cxt = tc.Context()
+        cxt.table = tc.table.Table(schema)
+        cxt.inserts = [cxt.table.insert(k, v) for k, v in zip(keys, values)]
+        cxt.merged = tc.After(cxt.inserts, cxt.table.where({"views": slice(10, 40), "likes": slice(0, 5)}))
+        cxt.result = tc.Tuple([cxt.merged.count(), cxt.merged.where({"likes": slice(0, 2)}).count()])
+
+        result = self.host.post(ENDPOINT, cxt)
+        self.assertEqual(result, [15, 6])
+
+    def testSliceMergedIndices(self):
+        schema = tc.table.Schema([
+            tc.Column("0", tc.U64),
+            tc.Column("1", tc.U64),
+        ], [
+            tc.Column("value", tc.U64),
+        ])
+
+        schema.create_index("0", ["0"]).create_index("value", ["value"])
+
+        data = [([i // 3, i % 3], i % 2) for i in range(9)]
+
+        cxt = tc.Context()
+        cxt.table = tc.table.Table(schema)
+        cxt.inserts = [cxt.table.insert(key, [value]) for (key, value) in data]
+        cxt.result = tc.After(cxt.inserts, cxt.table.where({"0": 1, "value": 0}).rows())
+
+        actual = self.host.post(ENDPOINT, cxt)
+        self.assertEqual(actual, [key + [value] for key, value in data if key[0] == 1 and value == 0])
+
+    def testSliceNestedMergeOutOfBounds(self):
+        schema = tc.table.Schema(
+            [tc.Column("name", tc.String, 512)],
+            [tc.Column("views", tc.UInt), tc.Column("likes", tc.UInt)]
+        ).create_index("views", ["views"]).create_index("likes", ["likes"])
+
+        cxt = tc.Context()
+        cxt.table = tc.table.Table(schema)
+        cxt.merged = cxt.table.where({"views": slice(10, 40), "likes": slice(0, 5)})
+        cxt.result = cxt.merged.where({"likes": slice(5, 10)}).count()
+
+        self.assertRaises(tc.error.BadRequest, lambda: self.host.post(ENDPOINT, cxt))
+
+    def testSliceSnapshot(self):
+        count = 10
+        schema = tc.table.Schema(
+            [tc.Column("id", tc.U64)], [tc.Column("views", tc.UInt)]).create_index("views", ["views"])
+
+        cxt = tc.Context()
+        cxt.table = tc.table.Table.load(schema, [[i, i] for i in range(count)])
+
+        # every row read inserts a new row within the same bounds, which the stream must not see
+        @tc.closure(cxt.table)
+        @tc.get_op
+        def step(row: tc.Tuple):
+            return cxt.table.insert((tc.U64(row[0]) + count,), (tc.UInt(row[1]) + count,))
+
+        cxt.inserts = cxt.table.where({"views": slice(count * 10)}).rows().for_each(step)
+        cxt.result = tc.After(cxt.inserts, tc.Tuple([
+            cxt.table.count(), cxt.table.where({"views": slice(count, count * 10)}).count()]))
+
+        result = self.host.post(ENDPOINT, cxt)
+        self.assertEqual(result, [count * 2, count])
+
+    def testNullable(self):
+        schema = tc.table.Schema(
+            [tc.Column("name", tc.String, 512)],
+            [tc.Column("views", tc.UInt), tc.Column("likes", tc.UInt, nullable=True)],
+        ).create_index("likes", ["likes"])
+
+        cxt = tc.Context()
+        cxt.table = tc.table.Table(schema)
+        cxt.inserts = [
+            cxt.table.insert(("one",), (1, 1)),
+            cxt.table.insert(("two",), (2, None)),
+            cxt.table.insert(("three",), (3, None)),
+        ]
+        cxt.result = tc.After(cxt.inserts, tc.Tuple([
+            cxt.table[("two",)],
+            cxt.table.where({"likes": None}).count(),
+        ]))
+
+        result = self.host.post(ENDPOINT, cxt)
+        self.assertEqual(result, [["two", 2, None], 2])
+
+    def testReverseSlice(self):
+        count = 50
+        values = [(v,) for v in range(count)]
+        keys = [(num2words(i),) for i in range(count)]
+
+        cxt = tc.Context()
+        cxt.table = tc.table.Table(SCHEMA)
+        cxt.inserts = [cxt.table.insert(k, v) for k, v in zip(keys, values)]
+        cxt.reversed = cxt.table.where({"views": slice(5, 25)}).reverse()
+        cxt.result = tc.After(cxt.inserts, cxt.reversed.where({"views": slice(10, 20)}))
+
+        result = self.host.post(ENDPOINT, cxt)
+        rows = [[num2words(i), i] for i in reversed(range(10, 20))]
+        self.assertEqual(result, expected(SCHEMA, rows))
+
+    def testSliceCompactRange(self):
+        count = 50
+        values = [(v,) for v in range(count)]
+        keys = [(num2words(i),) for i in range(count)]
+
+        cxt = tc.Context()
+        cxt.table = tc.table.Table(SCHEMA)
+        cxt.inserts = [cxt.table.insert(k, v) for k, v in zip(keys, values)]
+        cxt.result = tc.After(cxt.inserts, cxt.table.count({"views": [10, 20]}))
+
+        result = self.host.post(ENDPOINT, cxt)
+        self.assertEqual(result, 10)
+
+    def testSliceCaseInsensitive(self):
+        schema = tc.table.Schema(
+            [tc.Column("name", tc.String, 512, case_insensitive=True)], [tc.Column("views", tc.UInt)])
+
+        keys = [("alice",), ("Bob",), ("Alice",), ("carol",)]
+
+        cxt = tc.Context()
+        cxt.table = tc.table.Table(schema)
+        cxt.inserts = [cxt.table.insert(k, (v,)) for v, k in enumerate(keys)]
+        cxt.result = tc.After(cxt.inserts, cxt.table.where({"name": "alice"}))
+
+        result = self.host.post(ENDPOINT, cxt)
+        self.assertEqual(result, expected(schema, [["alice", 0], ["Alice", 2]]))
+
+    def testSliceUnknownColumn(self):
+        cxt = tc.Context()
+        cxt.table = tc.table.Table(SCHEMA)
+        cxt.result = cxt.table.count({"likes": 1})
+
+        self.assertRaises(tc.error.NotFound, lambda: self.host.post(ENDPOINT, cxt))
+
+    def testSliceTypo(self):
+        cxt = tc.Context()
+        cxt.table = tc.table.Table(SCHEMA)
+        cxt.result = cxt.table.where({"veiws": slice(10)})
+
+        self.assertRaises(tc.error.NotFound, lambda: self.host.post(ENDPOINT, cxt))
+
     def testSelect(self):
         count = 5
         values = [[v] for v in range(count)]
@@ -88,6 +781,26 @@ class TableTests(unittest.TestCase):
 
         self.assertEqual(actual, expected)
 
+    def testSelectOrderBySubset(self):
+        count = 5
+        values = [[v] for v in range(count)]
+        keys = [[num2words(i)] for i in range(count)]
+
+        cxt = tc.Context()
+        cxt.table = tc.table.Table(SCHEMA)
+        cxt.inserts = [cxt.table.insert(k, v) for k, v in zip(keys, values)]
+        cxt.result = tc.After(cxt.inserts, cxt.table.select(["name", "views"]).order_by(["views"], True).first())
+
+        result = self.host.post(ENDPOINT, cxt)
+        self.assertEqual(result, ["four", 4])
+
+    def testSelectOrderByUnselected(self):
+        cxt = tc.Context()
+        cxt.table = tc.table.Table(SCHEMA)
+        cxt.result = cxt.table.select(["name"]).order_by(["views"])
+
+        self.assertRaises(tc.error.BadRequest, lambda: self.host.post(ENDPOINT, cxt))
+
     @classmethod
     def tearDownClass(cls):
         cls.host.stop()
@@ -230,6 +943,155 @@ class ErrorTest(unittest.TestCase):
             tc.error.BadRequest,
             lambda: self.host.put("/test/table/table", "one", [1]))
 
+    def testListNamespaces(self):
+        namespaces = set()
+        for namespace in self.host.get("/host"):
+            namespaces.update(namespace)
+
+        self.assertEqual(namespaces, {"/host", "/state", "/transact/hypothetical", "/test/table"})
+
+    def tearDown(self):
+        self.host.stop()
+
+
+class VersionTest(unittest.TestCase):
+    SCHEMA = tc.table.Schema(
+        [tc.Column("name", tc.String, 512)], [tc.Column("views", tc.UInt), tc.Column("version", tc.UInt)])
+
+    def setUp(self):
+        schema = self.SCHEMA
+
+        class Persistent(tc.Cluster, metaclass=tc.Meta):
+            __uri__ = tc.URI(f"/test/table")
+
+            def _configure(self):
+                self.table = tc.chain.Block(tc.table.Table(schema))
+
+        self.host = start_host("table_version", [Persistent])
+
+    def testUpdateIfVersion(self):
+        self.host.put("/test/table/table", ["one"], [0, 0])
+
+        params = {"key": ["one"], "version": "version", "expected": 0, "values": {"views": 1}}
+        self.assertIsNone(self.host.post("/test/table/table/update_if_version", params))
+        self.assertEqual(self.host.get("/test/table/table", ["one"]), ["one", 1, 1])
+
+        params = {"key": ["one"], "version": "version", "expected": 0, "values": {"views": 2}}
+        self.assertRaises(
+            tc.error.Conflict,
+            lambda: self.host.post("/test/table/table/update_if_version", params))
+
+        self.assertEqual(self.host.get("/test/table/table", ["one"]), ["one", 1, 1])
+
+    def testUpdateIfVersionRace(self):
+        self.host.put("/test/table/table", ["one"], [0, 0])
+
+        def update(views):
+            params = {"key": ["one"], "version": "version", "expected": 0, "values": {"views": views}}
+
+            try:
+                self.host.post("/test/table/table/update_if_version", params)
+                return views
+            except tc.error.Conflict:
+                return None
+
+        with ThreadPoolExecutor(max_workers=2) as pool:
+            results = list(pool.map(update, [1, 2]))
+
+        succeeded = [views for views in results if views is not None]
+        self.assertEqual(len(succeeded), 1)
+        self.assertEqual(self.host.get("/test/table/table", ["one"]), ["one", succeeded[0], 1])
+
+    def tearDown(self):
+        self.host.stop()
+
+
+class AlterTest(unittest.TestCase):
+    def setUp(self):
+        class Persistent(tc.Cluster, metaclass=tc.Meta):
+            __uri__ = tc.URI(f"/test/table")
+
+            def _configure(self):
+                self.table = tc.chain.Block(tc.table.Table(SCHEMA))
+
+        self.host = start_host("table_alter", [Persistent])
+
+    def testAddColumn(self):
+        self.host.put("/test/table/table", ["one"], [1])
+
+        params = {"column": tc.to_json(tc.Column("likes", tc.UInt)), "default": 0}
+        self.host.post("/test/table/table/add_column", params)
+        self.host.put("/test/table/table", ["two"], [2, 1])
+
+        self.assertEqual(len(self.host.get("/test/table/table/columns")), 3)
+        self.assertEqual(self.host.get("/test/table/table", ["one"]), ["one", 1, 0])
+
+        self.host.stop()
+        self.host.start()
+
+        self.assertEqual(len(self.host.get("/test/table/table/columns")), 3)
+        self.assertEqual(self.host.get("/test/table/table", ["one"]), ["one", 1, 0])
+        self.assertEqual(self.host.get("/test/table/table", ["two"]), ["two", 2, 1])
+
+    def testDropColumn(self):
+        self.host.put("/test/table/table", ["one"], [1])
+        self.host.post("/test/table/table/drop_column", {"name": "views"})
+        self.host.put("/test/table/table", ["two"], [])
+
+        self.assertEqual(len(self.host.get("/test/table/table/columns")), 1)
+        self.assertEqual(self.host.get("/test/table/table", ["one"]), ["one"])
+
+        self.host.stop()
+        self.host.start()
+
+        self.assertEqual(len(self.host.get("/test/table/table/columns")), 1)
+        self.assertEqual(self.host.get("/test/table/table", ["one"]), ["one"])
+        self.assertEqual(self.host.get("/test/table/table/count"), 2)
+
+    def testDropIndex(self):
+        self.host.put("/test/table/table", ["one"], [1])
+        self.host.get("/test/table/table/order", ["views"])
+        self.host.post("/test/table/table/drop_index", {"name": "views"})
+
+        self.assertRaises(tc.error.BadRequest, lambda: self.host.get("/test/table/table/order", ["views"]))
+
+        self.host.stop()
+        self.host.start()
+
+        self.assertRaises(tc.error.BadRequest, lambda: self.host.get("/test/table/table/order", ["views"]))
+        self.assertEqual(self.host.get("/test/table/table", ["one"]), ["one", 1])
+
+    def testRecreateIndex(self):
+        self.host.put("/test/table/table", ["one"], [1])
+        self.host.post("/test/table/table/drop_index", {"name": "views"})
+        self.host.delete("/test/table/table", ["one"])
+        self.host.put("/test/table/table", ["two"], [2])
+        self.host.post("/test/table/table/create_index", {"name": "views", "columns": ["views"]})
+
+        self.assertEqual(self.host.get("/test/table/table/verify_indices"), [])
+
+        self.host.stop()
+        self.host.start()
+
+        self.assertEqual(self.host.get("/test/table/table/verify_indices"), [])
+        self.host.get("/test/table/table/order", ["views"])
+
+    def testRenameColumn(self):
+        self.host.put("/test/table/table", ["one"], [1])
+        self.host.post("/test/table/table/rename_column", {"old": "views", "new": "likes"})
+
+        columns = [column[0] for column in self.host.get("/test/table/table/columns")]
+        self.assertEqual(columns, ["name", "likes"])
+        self.host.get("/test/table/table/order", ["likes"])
+
+        self.host.stop()
+        self.host.start()
+
+        columns = [column[0] for column in self.host.get("/test/table/table/columns")]
+        self.assertEqual(columns, ["name", "likes"])
+        self.host.get("/test/table/table/order", ["likes"])
+        self.assertEqual(self.host.get("/test/table/table", ["one"]), ["one", 1])
+
     def tearDown(self):
         self.host.stop()
 
@@ -5,7 +5,7 @@ import numpy as np
 import tinychain as tc
 import unittest
 
-from testutils import DEFAULT_PORT, start_host, PersistenceTest
+from testutils import DEFAULT_PORT, expect_dense, start_host, PersistenceTest
 
 
 ENDPOINT = "/transact/hypothetical"
@@ -28,6 +28,153 @@ class DenseTests(unittest.TestCase):
         actual = self.host.post(ENDPOINT, cxt)
         self.assertEqual(expected, actual)
 
+    def testRangeWithDtype(self):
+        cxt = tc.Context()
+        cxt.result = tc.Tuple([
+            tc.tensor.Dense.arange([5], 0, 5, tc.F32),
+            tc.tensor.Dense.arange([5], 0, 5),
+        ])
+
+        explicit, inferred = self.host.post(ENDPOINT, cxt)
+        self.assertEqual(explicit, expect_dense(tc.F32, [5], [0., 1., 2., 3., 4.]))
+        self.assertEqual(inferred, expect_dense(tc.I64, [5], list(range(5))))
+
+    def testRangeWithInvalidDtype(self):
+        cxt = tc.Context()
+        cxt.result = tc.tensor.Dense.arange([5], 0.5, 5.5, tc.I32)
+
+        self.assertRaises(tc.error.BadRequest, lambda: self.host.post(ENDPOINT, cxt))
+
+    def testCreateInvalidSchema(self):
+        cxt = tc.Context()
+        cxt.result = tc.tensor.Dense.create([2, 3], tc.String)
+        self.assertRaises(tc.error.BadRequest, lambda: self.host.post(ENDPOINT, cxt))
+
+        cxt = tc.Context()
+        cxt.result = tc.tensor.Dense.create([2, "three"], tc.F32)
+        self.assertRaises(tc.error.BadRequest, lambda: self.host.post(ENDPOINT, cxt))
+
+    def testLoadWrongLength(self):
+        cxt = tc.Context()
+        cxt.result = tc.tensor.Dense.load([2, 3], tc.I32, [1, 2, 3, 4, 5])
+        self.assertRaises(tc.error.BadRequest, lambda: self.host.post(ENDPOINT, cxt))
+
+        cxt = tc.Context()
+        cxt.result = tc.tensor.Dense.load([2, 3], tc.I32, [1, 2, 3, 4, 5, 6, 7])
+        self.assertRaises(tc.error.BadRequest, lambda: self.host.post(ENDPOINT, cxt))
+
+    def testLinspace(self):
+        cxt = tc.Context()
+        cxt.result = tc.Tuple([
+            tc.tensor.Dense.linspace(0, 1, 5),
+            tc.tensor.Dense.linspace(0, 8, 5),
+            tc.tensor.Dense.linspace(3, 7, 1),
+        ])
+
+        floats, ints, single = self.host.post(ENDPOINT, cxt)
+        self.assertEqual(floats, expect_dense(tc.F64, [5], [0., 0.25, 0.5, 0.75, 1.]))
+        self.assertEqual(ints, expect_dense(tc.I64, [5], [0, 2, 4, 6, 8]))
+        self.assertEqual(single, expect_dense(tc.I64, [1], [3]))
+
+    def testLinspaceEmpty(self):
+        cxt = tc.Context()
+        cxt.result = tc.tensor.Dense.linspace(0, 1, 0)
+
+        self.assertRaises(tc.error.BadRequest, lambda: self.host.post(ENDPOINT, cxt))
+
+    def testMeshgrid(self):
+        cxt = tc.Context()
+        cxt.x = tc.tensor.Dense.load([2], tc.I32, [1, 2])
+        cxt.y = tc.tensor.Dense.load([3], tc.I32, [3, 4, 5])
+        cxt.result = tc.tensor.Dense.meshgrid([cxt.x, cxt.y])
+
+        x, y = self.host.post(ENDPOINT, cxt)
+        self.assertEqual(x, expect_dense(tc.I32, [2, 3], [1, 1, 1, 2, 2, 2]))
+        self.assertEqual(y, expect_dense(tc.I32, [2, 3], [3, 4, 5, 3, 4, 5]))
+
+    def testMeshgridNot1D(self):
+        cxt = tc.Context()
+        cxt.x = tc.tensor.Dense.load([2], tc.I32, [1, 2])
+        cxt.y = tc.tensor.Dense.load([1, 2], tc.I32, [3, 4])
+        cxt.result = tc.tensor.Dense.meshgrid([cxt.x, cxt.y])
+
+        self.assertRaises(tc.error.BadRequest, lambda: self.host.post(ENDPOINT, cxt))
+
+    def testConstantWithBlockSize(self):
+        shape = [10, 20]
+
+        cxt = tc.Context()
+        cxt.tensor = tc.tensor.Dense.constant(shape, 1, block_size=16)
+        cxt.result = tc.After([
+            cxt.tensor[3, 5:15].write(2),
+            cxt.tensor[9, 19].write(3),
+        ], tc.Tuple([cxt.tensor, cxt.tensor + tc.tensor.Dense.ones(shape, tc.I64)]))
+
+        actual, incremented = self.host.post(ENDPOINT, cxt)
+
+        expected = np.ones(shape, dtype=np.int64)
+        expected[3, 5:15] = 2
+        expected[9, 19] = 3
+        self.assertEqual(actual, expect_dense(tc.I64, shape, expected.flatten()))
+        self.assertEqual(incremented, expect_dense(tc.I64, shape, (expected + 1).flatten()))
+
+    def testConstantWithInvalidBlockSize(self):
+        cxt = tc.Context()
+        cxt.tensor = tc.tensor.Dense.constant([10, 20], 1, block_size=100)
+
+        self.assertRaises(tc.error.BadRequest, lambda: self.host.post(ENDPOINT, cxt))
+
+    def testDot(self):
+        cxt = tc.Context()
+        cxt.l = tc.tensor.Dense.load([3], tc.I32, [1, 2, 3])
+        cxt.r = tc.tensor.Dense.load([3], tc.I32, [4, 5, 6])
+        cxt.sparse = tc.tensor.Sparse.load([3], tc.I32, [[[0], 4], [[2], 6]])
+        cxt.result = tc.Tuple([cxt.l.dot(cxt.r), cxt.sparse.dot(cxt.l)])
+
+        actual = self.host.post(ENDPOINT, cxt)
+        self.assertEqual(actual, [32, 22])
+
+    def testDotWrongLength(self):
+        cxt = tc.Context()
+        cxt.l = tc.tensor.Dense.load([3], tc.I32, [1, 2, 3])
+        cxt.r = tc.tensor.Dense.load([2], tc.I32, [4, 5])
+        cxt.result = cxt.l.dot(cxt.r)
+
+        self.assertRaises(tc.error.BadRequest, lambda: self.host.post(ENDPOINT, cxt))
+
+    def testOuter(self):
+        cxt = tc.Context()
+        cxt.l = tc.tensor.Dense.load([3], tc.I32, [1, 2, 3])
+        cxt.r = tc.tensor.Dense.load([2], tc.I32, [4, 5])
+        cxt.result = cxt.l.outer(cxt.r)
+
+        actual = self.host.post(ENDPOINT, cxt)
+        expected = expect_dense(tc.I32, [3, 2], [4, 5, 8, 10, 12, 15])
+        self.assertEqual(actual, expected)
+
+    def testOuterNotAVector(self):
+        cxt = tc.Context()
+        cxt.l = tc.tensor.Dense.ones([3, 1], tc.I32)
+        cxt.r = tc.tensor.Dense.ones([2], tc.I32)
+        cxt.result = cxt.l.outer(cxt.r)
+
+        self.assertRaises(tc.error.BadRequest, lambda: self.host.post(ENDPOINT, cxt))
+
+    def testTrace(self):
+        cxt = tc.Context()
+        cxt.identity = tc.tensor.Dense.load([3, 3], tc.I32, [1, 0, 0, 0, 1, 0, 0, 0, 1])
+        cxt.matrix = tc.tensor.Dense.arange([2, 3], 0, 6)
+        cxt.result = tc.Tuple([
+            cxt.identity.trace(),
+            cxt.matrix.trace(),
+            cxt.matrix.trace(1),
+            cxt.matrix.trace(-1),
+            cxt.matrix.trace(5),
+        ])
+
+        actual = self.host.post(ENDPOINT, cxt)
+        self.assertEqual(actual, [3, 4, 6, 3, 0])
+
     def testSlice(self):
         shape = [2, 5]
 
@@ -39,6 +186,65 @@ class DenseTests(unittest.TestCase):
         expected = expect_dense(tc.I64, [2], np.arange(1, 11).reshape([2, 5])[1, 2:-1])
         self.assertEqual(actual, expected)
 
+    def testChunk(self):
+        cxt = tc.Context()
+        cxt.tensor = tc.tensor.Dense.arange([10, 10], 0, 100)
+        cxt.result = tc.Tuple([cxt.tensor.chunk(i, i + 10) for i in range(0, 100, 10)])
+
+        actual = self.host.post(ENDPOINT, cxt)
+        self.assertEqual(actual, [list(range(i, i + 10)) for i in range(0, 100, 10)])
+
+    def testChunkOutOfBounds(self):
+        cxt = tc.Context()
+        cxt.tensor = tc.tensor.Dense.arange([10, 10], 0, 100)
+        cxt.result = cxt.tensor.chunk(90, 101)
+
+        self.assertRaises(tc.error.BadRequest, lambda: self.host.post(ENDPOINT, cxt))
+
+    def testSliceOutOfBounds(self):
+        cxt = tc.Context()
+        cxt.tensor = tc.tensor.Dense.arange([2, 5], 1, 11)
+        cxt.result = cxt.tensor[1, 5]
+
+        self.assertRaises(tc.error.BadRequest, lambda: self.host.post(ENDPOINT, cxt))
+
+        cxt = tc.Context()
+        cxt.tensor = tc.tensor.Dense.arange([2, 5], 1, 11)
+        cxt.result = cxt.tensor[1, 2:5]
+
+        actual = self.host.post(ENDPOINT, cxt)
+        self.assertEqual(actual, expect_dense(tc.I64, [3], [8, 9, 10]))
+
+    def testNormalizeAlongAxis(self):
+        shape = [3, 4]
+        x = np.arange(12).reshape(shape)
+
+        cxt = tc.Context()
+        cxt.tensor = tc.tensor.Dense.load(shape, tc.I32, x.flatten().tolist())
+        cxt.result = cxt.tensor.apply_along_axis(1, "normalize")
+
+        actual = self.host.post(ENDPOINT, cxt)
+        expected = (x - x.mean(axis=1, keepdims=True)) / x.std(axis=1, keepdims=True)
+        self.assertTrue(all_close(actual, expected))
+
+    def testSoftmax(self):
+        shape = [3, 4]
+        x = np.arange(12).reshape(shape) * 100
+
+        cxt = tc.Context()
+        cxt.tensor = tc.tensor.Dense.load(shape, tc.I32, x.flatten().tolist())
+        cxt.result = cxt.tensor.softmax(0)
+
+        actual = self.host.post(ENDPOINT, cxt)
+        self.assertEqual(actual[tc.uri(tc.tensor.Dense)][0], [shape, str(tc.uri(tc.F64))])
+
+        exp = np.exp(x - x.max(axis=0, keepdims=True))
+        expected = exp / exp.sum(axis=0, keepdims=True)
+        self.assertTrue(all_close(actual, expected))
+
+        actual = np.array(actual[tc.uri(tc.tensor.Dense)][1]).reshape(shape)
+        self.assertTrue(np.allclose(actual.sum(axis=0), np.ones([4])))
+
     def testAssignSlice(self):
         cxt = tc.Context()
         cxt.big = tc.tensor.Dense.zeros([2, 2, 5], tc.I32)
@@ -53,6 +259,68 @@ class DenseTests(unittest.TestCase):
 
         self.assertEqual(actual, expected)
 
+    def testAssignSliceWithBroadcast(self):
+        cxt = tc.Context()
+        cxt.big = tc.tensor.Dense.zeros([2, 3, 4], tc.I32)
+        cxt.row = tc.tensor.Dense.arange([4], 1, 5)
+        cxt.result = tc.After(cxt.big[1, :2].write(cxt.row), cxt.big)
+
+        actual = self.host.post(ENDPOINT, cxt)
+
+        expected = np.zeros([2, 3, 4], np.int64)
+        expected[1, :2] = np.arange(1, 5)
+        expected = expect_dense(tc.I32, [2, 3, 4], expected.flatten())
+
+        self.assertEqual(actual, expected)
+
+    def testAssignSliceIncompatible(self):
+        cxt = tc.Context()
+        cxt.big = tc.tensor.Dense.zeros([2, 3, 4], tc.I32)
+        cxt.row = tc.tensor.Dense.arange([3], 1, 4)
+        cxt.result = tc.After(cxt.big[1].write(cxt.row), cxt.big)
+
+        self.assertRaises(tc.error.BadRequest, lambda: self.host.post(ENDPOINT, cxt))
+
+    def testAccumulate(self):
+        shape = [2, 3]
+
+        cxt = tc.Context()
+        cxt.tensor = tc.tensor.Dense.arange(shape, 0, 6)
+        cxt.row = tc.tensor.Dense.arange([3], 1, 4)
+        cxt.result = tc.After([
+            cxt.tensor[0].accumulate(cxt.row),
+            cxt.tensor[1, 2].accumulate(10),
+        ], cxt.tensor)
+
+        actual = self.host.post(ENDPOINT, cxt)
+
+        expected = np.arange(0, 6).reshape(shape)
+        expected[0] += np.arange(1, 4)
+        expected[1, 2] += 10
+        self.assertEqual(actual, expect_dense(tc.I64, shape, expected.flatten()))
+
+    def testAccumulateConcurrent(self):
+        shape = [2, 3]
+
+        cxt = tc.Context()
+        cxt.dense = tc.tensor.Dense.zeros(shape, tc.I32)
+        cxt.sparse = tc.tensor.Sparse.zeros(shape, tc.I32)
+        cxt.result = tc.After(
+            [tensor[0].accumulate(1) for tensor in (cxt.dense, cxt.sparse) for _ in range(3)] +
+            [tensor[0, 1].accumulate(2) for tensor in (cxt.dense, cxt.sparse) for _ in range(3)],
+            (cxt.dense, cxt.sparse))
+
+        dense, sparse = self.host.post(ENDPOINT, cxt)
+        self.assertEqual(dense, expect_dense(tc.I32, shape, [3, 9, 3, 0, 0, 0]))
+        self.assertEqual(sparse, expect_sparse(tc.I32, shape, [[[0, 0], 3], [[0, 1], 9], [[0, 2], 3]]))
+
+    def testAccumulateBool(self):
+        cxt = tc.Context()
+        cxt.tensor = tc.tensor.Dense.zeros([2, 3], tc.Bool)
+        cxt.result = tc.After(cxt.tensor[0].accumulate(1), cxt.tensor)
+
+        self.assertRaises(tc.error.BadRequest, lambda: self.host.post(ENDPOINT, cxt))
+
     def testAdd(self):
         cxt = tc.Context()
         cxt.left = tc.tensor.Dense.arange([5, 2, 2], 1., 21.)
@@ -90,6 +358,57 @@ class DenseTests(unittest.TestCase):
         expected = expect_dense(tc.F64, shape, np.arange(1, 4))
         self.assertEqual(actual, expected)
 
+    def testDivByZero(self):
+        cxt = tc.Context()
+        cxt.left = tc.tensor.Dense.arange([3], 1, 4)
+        cxt.right = tc.tensor.Dense.load([3], tc.I64, [1, 0, 2])
+        cxt.result = cxt.left / cxt.right
+
+        self.assertRaises(tc.error.BadRequest, lambda: self.host.post(ENDPOINT, cxt))
+
+        cxt = tc.Context()
+        cxt.left = tc.tensor.Dense.arange([3], 1, 4)
+        cxt.result = cxt.left / 0
+
+        self.assertRaises(tc.error.BadRequest, lambda: self.host.post(ENDPOINT, cxt))
+
+    def testDivFloatByZero(self):
+        cxt = tc.Context()
+        cxt.left = tc.tensor.Dense.arange([3], -1., 2.)
+        cxt.quotient = cxt.left / 0.
+        cxt.result = tc.Tuple([cxt.quotient < -1e308, cxt.quotient > 1e308])
+
+        negative, positive = self.host.post(ENDPOINT, cxt)
+        self.assertEqual(negative, expect_dense(tc.Bool, [3], [True, False, False]))
+        self.assertEqual(positive, expect_dense(tc.Bool, [3], [False, False, True]))
+
+    def testDivFloatByZeroTensor(self):
+        cxt = tc.Context()
+        cxt.left = tc.tensor.Dense.arange([3], -1., 2.)
+        cxt.right = tc.tensor.Dense.load([3], tc.F64, [1., 0., 2.])
+        cxt.result = cxt.left / cxt.right
+
+        # unlike division by a constant, a non-finite combination of two tensors is an error
+        self.assertRaises(tc.error.BadRequest, lambda: self.host.post(ENDPOINT, cxt))
+
+    def testCollectionEq(self):
+        x = np.arange(6).reshape([2, 3]) / 3
+
+        cxt = tc.Context()
+        cxt.tensor = load_dense(x, tc.F64)
+        cxt.same = load_dense(x, tc.F64)
+        cxt.near = load_dense(x + 1e-9, tc.F64)
+        cxt.far = load_dense(x + 1e-3, tc.F64)
+
+        # Tensor.eq is element-wise, so compare the Tensors as members of a Tuple
+        cxt.result = tc.Tuple([
+            tc.Tuple([cxt.tensor]).eq([cxt.same]),
+            tc.Tuple([cxt.tensor]).eq([cxt.near]),
+            tc.Tuple([cxt.tensor]).eq([cxt.far]),
+        ])
+
+        self.assertEqual(self.host.post(ENDPOINT, cxt), [True, True, False])
+
     def testMul(self):
         shape = [5, 2, 1]
 
@@ -199,6 +518,62 @@ class DenseTests(unittest.TestCase):
         expected = np.mean(x, axis)
         self.assertTrue(all_close(actual, expected))
 
+    def testAnyAllAxis(self):
+        x = np.array([[True, False, False], [False, False, False]])
+
+        cxt = tc.Context()
+        cxt.x = load_dense(x, tc.Bool)
+        cxt.result = tc.Tuple([cxt.x.any(1), cxt.x.all(0), cxt.x.any()])
+
+        any1, all0, any_all = self.host.post(ENDPOINT, cxt)
+        self.assertEqual(any1, expect_dense(tc.Bool, [2], [True, False]))
+        self.assertEqual(all0, expect_dense(tc.Bool, [3], [False, False, False]))
+        self.assertTrue(any_all)
+
+    def testCopy(self):
+        cxt = tc.Context()
+        cxt.x = tc.tensor.Dense.load([4], tc.I32, [1, 2, 3, 4])
+        cxt.snapshot = cxt.x.copy()
+        cxt.result = tc.After(cxt.snapshot, tc.After(cxt.x[0:2].write(9), (cxt.snapshot, cxt.x)))
+
+        snapshot, x = self.host.post(ENDPOINT, cxt)
+        self.assertEqual(snapshot, expect_dense(tc.I32, [4], [1, 2, 3, 4]))
+        self.assertEqual(x, expect_dense(tc.I32, [4], [9, 9, 3, 4]))
+
+    def testDiff(self):
+        cxt = tc.Context()
+        cxt.x = tc.tensor.Dense.load([2, 3], tc.F32, [1, 2, 3, 4, 5, 6])
+        cxt.y = tc.tensor.Dense.load([2, 3], tc.F32, [1, 2.5, 3, 4, 5.01, 7])
+        cxt.result = tc.Tuple([cxt.x.diff(cxt.y), cxt.x.diff(cxt.y, tolerance=0.1)])
+
+        exact, approx = self.host.post(ENDPOINT, cxt)
+        self.assertEqual(len(exact), 3)
+        self.assertEqual([coord for coord, _, _ in approx], [[0, 1], [1, 2]])
+        self.assertEqual([[l, r] for _, l, r in approx], [[2, 2.5], [6, 7]])
+
+    def testMaxMin(self):
+        x = np.array([[3, -1, 4], [1, 5, -9], [2, 6, 5]])
+
+        cxt = tc.Context()
+        cxt.x = load_dense(x, tc.I32)
+        cxt.result = tc.Tuple([cxt.x.max(0), cxt.x.min(1), cxt.x.max(1, keepdims=True)])
+
+        max0, min1, max1 = self.host.post(ENDPOINT, cxt)
+        self.assertEqual(max0, expect_dense(tc.I32, [3], x.max(axis=0).tolist()))
+        self.assertEqual(min1, expect_dense(tc.I32, [3], x.min(axis=1).tolist()))
+        self.assertEqual(max1, expect_dense(tc.I32, [3, 1], x.max(axis=1).tolist()))
+
+    def testMaxMinAll(self):
+        x = np.array([[3., -1.5, 4.], [1., 5.5, -9.]])
+
+        cxt = tc.Context()
+        cxt.x = load_dense(x, tc.F64)
+        cxt.result = tc.Tuple([cxt.x.max(), cxt.x.min()])
+
+        actual_max, actual_min = self.host.post(ENDPOINT, cxt)
+        self.assertEqual(actual_max, 5.5)
+        self.assertEqual(actual_min, -9.)
+
     def testProduct(self):
         shape = [2, 3, 4]
         axis = 1
@@ -244,6 +619,34 @@ class DenseTests(unittest.TestCase):
         expected = np.sum(np.arange(0, 120).reshape(shape), axis)
         self.assertEqual(actual, expect_dense(tc.F64, [4, 2, 5], expected.flatten()))
 
+    def testSumKeepdims(self):
+        shape = [3, 4]
+        x = np.arange(12).reshape(shape)
+
+        cxt = tc.Context()
+        cxt.tensor = load_dense(x, tc.I32)
+        cxt.result = tc.Tuple([
+            cxt.tensor.sum(1, keepdims=True),
+            cxt.tensor.product(0, keepdims=True),
+            cxt.tensor[0].sum(0, keepdims=True),
+        ])
+
+        summed, product, vector = self.host.post(ENDPOINT, cxt)
+        self.assertEqual(summed, expect_dense(tc.I64, [3, 1], x.sum(axis=1).tolist()))
+        self.assertEqual(product, expect_dense(tc.I32, [1, 4], x.prod(axis=0).tolist()))
+        self.assertEqual(vector, expect_dense(tc.I64, [1], [x[0].sum()]))
+
+    def testSumOverflow(self):
+        shape = [2, 4]
+
+        cxt = tc.Context()
+        cxt.tensor = tc.tensor.Dense.load(shape, tc.U8, [200] * 8)
+        cxt.result = (cxt.tensor.sum(1), cxt.tensor.sum())
+
+        summed, total = self.host.post(ENDPOINT, cxt)
+        self.assertEqual(summed, expect_dense(tc.U64, [2], [800, 800]))
+        self.assertEqual(total, 1600)
+
     def testSumAll(self):
         shape = [5, 2]
 
@@ -272,6 +675,24 @@ class DenseTests(unittest.TestCase):
         self.assertEqual(reshaped, expected)
         self.assertEqual(expanded, expected)
 
+    def testTransposeCopy(self):
+        shape = [2, 3, 4]
+        permutation = [2, 0, 1]
+
+        cxt = tc.Context()
+        cxt.tensor = tc.tensor.Dense.arange(shape, 0, 24)
+        cxt.view = cxt.tensor.transpose(permutation)
+        cxt.copy = cxt.tensor.transpose(permutation, copy=True)
+        cxt.result = tc.Tuple([cxt.view, cxt.copy, (cxt.view == cxt.copy).all()])
+
+        view, copy, eq = self.host.post(ENDPOINT, cxt)
+
+        expected = np.transpose(np.arange(0, 24).reshape(shape), permutation)
+        expected = expect_dense(tc.I64, [4, 2, 3], expected.flatten())
+        self.assertEqual(view, expected)
+        self.assertEqual(copy, expected)
+        self.assertTrue(eq)
+
     def testSliceAndTransposeAndSliceAndSlice(self):
         self.maxDiff = None
         shape = [2, 3, 4, 5]
@@ -329,6 +750,119 @@ class DenseTests(unittest.TestCase):
         expected = np.tile(x, multiples)
         self.assertEqual(actual, expect_dense(tc.I32, list(expected.shape), expected.flatten().tolist()))
 
+    def testRepeat(self):
+        x = np.array([1, 2, 3])
+
+        cxt = tc.Context()
+        cxt.x = load_dense(x, tc.I32)
+        cxt.result = (cxt.x.repeat(2, 0), cxt.x.repeat([1, 3, 2], 0))
+
+        scalar, per_element = self.host.post(ENDPOINT, cxt)
+        self.assertEqual(scalar, expect_dense(tc.I32, [6], np.repeat(x, 2).tolist()))
+        self.assertEqual(per_element, expect_dense(tc.I32, [6], np.repeat(x, [1, 3, 2]).tolist()))
+
+    def testRepeatZero(self):
+        cxt = tc.Context()
+        cxt.x = tc.tensor.Dense.arange([3], 0, 3)
+        cxt.result = cxt.x.repeat(0, 0)
+
+        self.assertRaises(tc.error.BadRequest, lambda: self.host.post(ENDPOINT, cxt))
+
+    def testRepeatFraction(self):
+        cxt = tc.Context()
+        cxt.x = tc.tensor.Dense.arange([3], 0, 3)
+        cxt.result = cxt.x.repeat([1, 1.5, 2], 0)
+
+        self.assertRaises(tc.error.BadRequest, lambda: self.host.post(ENDPOINT, cxt))
+
+    def testCumulative(self):
+        x = np.array([[1, 3, 2, 5, 4], [9, 7, 8, 6, 10]])
+
+        cxt = tc.Context()
+        cxt.vector = tc.tensor.Dense.load([5], tc.I32, [1, 3, 2, 5, 4])
+        cxt.matrix = load_dense(x, tc.I32)
+        cxt.result = tc.Tuple([
+            cxt.vector.cummax(),
+            cxt.matrix.cummin(1),
+            cxt.matrix.cummax(0),
+            cxt.matrix.cummin(),
+        ])
+
+        cummax, cummin_1, cummax_0, cummin_flat = self.host.post(ENDPOINT, cxt)
+        self.assertEqual(cummax, expect_dense(tc.I32, [5], [1, 3, 3, 5, 5]))
+        self.assertEqual(cummin_1, expect_dense(tc.I32, [2, 5], np.minimum.accumulate(x, 1).flatten().tolist()))
+        self.assertEqual(cummax_0, expect_dense(tc.I32, [2, 5], np.maximum.accumulate(x, 0).flatten().tolist()))
+        self.assertEqual(cummin_flat, expect_dense(tc.I32, [10], np.minimum.accumulate(x.flatten()).tolist()))
+
+    def testGather(self):
+        x = np.arange(12).reshape([4, 3])
+        order = [2, 0, 3, 1, 2]
+
+        cxt = tc.Context()
+        cxt.matrix = load_dense(x, tc.I32)
+        cxt.indices = tc.tensor.Dense.load([5], tc.U64, order)
+        cxt.result = tc.Tuple([cxt.matrix.gather(cxt.indices, 0), cxt.matrix.gather(cxt.indices[:2], 1)])
+
+        rows, columns = self.host.post(ENDPOINT, cxt)
+        self.assertEqual(rows, expect_dense(tc.I32, [5, 3], x[order].flatten().tolist()))
+        self.assertEqual(columns, expect_dense(tc.I32, [4, 2], x[:, order[:2]].flatten().tolist()))
+
+    def testGatherOutOfBounds(self):
+        cxt = tc.Context()
+        cxt.matrix = tc.tensor.Dense.arange([4, 3], 0, 12)
+        cxt.indices = tc.tensor.Dense.load([2], tc.U64, [1, 4])
+        cxt.result = cxt.matrix.gather(cxt.indices, 0)
+
+        self.assertRaises(tc.error.BadRequest, lambda: self.host.post(ENDPOINT, cxt))
+
+    def testScatter(self):
+        shape = [4, 3]
+        values = np.arange(1, 10).reshape([3, 3])
+
+        cxt = tc.Context()
+        cxt.zeros = tc.tensor.Dense.zeros(shape, tc.I32)
+        cxt.indices = tc.tensor.Dense.load([3], tc.U64, [3, 1, 3])
+        cxt.values = load_dense(values, tc.I32)
+        cxt.scattered = cxt.zeros.scatter(cxt.indices, cxt.values, 0)
+        cxt.result = tc.Tuple([cxt.scattered, cxt.scattered[1], cxt.scattered[3]])
+
+        scattered, row_1, row_3 = self.host.post(ENDPOINT, cxt)
+
+        expected = np.zeros(shape, dtype=np.int32)
+        expected[1] = values[1]
+        expected[3] = values[2]  # the last write to a duplicate index wins
+        self.assertEqual(scattered, expect_dense(tc.I32, shape, expected.flatten().tolist()))
+        self.assertEqual(row_1, expect_dense(tc.I32, [3], values[1].tolist()))
+        self.assertEqual(row_3, expect_dense(tc.I32, [3], values[2].tolist()))
+
+    def testScatterOutOfBounds(self):
+        cxt = tc.Context()
+        cxt.zeros = tc.tensor.Dense.zeros([4, 3], tc.I32)
+        cxt.indices = tc.tensor.Dense.load([1], tc.U64, [4])
+        cxt.values = tc.tensor.Dense.ones([1, 3], tc.I32)
+        cxt.result = cxt.zeros.scatter(cxt.indices, cxt.values, 0)
+
+        self.assertRaises(tc.error.BadRequest, lambda: self.host.post(ENDPOINT, cxt))
+
+    def testRoll(self):
+        x = np.arange(6).reshape([2, 3])
+
+        cxt = tc.Context()
+        cxt.vector = tc.tensor.Dense.arange([4], 0, 4)
+        cxt.matrix = load_dense(x, tc.I32)
+        cxt.result = tc.Tuple([
+            cxt.vector.roll(1),
+            cxt.vector.roll(-5),
+            cxt.matrix.roll(4, 1),
+            cxt.matrix.roll(2),
+        ])
+
+        vector, negative, matrix, flat = self.host.post(ENDPOINT, cxt)
+        self.assertEqual(vector, expect_dense(tc.I64, [4], [3, 0, 1, 2]))
+        self.assertEqual(negative, expect_dense(tc.I64, [4], np.roll(np.arange(4), -5).tolist()))
+        self.assertEqual(matrix, expect_dense(tc.I32, [2, 3], np.roll(x, 4, 1).flatten().tolist()))
+        self.assertEqual(flat, expect_dense(tc.I32, [2, 3], np.roll(x, 2).flatten().tolist()))
+
     def testArgmax(self):
         shape = [2, 3, 4]
 
@@ -382,6 +916,17 @@ class SparseTests(unittest.TestCase):
         expected = expect_sparse(tc.I32, shape, [[coord, value]])
         self.assertEqual(actual, expected)
 
+    def testCopyFromWithConcurrency(self):
+        n = 5
+
+        cxt = tc.Context()
+        cxt.elements = tc.Stream.range((0, n)).map(tc.get_op(lambda i: ((i, i), 1)))
+        cxt.result = tc.tensor.Sparse.copy_from(([n, n], tc.I32), cxt.elements, concurrency=1)
+
+        actual = self.host.post(ENDPOINT, cxt)
+        expected = expect_sparse(tc.I32, [n, n], [[[i, i], 1] for i in range(n)])
+        self.assertEqual(actual, expected)
+
     def testWriteAndSlice(self):
         shape = [2, 5]
 
@@ -493,7 +1038,22 @@ class SparseTests(unittest.TestCase):
         expected = np.zeros(shape, dtype=np.int32)
         expected[0, 1:3] = 2
         expected = expected.sum(axis)
-        expected = expect_sparse(tc.I32, [2, 3, 5], expected)
+        expected = expect_sparse(tc.I64, [2, 3, 5], expected)
+        self.assertEqual(actual, expected)
+
+    def testMin(self):
+        shape = [2, 4, 3]
+        axis = 1
+
+        cxt = tc.Context()
+        cxt.big = tc.tensor.Sparse.zeros(shape, tc.I32)
+        cxt.result = tc.After(cxt.big[0, 1:3].write(-2), cxt.big.min(axis))
+
+        actual = self.host.post(ENDPOINT, cxt)
+        expected = np.zeros(shape, dtype=np.int32)
+        expected[0, 1:3] = -2
+        expected = expected.min(axis)
+        expected = expect_sparse(tc.I32, [2, 3], expected)
         self.assertEqual(actual, expected)
 
     def testProduct(self):
@@ -538,6 +1098,24 @@ class SparseTests(unittest.TestCase):
         expected = expect_sparse(tc.I32, expected.shape, expected)
         self.assertEqual(actual, expected)
 
+    def testAdjacency(self):
+        edges = [[0, 1], [1, 2], [2, 2], [3, 4]]
+
+        cxt = tc.Context()
+        cxt.edges = tc.tensor.Sparse.load([5, 5], tc.Bool, [[edge, True] for edge in edges])
+        cxt.result = (
+            tc.tensor.Sparse.adjacency(cxt.edges, 4),
+            tc.tensor.Sparse.adjacency(cxt.edges, 4, directed=False, self_loops=False),
+        )
+
+        directed, undirected = self.host.post(ENDPOINT, cxt)
+
+        expected = [[[0, 1], 1], [[1, 2], 1], [[2, 2], 1]]
+        self.assertEqual(directed, expect_sparse(tc.F32, [4, 4], expected))
+
+        expected = [[[0, 1], 1], [[1, 0], 1], [[1, 2], 1], [[2, 1], 1]]
+        self.assertEqual(undirected, expect_sparse(tc.F32, [4, 4], expected))
+
     def testArgmax(self):
         shape = [2, 3]
 
@@ -605,6 +1183,296 @@ class TensorTests(unittest.TestCase):
         expected = expected * np.arange(0, 3)
         self.assertEqual(actual, expect_sparse(tc.I64, [2, 3], expected))
 
+    def testMulLargeSparseByDense(self):
+        shape = [100, 100, 10]
+
+        cxt = tc.Context()
+        cxt.dense = tc.tensor.Dense.ones(shape, tc.I32) * 3
+        cxt.sparse = tc.tensor.Sparse.zeros(shape, tc.I32)
+        cxt.result = tc.After([
+            cxt.sparse[0, 0, 0].write(2),
+            cxt.sparse[99, 99, 9].write(5),
+        ], tc.Tuple([cxt.dense * cxt.sparse, cxt.dense.logical_and(cxt.sparse)]))
+
+        product, conjunction = self.host.post(ENDPOINT, cxt)
+
+        expected = [[[0, 0, 0], 6], [[99, 99, 9], 15]]
+        self.assertEqual(product, expect_sparse(tc.I64, shape, expected))
+
+        expected = [[[0, 0, 0], True], [[99, 99, 9], True]]
+        self.assertEqual(conjunction, expect_sparse(tc.Bool, shape, expected))
+
+    def testCountNonzero(self):
+        shape = [3, 4]
+        coords = [[0, 1], [1, 1], [2, 3]]
+
+        cxt = tc.Context()
+        cxt.dense = tc.tensor.Dense.zeros(shape, tc.I32)
+        cxt.sparse = tc.tensor.Sparse.zeros(shape, tc.I32)
+        cxt.result = tc.After(
+            [cxt.dense[coord].write(2) for coord in coords] + [cxt.sparse[coord].write(2) for coord in coords],
+            tc.Tuple([
+                cxt.dense.count_nonzero(), cxt.sparse.count_nonzero(),
+                cxt.dense.count_nonzero(0), cxt.sparse.count_nonzero(0),
+            ]))
+
+        dense_total, sparse_total, dense_by_axis, sparse_by_axis = self.host.post(ENDPOINT, cxt)
+
+        self.assertEqual(dense_total, 3)
+        self.assertEqual(sparse_total, 3)
+        self.assertEqual(dense_by_axis, expect_dense(tc.U64, [4], [0, 2, 0, 1]))
+        self.assertEqual(sparse_by_axis, expect_sparse(tc.U64, [4], [[[1], 2], [[3], 1]]))
+
+    def testToTable(self):
+        shape = [3, 4]
+
+        cxt = tc.Context()
+        cxt.sparse = tc.tensor.Sparse.zeros(shape, tc.I32)
+        cxt.result = tc.After([
+            cxt.sparse[0, 1].write(2),
+            cxt.sparse[2, 3].write(5),
+        ], tc.Tuple([
+            cxt.sparse.to_table().count(),
+            cxt.sparse.to_table()[(2, 3)],
+            cxt.sparse.to_table(include_zeros=True).count(),
+        ]))
+
+        count, row, count_with_zeros = self.host.post(ENDPOINT, cxt)
+        self.assertEqual(count, 2)
+        self.assertEqual(row, [2, 3, 5])
+        self.assertEqual(count_with_zeros, 12)
+
+    def testFloatPredicates(self):
+        # NaN and infinity can't be encoded as JSON, so compute them on the host
+        cxt = tc.Context()
+        cxt.nan = tc.tensor.Dense.load([3], tc.F64, [1., -1., 2.]).log()
+        cxt.inf = tc.tensor.Dense.load([3], tc.F64, [1., 1000., -1000.]).exp()
+        cxt.result = (cxt.nan.isnan(), cxt.nan.isfinite(), cxt.inf.isinf(), (-cxt.inf).isinf(), cxt.inf.isfinite())
+
+        isnan, nan_isfinite, isinf, neg_isinf, inf_isfinite = self.host.post(ENDPOINT, cxt)
+        self.assertEqual(isnan, expect_dense(tc.Bool, [3], [False, True, False]))
+        self.assertEqual(nan_isfinite, expect_dense(tc.Bool, [3], [True, False, True]))
+        self.assertEqual(isinf, expect_dense(tc.Bool, [3], [False, True, False]))
+        self.assertEqual(neg_isinf, expect_dense(tc.Bool, [3], [False, True, False]))
+        self.assertEqual(inf_isfinite, expect_dense(tc.Bool, [3], [True, False, True]))
+
+    def testFloatPredicatesInt(self):
+        cxt = tc.Context()
+        cxt.x = tc.tensor.Dense.load([3], tc.I32, [1, 0, -1])
+        cxt.result = (cxt.x.isnan(), cxt.x.isinf(), cxt.x.isfinite())
+
+        isnan, isinf, isfinite = self.host.post(ENDPOINT, cxt)
+        self.assertEqual(isnan, expect_dense(tc.Bool, [3], [False] * 3))
+        self.assertEqual(isinf, expect_dense(tc.Bool, [3], [False] * 3))
+        self.assertEqual(isfinite, expect_dense(tc.Bool, [3], [True] * 3))
+
+    def testNanToNum(self):
+        # NaN and infinity can't be encoded as JSON, so compute them on the host
+        cxt = tc.Context()
+        cxt.x = tc.tensor.Dense.load([3], tc.F64, [1., -1., 0.]).log()
+        cxt.y = tc.tensor.Dense.load([2], tc.F64, [0., 1000.]).exp()
+        cxt.result = (cxt.x.nan_to_num(nan=2., neginf=-1e30), cxt.y.nan_to_num(posinf=1e30))
+
+        x, y = self.host.post(ENDPOINT, cxt)
+        self.assertEqual(x, expect_dense(tc.F64, [3], [0., 2., -1e30]))
+        self.assertEqual(y, expect_dense(tc.F64, [2], [1., 1e30]))
+
+    def testCastBool(self):
+        cxt = tc.Context()
+        cxt.flags = tc.tensor.Dense.load([4], tc.Bool, [True, False, True, False])
+        cxt.x = tc.tensor.Dense.load([4], tc.F64, [0., -2.5, 0.1, 0.])
+        cxt.result = (
+            cxt.flags.cast(tc.I32),
+            cxt.x.astype(tc.Bool),
+            cxt.x.astype(tc.Bool).astype(tc.F64))
+
+        ints, flags, floats = self.host.post(ENDPOINT, cxt)
+        self.assertEqual(ints, expect_dense(tc.I32, [4], [1, 0, 1, 0]))
+        self.assertEqual(flags, expect_dense(tc.Bool, [4], [False, True, True, False]))
+        self.assertEqual(floats, expect_dense(tc.F64, [4], [0., 1., 1., 0.]))
+
+    def testMovingAverage(self):
+        cxt = tc.Context()
+        cxt.x = tc.tensor.Dense.load([5], tc.F64, [1., 2., 3., 4., 5.])
+        cxt.result = (cxt.x.moving_average(3), cxt.x.moving_average(3, mode="full"))
+
+        valid, full = self.host.post(ENDPOINT, cxt)
+        self.assertEqual(valid, expect_dense(tc.F64, [3], [2., 3., 4.]))
+        self.assertEqual(full, expect_dense(tc.F64, [5], [1., 1.5, 2., 3., 4.]))
+
+    def testMovingAverageInvalidWindow(self):
+        cxt = tc.Context()
+        cxt.x = tc.tensor.Dense.load([5], tc.F64, [1., 2., 3., 4., 5.])
+        cxt.result = cxt.x.moving_average(6)
+
+        self.assertRaises(tc.error.BadRequest, lambda: self.host.post(ENDPOINT, cxt))
+
+    def testQuantile(self):
+        x = np.array([[1, 2, 3, 4], [8, 6, 4, 2]])
+
+        cxt = tc.Context()
+        cxt.x = load_dense(x, tc.I32)
+        cxt.result = (cxt.x[0].quantile(0.5), cxt.x.quantile(0.5, axis=1), cxt.x.quantile((0., 1.), axis=0))
+
+        median, medians, extrema = self.host.post(ENDPOINT, cxt)
+        self.assertEqual(median, 2.5)
+        self.assertEqual(medians, expect_dense(tc.F64, [2], np.quantile(x, 0.5, axis=1).tolist()))
+        self.assertEqual(extrema, expect_dense(tc.F64, [2, 4], np.quantile(x, [0., 1.], axis=0).flatten().tolist()))
+
+    def testQuantileOutOfRange(self):
+        cxt = tc.Context()
+        cxt.x = tc.tensor.Dense.load([4], tc.I32, [1, 2, 3, 4])
+        cxt.result = cxt.x.quantile(1.5)
+
+        self.assertRaises(tc.error.BadRequest, lambda: self.host.post(ENDPOINT, cxt))
+
+    def testTopK(self):
+        cxt = tc.Context()
+        cxt.x = tc.tensor.Dense.load([5], tc.I32, [3, 1, 4, 1, 5])
+        cxt.result = (cxt.x.topk(2), cxt.x.topk(2, largest=False))
+
+        (values, indices), (smallest, smallest_indices) = self.host.post(ENDPOINT, cxt)
+        self.assertEqual(values, expect_dense(tc.I32, [2], [5, 4]))
+        self.assertEqual(indices, expect_dense(tc.U64, [2], [4, 2]))
+        self.assertEqual(smallest, expect_dense(tc.I32, [2], [1, 1]))
+        self.assertEqual(smallest_indices, expect_dense(tc.U64, [2], [1, 3]))
+
+    def testTopKTooLarge(self):
+        cxt = tc.Context()
+        cxt.x = tc.tensor.Dense.load([5], tc.I32, [3, 1, 4, 1, 5])
+        cxt.result = cxt.x.topk(6)
+
+        self.assertRaises(tc.error.BadRequest, lambda: self.host.post(ENDPOINT, cxt))
+
+    def testUnique(self):
+        cxt = tc.Context()
+        cxt.dense = tc.tensor.Dense.load([6], tc.I32, [3, 1, 2, 3, 2, 3])
+        cxt.sparse = tc.tensor.Sparse.load([4], tc.I32, [[[1], 2], [[3], 2]])
+        cxt.result = (cxt.dense.unique(), cxt.dense.unique(return_counts=True), cxt.sparse.unique(True))
+
+        values, (counted, counts), (sparse_values, sparse_counts) = self.host.post(ENDPOINT, cxt)
+        self.assertEqual(values, expect_dense(tc.I32, [3], [1, 2, 3]))
+        self.assertEqual(counted, values)
+        self.assertEqual(counts, expect_dense(tc.U64, [3], [1, 2, 3]))
+        self.assertEqual(sparse_values, expect_dense(tc.I32, [2], [0, 2]))
+        self.assertEqual(sparse_counts, expect_dense(tc.U64, [2], [2, 2]))
+
+    def testMaskedFill(self):
+        shape = [2, 3]
+        x = np.arange(6).reshape(shape)
+        mask = np.array([True, False, True])
+
+        cxt = tc.Context()
+        cxt.dense = load_dense(x, tc.I32)
+        cxt.sparse = tc.tensor.Sparse.load(shape, tc.I32, [[[0, 1], 1], [[1, 2], 5]])
+        cxt.mask = tc.tensor.Dense.load([3], tc.Bool, mask.tolist())
+        cxt.result = (cxt.dense.masked_fill(cxt.mask, -100), cxt.sparse.masked_fill(cxt.mask, -100))
+
+        dense, sparse = self.host.post(ENDPOINT, cxt)
+
+        expected = np.where(mask, -100, x)
+        self.assertEqual(dense, expect_dense(tc.I32, shape, expected.flatten().tolist()))
+
+        expected = [[[0, 0], -100], [[0, 1], 1], [[0, 2], -100], [[1, 0], -100], [[1, 2], -100]]
+        self.assertEqual(sparse, expect_sparse(tc.I32, shape, expected))
+
+    def testMaskedFillIncompatibleMask(self):
+        cxt = tc.Context()
+        cxt.tensor = tc.tensor.Dense.zeros([2, 3], tc.F32)
+        cxt.mask = tc.tensor.Dense.ones([2], tc.Bool)
+        cxt.result = cxt.tensor.masked_fill(cxt.mask, 1.)
+
+        self.assertRaises(tc.error.BadRequest, lambda: self.host.post(ENDPOINT, cxt))
+
+    def testMaskedFillInfiniteInt(self):
+        cxt = tc.Context()
+        cxt.tensor = tc.tensor.Dense.zeros([2, 3], tc.I32)
+        cxt.mask = tc.tensor.Dense.ones([3], tc.Bool)
+        cxt.result = cxt.tensor.masked_fill(cxt.mask, -math.inf)
+
+        self.assertRaises(tc.error.BadRequest, lambda: self.host.post(ENDPOINT, cxt))
+
+    def testMaskedMean(self):
+        shape = [2, 4]
+        x = np.arange(8).reshape(shape)
+        mask = np.array([True, False, True, False])
+
+        cxt = tc.Context()
+        cxt.dense = load_dense(x, tc.I32)
+        cxt.mask = tc.tensor.Dense.load([4], tc.Bool, mask.tolist())
+        cxt.result = (
+            cxt.dense.masked_sum(cxt.mask),
+            cxt.dense.masked_mean(cxt.mask),
+            cxt.dense.masked_mean(cxt.mask, axis=1),
+        )
+
+        total, mean, mean_axis = self.host.post(ENDPOINT, cxt)
+
+        masked = np.broadcast_to(mask, shape)
+        self.assertEqual(total, int(x[masked].sum()))
+        self.assertAlmostEqual(mean, x[masked].mean())
+
+        expected = np.ma.masked_array(x, ~masked).mean(axis=1)
+        self.assertEqual(mean_axis, expect_dense(tc.F64, [2], expected.tolist()))
+
+    def testMaskedMeanEmpty(self):
+        cxt = tc.Context()
+        cxt.tensor = tc.tensor.Dense.ones([2, 3], tc.F32)
+        cxt.mask = tc.tensor.Dense.zeros([3], tc.Bool)
+        cxt.result = cxt.tensor.masked_mean(cxt.mask)
+
+        self.assertRaises(tc.error.BadRequest, lambda: self.host.post(ENDPOINT, cxt))
+
+    def testMaskedSum1DAxis(self):
+        cxt = tc.Context()
+        cxt.tensor = tc.tensor.Dense.ones([3], tc.F32)
+        cxt.mask = tc.tensor.Dense.ones([3], tc.Bool)
+        cxt.result = cxt.tensor.masked_sum(cxt.mask, axis=0)
+
+        self.assertRaises(tc.error.BadRequest, lambda: self.host.post(ENDPOINT, cxt))
+
+    def testRelu(self):
+        shape = [2, 3]
+        x = np.array([[-2, 0, 3], [4, -5, 0]])
+
+        cxt = tc.Context()
+        cxt.dense = tc.tensor.Dense.load(shape, tc.I32, x.flatten().tolist())
+        cxt.sparse = tc.tensor.Sparse.load(shape, tc.I32, [[[0, 0], -2], [[0, 2], 3], [[1, 0], 4], [[1, 1], -5]])
+        cxt.result = (cxt.dense.relu(), cxt.sparse.relu())
+
+        dense, sparse = self.host.post(ENDPOINT, cxt)
+        self.assertEqual(dense, expect_dense(tc.I32, shape, np.maximum(x, 0).flatten()))
+        self.assertEqual(sparse, expect_sparse(tc.I32, shape, [[[0, 2], 3], [[1, 0], 4]]))
+
+    def testSigmoid(self):
+        shape = [2, 3]
+        x = np.array([[-1000, -1, 0], [1, 2, 1000]])
+
+        cxt = tc.Context()
+        cxt.dense = tc.tensor.Dense.load(shape, tc.I32, x.flatten().tolist())
+        cxt.sparse = tc.tensor.Sparse.load(shape, tc.I32, [[[0, 1], -1], [[1, 0], 1]])
+        cxt.result = (cxt.dense.sigmoid(), cxt.sparse.sigmoid())
+
+        dense, sparse = self.host.post(ENDPOINT, cxt)
+        self.assertEqual(dense[tc.uri(tc.tensor.Dense)][0], [shape, str(tc.uri(tc.F64))])
+        self.assertTrue(all_close(dense, 1 / (1 + np.exp(-x.astype(np.float64)))))
+
+        # sigmoid(0) == 0.5, so the result for a sparse input is dense
+        expected = np.array([[0, -1, 0], [1, 0, 0]])
+        self.assertTrue(all_close(sparse, 1 / (1 + np.exp(-expected))))
+
+    def testTanh(self):
+        shape = [2, 3]
+        x = np.array([[-3, -1, 0], [1, 2, 3]])
+
+        cxt = tc.Context()
+        cxt.dense = tc.tensor.Dense.load(shape, tc.I32, x.flatten().tolist())
+        cxt.result = cxt.dense.tanh()
+
+        actual = self.host.post(ENDPOINT, cxt)
+        self.assertEqual(actual[tc.uri(tc.tensor.Dense)][0], [shape, str(tc.uri(tc.F64))])
+        self.assertTrue(all_close(actual, np.tanh(x)))
+
     def testSubAndSum(self):
         x = 300
         y = 250
@@ -641,6 +1509,17 @@ class TensorTests(unittest.TestCase):
         expected = expect_sparse(tc.I32, [3, 3], matrix)
         self.assertEqual(actual, expected)
 
+    def testToSparseWithThreshold(self):
+        x = np.array([[1., 1e-6, 0.], [-1e-7, -2., 3e-3]])
+
+        cxt = tc.Context()
+        cxt.dense = load_dense(x, tc.F64)
+        cxt.result = cxt.dense.to_sparse(1e-4)
+
+        actual = self.host.post(ENDPOINT, cxt)
+        expected = np.where(np.abs(x) > 1e-4, x, 0.)
+        self.assertEqual(actual, expect_sparse(tc.F64, [2, 3], expected))
+
     def testConcatenate(self):
         x1 = np.ones([5, 8], np.int)
         x2 = np.ones([5, 4], np.int) * 2
@@ -720,17 +1599,56 @@ class ChainTests(PersistenceTest, unittest.TestCase):
             self.assertEqual(actual, eq)
 
 
-def all_close(actual, expected):
-    return np.allclose(actual[tc.uri(tc.tensor.Dense)][1], expected.flatten())
+class ReloadTests(PersistenceTest, unittest.TestCase):
+    NUM_HOSTS = 1
+    NAME = "tensor_reload"
 
+    def cluster(self, chain_type):
+        class Persistent(tc.Cluster, metaclass=tc.Meta):
+            __uri__ = tc.URI(f"http://127.0.0.1:{DEFAULT_PORT}/test/tensor")
 
-def expect_dense(dtype, shape, flat):
-    return {
-        str(tc.uri(tc.tensor.Dense)): [
-            [list(shape), str(tc.uri(dtype))],
-            list(flat),
-        ]
-    }
+            def _configure(self):
+                schema = ([2, 2], tc.I32)
+                self.dense = chain_type(tc.tensor.Dense(schema))
+                self.sparse = chain_type(tc.tensor.Sparse(schema))
+
+            @tc.put_method
+            def fill(self, txn):
+                return self.dense.write(tc.tensor.Dense.constant([2, 2], 5, block_size=2))
+
+        return Persistent
+
+    def execute(self, hosts):
+        host = hosts[0]
+
+        host.put("/test/tensor/dense", [0, 1], 2)
+        host.put("/test/tensor/dense", [1, 0], 3)
+
+        host.put("/test/tensor/sparse", [0, 1], 2)
+        host.put("/test/tensor/sparse", [0, 1], 0)
+        host.put("/test/tensor/sparse", [1, 0], 3)
+
+        host.stop()
+        host.start()
+
+        actual = host.get("/test/tensor/dense")
+        self.assertEqual(actual, expect_dense(tc.I32, [2, 2], [0, 2, 3, 0]))
+
+        actual = host.get("/test/tensor/sparse")
+        self.assertEqual(actual, expect_sparse(tc.I32, [2, 2], [[[1, 0], 3]]))
+
+        # a tensor with a custom block size can be written to and restored from the chain
+        host.put("/test/tensor/fill")
+
+        host.stop()
+        host.start()
+
+        actual = host.get("/test/tensor/dense")
+        self.assertEqual(actual, expect_dense(tc.I32, [2, 2], [5] * 4))
+
+
+def all_close(actual, expected):
+    return np.allclose(actual[tc.uri(tc.tensor.Dense)][1], expected.flatten())
 
 
 def expect_sparse(dtype, shape, values):
@@ -160,6 +160,15 @@ class PersistenceTest(object):
             host.stop()
 
 
+def expect_dense(dtype, shape, flat):
+    return {
+        str(tc.uri(tc.tensor.Dense)): [
+            [list(shape), str(tc.uri(dtype))],
+            list(flat),
+        ]
+    }
+
+
 def maybe_create_dir(path, force):
     path = pathlib.Path(path)
     if path.exists() and path.is_dir():